@@ -1,7 +1,8 @@
+use crate::json::convert_json;
 use crate::webdrivercommands::WebDriverCommands;
 use crate::WebDriverSession;
 use crate::{
-    common::{command::Command, connection_common::convert_json, keys::TypingData},
+    common::{command::Command, keys::TypingData},
     error::WebDriverResult,
 };
 
@@ -44,7 +45,7 @@ impl<'a> Alert<'a> {
     /// ```
     pub fn text(&self) -> WebDriverResult<String> {
         let v = self.cmd(Command::GetAlertText)?;
-        convert_json::<String>(&v["value"])
+        convert_json::<String>("Get Alert Text", &v["value"])
     }
 
     /// Dismiss the active alert.
@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::secret::REDACTED;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{Cookie, ScriptArgs, WebDriverSession};
+
+/// The cookies, web storage and IndexedDB contents that keep a user signed
+/// in, so that a fresh session can start signed in without going through
+/// the login UI.
+///
+/// Storage is keyed by origin, e.g. `https://example.com`. IndexedDB is
+/// dumped as JSON, so only records whose keys and values are plain JSON
+/// data survive the round trip; dates, blobs and typed arrays do not.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::AuthState;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// let state = AuthState::from_file("alice.json")?;
+/// driver.seed_auth_state(&state)?;
+/// driver.get("http://localhost:8000/account")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthState {
+    /// The cookies, with their domains.
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+    /// The `localStorage` items of each origin.
+    #[serde(default)]
+    pub local_storage: BTreeMap<String, BTreeMap<String, String>>,
+    /// The `sessionStorage` items of each origin.
+    #[serde(default)]
+    pub session_storage: BTreeMap<String, BTreeMap<String, String>>,
+    /// The IndexedDB databases of each origin, as dumped by the browser.
+    #[serde(default)]
+    pub indexed_db_dump: BTreeMap<String, Value>,
+}
+
+impl AuthState {
+    /// Create a new, empty AuthState.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read an AuthState from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> WebDriverResult<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Write the AuthState to a JSON file, replacing any existing file.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> WebDriverResult<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The origins that have storage in this state.
+    fn storage_origins(&self) -> BTreeSet<String> {
+        self.local_storage
+            .keys()
+            .chain(self.session_storage.keys())
+            .chain(self.indexed_db_dump.keys())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Options for
+/// [WebDriver::export_auth_state()](trait.WebDriverCommands.html#method.export_auth_state).
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::AuthExportOptions;
+///
+/// let options = AuthExportOptions::new()
+///     .exclude(&["_ga", "analytics-consent"])
+///     .redact(&["refresh_token"])
+///     .redact_secrets();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AuthExportOptions {
+    exclude: Vec<String>,
+    redact: Vec<String>,
+    redact_secrets: bool,
+    indexed_db: bool,
+}
+
+impl AuthExportOptions {
+    /// Create a new AuthExportOptions that exports every cookie and storage
+    /// item as it is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leave out the cookies and storage items with any of these names.
+    pub fn exclude(mut self, names: &[&str]) -> Self {
+        self.exclude.extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Replace the values of the cookies and storage items with any of these
+    /// names with `[REDACTED]`, e.g. to share a state without its tokens.
+    pub fn redact(mut self, names: &[&str]) -> Self {
+        self.redact.extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Replace any secrets sent with
+    /// [WebElement::send_secret()](struct.WebElement.html#method.send_secret)
+    /// wherever they appear in cookie and storage values.
+    pub fn redact_secrets(mut self) -> Self {
+        self.redact_secrets = true;
+        self
+    }
+
+    /// Also dump the IndexedDB databases of each origin. This needs
+    /// `indexedDB.databases()`, which Firefox only supports from version 126.
+    pub fn with_indexed_db(mut self) -> Self {
+        self.indexed_db = true;
+        self
+    }
+
+    /// Apply the options to a value, returning None if it is excluded.
+    fn filter(&self, session: &WebDriverSession, name: &str, value: &str) -> Option<String> {
+        if self.exclude.iter().any(|n| n == name) {
+            None
+        } else if self.redact.iter().any(|n| n == name) {
+            Some(REDACTED.to_string())
+        } else if self.redact_secrets {
+            Some(session.redact(value))
+        } else {
+            Some(value.to_string())
+        }
+    }
+}
+
+/// Returns true if the cookie would be sent to the host.
+pub(crate) fn cookie_matches(cookie: &Cookie, host: &str) -> bool {
+    match cookie.domain() {
+        Some(domain) => {
+            let domain = domain.trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{}", domain))
+        }
+        None => false,
+    }
+}
+
+/// Parse an origin, returning it in its canonical form and its host.
+pub(crate) fn parse_origin(origin: &str) -> WebDriverResult<(String, String)> {
+    let url = Url::parse(origin).ok();
+    match url.as_ref().and_then(|u| Some((u, u.host_str()?))) {
+        Some((url, host)) => Ok((url.origin().ascii_serialization(), host.to_string())),
+        None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "{:?} is not an origin such as https://example.com",
+            origin
+        )))),
+    }
+}
+
+/// A cheap page on the origin, on which storage and cookies can be set
+/// without the site's own scripts running.
+pub(crate) fn blank_page(origin: &str) -> String {
+    format!("{}/robots.txt", origin)
+}
+
+const SEED_STORAGE_SCRIPT: &str = r#"
+    const [local, session] = arguments;
+    for (const [key, value] of Object.entries(local || {})) localStorage.setItem(key, value);
+    for (const [key, value] of Object.entries(session || {})) sessionStorage.setItem(key, value);
+"#;
+
+/// Recreates the dumped IndexedDB databases, creating any missing object
+/// stores and indexes, and puts each record.
+const SEED_INDEXED_DB_SCRIPT: &str = r#"
+    const [dump, done] = [arguments[0], arguments[arguments.length - 1]];
+    const request = (r) => new Promise((resolve, reject) => {
+        r.onsuccess = () => resolve(r.result);
+        r.onerror = () => reject(r.error);
+    });
+    const restore = async (name, db) => {
+        const open = indexedDB.open(name, db.version);
+        open.onupgradeneeded = () => {
+            const conn = open.result;
+            for (const [storeName, store] of Object.entries(db.stores)) {
+                const target = conn.objectStoreNames.contains(storeName)
+                    ? open.transaction.objectStore(storeName)
+                    : conn.createObjectStore(storeName, {
+                        keyPath: store.keyPath,
+                        autoIncrement: store.autoIncrement,
+                    });
+                for (const index of store.indexes || []) {
+                    if (!target.indexNames.contains(index.name)) {
+                        target.createIndex(index.name, index.keyPath, {
+                            unique: index.unique,
+                            multiEntry: index.multiEntry,
+                        });
+                    }
+                }
+            }
+        };
+        const conn = await request(open);
+        for (const [storeName, store] of Object.entries(db.stores)) {
+            if (!conn.objectStoreNames.contains(storeName)) continue;
+            const tx = conn.transaction(storeName, "readwrite");
+            const target = tx.objectStore(storeName);
+            for (const record of store.records) {
+                if (target.keyPath === null) target.put(record.value, record.key);
+                else target.put(record.value);
+            }
+            await new Promise((resolve, reject) => {
+                tx.oncomplete = resolve;
+                tx.onerror = () => reject(tx.error);
+            });
+        }
+        conn.close();
+    };
+    (async () => {
+        for (const [name, db] of Object.entries(dump || {})) await restore(name, db);
+    })().then(() => done(null), (e) => done(String(e)));
+"#;
+
+const READ_STORAGE_SCRIPT: &str = r#"
+    const read = (storage) => {
+        const items = {};
+        for (let i = 0; i < storage.length; i++) {
+            const key = storage.key(i);
+            items[key] = storage.getItem(key);
+        }
+        return items;
+    };
+    return [read(localStorage), read(sessionStorage)];
+"#;
+
+/// Dumps every IndexedDB database of the origin in the form that
+/// SEED_INDEXED_DB_SCRIPT restores.
+const DUMP_INDEXED_DB_SCRIPT: &str = r#"
+    const done = arguments[arguments.length - 1];
+    const request = (r) => new Promise((resolve, reject) => {
+        r.onsuccess = () => resolve(r.result);
+        r.onerror = () => reject(r.error);
+    });
+    const dumpStore = async (store) => {
+        const [keys, values] = await Promise.all([
+            request(store.getAllKeys()),
+            request(store.getAll()),
+        ]);
+        return {
+            keyPath: store.keyPath,
+            autoIncrement: store.autoIncrement,
+            indexes: Array.from(store.indexNames, (name) => {
+                const index = store.index(name);
+                return {
+                    name,
+                    keyPath: index.keyPath,
+                    unique: index.unique,
+                    multiEntry: index.multiEntry,
+                };
+            }),
+            records: keys.map((key, i) => ({ key, value: values[i] })),
+        };
+    };
+    (async () => {
+        if (typeof indexedDB.databases !== "function") {
+            throw new Error("indexedDB.databases() is not supported by this browser");
+        }
+        const dump = {};
+        for (const info of await indexedDB.databases()) {
+            const conn = await request(indexedDB.open(info.name));
+            const stores = {};
+            const names = Array.from(conn.objectStoreNames);
+            if (names.length > 0) {
+                const tx = conn.transaction(names, "readonly");
+                for (const name of names) stores[name] = await dumpStore(tx.objectStore(name));
+            }
+            dump[info.name] = { version: conn.version, stores };
+            conn.close();
+        }
+        return dump;
+    })().then((dump) => done({ dump }), (e) => done({ error: String(e) }));
+"#;
+
+#[derive(Debug, Deserialize)]
+struct IndexedDbDump {
+    #[serde(default)]
+    dump: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+pub(crate) fn export_auth_state(
+    session: &WebDriverSession,
+    origins: &[&str],
+    options: &AuthExportOptions,
+) -> WebDriverResult<AuthState> {
+    let origins =
+        origins.iter().map(|origin| parse_origin(origin)).collect::<WebDriverResult<Vec<_>>>()?;
+    let start_url = session.current_url()?;
+    let mut navigated = false;
+    let mut state = AuthState::new();
+
+    for (origin, _) in &origins {
+        // Read in place if possible, to avoid leaving the current page.
+        let current = Url::parse(&session.current_url()?).ok();
+        if current.map(|u| u.origin().ascii_serialization()).as_ref() != Some(origin) {
+            session.get(blank_page(origin))?;
+            navigated = true;
+        }
+
+        for mut cookie in session.get_cookies()? {
+            let exists = state.cookies.iter().any(|c| {
+                c.name() == cookie.name()
+                    && c.domain() == cookie.domain()
+                    && c.path() == cookie.path()
+            });
+            if exists {
+                continue;
+            }
+            let value = match cookie.value() {
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            };
+            if let Some(filtered) = options.filter(session, cookie.name(), &value) {
+                if filtered != value {
+                    cookie.set_value(Value::String(filtered));
+                }
+                state.cookies.push(cookie);
+            }
+        }
+
+        let (local, session_items): (BTreeMap<String, String>, BTreeMap<String, String>) =
+            session.execute_script(READ_STORAGE_SCRIPT)?.convert()?;
+        let filter = |items: BTreeMap<String, String>| -> BTreeMap<String, String> {
+            items
+                .into_iter()
+                .filter_map(|(k, v)| options.filter(session, &k, &v).map(|v| (k, v)))
+                .collect()
+        };
+        state.local_storage.insert(origin.clone(), filter(local));
+        state.session_storage.insert(origin.clone(), filter(session_items));
+
+        if options.indexed_db {
+            let result: IndexedDbDump =
+                session.execute_async_script(DUMP_INDEXED_DB_SCRIPT)?.convert()?;
+            if let Some(e) = result.error {
+                return Err(WebDriverError::JavascriptError(WebDriverErrorInfo::new(&format!(
+                    "failed to dump IndexedDB for {}: {}",
+                    origin, e
+                ))));
+            }
+            let mut dump = result.dump.unwrap_or(Value::Null);
+            if options.redact_secrets {
+                dump = serde_json::from_str(&session.redact(&dump.to_string()))?;
+            }
+            state.indexed_db_dump.insert(origin.clone(), dump);
+        }
+    }
+
+    if navigated {
+        session.get(start_url)?;
+    }
+    Ok(state)
+}
+
+pub(crate) fn seed_auth_state(
+    session: &WebDriverSession,
+    state: &AuthState,
+) -> WebDriverResult<()> {
+    let mut origins = Vec::new();
+    for origin in state.storage_origins() {
+        origins.push(parse_origin(&origin)?);
+    }
+    // Cookies for domains without storage need an origin of their own.
+    for cookie in &state.cookies {
+        if !origins.iter().any(|(_, host)| cookie_matches(cookie, host)) {
+            if let Some(domain) = cookie.domain() {
+                origins.push(parse_origin(&format!("https://{}", domain.trim_start_matches('.')))?);
+            }
+        }
+    }
+
+    let mut seeded = vec![false; state.cookies.len()];
+    for (i, (origin, host)) in origins.iter().enumerate() {
+        session.get(blank_page(origin))?;
+        for (cookie, seeded) in state.cookies.iter().zip(seeded.iter_mut()) {
+            // Cookies without a domain belong to the first origin.
+            let matches = match cookie.domain() {
+                Some(_) => cookie_matches(cookie, host),
+                None => i == 0,
+            };
+            if !*seeded && matches {
+                session.add_cookie(cookie.clone())?;
+                *seeded = true;
+            }
+        }
+
+        let mut args = ScriptArgs::new();
+        args.push(state.local_storage.get(origin))?;
+        args.push(state.session_storage.get(origin))?;
+        session.execute_script_with_args(SEED_STORAGE_SCRIPT, &args)?;
+
+        if let Some(dump) = state.indexed_db_dump.get(origin) {
+            let mut args = ScriptArgs::new();
+            args.push(dump)?;
+            let error: Option<String> =
+                session.execute_async_script_with_args(SEED_INDEXED_DB_SCRIPT, &args)?.convert()?;
+            if let Some(e) = error {
+                return Err(WebDriverError::JavascriptError(WebDriverErrorInfo::new(&format!(
+                    "failed to restore IndexedDB for {}: {}",
+                    origin, e
+                ))));
+            }
+        }
+    }
+    session.get("about:blank")
+}
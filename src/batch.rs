@@ -0,0 +1,243 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::common::command::Command;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::json::convert_json;
+use crate::webelement::{convert_element_sync, convert_elements_sync};
+use crate::{By, WebDriverSession, WebElement};
+
+/// The most connections used to send the commands in a batch, including the
+/// session's own connection.
+pub(crate) const MAX_BATCH_CONNECTIONS: usize = 4;
+
+/// Creates extra connections to the WebDriver server for batches.
+#[derive(Clone)]
+pub(crate) struct ConnectionFactory(
+    Arc<dyn Fn() -> WebDriverResult<Box<dyn WebDriverHttpClientSync>> + Send + Sync>,
+);
+
+impl ConnectionFactory {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: Fn() -> WebDriverResult<Box<dyn WebDriverHttpClientSync>> + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn create(&self) -> WebDriverResult<Box<dyn WebDriverHttpClientSync>> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for ConnectionFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ConnectionFactory")
+    }
+}
+
+/// A value that can be returned from a batched command.
+pub trait BatchOutput<'a>: Sized {
+    /// Convert the `value` of the response.
+    fn from_batch(session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self>;
+}
+
+impl<'a> BatchOutput<'a> for String {
+    fn from_batch(_session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        convert_json("Batched Command", value)
+    }
+}
+
+impl<'a> BatchOutput<'a> for bool {
+    fn from_batch(_session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        convert_json("Batched Command", value)
+    }
+}
+
+impl<'a> BatchOutput<'a> for Option<String> {
+    fn from_batch(_session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        Ok(match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        })
+    }
+}
+
+impl<'a> BatchOutput<'a> for Value {
+    fn from_batch(_session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl<'a> BatchOutput<'a> for WebElement<'a> {
+    fn from_batch(session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        convert_element_sync(session, value)
+    }
+}
+
+impl<'a> BatchOutput<'a> for Vec<WebElement<'a>> {
+    fn from_batch(session: &'a WebDriverSession, value: &Value) -> WebDriverResult<Self> {
+        convert_elements_sync(session, value)
+    }
+}
+
+/// Identifies the result of a command added to a [Batch](struct.Batch.html),
+/// and the type it is converted to.
+pub struct BatchKey<T> {
+    index: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for BatchKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchKey<T> {}
+
+impl<T> fmt::Debug for BatchKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BatchKey({})", self.index)
+    }
+}
+
+/// A set of independent, read-only commands to send together. See
+/// [WebDriver::batch()](../trait.WebDriverCommands.html#method.batch).
+pub struct Batch<'a> {
+    session: &'a WebDriverSession,
+    commands: Vec<Command>,
+}
+
+impl fmt::Debug for Batch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch").field("commands", &self.commands.len()).finish()
+    }
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(session: &'a WebDriverSession) -> Self {
+        Self {
+            session,
+            commands: Vec::new(),
+        }
+    }
+
+    fn add<T>(&mut self, command: Command) -> BatchKey<T> {
+        self.commands.push(command);
+        BatchKey {
+            index: self.commands.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Get the title of the current page.
+    pub fn title(&mut self) -> BatchKey<String> {
+        self.add(Command::GetTitle)
+    }
+
+    /// Get the current URL.
+    pub fn current_url(&mut self) -> BatchKey<String> {
+        self.add(Command::GetCurrentUrl)
+    }
+
+    /// Get the source of the current page.
+    pub fn page_source(&mut self) -> BatchKey<String> {
+        self.add(Command::GetPageSource)
+    }
+
+    /// Find the first element matching the selector.
+    pub fn find_element(&mut self, by: By) -> BatchKey<WebElement<'a>> {
+        self.add(Command::FindElement(by.get_w3c_selector()))
+    }
+
+    /// Find all elements matching the selector.
+    pub fn find_elements(&mut self, by: By) -> BatchKey<Vec<WebElement<'a>>> {
+        self.add(Command::FindElements(by.get_w3c_selector()))
+    }
+
+    /// Get the text of the element.
+    pub fn text(&mut self, element: &WebElement) -> BatchKey<String> {
+        self.add(Command::GetElementText(element.element_id.clone()))
+    }
+
+    /// Get the tag name of the element.
+    pub fn tag_name(&mut self, element: &WebElement) -> BatchKey<String> {
+        self.add(Command::GetElementTagName(element.element_id.clone()))
+    }
+
+    /// Get the specified attribute of the element.
+    pub fn get_attribute(&mut self, element: &WebElement, name: &str) -> BatchKey<Option<String>> {
+        self.add(Command::GetElementAttribute(element.element_id.clone(), name.to_string()))
+    }
+
+    /// Get the specified property of the element.
+    pub fn get_property(&mut self, element: &WebElement, name: &str) -> BatchKey<Option<String>> {
+        self.add(Command::GetElementProperty(element.element_id.clone(), name.to_string()))
+    }
+
+    /// Get the specified CSS property of the element.
+    pub fn get_css_property(&mut self, element: &WebElement, name: &str) -> BatchKey<String> {
+        self.add(Command::GetElementCSSValue(element.element_id.clone(), name.to_string()))
+    }
+
+    /// Get whether the element is displayed.
+    pub fn is_displayed(&mut self, element: &WebElement) -> BatchKey<bool> {
+        self.add(Command::IsElementDisplayed(element.element_id.clone()))
+    }
+
+    /// Get whether the element is enabled.
+    pub fn is_enabled(&mut self, element: &WebElement) -> BatchKey<bool> {
+        self.add(Command::IsElementEnabled(element.element_id.clone()))
+    }
+
+    /// Get whether the element is selected.
+    pub fn is_selected(&mut self, element: &WebElement) -> BatchKey<bool> {
+        self.add(Command::IsElementSelected(element.element_id.clone()))
+    }
+
+    pub(crate) fn run(self) -> BatchResults<'a> {
+        let results = self.session.execute_batch(self.commands);
+        BatchResults {
+            session: self.session,
+            results: results.into_iter().map(Some).collect(),
+        }
+    }
+}
+
+/// The results of the commands in a [Batch](struct.Batch.html). Each command
+/// has its own result, so one failing does not affect the others.
+#[derive(Debug)]
+pub struct BatchResults<'a> {
+    session: &'a WebDriverSession,
+    results: Vec<Option<WebDriverResult<Value>>>,
+}
+
+impl<'a> BatchResults<'a> {
+    /// Take the result of the command with the specified key. Each result
+    /// can only be taken once.
+    pub fn take<T: BatchOutput<'a>>(&mut self, key: BatchKey<T>) -> WebDriverResult<T> {
+        match self.results.get_mut(key.index).and_then(Option::take) {
+            Some(result) => T::from_batch(self.session, &result?["value"]),
+            None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "the result of batched command {} has already been taken",
+                key.index
+            )))),
+        }
+    }
+
+    /// The number of commands in the batch.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns true if the batch had no commands.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
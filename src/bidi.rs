@@ -0,0 +1,359 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::Capabilities;
+
+use crate::console::{ConsoleFilter, ConsoleStream};
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::websocket::WsClient;
+
+/// Events emitted by the `log` module.
+pub const LOG_EVENTS: &[&str] = &["log.entryAdded"];
+
+/// Events emitted by the `network` module.
+pub const NETWORK_EVENTS: &[&str] = &[
+    "network.beforeRequestSent",
+    "network.responseStarted",
+    "network.responseCompleted",
+    "network.fetchError",
+];
+
+/// Events emitted by the `browsingContext` module.
+pub const BROWSING_CONTEXT_EVENTS: &[&str] = &[
+    "browsingContext.contextCreated",
+    "browsingContext.contextDestroyed",
+    "browsingContext.navigationStarted",
+    "browsingContext.fragmentNavigated",
+    "browsingContext.domContentLoaded",
+    "browsingContext.load",
+];
+
+/// An event received from the browser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiDiEvent {
+    /// The event name, e.g. `log.entryAdded`.
+    pub method: String,
+    /// The raw event parameters.
+    pub params: Value,
+}
+
+impl BiDiEvent {
+    /// Parse the event as a log entry, if it is one.
+    pub fn log_entry(&self) -> Option<LogEntry> {
+        if self.method != "log.entryAdded" {
+            return None;
+        }
+        serde_json::from_value(self.params.clone()).ok()
+    }
+
+    /// Parse the event as a network event, if it is one.
+    pub fn network_event(&self) -> Option<NetworkEvent> {
+        if !self.method.starts_with("network.") {
+            return None;
+        }
+        let request = &self.params["request"];
+        Some(NetworkEvent {
+            method: self.method.clone(),
+            context: self.params["context"].as_str().map(String::from),
+            request_id: request["request"].as_str().unwrap_or_default().to_string(),
+            url: request["url"].as_str().unwrap_or_default().to_string(),
+            http_method: request["method"].as_str().unwrap_or_default().to_string(),
+            status: self.params["response"]["status"].as_u64().map(|s| s as u16),
+            error_text: self.params["errorText"].as_str().map(String::from),
+            timestamp: self.params["timestamp"].as_u64().unwrap_or_default(),
+        })
+    }
+
+    /// Parse the event as a browsing context event, if it is one.
+    pub fn context_event(&self) -> Option<ContextEvent> {
+        if !self.method.starts_with("browsingContext.") {
+            return None;
+        }
+        Some(ContextEvent {
+            method: self.method.clone(),
+            context: self.params["context"].as_str().unwrap_or_default().to_string(),
+            url: self.params["url"].as_str().unwrap_or_default().to_string(),
+            navigation: self.params["navigation"].as_str().map(String::from),
+        })
+    }
+}
+
+/// A `log.entryAdded` event, for console messages and JavaScript errors.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LogEntry {
+    /// The log level: `debug`, `info`, `warn` or `error`.
+    pub level: String,
+    /// The entry type, e.g. `console` or `javascript`.
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    /// The message text.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// The time the entry was added, in milliseconds since the epoch.
+    pub timestamp: u64,
+    /// The console method, e.g. `log` or `error`, for console entries.
+    #[serde(default)]
+    pub method: Option<String>,
+}
+
+/// A `network.*` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkEvent {
+    /// The event name, e.g. `network.responseCompleted`.
+    pub method: String,
+    /// The browsing context that made the request, if any.
+    pub context: Option<String>,
+    /// The request id, which is the same for all events for a request.
+    pub request_id: String,
+    /// The request URL.
+    pub url: String,
+    /// The HTTP method, e.g. `GET`.
+    pub http_method: String,
+    /// The response status, for response events.
+    pub status: Option<u16>,
+    /// The error, for `network.fetchError` events.
+    pub error_text: Option<String>,
+    /// The time of the event, in milliseconds since the epoch.
+    pub timestamp: u64,
+}
+
+/// A `browsingContext.*` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextEvent {
+    /// The event name, e.g. `browsingContext.load`.
+    pub method: String,
+    /// The browsing context id.
+    pub context: String,
+    /// The URL of the browsing context.
+    pub url: String,
+    /// The navigation id, for navigation events.
+    pub navigation: Option<String>,
+}
+
+/// A synchronous WebDriver BiDi client.
+///
+/// BiDi is the cross-browser successor to CDP, and provides event-driven
+/// access to log messages, network traffic and browsing contexts in Firefox
+/// as well as Chrome. The session must be started with the `webSocketUrl`
+/// capability, using [BiDiSession::enable()](struct.BiDiSession.html#method.enable).
+///
+/// Messages are read on a background thread, so events are collected even
+/// while your test is busy doing something else. Events are only delivered
+/// to streams that existed when they arrived.
+///
+/// Only available with the `bidi` feature.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::bidi::BiDiSession;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let mut caps = DesiredCapabilities::firefox();
+/// BiDiSession::enable(&mut caps)?;
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// let bidi = driver.bidi()?;
+/// let logs = bidi.subscribe_log()?;
+///
+/// driver.get("http://webappdemo")?;
+/// driver.execute_script(r#"console.error("something went wrong");"#)?;
+/// let event = logs.next_timeout(Duration::from_secs(5))?;
+/// let entry = event.log_entry().unwrap();
+/// assert_eq!(entry.level, "error");
+/// #     Ok(())
+/// # }
+/// ```
+pub struct BiDiSession {
+    client: WsClient,
+    command_timeout: Duration,
+}
+
+impl std::fmt::Debug for BiDiSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BiDiSession").field("command_timeout", &self.command_timeout).finish()
+    }
+}
+
+impl BiDiSession {
+    /// Request a BiDi WebSocket URL when the session is created. This must be
+    /// done before the session is started.
+    pub fn enable<C: Capabilities>(capabilities: &mut C) -> WebDriverResult<()> {
+        capabilities.add("webSocketUrl", true)
+    }
+
+    /// Connect to the BiDi WebSocket URL returned in the `webSocketUrl`
+    /// capability. Usually you would use
+    /// [WebDriver::bidi()](../struct.GenericWebDriver.html#method.bidi) instead.
+    pub fn connect(url: &str) -> WebDriverResult<Self> {
+        Ok(Self {
+            client: WsClient::connect(url)?,
+            command_timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Set how long to wait for the response to a command. The default is 30 seconds.
+    pub fn set_command_timeout(&mut self, timeout: Duration) {
+        self.command_timeout = timeout;
+    }
+
+    /// Send a BiDi command and wait for its result.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// # use thirtyfour_sync::bidi::BiDiSession;
+    /// use serde_json::json;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let mut caps = DesiredCapabilities::firefox();
+    /// #     BiDiSession::enable(&mut caps)?;
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let bidi = driver.bidi()?;
+    /// let tree = bidi.command("browsingContext.getTree", json!({}))?;
+    /// println!("{} top-level contexts", tree["contexts"].as_array().unwrap().len());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn command(&self, method: &str, params: Value) -> WebDriverResult<Value> {
+        let message = json!({"method": method, "params": params});
+        let response = self.client.request(message, self.command_timeout)?;
+        if response["type"] == "error" {
+            return Err(WebDriverError::UnknownResponse(format!(
+                "{} failed: {}: {}",
+                method,
+                response["error"].as_str().unwrap_or("unknown error"),
+                response["message"].as_str().unwrap_or_default()
+            )));
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Subscribe to the specified events, or whole modules such as `"log"`,
+    /// and return a stream that receives them.
+    pub fn subscribe(&self, events: &[&str]) -> WebDriverResult<EventStream> {
+        let names: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+        let receiver = self.client.listen(Box::new(move |message| {
+            let method = message["method"].as_str().unwrap_or_default();
+            // Subscribing to a module, e.g. `log`, receives all of its events.
+            message["type"] == "event"
+                && names.iter().any(|e| {
+                    e == method
+                        || matches!(method.strip_prefix(e.as_str()), Some(m) if m.starts_with('.'))
+                })
+        }));
+        self.command("session.subscribe", json!({ "events": events }))?;
+        Ok(EventStream {
+            receiver,
+        })
+    }
+
+    /// Subscribe to console messages and JavaScript errors.
+    pub fn subscribe_log(&self) -> WebDriverResult<EventStream> {
+        self.subscribe(LOG_EVENTS)
+    }
+
+    /// Subscribe to console messages and JavaScript errors, converted to
+    /// [ConsoleEntry](../console/struct.ConsoleEntry.html) and filtered with
+    /// the specified filter. The stream receives entries while this session
+    /// is open.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::bidi::BiDiSession;
+    /// use thirtyfour_sync::console::{ConsoleFilter, ConsoleLevel};
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// BiDiSession::enable(&mut caps)?;
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let bidi = driver.bidi()?;
+    /// let console = bidi.subscribe_console(ConsoleFilter::new().with_min_level(ConsoleLevel::Warning))?;
+    /// let tail = std::thread::spawn(move || {
+    ///     for entry in console.iter() {
+    ///         println!("{}", entry);
+    ///     }
+    /// });
+    ///
+    /// driver.get("http://webappdemo")?;
+    /// drop(bidi);
+    /// tail.join().unwrap();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_console(&self, filter: ConsoleFilter) -> WebDriverResult<ConsoleStream> {
+        Ok(ConsoleStream::from_bidi(self.subscribe_log()?, filter))
+    }
+
+    /// Subscribe to network requests and responses.
+    pub fn subscribe_network(&self) -> WebDriverResult<EventStream> {
+        self.subscribe(NETWORK_EVENTS)
+    }
+
+    /// Subscribe to the creation, destruction and navigation of browsing
+    /// contexts, i.e. tabs, windows and frames.
+    pub fn subscribe_browsing_context(&self) -> WebDriverResult<EventStream> {
+        self.subscribe(BROWSING_CONTEXT_EVENTS)
+    }
+}
+
+/// A stream of events from a [BiDiSession](struct.BiDiSession.html) subscription.
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: Receiver<Value>,
+}
+
+fn to_event(message: Value) -> BiDiEvent {
+    BiDiEvent {
+        method: message["method"].as_str().unwrap_or_default().to_string(),
+        params: message["params"].clone(),
+    }
+}
+
+impl EventStream {
+    /// Return the next event if one has already arrived.
+    pub fn try_next(&self) -> Option<BiDiEvent> {
+        self.receiver.try_recv().ok().map(to_event)
+    }
+
+    /// Wait for the next event, returning a Timeout error if none arrives
+    /// within the timeout.
+    pub fn next_timeout(&self, timeout: Duration) -> WebDriverResult<BiDiEvent> {
+        self.receiver.recv_timeout(timeout).map(to_event).map_err(|e| match e {
+            RecvTimeoutError::Timeout => {
+                WebDriverError::Timeout("no BiDi event received".to_string())
+            }
+            RecvTimeoutError::Disconnected => {
+                WebDriverError::FatalError("BiDi connection closed".to_string())
+            }
+        })
+    }
+
+    /// Wait for an event that matches the predicate, discarding any others.
+    pub fn wait_for<F>(&self, predicate: F, timeout: Duration) -> WebDriverResult<BiDiEvent>
+    where
+        F: Fn(&BiDiEvent) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let event = self.next_timeout(remaining).map_err(|e| match e {
+                WebDriverError::Timeout(_) => {
+                    WebDriverError::Timeout("no BiDi event matched the predicate".to_string())
+                }
+                e => e,
+            })?;
+            if predicate(&event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Return all events that have already arrived.
+    pub fn drain(&self) -> Vec<BiDiEvent> {
+        self.receiver.try_iter().map(to_event).collect()
+    }
+}
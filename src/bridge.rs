@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::http::reqwest_sync::ReqwestDriverSync;
+use crate::{SessionId, WebDriverSession, WebElement};
+
+fn check_same_session(sync_id: &SessionId, async_id: &SessionId) -> WebDriverResult<()> {
+    if sync_id == async_id {
+        Ok(())
+    } else {
+        Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "element belongs to session {} but the target session is {}",
+            async_id, sync_id
+        ))))
+    }
+}
+
+/// Create a sync session handle for the same session as the specified async
+/// session. The WebDriver server URL must be the one the async session was
+/// created with.
+///
+/// Both crates identify sessions and elements by the ids assigned by the
+/// WebDriver server, so sync and async handles with the same ids refer to
+/// the same browser session or element. The session must be created by the
+/// async crate, since it provides no way to attach to an existing session.
+///
+/// The returned handle does not own the session, so dropping it does not end
+/// the session. It makes blocking requests, so it must not be used directly
+/// inside an async task. Use something like `tokio::task::spawn_blocking()`
+/// instead.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::bridge;
+/// use thirtyfour_sync::prelude::*;
+///
+/// /// Read the title synchronously from a session started by async code.
+/// fn read_title(async_driver: &thirtyfour::WebDriver) -> WebDriverResult<String> {
+///     let session = bridge::sync_session(&async_driver.session, "http://localhost:4444/wd/hub")?;
+///     session.title()
+/// }
+/// ```
+pub fn sync_session(
+    session: &thirtyfour::WebDriverSession,
+    server_url: &str,
+) -> WebDriverResult<WebDriverSession> {
+    let conn = ReqwestDriverSync::create(server_url)?;
+    Ok(WebDriverSession::new(session.session_id().clone(), Arc::new(Mutex::new(conn))))
+}
+
+/// Create a sync element handle for the specified async element, in the
+/// sync session for the same browser session.
+pub fn sync_element<'s>(
+    element: &thirtyfour::WebElement,
+    session: &'s WebDriverSession,
+) -> WebDriverResult<WebElement<'s>> {
+    check_same_session(session.session_id(), element.session.session_id())?;
+    Ok(WebElement::new(session, element.element_id.clone()))
+}
+
+/// Create an async element handle for the specified sync element, in the
+/// async session for the same browser session.
+pub fn async_element<'s>(
+    element: &WebElement,
+    session: &'s thirtyfour::WebDriverSession,
+) -> WebDriverResult<thirtyfour::WebElement<'s>> {
+    check_same_session(session.session_id(), element.session().session_id())?;
+    Ok(thirtyfour::WebElement::new(session, element.element_id.clone()))
+}
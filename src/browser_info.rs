@@ -0,0 +1,57 @@
+use serde_json::Value;
+
+/// Information about the browser, taken from the session capabilities.
+/// See [WebDriver::browser_info()](trait.WebDriverCommands.html#method.browser_info).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserInfo {
+    /// The browser name, e.g. `chrome`, `firefox`, `MicrosoftEdge` or `safari`.
+    pub name: String,
+    /// The browser version, e.g. `120.0.6099.109`.
+    pub version: String,
+    /// The platform, e.g. `linux`, `mac` or `windows`.
+    pub platform: String,
+    /// The driver version, e.g. the chromedriver or geckodriver version, where
+    /// the driver reports it.
+    pub driver_version: Option<String>,
+    /// Whether the browser is running headless, where the driver reports it.
+    pub headless: Option<bool>,
+}
+
+impl BrowserInfo {
+    pub(crate) fn from_capabilities(caps: &Value) -> Self {
+        let str_of = |v: &Value| v.as_str().map(String::from);
+        let name = str_of(&caps["browserName"]).unwrap_or_default();
+        let driver_version = str_of(&caps["chrome"]["chromedriverVersion"])
+            .or_else(|| str_of(&caps["msedge"]["msedgedriverVersion"]))
+            .or_else(|| str_of(&caps["moz:geckodriverVersion"]))
+            // chromedriver reports e.g. "120.0.6099.109 (3419140ab665...)".
+            .map(|v| v.split_whitespace().next().unwrap_or_default().to_string());
+        Self {
+            name,
+            version: str_of(&caps["browserVersion"])
+                .or_else(|| str_of(&caps["version"]))
+                .unwrap_or_default(),
+            platform: str_of(&caps["platformName"])
+                .or_else(|| str_of(&caps["platform"]))
+                .unwrap_or_default(),
+            driver_version,
+            headless: caps["moz:headless"].as_bool(),
+        }
+    }
+
+    /// The major browser version, e.g. `120`.
+    pub fn major_version(&self) -> Option<u32> {
+        self.version.split('.').next()?.parse().ok()
+    }
+
+    /// Returns true if the browser is Chromium-based, i.e. Chrome or Edge.
+    pub fn is_chromium(&self) -> bool {
+        let name = self.name.to_lowercase();
+        name == "chrome" || name == "chromium" || name.contains("edge")
+    }
+
+    /// Returns true if the browser is Firefox.
+    pub fn is_firefox(&self) -> bool {
+        self.name.eq_ignore_ascii_case("firefox")
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebElement};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CanvasReadback {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    not_canvas: bool,
+    #[serde(default)]
+    data_url: String,
+    #[serde(default)]
+    blank: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Reads the canvas back as a PNG data URL from inside an animation frame
+/// callback. A WebGL canvas without `preserveDrawingBuffer` is cleared once
+/// each frame is presented, but its drawing buffer is still intact during
+/// the callbacks of the following frame, before the page draws again.
+/// Reports whether the frame is fully transparent, which is what a cleared
+/// buffer reads back as.
+const READBACK_SCRIPT: &str = r#"
+    const canvas = arguments[0];
+    const done = arguments[arguments.length - 1];
+    if (!(canvas instanceof HTMLCanvasElement)) {
+        done({ notCanvas: true });
+        return;
+    }
+    requestAnimationFrame(() => {
+        try {
+            const dataUrl = canvas.toDataURL("image/png");
+            const probe = document.createElement("canvas");
+            probe.width = Math.max(1, Math.min(canvas.width, 256));
+            probe.height = Math.max(1, Math.min(canvas.height, 256));
+            const ctx = probe.getContext("2d");
+            ctx.drawImage(canvas, 0, 0, probe.width, probe.height);
+            const data = ctx.getImageData(0, 0, probe.width, probe.height).data;
+            let blank = true;
+            for (let i = 3; i < data.length; i += 4) {
+                if (data[i] !== 0) {
+                    blank = false;
+                    break;
+                }
+            }
+            done({ dataUrl, blank });
+        } catch (e) {
+            // A canvas tainted by cross-origin images cannot be read back.
+            done({ error: String(e) });
+        }
+    });
+"#;
+
+/// The element's rectangle in page coordinates, for clipping a screenshot.
+const PAGE_RECT_SCRIPT: &str = r#"
+    const r = arguments[0].getBoundingClientRect();
+    return {
+        x: r.left + window.scrollX,
+        y: r.top + window.scrollY,
+        width: r.width,
+        height: r.height,
+    };
+"#;
+
+/// Makes every WebGL context created from now on keep its drawing buffer
+/// between frames, so that the canvas can be read back at any time.
+pub(crate) const PRESERVE_DRAWING_BUFFER_SCRIPT: &str = r#"
+(() => {
+    const getContext = HTMLCanvasElement.prototype.getContext;
+    HTMLCanvasElement.prototype.getContext = function (type, attributes) {
+        if (/^(experimental-)?webgl2?$/.test(type)) {
+            attributes = Object.assign({}, attributes, { preserveDrawingBuffer: true });
+        }
+        return getContext.call(this, type, attributes);
+    };
+})();
+"#;
+
+pub(crate) fn capture_canvas_frame(elem: &WebElement) -> WebDriverResult<Vec<u8>> {
+    let session = elem.session();
+    let mut args = ScriptArgs::new();
+    args.push(elem.clone())?;
+    let readback: CanvasReadback =
+        session.execute_async_script_with_args(READBACK_SCRIPT, &args)?.convert()?;
+    if readback.not_canvas {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+            "only <canvas> elements can be captured",
+        )));
+    }
+    if readback.error.is_none() && !readback.blank {
+        if let Some(data) = readback.data_url.strip_prefix("data:image/png;base64,") {
+            return Ok(base64::decode(data)?);
+        }
+    }
+    if let Some(e) = &readback.error {
+        log::debug!("canvas readback failed, taking a screenshot instead: {}", e);
+    }
+
+    // Fall back to a screenshot of the canvas as composited on screen.
+    if session.browser_info().is_chromium() {
+        let rect: PageRect =
+            session.execute_script_with_args(PAGE_RECT_SCRIPT, &args)?.convert()?;
+        let v = ChromeDevTools::new(session).execute_cdp_with_params(
+            "Page.captureScreenshot",
+            json!({
+                "format": "png",
+                "captureBeyondViewport": true,
+                "clip": {
+                    "x": rect.x,
+                    "y": rect.y,
+                    "width": rect.width,
+                    "height": rect.height,
+                    "scale": 1,
+                },
+            }),
+        )?;
+        let data = v["data"].as_str().ok_or_else(|| {
+            WebDriverError::UnknownResponse(format!("no screenshot data in {}", v))
+        })?;
+        return Ok(base64::decode(data)?);
+    }
+    elem.screenshot_as_png()
+}
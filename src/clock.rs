@@ -0,0 +1,85 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// The clocks of the test host, the WebDriver server and the browser,
+/// compared using a single script command. See
+/// [WebDriver::clock_report()](trait.WebDriverCommands.html#method.clock_report).
+///
+/// Skews are in milliseconds, positive when the other clock is ahead of the
+/// test host. They are measured against the midpoint of the command, so
+/// they are only accurate to within half of the round trip. The server time
+/// comes from the HTTP `Date` header, which has a resolution of one second,
+/// so a server skew below a second is not significant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockReport {
+    /// The time on the test host, at the midpoint of the command.
+    pub host_time: SystemTime,
+    /// The time on the WebDriver server, from the `Date` header of its
+    /// response. This is None if the server did not send one, or the HTTP
+    /// client does not record it. Behind Selenium Grid, this is the time on
+    /// the hub.
+    pub server_time: Option<SystemTime>,
+    /// The time in the browser, from `Date.now()`.
+    pub browser_time: SystemTime,
+    /// The time taken by the command.
+    pub round_trip: Duration,
+}
+
+/// The difference between two times in milliseconds, positive when `time`
+/// is after `reference`.
+fn skew_millis(time: SystemTime, reference: SystemTime) -> i64 {
+    match time.duration_since(reference) {
+        Ok(ahead) => ahead.as_millis() as i64,
+        Err(e) => -(e.duration().as_millis() as i64),
+    }
+}
+
+impl ClockReport {
+    /// The skew of the server clock relative to the test host.
+    pub fn server_skew_ms(&self) -> Option<i64> {
+        self.server_time.map(|t| skew_millis(t, self.host_time))
+    }
+
+    /// The skew of the browser clock relative to the test host.
+    pub fn browser_skew_ms(&self) -> i64 {
+        skew_millis(self.browser_time, self.host_time)
+    }
+
+    /// The largest skew between any two of the clocks.
+    pub fn max_skew(&self) -> Duration {
+        let mut skews = vec![0, self.browser_skew_ms()];
+        skews.extend(self.server_skew_ms());
+        let spread = skews.iter().max().unwrap_or(&0) - skews.iter().min().unwrap_or(&0);
+        Duration::from_millis(spread as u64)
+    }
+}
+
+impl fmt::Display for ClockReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "browser {:+}ms", self.browser_skew_ms())?;
+        match self.server_skew_ms() {
+            Some(skew) => write!(f, ", server {:+}ms", skew)?,
+            None => write!(f, ", server unknown")?,
+        }
+        write!(f, " relative to the test host (round trip {:?})", self.round_trip)
+    }
+}
+
+pub(crate) fn clock_report(session: &WebDriverSession) -> WebDriverResult<ClockReport> {
+    let start = SystemTime::now();
+    let browser_ms: f64 = session.execute_script("return Date.now();")?.convert()?;
+    let end = SystemTime::now();
+    let server_time = session.last_response_date();
+
+    let round_trip = end.duration_since(start).unwrap_or_default();
+    Ok(ClockReport {
+        host_time: start + round_trip / 2,
+        server_time,
+        browser_time: UNIX_EPOCH + Duration::from_millis(browser_ms as u64),
+        round_trip,
+    })
+}
@@ -0,0 +1,441 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+
+/// The CSS named colors, as RGB.
+const NAMED_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("black", (0, 0, 0)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blue", (0, 0, 255)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("cyan", (0, 255, 255)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("green", (0, 128, 0)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("magenta", (255, 0, 255)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("purple", (128, 0, 128)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("red", (255, 0, 0)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("wheat", (245, 222, 179)),
+    ("white", (255, 255, 255)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellow", (255, 255, 0)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+/// A color parsed from a CSS value, such as one returned by
+/// [WebElement::get_css_property_as()](struct.WebElement.html#method.get_css_property_as).
+///
+/// Colors can be parsed from hex (`#f00`, `#ff000080`), `rgb()`, `rgba()`,
+/// `hsl()` and `hsla()` in either the comma or space separated syntax, named
+/// colors and `transparent`. Colors compare equal if they have the same RGB
+/// channels and their alpha differs by less than one step of 1/255, so
+/// different representations of the same color are equal.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::Color;
+///
+/// # fn main() -> thirtyfour_sync::error::WebDriverResult<()> {
+/// let red: Color = "rgba(255, 0, 0, 1)".parse()?;
+/// assert_eq!(red, "#ff0000".parse()?);
+/// assert_eq!(red, "hsl(0 100% 50%)".parse()?);
+/// assert_eq!(red, Color::rgb(255, 0, 0));
+/// assert_ne!(red, "rgba(255, 0, 0, 0.5)".parse()?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Color {
+    /// The red channel, from 0 to 255.
+    pub red: u8,
+    /// The green channel, from 0 to 255.
+    pub green: u8,
+    /// The blue channel, from 0 to 255.
+    pub blue: u8,
+    /// The opacity, from 0.0 (transparent) to 1.0 (opaque).
+    pub alpha: f64,
+}
+
+impl Color {
+    /// Create an opaque color from its RGB channels.
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self::rgba(red, green, blue, 1.0)
+    }
+
+    /// Create a color from its RGB channels and opacity. The opacity is
+    /// clamped to between 0.0 and 1.0.
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: f64) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Returns true if the color is fully transparent.
+    pub fn is_transparent(&self) -> bool {
+        self.alpha < 0.5 / 255.0
+    }
+
+    /// The color as a hex string, e.g. `#ff0000`, including the alpha
+    /// channel only if the color is not opaque.
+    pub fn to_hex(&self) -> String {
+        let alpha = (self.alpha * 255.0).round() as u8;
+        if alpha == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.red, self.green, self.blue, alpha)
+        }
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        self.red == other.red
+            && self.green == other.green
+            && self.blue == other.blue
+            && (self.alpha - other.alpha).abs() < 1.0 / 255.0
+    }
+}
+
+/// Formats the color the way browsers return computed colors, e.g.
+/// `rgb(255, 0, 0)` or `rgba(255, 0, 0, 0.5)`.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.alpha >= 1.0 {
+            write!(f, "rgb({}, {}, {})", self.red, self.green, self.blue)
+        } else {
+            write!(f, "rgba({}, {}, {}, {})", self.red, self.green, self.blue, self.alpha)
+        }
+    }
+}
+
+fn invalid_color(value: &str) -> WebDriverError {
+    WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+        "invalid CSS color: {:?}",
+        value
+    )))
+}
+
+/// Round a channel value from 0.0 to 255.0.
+fn channel(value: f64) -> u8 {
+    value.clamp(0.0, 255.0).round() as u8
+}
+
+/// Parse a number, or a percentage of the specified maximum.
+fn number_or_percentage(value: &str, max: f64) -> Option<f64> {
+    match value.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f64>().ok().map(|x| x / 100.0 * max),
+        None => value.parse().ok(),
+    }
+}
+
+/// Parse a hue, in degrees unless another unit is specified.
+fn hue(value: &str) -> Option<f64> {
+    let degrees = if let Some(x) = value.strip_suffix("deg") {
+        x.parse().ok()?
+    } else if let Some(x) = value.strip_suffix("grad") {
+        x.parse::<f64>().ok()? * 0.9
+    } else if let Some(x) = value.strip_suffix("rad") {
+        x.parse::<f64>().ok()?.to_degrees()
+    } else if let Some(x) = value.strip_suffix("turn") {
+        x.parse::<f64>().ok()? * 360.0
+    } else {
+        value.parse().ok()?
+    };
+    Some(degrees.rem_euclid(360.0))
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let saturation = saturation.clamp(0.0, 1.0);
+    let lightness = lightness.clamp(0.0, 1.0);
+    let f = |n: f64| {
+        let k = (n + hue / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        let x = lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0);
+        channel(x * 255.0)
+    };
+    (f(0.0), f(8.0), f(4.0))
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = match hex.len() {
+        3 | 4 => hex
+            .chars()
+            .map(|c| u8::from_str_radix(&c.to_string().repeat(2), 16).ok())
+            .collect::<Option<_>>()?,
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let alpha = digits.get(3).map(|a| *a as f64 / 255.0).unwrap_or(1.0);
+    Some(Color::rgba(digits[0], digits[1], digits[2], alpha))
+}
+
+/// Parse the arguments of a color function, either `a, b, c[, alpha]` or
+/// `a b c[ / alpha]`.
+fn parse_function(name: &str, args: &str) -> Option<Color> {
+    let (args, alpha) = match args.split_once('/') {
+        Some((args, alpha)) => (args, Some(alpha.trim())),
+        None => (args, None),
+    };
+    let mut parts: Vec<&str> = if args.contains(',') {
+        args.split(',').map(|x| x.trim()).collect()
+    } else {
+        args.split_whitespace().collect()
+    };
+    let alpha = match (alpha, parts.len()) {
+        (Some(alpha), 3) => alpha,
+        (None, 4) => parts.pop()?,
+        (None, 3) => "1",
+        _ => return None,
+    };
+    let alpha = number_or_percentage(alpha, 1.0)?;
+    match name {
+        "rgb" | "rgba" => {
+            let mut rgb = parts.iter().map(|x| number_or_percentage(x, 255.0).map(channel));
+            Some(Color::rgba(rgb.next()??, rgb.next()??, rgb.next()??, alpha))
+        }
+        "hsl" | "hsla" => {
+            let saturation = parts[1].strip_suffix('%')?.parse::<f64>().ok()? / 100.0;
+            let lightness = parts[2].strip_suffix('%')?.parse::<f64>().ok()? / 100.0;
+            let (red, green, blue) = hsl_to_rgb(hue(parts[0])?, saturation, lightness);
+            Some(Color::rgba(red, green, blue, alpha))
+        }
+        _ => None,
+    }
+}
+
+impl FromStr for Color {
+    type Err = WebDriverError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_ascii_lowercase();
+        let color = if let Some(hex) = value.strip_prefix('#') {
+            parse_hex(hex)
+        } else if value == "transparent" {
+            Some(Color::rgba(0, 0, 0, 0.0))
+        } else if let Some((name, args)) = value.split_once('(') {
+            args.strip_suffix(')').and_then(|args| parse_function(name.trim(), args))
+        } else {
+            NAMED_COLORS
+                .iter()
+                .find(|(name, _)| *name == value)
+                .map(|(_, (red, green, blue))| Color::rgb(*red, *green, *blue))
+        };
+        color.ok_or_else(|| invalid_color(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Color {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn equivalent_representations() {
+        let red = Color::rgb(255, 0, 0);
+        for value in &[
+            "#f00",
+            "#F00F",
+            "#ff0000",
+            "#ff0000ff",
+            "rgb(255, 0, 0)",
+            "rgb(255 0 0)",
+            "rgb(100%, 0%, 0%)",
+            "rgba(255, 0, 0, 1)",
+            "rgba(255 0 0 / 100%)",
+            "hsl(0, 100%, 50%)",
+            "hsl(360deg 100% 50%)",
+            "hsla(0turn, 100%, 50%, 1)",
+            "red",
+            "  RED ",
+        ] {
+            assert_eq!(parse(value), red, "{}", value);
+        }
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(parse("rebeccapurple"), parse("#663399"));
+        assert_eq!(parse("grey"), parse("gray"));
+        assert_eq!(parse("white"), Color::rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn alpha() {
+        assert_eq!(parse("rgba(255, 0, 0, 0.5)"), parse("#ff000080"));
+        assert_eq!(parse("rgb(255 0 0 / 50%)"), parse("rgba(255, 0, 0, 0.5)"));
+        assert_ne!(parse("rgba(255, 0, 0, 0.5)"), parse("red"));
+        assert!(parse("transparent").is_transparent());
+        assert!(parse("rgba(0, 0, 0, 0)").is_transparent());
+        assert!(!parse("rgba(0, 0, 0, 0.01)").is_transparent());
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(parse("#0a0b0c").to_hex(), "#0a0b0c");
+        assert_eq!(parse("rgba(10, 11, 12, 0.5)").to_hex(), "#0a0b0c80");
+        assert_eq!(parse("#0a0b0c").to_string(), "rgb(10, 11, 12)");
+        assert_eq!(parse("rgba(10, 11, 12, 0.5)").to_string(), "rgba(10, 11, 12, 0.5)");
+    }
+
+    #[test]
+    fn invalid_colors() {
+        for value in &[
+            "",
+            "#ff000",
+            "#gg0000",
+            "rgb(255, 0)",
+            "rgb(255, 0, 0",
+            "rgb(a, b, c)",
+            "hsl(0, 100, 50)",
+            "cmyk(0, 0, 0, 0)",
+            "reddish",
+        ] {
+            assert!(value.parse::<Color>().is_err(), "{}", value);
+        }
+    }
+}
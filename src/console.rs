@@ -0,0 +1,314 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "cdp-websocket")]
+use serde_json::Value;
+
+#[cfg(feature = "bidi")]
+use crate::bidi::{BiDiEvent, EventStream};
+use crate::error::{WebDriverError, WebDriverResult};
+#[cfg(feature = "cdp-websocket")]
+use crate::extensions::chrome::{CdpEvent, CdpEvents};
+
+/// How long `next()` waits for an event before checking again.
+const NEXT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The severity of a console entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConsoleLevel {
+    /// `console.debug()` and verbose browser messages.
+    Debug,
+    /// `console.log()`, `console.info()` and similar.
+    Info,
+    /// `console.warn()` and browser warnings.
+    Warning,
+    /// `console.error()`, failed assertions and uncaught exceptions.
+    Error,
+}
+
+impl ConsoleLevel {
+    fn parse(level: &str) -> Self {
+        match level {
+            "debug" | "verbose" | "trace" => ConsoleLevel::Debug,
+            "warn" | "warning" => ConsoleLevel::Warning,
+            "error" | "assert" => ConsoleLevel::Error,
+            _ => ConsoleLevel::Info,
+        }
+    }
+}
+
+impl fmt::Display for ConsoleLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warning => "warning",
+            ConsoleLevel::Error => "error",
+        };
+        write!(f, "{}", level)
+    }
+}
+
+/// A console message, uncaught exception or browser log message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsoleEntry {
+    /// The severity.
+    pub level: ConsoleLevel,
+    /// Where the entry came from: `console` for console messages,
+    /// `javascript` for uncaught exceptions, or a browser log source such as
+    /// `network` or `security`.
+    pub source: String,
+    /// The message text.
+    pub text: String,
+    /// The time the entry was added, in milliseconds since the epoch.
+    pub timestamp: u64,
+}
+
+impl fmt::Display for ConsoleEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.level, self.source, self.text)
+    }
+}
+
+/// The text of a DevTools `RemoteObject` passed to a console method.
+#[cfg(feature = "cdp-websocket")]
+fn remote_object_text(arg: &Value) -> String {
+    match &arg["value"] {
+        Value::String(s) => s.clone(),
+        Value::Null => arg["description"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| arg["type"].as_str().unwrap_or_default().to_string()),
+        v => v.to_string(),
+    }
+}
+
+impl ConsoleEntry {
+    /// Convert a BiDi `log.entryAdded` event.
+    #[cfg(feature = "bidi")]
+    fn from_bidi(event: &BiDiEvent) -> Option<Self> {
+        let entry = event.log_entry()?;
+        Some(Self {
+            level: ConsoleLevel::parse(&entry.level),
+            source: entry.entry_type,
+            text: entry.text.unwrap_or_default(),
+            timestamp: entry.timestamp,
+        })
+    }
+
+    /// Convert a DevTools `Runtime.consoleAPICalled`,
+    /// `Runtime.exceptionThrown` or `Log.entryAdded` event.
+    #[cfg(feature = "cdp-websocket")]
+    fn from_cdp(event: &CdpEvent) -> Option<Self> {
+        let params = &event.params;
+        let timestamp = |v: &Value| v.as_f64().unwrap_or_default() as u64;
+        match event.method.as_str() {
+            "Runtime.consoleAPICalled" => {
+                let args = params["args"].as_array()?;
+                let text: Vec<String> = args.iter().map(remote_object_text).collect();
+                Some(Self {
+                    level: ConsoleLevel::parse(params["type"].as_str().unwrap_or_default()),
+                    source: "console".to_string(),
+                    text: text.join(" "),
+                    timestamp: timestamp(&params["timestamp"]),
+                })
+            }
+            "Runtime.exceptionThrown" => {
+                let details = &params["exceptionDetails"];
+                let text = details["exception"]["description"]
+                    .as_str()
+                    .or_else(|| details["text"].as_str())
+                    .unwrap_or_default();
+                Some(Self {
+                    level: ConsoleLevel::Error,
+                    source: "javascript".to_string(),
+                    text: text.to_string(),
+                    timestamp: timestamp(&params["timestamp"]),
+                })
+            }
+            "Log.entryAdded" => {
+                let entry = &params["entry"];
+                Some(Self {
+                    level: ConsoleLevel::parse(entry["level"].as_str().unwrap_or_default()),
+                    source: entry["source"].as_str().unwrap_or_default().to_string(),
+                    text: entry["text"].as_str().unwrap_or_default().to_string(),
+                    timestamp: timestamp(&entry["timestamp"]),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Chooses which entries a [ConsoleStream](struct.ConsoleStream.html)
+/// receives. By default it receives every entry.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::console::{ConsoleFilter, ConsoleLevel};
+///
+/// let filter = ConsoleFilter::new()
+///     .with_min_level(ConsoleLevel::Warning)
+///     .with_source("console")
+///     .with_source("javascript");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleFilter {
+    min_level: ConsoleLevel,
+    sources: Vec<String>,
+}
+
+impl Default for ConsoleFilter {
+    fn default() -> Self {
+        Self {
+            min_level: ConsoleLevel::Debug,
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl ConsoleFilter {
+    /// Create a filter that accepts every entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only accept entries at the specified level or above.
+    pub fn with_min_level(mut self, level: ConsoleLevel) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Only accept entries from the specified source, and any others added
+    /// with this method, e.g. `console` or `javascript`.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.sources.push(source.to_string());
+        self
+    }
+
+    /// Returns true if the entry is accepted by this filter.
+    pub fn matches(&self, entry: &ConsoleEntry) -> bool {
+        entry.level >= self.min_level
+            && (self.sources.is_empty() || self.sources.contains(&entry.source))
+    }
+}
+
+#[derive(Debug)]
+enum Events {
+    #[cfg(feature = "bidi")]
+    BiDi(EventStream),
+    #[cfg(feature = "cdp-websocket")]
+    Cdp(CdpEvents),
+}
+
+/// A stream of browser console entries, delivered as they arrive. Only
+/// available with the `bidi` or `cdp-websocket` feature.
+///
+/// Create one with
+/// [BiDiSession::subscribe_console()](../bidi/struct.BiDiSession.html#method.subscribe_console)
+/// or
+/// [ChromeDevTools::connect_console()](../extensions/chrome/struct.ChromeDevTools.html#method.connect_console).
+/// Entries are collected on a background thread from the moment the stream
+/// is created, and only those accepted by its
+/// [ConsoleFilter](struct.ConsoleFilter.html) are returned.
+///
+/// The stream can be moved to another thread, e.g. to follow the log while
+/// the test drives the browser.
+#[derive(Debug)]
+pub struct ConsoleStream {
+    events: Events,
+    filter: ConsoleFilter,
+}
+
+impl ConsoleStream {
+    #[cfg(feature = "bidi")]
+    pub(crate) fn from_bidi(events: EventStream, filter: ConsoleFilter) -> Self {
+        Self {
+            events: Events::BiDi(events),
+            filter,
+        }
+    }
+
+    #[cfg(feature = "cdp-websocket")]
+    pub(crate) fn from_cdp(events: CdpEvents, filter: ConsoleFilter) -> Self {
+        Self {
+            events: Events::Cdp(events),
+            filter,
+        }
+    }
+
+    /// The filter for this stream.
+    pub fn filter(&self) -> &ConsoleFilter {
+        &self.filter
+    }
+
+    /// Convert an event, returning None if it is not a console entry or is
+    /// not accepted by the filter.
+    fn accept(&self, entry: Option<ConsoleEntry>) -> Option<ConsoleEntry> {
+        entry.filter(|e| self.filter.matches(e))
+    }
+
+    /// Return the next entry if one has already arrived.
+    pub fn try_next(&self) -> Option<ConsoleEntry> {
+        loop {
+            let entry = match &self.events {
+                #[cfg(feature = "bidi")]
+                Events::BiDi(events) => ConsoleEntry::from_bidi(&events.try_next()?),
+                #[cfg(feature = "cdp-websocket")]
+                Events::Cdp(events) => ConsoleEntry::from_cdp(&events.try_next_event()?),
+            };
+            if let Some(entry) = self.accept(entry) {
+                return Some(entry);
+            }
+        }
+    }
+
+    /// Wait for the next entry, returning a Timeout error if none arrives
+    /// within the timeout, or a FatalError if the connection is closed.
+    pub fn next_timeout(&self, timeout: Duration) -> WebDriverResult<ConsoleEntry> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let entry = match &self.events {
+                #[cfg(feature = "bidi")]
+                Events::BiDi(events) => {
+                    events.next_timeout(remaining).map(|e| ConsoleEntry::from_bidi(&e))
+                }
+                #[cfg(feature = "cdp-websocket")]
+                Events::Cdp(events) => {
+                    events.next_event_timeout(remaining).map(|e| ConsoleEntry::from_cdp(&e))
+                }
+            };
+            let entry = entry.map_err(|e| match e {
+                WebDriverError::Timeout(_) => {
+                    WebDriverError::Timeout("no console entry received".to_string())
+                }
+                e => e,
+            })?;
+            if let Some(entry) = self.accept(entry) {
+                return Ok(entry);
+            }
+        }
+    }
+
+    /// Wait for the next entry, for as long as it takes. Returns a
+    /// FatalError if the connection is closed.
+    pub fn next(&self) -> WebDriverResult<ConsoleEntry> {
+        loop {
+            match self.next_timeout(NEXT_POLL_INTERVAL) {
+                Err(WebDriverError::Timeout(_)) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Return every entry as it arrives, until the connection is closed.
+    pub fn iter(&self) -> impl Iterator<Item = ConsoleEntry> + '_ {
+        std::iter::from_fn(move || self.next().ok())
+    }
+
+    /// Return all entries that have already arrived.
+    pub fn drain(&self) -> Vec<ConsoleEntry> {
+        std::iter::from_fn(|| self.try_next()).collect()
+    }
+}
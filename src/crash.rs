@@ -0,0 +1,161 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thirtyfour::common::command::FormatRequestData;
+use thirtyfour::extensions::chrome::ChromeCommand;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// The error state of the UnknownError returned when the page crashed or ran
+/// out of memory. See `is_page_crashed()`.
+pub const PAGE_CRASHED: &str = "page crashed";
+
+/// Returns true if the error was returned because the page crashed, e.g.
+/// because the renderer process was killed or ran out of memory, rather than
+/// because a command failed. Use
+/// [PageCrash::from_error()](struct.PageCrash.html#method.from_error) for the
+/// details.
+pub fn is_page_crashed(error: &WebDriverError) -> bool {
+    matches!(error, WebDriverError::UnknownError(info) if info.error == PAGE_CRASHED)
+}
+
+/// The memory used by the page's JavaScript heap, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryMetrics {
+    /// The heap size in use.
+    pub used_size: u64,
+    /// The total heap size.
+    pub total_size: u64,
+}
+
+/// The details of a page crash, carried by the error that
+/// `is_page_crashed()` recognises.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::crash::PageCrash;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// if let Err(e) = driver.get("http://webappdemo") {
+///     match PageCrash::from_error(&e) {
+///         Some(crash) => println!("crashed on {:?}: {}", crash.url, crash.reason),
+///         None => return Err(e),
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageCrash {
+    /// The message from the WebDriver server, e.g. `tab crashed`.
+    pub reason: String,
+    /// Whether the server reported that the page ran out of memory.
+    pub out_of_memory: bool,
+    /// The URL most recently navigated to or fetched in this session, if any.
+    pub url: Option<String>,
+    /// The memory used by the page, where it could still be fetched using the
+    /// Chrome DevTools Protocol.
+    pub memory: Option<MemoryMetrics>,
+}
+
+impl PageCrash {
+    /// Get the details of the crash, if the error was returned because the
+    /// page crashed.
+    pub fn from_error(error: &WebDriverError) -> Option<Self> {
+        match error {
+            WebDriverError::UnknownError(info) if info.error == PAGE_CRASHED => {
+                serde_json::from_value(info.value.data.clone()?).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PageCrash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = if self.out_of_memory {
+            "page ran out of memory"
+        } else {
+            "page crashed"
+        };
+        write!(f, "{}", what)?;
+        if let Some(url) = &self.url {
+            write!(f, " on {}", url)?;
+        }
+        if let Some(memory) = &self.memory {
+            write!(f, " (JS heap {} of {} bytes)", memory.used_size, memory.total_size)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// The error info of the errors a WebDriver server reports a crash with.
+fn crash_info(error: &WebDriverError) -> Option<&WebDriverErrorInfo> {
+    match error {
+        WebDriverError::UnknownError(info)
+        | WebDriverError::InvalidSessionId(info)
+        | WebDriverError::JavascriptError(info)
+        | WebDriverError::WebDriverTimeout(info) => Some(info),
+        _ => None,
+    }
+}
+
+/// Returns true if the message means the page ran out of memory.
+fn is_out_of_memory(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("out of memory")
+        || message.split(|c: char| !c.is_ascii_alphanumeric()).any(|word| word == "oom")
+}
+
+/// Returns true if the message means the page crashed, e.g. chromedriver's
+/// `tab crashed`, `session deleted because of page crash` or `Aborted`.
+fn is_crash(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    let first_line = lower.lines().next().unwrap_or_default();
+    let first_line = first_line.trim_start_matches("unknown error:").trim();
+    lower.contains("tab crashed")
+        || lower.contains("page crash")
+        || first_line == "aborted"
+        || is_out_of_memory(message)
+}
+
+/// Fetch the memory used by the page, if the browser supports the Chrome
+/// DevTools Protocol and the page is still able to answer.
+fn memory_metrics(session: &WebDriverSession) -> Option<MemoryMetrics> {
+    if !session.browser_info().is_chromium() {
+        return None;
+    }
+    let command = ChromeCommand::ExecuteCdpCommand("Runtime.getHeapUsage".to_string(), json!({}));
+    let v = session.execute_quietly(command.format_request(session.session_id())).ok()?;
+    serde_json::from_value(v["value"].clone()).ok()
+}
+
+/// Convert the error to one that `is_page_crashed()` recognises, if it was
+/// returned because the page crashed.
+pub(crate) fn detect_crash(session: &WebDriverSession, error: WebDriverError) -> WebDriverError {
+    let info = match crash_info(&error) {
+        Some(info) if info.error != PAGE_CRASHED && is_crash(&info.value.message) => info,
+        _ => return error,
+    };
+    let crash = PageCrash {
+        reason: info.value.message.clone(),
+        out_of_memory: is_out_of_memory(&info.value.message),
+        url: session.last_url().map(|url| session.redact(&url)),
+        memory: memory_metrics(session),
+    };
+    let mut crashed = WebDriverErrorInfo::new(&crash.to_string());
+    crashed.status = info.status;
+    crashed.error = PAGE_CRASHED.to_string();
+    crashed.value.error = info.value.error.clone();
+    crashed.value.stacktrace = info.value.stacktrace.clone();
+    crashed.value.data = serde_json::to_value(&crash).ok();
+    WebDriverError::UnknownError(crashed)
+}
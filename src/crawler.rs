@@ -0,0 +1,522 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::extensions::chrome::NetworkMonitor;
+use crate::politeness::{is_disallowed, Politeness};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{BrowserInfo, WebDriver, WebDriverSession};
+
+type UrlFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A page visited during a crawl.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawledPage {
+    /// The URL that was requested.
+    pub url: String,
+    /// The URL of the document after any redirects.
+    pub final_url: String,
+    /// The number of links followed from the start URL to reach this page.
+    pub depth: usize,
+    /// The page that linked to this page, if any.
+    pub referrer: Option<String>,
+    /// The HTTP status of the document, where the browser reports it.
+    pub status: Option<u16>,
+    /// The page title.
+    pub title: String,
+    /// All http(s) links found on the page, with fragments removed.
+    pub links: Vec<String>,
+}
+
+/// A link that could not be loaded successfully.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    /// The URL of the link.
+    pub url: String,
+    /// The page containing the link, if any.
+    pub referrer: Option<String>,
+    /// The HTTP status returned for the link, if any.
+    pub status: Option<u16>,
+    /// The error, if the page could not be loaded or read at all.
+    pub error: Option<String>,
+}
+
+/// The results of a crawl.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlReport {
+    /// All pages that were visited, in the order they completed.
+    pub pages: Vec<CrawledPage>,
+    /// All links that returned an HTTP error status or failed to load.
+    pub broken_links: Vec<BrokenLink>,
+    /// Links that were skipped because `robots.txt` disallows them.
+    pub disallowed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageData {
+    url: String,
+    origin: String,
+    title: String,
+    status: Option<u16>,
+    links: Vec<LinkData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkData {
+    href: String,
+    origin: String,
+}
+
+const PAGE_DATA_SCRIPT: &str = r#"
+    const nav = performance.getEntriesByType("navigation")[0];
+    const status = nav && nav.responseStatus ? nav.responseStatus : null;
+    const links = [];
+    for (const a of document.querySelectorAll("a[href]")) {
+        try {
+            const u = new URL(a.href, document.baseURI);
+            if (u.protocol !== "http:" && u.protocol !== "https:") {
+                continue;
+            }
+            links.push({ href: u.href, origin: u.origin });
+        } catch (e) {}
+    }
+    return {
+        url: location.href,
+        origin: location.origin,
+        title: document.title,
+        status: status,
+        links: links
+    };
+"#;
+
+/// A breadth-first, browser-based site crawler.
+///
+/// The crawler starts one or more WebDriver sessions, loads the start URL and
+/// follows links breadth-first, invoking a callback for every page. Because
+/// pages are rendered by a real browser, this works for sites that are fully
+/// client-rendered.
+///
+/// By default only links with the same origin as the start page are followed.
+/// In Chromium-based browsers, HTTP status codes are taken from the network
+/// events in the performance log, which the crawler enables. Other browsers
+/// report them in their navigation timing data, where they support it.
+///
+/// A page that fails to load, or that cannot be read once loaded, is listed
+/// in the report's broken links and the crawl continues.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::crawler::Crawler;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let crawler = Crawler::new("http://localhost:4444/wd/hub", &caps)?
+///     .with_max_depth(2)
+///     .with_sessions(2)
+///     .with_url_filter(|url| !url.contains("/logout"));
+/// let report = crawler.crawl("http://webappdemo", |_driver, page| {
+///     println!("{} ({:?}): {}", page.url, page.status, page.title);
+///     Ok(())
+/// })?;
+/// for link in &report.broken_links {
+///     println!("Broken link: {} on {:?}", link.url, link.referrer);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Crawler {
+    server_url: String,
+    capabilities: Value,
+    sessions: usize,
+    max_depth: usize,
+    max_pages: Option<usize>,
+    same_origin: bool,
+    filters: Vec<UrlFilter>,
+    politeness: Option<Politeness>,
+}
+
+impl fmt::Debug for Crawler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Crawler")
+            .field("server_url", &self.server_url)
+            .field("capabilities", &self.capabilities)
+            .field("sessions", &self.sessions)
+            .field("max_depth", &self.max_depth)
+            .field("max_pages", &self.max_pages)
+            .field("same_origin", &self.same_origin)
+            .field("filters", &self.filters.len())
+            .field("politeness", &self.politeness)
+            .finish()
+    }
+}
+
+impl Crawler {
+    /// Create a new Crawler that will start sessions on the specified server
+    /// using the specified capabilities.
+    pub fn new<C>(server_url: &str, capabilities: C) -> WebDriverResult<Self>
+    where
+        C: Serialize,
+    {
+        Ok(Self {
+            server_url: server_url.to_string(),
+            capabilities: serde_json::to_value(capabilities)?,
+            sessions: 1,
+            max_depth: 3,
+            max_pages: None,
+            same_origin: true,
+            filters: Vec::new(),
+            politeness: None,
+        })
+    }
+
+    /// Set the number of browser sessions used to crawl concurrently.
+    pub fn with_sessions(mut self, sessions: usize) -> Self {
+        self.sessions = sessions.max(1);
+        self
+    }
+
+    /// Set the maximum number of links to follow from the start URL.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Stop after the specified number of pages have been visited.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Follow links to other origins as well. The default is to stay on the
+    /// origin of the start page.
+    pub fn allow_cross_origin(mut self) -> Self {
+        self.same_origin = false;
+        self
+    }
+
+    /// Only follow links for which the filter returns true.
+    /// If multiple filters are added, all of them must return true.
+    pub fn with_url_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Apply the specified politeness policy to all sessions. Links that
+    /// `robots.txt` disallows are skipped and listed in the report.
+    pub fn with_politeness(mut self, politeness: Politeness) -> Self {
+        self.politeness = Some(politeness);
+        self
+    }
+
+    /// Crawl the site starting from the specified URL, calling `on_page` for
+    /// every page visited.
+    ///
+    /// If the callback returns an error, the crawl is stopped and that error
+    /// is returned.
+    pub fn crawl<F>(&self, start_url: &str, on_page: F) -> WebDriverResult<CrawlReport>
+    where
+        F: Fn(&WebDriverSession, &CrawledPage) -> WebDriverResult<()> + Send + Sync + 'static,
+    {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(CrawlState::new(start_url, self.max_pages)),
+            cond: Condvar::new(),
+        });
+        let on_page = Arc::new(on_page);
+
+        let mut capabilities = self.capabilities.clone();
+        let chromium = BrowserInfo::from_capabilities(&capabilities).is_chromium();
+        if chromium {
+            capabilities["goog:loggingPrefs"]["performance"] = json!("ALL");
+        }
+
+        let mut drivers = Vec::new();
+        for _ in 0..self.sessions {
+            let mut driver = WebDriver::new(&self.server_url, &capabilities)?;
+            driver.set_politeness(self.politeness.clone());
+            drivers.push(driver);
+        }
+
+        let mut handles = Vec::new();
+        for driver in drivers {
+            let crawler = self.clone();
+            let shared = shared.clone();
+            let on_page = on_page.clone();
+            handles.push(thread::spawn(move || {
+                if let Err(e) = crawler.worker(&driver, chromium, &shared, on_page.as_ref()) {
+                    let mut state = shared.lock();
+                    state.error.get_or_insert(e);
+                    state.finished = true;
+                    shared.cond.notify_all();
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle
+                .join()
+                .map_err(|_| WebDriverError::FatalError("crawler thread panicked".to_string()))?;
+        }
+
+        let mut state = shared.lock();
+        match state.error.take() {
+            Some(e) => Err(e),
+            None => Ok(std::mem::take(&mut state.report)),
+        }
+    }
+
+    fn worker<F>(
+        &self,
+        driver: &WebDriver,
+        chromium: bool,
+        shared: &Shared,
+        on_page: &F,
+    ) -> WebDriverResult<()>
+    where
+        F: Fn(&WebDriverSession, &CrawledPage) -> WebDriverResult<()>,
+    {
+        let monitor = if chromium {
+            Some(NetworkMonitor::new(driver.session()))
+        } else {
+            None
+        };
+        while let Some(item) = shared.next_item() {
+            let result = self.visit(driver, monitor.as_ref(), item, on_page);
+            let mut state = shared.lock();
+            state.in_flight -= 1;
+            let result = result.map(|x| state.record(self, x));
+            shared.cond.notify_all();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn visit<F>(
+        &self,
+        driver: &WebDriver,
+        monitor: Option<&NetworkMonitor<'_>>,
+        item: QueueItem,
+        on_page: &F,
+    ) -> WebDriverResult<Visit>
+    where
+        F: Fn(&WebDriverSession, &CrawledPage) -> WebDriverResult<()>,
+    {
+        // Without the network events, the status falls back to navigation
+        // timing.
+        let monitor = monitor.filter(|m| m.clear().is_ok());
+        if let Err(e) = driver.get(item.url.as_str()) {
+            if is_disallowed(&e) {
+                return Ok(Visit::Disallowed(item.url));
+            }
+            return Ok(Visit::Failed(BrokenLink {
+                url: item.url,
+                referrer: item.referrer,
+                status: None,
+                error: Some(e.to_string()),
+            }));
+        }
+
+        let data: WebDriverResult<PageData> =
+            driver.execute_script(PAGE_DATA_SCRIPT).and_then(|x| x.convert());
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                return Ok(Visit::Failed(BrokenLink {
+                    url: item.url,
+                    referrer: item.referrer,
+                    status: None,
+                    error: Some(e.to_string()),
+                }))
+            }
+        };
+        let status = monitor.and_then(|m| document_status(m, &data.url)).or(data.status);
+        let links = data.links.iter().map(|x| normalize_url(&x.href)).collect();
+        let origins = data.links.into_iter().map(|x| x.origin).collect();
+        let page = CrawledPage {
+            url: item.url,
+            final_url: data.url,
+            depth: item.depth,
+            referrer: item.referrer,
+            status,
+            title: data.title,
+            links,
+        };
+        on_page(driver.session(), &page)?;
+        Ok(Visit::Loaded(page, data.origin, origins))
+    }
+
+    fn should_follow(&self, url: &str, origin: &str, start_origin: Option<&str>) -> bool {
+        if self.same_origin && start_origin.map(|x| x != origin).unwrap_or(false) {
+            return false;
+        }
+        self.filters.iter().all(|f| f(url))
+    }
+}
+
+/// The URL with any fragment removed, so that links to different parts of a
+/// page are only visited once.
+fn normalize_url(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut url) => {
+            url.set_fragment(None);
+            url.into()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// The HTTP status of the document at the specified URL, from the network
+/// events logged while it loaded.
+fn document_status(monitor: &NetworkMonitor<'_>, url: &str) -> Option<u16> {
+    let url = normalize_url(url);
+    let events = monitor.events().ok()?;
+    let event = events.iter().rev().find(|e| {
+        e.method == "Network.responseReceived"
+            && e.params["type"] == "Document"
+            && e.params["response"]["url"].as_str().is_some_and(|x| normalize_url(x) == url)
+    })?;
+    event.params["response"]["status"].as_u64().map(|x| x as u16)
+}
+
+enum Visit {
+    Loaded(CrawledPage, String, Vec<String>),
+    Failed(BrokenLink),
+    Disallowed(String),
+}
+
+struct QueueItem {
+    url: String,
+    depth: usize,
+    referrer: Option<String>,
+}
+
+struct CrawlState {
+    queue: VecDeque<QueueItem>,
+    seen: HashSet<String>,
+    start_origin: Option<String>,
+    in_flight: usize,
+    started: usize,
+    max_pages: Option<usize>,
+    finished: bool,
+    error: Option<WebDriverError>,
+    report: CrawlReport,
+}
+
+impl CrawlState {
+    fn new(start_url: &str, max_pages: Option<usize>) -> Self {
+        let mut state = Self {
+            queue: VecDeque::new(),
+            seen: HashSet::new(),
+            start_origin: None,
+            in_flight: 0,
+            started: 0,
+            max_pages,
+            finished: false,
+            error: None,
+            report: CrawlReport::default(),
+        };
+        let start_url = normalize_url(start_url);
+        state.seen.insert(start_url.clone());
+        state.queue.push_back(QueueItem {
+            url: start_url,
+            depth: 0,
+            referrer: None,
+        });
+        state
+    }
+
+    fn record(&mut self, crawler: &Crawler, visit: Visit) {
+        match visit {
+            Visit::Failed(broken) => self.report.broken_links.push(broken),
+            Visit::Disallowed(url) => self.report.disallowed.push(url),
+            Visit::Loaded(page, origin, link_origins) => {
+                if self.start_origin.is_none() {
+                    self.start_origin = Some(origin);
+                }
+
+                if let Some(status) = page.status.filter(|x| *x >= 400) {
+                    self.report.broken_links.push(BrokenLink {
+                        url: page.url.clone(),
+                        referrer: page.referrer.clone(),
+                        status: Some(status),
+                        error: None,
+                    });
+                }
+
+                if page.depth < crawler.max_depth {
+                    for (link, link_origin) in page.links.iter().zip(link_origins) {
+                        let follow =
+                            crawler.should_follow(link, &link_origin, self.start_origin.as_deref());
+                        if follow && self.seen.insert(link.clone()) {
+                            self.queue.push_back(QueueItem {
+                                url: link.clone(),
+                                depth: page.depth + 1,
+                                referrer: Some(page.url.clone()),
+                            });
+                        }
+                    }
+                }
+                self.report.pages.push(page);
+            }
+        }
+    }
+}
+
+struct Shared {
+    state: Mutex<CrawlState>,
+    cond: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CrawlState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for the next URL to visit. Returns None once the crawl is complete.
+    fn next_item(&self) -> Option<QueueItem> {
+        let mut state = self.lock();
+        loop {
+            if state.finished {
+                return None;
+            }
+            if state.max_pages.map(|x| state.started >= x).unwrap_or(false) {
+                state.queue.clear();
+            }
+            if let Some(item) = state.queue.pop_front() {
+                state.in_flight += 1;
+                state.started += 1;
+                return Some(item);
+            }
+            if state.in_flight == 0 {
+                state.finished = true;
+                self.cond.notify_all();
+                return None;
+            }
+            state = self.cond.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_url_is_normalized_like_links() {
+        let state = CrawlState::new("http://example.com/docs#intro", None);
+        assert!(state.seen.contains("http://example.com/docs"));
+        assert_eq!(state.queue[0].url, "http://example.com/docs");
+        assert_eq!(normalize_url("http://Example.com"), "http://example.com/");
+        assert_eq!(normalize_url("not a url#x"), "not a url#x");
+    }
+}
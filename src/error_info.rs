@@ -0,0 +1,49 @@
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+
+/// Match the errors that carry a WebDriverErrorInfo, i.e. those returned by
+/// the WebDriver server or by this crate in its place.
+macro_rules! match_info {
+    ($error:expr) => {
+        match $error {
+            WebDriverError::NotInSpec(info)
+            | WebDriverError::ElementClickIntercepted(info)
+            | WebDriverError::ElementNotInteractable(info)
+            | WebDriverError::InsecureCertificate(info)
+            | WebDriverError::InvalidArgument(info)
+            | WebDriverError::InvalidCookieDomain(info)
+            | WebDriverError::InvalidElementState(info)
+            | WebDriverError::InvalidSelector(info)
+            | WebDriverError::InvalidSessionId(info)
+            | WebDriverError::JavascriptError(info)
+            | WebDriverError::MoveTargetOutOfBounds(info)
+            | WebDriverError::NoSuchAlert(info)
+            | WebDriverError::NoSuchCookie(info)
+            | WebDriverError::NoSuchElement(info)
+            | WebDriverError::NoSuchFrame(info)
+            | WebDriverError::NoSuchWindow(info)
+            | WebDriverError::ScriptTimeout(info)
+            | WebDriverError::SessionNotCreated(info)
+            | WebDriverError::StaleElementReference(info)
+            | WebDriverError::WebDriverTimeout(info)
+            | WebDriverError::UnableToSetCookie(info)
+            | WebDriverError::UnableToCaptureScreen(info)
+            | WebDriverError::UnexpectedAlertOpen(info)
+            | WebDriverError::UnknownCommand(info)
+            | WebDriverError::UnknownError(info)
+            | WebDriverError::UnknownMethod(info)
+            | WebDriverError::UnsupportedOperation(info) => Some(info),
+            _ => None,
+        }
+    };
+}
+
+/// The error info, for errors returned by the WebDriver server or by this
+/// crate in its place.
+pub(crate) fn error_info(error: &WebDriverError) -> Option<&WebDriverErrorInfo> {
+    match_info!(error)
+}
+
+/// The error info, for changing it, e.g. to redact the message.
+pub(crate) fn error_info_mut(error: &mut WebDriverError) -> Option<&mut WebDriverErrorInfo> {
+    match_info!(error)
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+/// The battery state reported by `navigator.getBattery()`, as set with
+/// [ChromeDevTools::set_battery()](struct.ChromeDevTools.html#method.set_battery).
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::extensions::chrome::BatteryState;
+///
+/// let low = BatteryState::discharging(0.05, 600.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryState {
+    /// Whether the battery is charging.
+    pub charging: bool,
+    /// The charge level, from 0.0 to 1.0.
+    pub level: f64,
+    /// The seconds until the battery is fully charged, if charging.
+    pub charging_time: Option<f64>,
+    /// The seconds until the battery is empty, if discharging.
+    pub discharging_time: Option<f64>,
+}
+
+impl BatteryState {
+    /// A battery that is charging, with the seconds until it is full.
+    pub fn charging(level: f64, seconds_to_full: f64) -> Self {
+        Self {
+            charging: true,
+            level,
+            charging_time: Some(seconds_to_full),
+            discharging_time: None,
+        }
+    }
+
+    /// A battery that is discharging, with the seconds until it is empty.
+    pub fn discharging(level: f64, seconds_to_empty: f64) -> Self {
+        Self {
+            charging: false,
+            level,
+            charging_time: None,
+            discharging_time: Some(seconds_to_empty),
+        }
+    }
+}
+
+/// Replaces `navigator.getBattery()` with a stub reporting `__STATE__`,
+/// which is replaced with the state as JSON. If the
+/// stub is already installed, the state is updated and the change events
+/// are dispatched, as a real battery would.
+///
+/// Unknown times are reported as `Infinity`, as the Battery Status API
+/// specifies.
+pub(crate) const BATTERY_SCRIPT: &str = r#"
+(() => {
+    const state = __STATE__;
+    const battery = window.__thirtyfourBattery;
+    if (battery) {
+        const changed = [];
+        if (battery.state.charging !== state.charging) changed.push("chargingchange");
+        if (battery.state.level !== state.level) changed.push("levelchange");
+        if (battery.state.chargingTime !== state.chargingTime) changed.push("chargingtimechange");
+        if (battery.state.dischargingTime !== state.dischargingTime) {
+            changed.push("dischargingtimechange");
+        }
+        battery.state = state;
+        for (const type of changed) {
+            const event = new Event(type);
+            battery.manager.dispatchEvent(event);
+            const handler = battery.manager["on" + type];
+            if (typeof handler === "function") handler.call(battery.manager, event);
+        }
+        return;
+    }
+
+    const manager = new EventTarget();
+    const stub = { state, manager };
+    const define = (name, get) => Object.defineProperty(manager, name, { get, configurable: true });
+    define("charging", () => stub.state.charging);
+    define("level", () => stub.state.level);
+    define("chargingTime", () => stub.state.chargingTime === null ? Infinity : stub.state.chargingTime);
+    define("dischargingTime", () =>
+        stub.state.dischargingTime === null ? Infinity : stub.state.dischargingTime);
+    for (const type of ["chargingchange", "levelchange", "chargingtimechange", "dischargingtimechange"]) {
+        manager["on" + type] = null;
+    }
+    window.__thirtyfourBattery = stub;
+    Object.defineProperty(Navigator.prototype, "getBattery", {
+        value: () => Promise.resolve(manager),
+        configurable: true,
+        writable: true,
+    });
+})();
+"#;
@@ -0,0 +1,34 @@
+use serde::Deserialize;
+
+/// The browser version, as returned by `Browser.getVersion`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserVersion {
+    /// The DevTools protocol version, e.g. `1.3`.
+    pub protocol_version: String,
+    /// The product name and version, e.g. `Chrome/120.0.6099.109`.
+    pub product: String,
+    /// The product revision.
+    pub revision: String,
+    /// The user agent.
+    pub user_agent: String,
+    /// The V8 version.
+    pub js_version: String,
+}
+
+impl BrowserVersion {
+    /// The version part of the product, e.g. `120.0.6099.109`.
+    pub fn version(&self) -> &str {
+        self.product.split('/').nth(1).unwrap_or_default()
+    }
+
+    /// The major version, e.g. `120`.
+    pub fn major_version(&self) -> Option<u32> {
+        self.version().split('.').next()?.parse().ok()
+    }
+
+    /// Returns true if the browser is running headless.
+    pub fn is_headless(&self) -> bool {
+        self.product.starts_with("Headless")
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::WebDriverResult;
+use crate::extensions::chrome::ChromeDevTools;
+use crate::json::convert_json;
+use crate::WebDriverSession;
+
+/// A Cache Storage cache, as created by `caches.open()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cache {
+    /// The opaque id used to refer to this cache in other CacheStorage calls.
+    pub cache_id: String,
+    /// The security origin that owns the cache.
+    pub security_origin: String,
+    /// The name of the cache.
+    pub cache_name: String,
+}
+
+/// An HTTP header name and value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheHeader {
+    /// The header name.
+    pub name: String,
+    /// The header value.
+    pub value: String,
+}
+
+/// A request/response pair stored in a cache.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    /// The request URL.
+    #[serde(rename = "requestURL")]
+    pub request_url: String,
+    /// The request method.
+    pub request_method: String,
+    /// The request headers.
+    pub request_headers: Vec<CacheHeader>,
+    /// The time the response was cached, in seconds since the Unix epoch.
+    pub response_time: f64,
+    /// The HTTP status code of the cached response.
+    pub response_status: u16,
+    /// The HTTP status text of the cached response.
+    pub response_status_text: String,
+    /// The response type, e.g. `basic`, `cors` or `opaqueResponse`.
+    pub response_type: String,
+    /// The response headers.
+    pub response_headers: Vec<CacheHeader>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CacheNames {
+    caches: Vec<Cache>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntries {
+    cache_data_entries: Vec<CacheEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedResponse {
+    response: CachedResponseBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedResponseBody {
+    body: String,
+}
+
+/// Inspect the Cache Storage (e.g. Service Worker caches) of Chromium-based
+/// browsers, via the CDP `CacheStorage` domain.
+///
+/// # Example
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::chrome::CacheStorage;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.get("http://webappdemo")?;
+/// let cache_storage = CacheStorage::new(driver.session());
+/// for cache in cache_storage.caches("http://webappdemo")? {
+///     for entry in cache_storage.entries(&cache.cache_id)? {
+///         println!("{}: {} {}", cache.cache_name, entry.request_method, entry.request_url);
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheStorage<'a> {
+    pub session: &'a WebDriverSession,
+}
+
+impl<'a> CacheStorage<'a> {
+    /// Create a new CacheStorage struct.
+    pub fn new(session: &'a WebDriverSession) -> Self {
+        Self {
+            session,
+        }
+    }
+
+    fn dev_tools(&self) -> ChromeDevTools<'a> {
+        ChromeDevTools::new(self.session)
+    }
+
+    /// List the caches for the specified security origin, e.g. `https://example.com`.
+    pub fn caches(&self, security_origin: &str) -> WebDriverResult<Vec<Cache>> {
+        let v = self.dev_tools().execute_cdp_with_params(
+            "CacheStorage.requestCacheNames",
+            json!({ "securityOrigin": security_origin }),
+        )?;
+        let names: CacheNames = convert_json("CacheStorage.requestCacheNames", &v)?;
+        Ok(names.caches)
+    }
+
+    /// Return the cache with the specified name for the specified origin, if any.
+    pub fn cache(&self, security_origin: &str, cache_name: &str) -> WebDriverResult<Option<Cache>> {
+        Ok(self.caches(security_origin)?.into_iter().find(|x| x.cache_name == cache_name))
+    }
+
+    /// List all entries in the specified cache.
+    pub fn entries(&self, cache_id: &str) -> WebDriverResult<Vec<CacheEntry>> {
+        let v = self.dev_tools().execute_cdp_with_params(
+            "CacheStorage.requestEntries",
+            json!({ "cacheId": cache_id }),
+        )?;
+        let entries: CacheEntries = convert_json("CacheStorage.requestEntries", &v)?;
+        Ok(entries.cache_data_entries)
+    }
+
+    /// Fetch the body of the cached response for the specified request URL.
+    ///
+    /// The request headers must match any `Vary` headers of the cached
+    /// response. Use `entry.request_headers` to look up a listed entry.
+    pub fn cached_response(
+        &self,
+        cache_id: &str,
+        request_url: &str,
+        request_headers: &[CacheHeader],
+    ) -> WebDriverResult<Vec<u8>> {
+        let v = self.dev_tools().execute_cdp_with_params(
+            "CacheStorage.requestCachedResponse",
+            json!({
+                "cacheId": cache_id,
+                "requestURL": request_url,
+                "requestHeaders": request_headers
+            }),
+        )?;
+        let response: CachedResponse = convert_json("CacheStorage.requestCachedResponse", &v)?;
+        Ok(base64::decode(&response.response.body)?)
+    }
+
+    /// Delete the specified cache.
+    pub fn delete_cache(&self, cache_id: &str) -> WebDriverResult<()> {
+        self.dev_tools()
+            .execute_cdp_with_params("CacheStorage.deleteCache", json!({ "cacheId": cache_id }))?;
+        Ok(())
+    }
+
+    /// Delete the entry for the specified request URL from the specified cache.
+    pub fn delete_entry(&self, cache_id: &str, request_url: &str) -> WebDriverResult<()> {
+        self.dev_tools().execute_cdp_with_params(
+            "CacheStorage.deleteEntry",
+            json!({ "cacheId": cache_id, "request": request_url }),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use serde::Serialize;
+
+use crate::extensions::chrome::NetworkConditions;
+
+/// The connection type reported by `navigator.connection.type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionType {
+    /// No connection.
+    None,
+    /// A 2G cellular connection.
+    Cellular2g,
+    /// A 3G cellular connection.
+    Cellular3g,
+    /// A 4G cellular connection.
+    Cellular4g,
+    /// A Bluetooth connection.
+    Bluetooth,
+    /// A wired connection.
+    Ethernet,
+    /// A Wi-Fi connection.
+    Wifi,
+    /// A WiMAX connection.
+    Wimax,
+    /// Some other connection.
+    Other,
+}
+
+/// The effective connection type reported by
+/// `navigator.connection.effectiveType`.
+///
+/// Chrome works out the effective type from the round trip time and
+/// throughput, so each variant corresponds to network conditions within
+/// its range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectiveConnectionType {
+    /// `slow-2g`.
+    Slow2g,
+    /// `2g`.
+    Cellular2g,
+    /// `3g`.
+    Cellular3g,
+    /// `4g`.
+    Cellular4g,
+}
+
+impl EffectiveConnectionType {
+    /// Network conditions that Chrome reports as this effective type.
+    /// Throughput is in bytes per second and latency in milliseconds.
+    pub fn conditions(&self) -> NetworkConditions {
+        let (latency, download_throughput, upload_throughput) = match self {
+            EffectiveConnectionType::Slow2g => (2000, 6_000, 6_000),
+            EffectiveConnectionType::Cellular2g => (1400, 8_750, 4_000),
+            EffectiveConnectionType::Cellular3g => (300, 90_000, 30_000),
+            EffectiveConnectionType::Cellular4g => (50, 1_250_000, 400_000),
+        };
+        let mut conditions = NetworkConditions::new();
+        conditions.latency = latency;
+        conditions.download_throughput = download_throughput;
+        conditions.upload_throughput = upload_throughput;
+        conditions
+    }
+
+    /// The matching connection type.
+    pub fn connection_type(&self) -> ConnectionType {
+        match self {
+            EffectiveConnectionType::Slow2g | EffectiveConnectionType::Cellular2g => {
+                ConnectionType::Cellular2g
+            }
+            EffectiveConnectionType::Cellular3g => ConnectionType::Cellular3g,
+            EffectiveConnectionType::Cellular4g => ConnectionType::Cellular4g,
+        }
+    }
+}
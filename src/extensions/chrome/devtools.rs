@@ -1,9 +1,27 @@
-use crate::common::connection_common::convert_json;
+use crate::canvas::PRESERVE_DRAWING_BUFFER_SCRIPT;
+#[cfg(feature = "cdp-websocket")]
+use crate::console::{ConsoleFilter, ConsoleStream};
 use crate::error::WebDriverResult;
-use crate::extensions::chrome::NetworkConditions;
-use crate::WebDriverSession;
+use crate::extensions::chrome::battery::BATTERY_SCRIPT;
+use crate::extensions::chrome::geolocation::simulate_route;
+use crate::extensions::chrome::notifications::{NOTIFICATIONS_KEY, NOTIFICATION_HOOK_SCRIPT};
+use crate::extensions::chrome::runtime::remote_object_result;
+#[cfg(feature = "cdp-websocket")]
+use crate::extensions::chrome::target::browser_websocket_url;
+use crate::extensions::chrome::{
+    BatteryState, BrowserVersion, CallArgument, CapturedNotification, ConnectionType,
+    DeviceOrientation, NetworkConditions, RemoteObject, RouteOptions, SensorReading, SensorType,
+    TargetInfo, UserAgentMetadata,
+};
+#[cfg(feature = "cdp-websocket")]
+use crate::extensions::chrome::{CdpConnection, CdpEvents};
+use crate::json::convert_json;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{WebDriverSession, WindowHandle};
 use serde_json::{json, Value};
+use std::time::Duration;
 use thirtyfour::extensions::chrome::ChromeCommand;
+use thirtyfour::ChromeCapabilities;
 
 /// The ChromeDevTools struct allows you to interact with Chromium-based browsers via
 /// the Chrome Devtools Protocol (CDP).
@@ -89,7 +107,7 @@ impl<'a> ChromeDevTools<'a> {
     /// ```
     pub fn get_network_conditions(&self) -> WebDriverResult<NetworkConditions> {
         let v = self.cmd(ChromeCommand::GetNetworkConditions)?;
-        convert_json(&v["value"])
+        convert_json("Get Network Conditions", &v["value"])
     }
 
     /// Set the network conditions.
@@ -126,6 +144,51 @@ impl<'a> ChromeDevTools<'a> {
         Ok(())
     }
 
+    /// Set the network conditions together with the connection type reported
+    /// by `navigator.connection`. The effective type reported by
+    /// `navigator.connection.effectiveType` follows from the latency and
+    /// throughput.
+    ///
+    /// Unlike `set_network_conditions()`, this uses the Chrome DevTools
+    /// Protocol, since chromedriver does not pass the connection type on.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{ChromeDevTools, EffectiveConnectionType};
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let slow = EffectiveConnectionType::Slow2g;
+    /// dev_tools.emulate_connection(&slow.conditions(), slow.connection_type())?;
+    /// driver.get("http://webappdemo")?;
+    /// let effective_type: String =
+    ///     driver.execute_script("return navigator.connection.effectiveType;")?.convert()?;
+    /// assert_eq!(effective_type, "slow-2g");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn emulate_connection(
+        &self,
+        conditions: &NetworkConditions,
+        connection_type: ConnectionType,
+    ) -> WebDriverResult<()> {
+        self.execute_cdp("Network.enable")?;
+        self.execute_cdp_with_params(
+            "Network.emulateNetworkConditions",
+            json!({
+                "offline": conditions.offline,
+                "latency": conditions.latency,
+                "downloadThroughput": conditions.download_throughput,
+                "uploadThroughput": conditions.upload_throughput,
+                "connectionType": connection_type,
+            }),
+        )?;
+        Ok(())
+    }
+
     /// Execute the specified command without parameters.
     /// For commands that require parameters, use `execute_cdp_with_params()` instead.
     ///
@@ -178,6 +241,760 @@ impl<'a> ChromeDevTools<'a> {
         Ok(v["value"].clone())
     }
 
+    /// Evaluate a JavaScript expression in the page and return the result as a
+    /// [RemoteObject](struct.RemoteObject.html). Objects are returned by
+    /// reference, so they can be used in later calls to
+    /// [call_function_on()](#method.call_function_on). If the expression
+    /// returns a promise, the promise is awaited.
+    ///
+    /// An uncaught exception is returned as a JavascriptError.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let count: u32 = dev_tools.evaluate("document.links.length")?.convert()?;
+    ///
+    /// let links = dev_tools.evaluate("document.links")?;
+    /// assert_eq!(links.class_name.as_deref(), Some("HTMLCollection"));
+    /// #     dev_tools.release_object(links.object_id.as_deref().unwrap())?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn evaluate(&self, expression: &str) -> WebDriverResult<RemoteObject> {
+        let v = self.execute_cdp_with_params(
+            "Runtime.evaluate",
+            json!({"expression": expression, "awaitPromise": true}),
+        )?;
+        remote_object_result(v)
+    }
+
+    /// Call a function with the specified remote object as `this`, and return
+    /// the result as a [RemoteObject](struct.RemoteObject.html).
+    ///
+    /// The function declaration is a string such as `"function(n) { return
+    /// this.items.slice(0, n); }"`. Arguments may be JSON values or other
+    /// remote objects.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{CallArgument, ChromeDevTools};
+    /// use serde_json::json;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let store = dev_tools.evaluate("new Map()")?;
+    /// let store_id = store.object_id.clone().unwrap();
+    ///
+    /// // The Map stays alive between calls.
+    /// dev_tools.call_function_on(
+    ///     &store_id,
+    ///     "function(k, v) { this.set(k, v); }",
+    ///     &[json!("user").into(), json!("alice").into()],
+    /// )?;
+    /// let size: u32 =
+    ///     dev_tools.call_function_on(&store_id, "function() { return this.size; }", &[])?.convert()?;
+    /// assert_eq!(size, 1);
+    /// dev_tools.release_object(&store_id)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn call_function_on(
+        &self,
+        object_id: &str,
+        function_declaration: &str,
+        args: &[CallArgument],
+    ) -> WebDriverResult<RemoteObject> {
+        let args: Vec<Value> = args.iter().map(|a| a.to_json()).collect();
+        let v = self.execute_cdp_with_params(
+            "Runtime.callFunctionOn",
+            json!({
+                "objectId": object_id,
+                "functionDeclaration": function_declaration,
+                "arguments": args,
+                "awaitPromise": true,
+            }),
+        )?;
+        remote_object_result(v)
+    }
+
+    /// Release the remote object with the specified id, so it can be garbage
+    /// collected.
+    pub fn release_object(&self, object_id: &str) -> WebDriverResult<()> {
+        self.execute_cdp_with_params("Runtime.releaseObject", json!({ "objectId": object_id }))?;
+        Ok(())
+    }
+
+    /// Get the browser version.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let version = dev_tools.browser_version()?;
+    /// println!("{} (V8 {})", version.product, version.js_version);
+    /// if version.major_version() < Some(100) {
+    ///     println!("Chrome is too old");
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn browser_version(&self) -> WebDriverResult<BrowserVersion> {
+        let v = self.execute_cdp("Browser.getVersion")?;
+        convert_json("Browser.getVersion", &v)
+    }
+
+    /// Override the user agent and the matching client hints. The override
+    /// applies to requests and to `navigator.userAgent` and
+    /// `navigator.userAgentData`, until the session ends.
+    ///
+    /// To set the user agent before the browser starts, use
+    /// [set_user_agent_capability()](#method.set_user_agent_capability).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{Brand, ChromeDevTools, UserAgentMetadata};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.set_user_agent(
+    ///     "Mozilla/5.0 (Linux; Android 14; Pixel 7) AppleWebKit/537.36 \
+    ///      (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+    ///     &UserAgentMetadata {
+    ///         platform: "Android".to_string(),
+    ///         platform_version: "14".to_string(),
+    ///         model: "Pixel 7".to_string(),
+    ///         mobile: true,
+    ///         brands: vec![Brand::new("Chromium", "120"), Brand::new("Google Chrome", "120")],
+    ///         ..Default::default()
+    ///     },
+    /// )?;
+    /// driver.get("http://webappdemo")?;
+    /// let mobile: bool = driver.execute_script("return navigator.userAgentData.mobile;")?.convert()?;
+    /// assert!(mobile);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_user_agent(
+        &self,
+        user_agent: &str,
+        metadata: &UserAgentMetadata,
+    ) -> WebDriverResult<()> {
+        self.override_user_agent(user_agent, None, metadata)
+    }
+
+    pub(crate) fn override_user_agent(
+        &self,
+        user_agent: &str,
+        accept_language: Option<&str>,
+        metadata: &UserAgentMetadata,
+    ) -> WebDriverResult<()> {
+        let mut params = json!({
+            "userAgent": user_agent,
+            "userAgentMetadata": metadata,
+        });
+        if let Some(accept_language) = accept_language {
+            params["acceptLanguage"] = Value::from(accept_language);
+        }
+        self.execute_cdp_with_params("Network.setUserAgentOverride", params)?;
+        Ok(())
+    }
+
+    /// Set the user agent, and optionally the client hints, in the
+    /// capabilities so that they apply from the first request. This must be
+    /// done before the session is started.
+    ///
+    /// The client hints are passed via chromedriver's `mobileEmulation`
+    /// option, which requires a recent version of chromedriver.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// ChromeDevTools::set_user_agent_capability(&mut caps, "my-monitoring-bot/1.0", None)?;
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_user_agent_capability(
+        capabilities: &mut ChromeCapabilities,
+        user_agent: &str,
+        metadata: Option<&UserAgentMetadata>,
+    ) -> WebDriverResult<()> {
+        capabilities.add_chrome_arg(&format!("--user-agent={}", user_agent))?;
+        if let Some(metadata) = metadata {
+            capabilities.add_chrome_option(
+                "mobileEmulation",
+                json!({"userAgent": user_agent, "clientHints": metadata}),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Create a new isolated browser context, similar to an incognito window.
+    /// Pages in different browser contexts do not share cookies, storage or
+    /// cache, so one session can act as several users at once.
+    ///
+    /// Returns the id of the new browser context.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let main_window = driver.current_window_handle()?;
+    ///
+    /// // Log in as a second user without affecting the first.
+    /// let context = dev_tools.create_browser_context()?;
+    /// let window = dev_tools.new_page_in_context(&context, "http://webappdemo")?;
+    /// driver.switch_to().window(&window)?;
+    /// driver.find_element(By::Name("input1"))?.send_keys("bob")?;
+    ///
+    /// driver.switch_to().window(&main_window)?;
+    /// dev_tools.dispose_browser_context(&context)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn create_browser_context(&self) -> WebDriverResult<String> {
+        let v = self.execute_cdp("Target.createBrowserContext")?;
+        convert_json("Target.createBrowserContext", &v["browserContextId"])
+    }
+
+    /// List the ids of all browser contexts created with
+    /// [create_browser_context()](#method.create_browser_context).
+    /// The default browser context is not included.
+    pub fn browser_contexts(&self) -> WebDriverResult<Vec<String>> {
+        let v = self.execute_cdp("Target.getBrowserContexts")?;
+        convert_json("Target.getBrowserContexts", &v["browserContextIds"])
+    }
+
+    /// Open a new page in the specified browser context and return its window
+    /// handle, for use with `driver.switch_to().window()`.
+    pub fn new_page_in_context(
+        &self,
+        context_id: &str,
+        url: &str,
+    ) -> WebDriverResult<WindowHandle> {
+        let v = self.execute_cdp_with_params(
+            "Target.createTarget",
+            json!({"url": url, "browserContextId": context_id}),
+        )?;
+        // chromedriver uses the CDP target id as the window handle.
+        convert_json::<String>("Target.createTarget", &v["targetId"]).map(WindowHandle::from)
+    }
+
+    /// Close all pages in the specified browser context and discard its
+    /// cookies and storage.
+    ///
+    /// If the current window belongs to the context, switch to another
+    /// window afterwards.
+    pub fn dispose_browser_context(&self, context_id: &str) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Target.disposeBrowserContext",
+            json!({ "browserContextId": context_id }),
+        )?;
+        Ok(())
+    }
+
+    /// List all CDP targets: pages, out-of-process iframes, workers and so on.
+    ///
+    /// Commands sent via `execute_cdp()` only run in the top-level page. To run
+    /// commands in another target, use [connect()](#method.connect).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let workers = dev_tools.targets()?.into_iter().filter(|t| t.is_worker()).count();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn targets(&self) -> WebDriverResult<Vec<TargetInfo>> {
+        let v = self.execute_cdp("Target.getTargets")?;
+        convert_json("Target.getTargets", &v["targetInfos"])
+    }
+
+    /// Open a direct connection to the browser's DevTools websocket, which
+    /// can attach to any target. Only available with the `cdp-websocket`
+    /// feature.
+    ///
+    /// The websocket address is taken from the `se:cdp` capability if present,
+    /// or else from the `debuggerAddress` in `goog:chromeOptions`. See
+    /// [CdpConnection](struct.CdpConnection.html) for details.
+    #[cfg(feature = "cdp-websocket")]
+    pub fn connect(&self) -> WebDriverResult<CdpConnection> {
+        CdpConnection::connect(&browser_websocket_url(self.session)?)
+    }
+
+    /// Open a direct connection to the browser's DevTools websocket, enable
+    /// the specified domains, such as `Network` or `Runtime`, for the current
+    /// window, and receive their events. Only available with the
+    /// `cdp-websocket` feature.
+    ///
+    /// Events such as `Network.responseReceived` and
+    /// `Runtime.consoleAPICalled` cannot be received through chromedriver,
+    /// which only passes on commands. See [CdpEvents](struct.CdpEvents.html)
+    /// for details.
+    #[cfg(feature = "cdp-websocket")]
+    pub fn connect_events(&self, domains: &[&str]) -> WebDriverResult<CdpEvents> {
+        CdpEvents::connect(self.session, self.connect()?, domains)
+    }
+
+    /// Open a direct connection to the browser's DevTools websocket and
+    /// receive console messages, uncaught exceptions and browser log messages
+    /// from the current window, filtered with the specified filter. Only
+    /// available with the `cdp-websocket` feature.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::console::{ConsoleFilter, ConsoleLevel};
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let filter = ConsoleFilter::new().with_min_level(ConsoleLevel::Error);
+    /// let console = ChromeDevTools::new(driver.session()).connect_console(filter)?;
+    /// driver.get("http://webappdemo")?;
+    /// driver.execute_script(r#"console.error("something went wrong");"#)?;
+    /// let entry = console.next_timeout(Duration::from_secs(5))?;
+    /// assert_eq!(entry.text, "something went wrong");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "cdp-websocket")]
+    pub fn connect_console(&self, filter: ConsoleFilter) -> WebDriverResult<ConsoleStream> {
+        Ok(ConsoleStream::from_cdp(self.connect_events(&["Runtime", "Log"])?, filter))
+    }
+
+    /// Override the storage quota for the specified origin, so that code
+    /// handling `QuotaExceededError` can be exercised.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.override_storage_quota("http://webappdemo", 1024)?;
+    /// driver.get("http://webappdemo")?;
+    /// #     dev_tools.reset_storage_quota("http://webappdemo")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn override_storage_quota(&self, origin: &str, quota_bytes: u64) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Storage.overrideQuotaForOrigin",
+            json!({ "origin": origin, "quotaSize": quota_bytes }),
+        )?;
+        Ok(())
+    }
+
+    /// Remove a storage quota override set by `override_storage_quota()`.
+    pub fn reset_storage_quota(&self, origin: &str) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Storage.overrideQuotaForOrigin",
+            json!({ "origin": origin }),
+        )?;
+        Ok(())
+    }
+
+    /// Grant or deny the `persistent-storage` permission for all origins, which
+    /// controls the result of `navigator.storage.persist()`.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.set_persistent_storage(true)?;
+    /// let persisted: bool = driver
+    ///     .execute_async_script("navigator.storage.persist().then(arguments[0]);")?
+    ///     .convert()?;
+    /// assert!(persisted);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_persistent_storage(&self, granted: bool) -> WebDriverResult<()> {
+        let setting = if granted {
+            "granted"
+        } else {
+            "denied"
+        };
+        self.execute_cdp_with_params(
+            "Browser.setPermission",
+            json!({ "permission": { "name": "persistent-storage" }, "setting": setting }),
+        )?;
+        Ok(())
+    }
+
+    /// Override the position reported by the Geolocation API, and grant the
+    /// `geolocation` permission. The accuracy is in metres.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.set_geolocation(51.5074, -0.1278, 10.0)?;
+    /// driver.get("http://webappdemo")?;
+    /// #     dev_tools.clear_geolocation()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_geolocation(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        accuracy: f64,
+    ) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Browser.setPermission",
+            json!({ "permission": { "name": "geolocation" }, "setting": "granted" }),
+        )?;
+        self.execute_cdp_with_params(
+            "Emulation.setGeolocationOverride",
+            json!({ "latitude": latitude, "longitude": longitude, "accuracy": accuracy }),
+        )?;
+        Ok(())
+    }
+
+    /// Remove a geolocation override set by `set_geolocation()`.
+    pub fn clear_geolocation(&self) -> WebDriverResult<()> {
+        self.execute_cdp("Emulation.clearGeolocationOverride")?;
+        Ok(())
+    }
+
+    /// Move the reported position along a route while the closure runs, for
+    /// testing pages that track the user's location live.
+    ///
+    /// Each point is a latitude, a longitude and how long to stay there. A
+    /// background thread updates the geolocation override as each dwell time
+    /// passes, and stops when the closure returns. Unless the route repeats,
+    /// the position stays at the last point once it is reached.
+    ///
+    /// The closure's error is returned in preference to any error from
+    /// updating the position.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// use thirtyfour_sync::extensions::chrome::{ChromeDevTools, RouteOptions};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// let route = [
+    ///     (51.5007, -0.1246, Duration::from_secs(2)),
+    ///     (51.5014, -0.1419, Duration::from_secs(2)),
+    ///     (51.5081, -0.0759, Duration::from_secs(2)),
+    /// ];
+    /// dev_tools.simulate_route(&route, &RouteOptions::new().with_accuracy(5.0), || {
+    ///     driver.get("http://localhost:8000/tracking")?;
+    ///     std::thread::sleep(Duration::from_secs(5));
+    ///     let distance = driver.find_element(By::Id("distance"))?.text()?;
+    ///     assert_ne!(distance, "0 km");
+    ///     Ok(())
+    /// })?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn simulate_route<F, T>(
+        &self,
+        route: &[(f64, f64, Duration)],
+        options: &RouteOptions,
+        f: F,
+    ) -> WebDriverResult<T>
+    where
+        F: FnOnce() -> WebDriverResult<T>,
+    {
+        simulate_route(self, route, options, f)
+    }
+
+    /// Override the orientation reported by `deviceorientation` events, as
+    /// if the device had been rotated.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{ChromeDevTools, DeviceOrientation};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// // Tilt the device to the right.
+    /// dev_tools.set_device_orientation(&DeviceOrientation::new(0.0, 0.0, 45.0))?;
+    /// #     dev_tools.clear_device_orientation()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_device_orientation(&self, orientation: &DeviceOrientation) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "DeviceOrientation.setDeviceOrientationOverride",
+            json!({
+                "alpha": orientation.alpha,
+                "beta": orientation.beta,
+                "gamma": orientation.gamma,
+            }),
+        )?;
+        Ok(())
+    }
+
+    /// Remove a device orientation override set by `set_device_orientation()`.
+    pub fn clear_device_orientation(&self) -> WebDriverResult<()> {
+        self.execute_cdp("DeviceOrientation.clearDeviceOrientationOverride")?;
+        Ok(())
+    }
+
+    /// Override the readings of a sensor, which also drives `devicemotion`
+    /// events. The sensor is overridden, and reported as present, until
+    /// `clear_sensor()` is called.
+    ///
+    /// Returns an error if the kind of reading does not suit the sensor.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{ChromeDevTools, SensorReading, SensorType};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// // Shake the device along the x axis.
+    /// dev_tools.set_sensor(SensorType::Accelerometer, SensorReading::Xyz(15.0, 0.0, 9.8))?;
+    /// dev_tools.set_sensor(SensorType::AmbientLight, SensorReading::Single(5.0))?;
+    /// #     dev_tools.clear_sensor(SensorType::Accelerometer)?;
+    /// #     dev_tools.clear_sensor(SensorType::AmbientLight)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_sensor(&self, sensor: SensorType, reading: SensorReading) -> WebDriverResult<()> {
+        let reading = reading.to_json(sensor)?;
+        self.execute_cdp_with_params(
+            "Emulation.setSensorOverrideEnabled",
+            json!({ "enabled": true, "type": sensor.name() }),
+        )?;
+        self.execute_cdp_with_params(
+            "Emulation.setSensorOverrideReadings",
+            json!({ "type": sensor.name(), "reading": reading }),
+        )?;
+        Ok(())
+    }
+
+    /// Remove a sensor override set by `set_sensor()`, so the real sensor, if
+    /// any, is used again.
+    pub fn clear_sensor(&self, sensor: SensorType) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Emulation.setSensorOverrideEnabled",
+            json!({ "enabled": false, "type": sensor.name() }),
+        )?;
+        Ok(())
+    }
+
+    /// Override the state reported by the Idle Detection API, and grant the
+    /// `idle-detection` permission.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// // The user walked away and the screen locked.
+    /// dev_tools.set_idle_override(true, true)?;
+    /// #     dev_tools.clear_idle_override()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_idle_override(&self, user_idle: bool, screen_locked: bool) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Browser.setPermission",
+            json!({ "permission": { "name": "idle-detection" }, "setting": "granted" }),
+        )?;
+        self.execute_cdp_with_params(
+            "Emulation.setIdleOverride",
+            json!({ "isUserActive": !user_idle, "isScreenUnlocked": !screen_locked }),
+        )?;
+        Ok(())
+    }
+
+    /// Remove an idle state override set by `set_idle_override()`.
+    pub fn clear_idle_override(&self) -> WebDriverResult<()> {
+        self.execute_cdp("Emulation.clearIdleOverride")?;
+        Ok(())
+    }
+
+    /// Grant the `notifications` permission, and record each notification
+    /// that the page shows, either with the `Notification` constructor or
+    /// via a service worker registration. This applies to the current page
+    /// and every page loaded afterwards.
+    ///
+    /// Notifications are recorded in session storage, so they are kept
+    /// across navigation within an origin. Use `captured_notifications()` to
+    /// read them.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.capture_notifications()?;
+    /// driver.get("http://webappdemo")?;
+    /// driver.execute_script(r#"new Notification("New message", { body: "Hello" });"#)?;
+    /// let notifications = dev_tools.captured_notifications()?;
+    /// assert_eq!(notifications[0].title, "New message");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn capture_notifications(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Browser.setPermission",
+            json!({ "permission": { "name": "notifications" }, "setting": "granted" }),
+        )?;
+        self.execute_cdp_with_params(
+            "Page.addScriptToEvaluateOnNewDocument",
+            json!({ "source": NOTIFICATION_HOOK_SCRIPT }),
+        )?;
+        self.session.execute_script(NOTIFICATION_HOOK_SCRIPT)?;
+        Ok(())
+    }
+
+    /// The notifications shown so far by pages of the current origin, since
+    /// `capture_notifications()` was called, oldest first.
+    pub fn captured_notifications(&self) -> WebDriverResult<Vec<CapturedNotification>> {
+        let script = format!(
+            "return JSON.parse(sessionStorage.getItem({:?}) || \"[]\");",
+            NOTIFICATIONS_KEY
+        );
+        self.session.execute_script(&script)?.convert()
+    }
+
+    /// Forget the notifications captured for the current origin.
+    pub fn clear_captured_notifications(&self) -> WebDriverResult<()> {
+        let script = format!("sessionStorage.removeItem({:?});", NOTIFICATIONS_KEY);
+        self.session.execute_script(&script)?;
+        Ok(())
+    }
+
+    /// Replace `navigator.getBattery()` with a stub that reports the
+    /// specified battery state, in the current page and every page loaded
+    /// afterwards. Calling this again updates the state, and fires the
+    /// battery's change events in the current page.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::{BatteryState, ChromeDevTools};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let dev_tools = ChromeDevTools::new(driver.session());
+    /// dev_tools.set_battery(&BatteryState::discharging(0.05, 600.0))?;
+    /// driver.get("http://webappdemo")?;
+    /// let level: f64 = driver
+    ///     .execute_async_script("navigator.getBattery().then((b) => arguments[0](b.level));")?
+    ///     .convert()?;
+    /// assert_eq!(level, 0.05);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_battery(&self, state: &BatteryState) -> WebDriverResult<()> {
+        let script = BATTERY_SCRIPT.replace("__STATE__", &json!(state).to_string());
+        self.execute_cdp_with_params(
+            "Page.addScriptToEvaluateOnNewDocument",
+            json!({ "source": script }),
+        )?;
+        self.session.execute_script(&script)?;
+        Ok(())
+    }
+
+    /// Make every WebGL canvas created from now on keep its drawing buffer
+    /// between frames, so that
+    /// [WebElement::capture_webgl_frame()](../../struct.WebElement.html#method.capture_webgl_frame)
+    /// can always read it back. This applies to pages loaded afterwards.
+    ///
+    /// Preserving the drawing buffer costs a copy per frame, so animations
+    /// may run slower than they would for users.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// ChromeDevTools::new(driver.session()).preserve_drawing_buffers()?;
+    /// driver.get("http://localhost:8000/dashboard")?;
+    /// let png = driver.find_element(By::Tag("canvas"))?.capture_webgl_frame()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn preserve_drawing_buffers(&self) -> WebDriverResult<()> {
+        self.execute_cdp_with_params(
+            "Page.addScriptToEvaluateOnNewDocument",
+            json!({ "source": PRESERVE_DRAWING_BUFFER_SCRIPT }),
+        )?;
+        Ok(())
+    }
+
     /// Get the list of sinks available for cast.
     pub fn get_sinks(&self) -> WebDriverResult<Value> {
         let v = self.cmd(ChromeCommand::GetSinks)?;
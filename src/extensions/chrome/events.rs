@@ -0,0 +1,253 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::extensions::chrome::CdpConnection;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// How often a listener thread checks whether it should stop.
+const LISTENER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An event received over the DevTools protocol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdpEvent {
+    /// The event name, e.g. `Network.responseReceived`.
+    pub method: String,
+    /// The raw event parameters.
+    pub params: Value,
+}
+
+fn to_event(message: Value) -> CdpEvent {
+    CdpEvent {
+        method: message["method"].as_str().unwrap_or_default().to_string(),
+        params: message["params"].clone(),
+    }
+}
+
+/// A stream of DevTools events from the current page, for the domains that
+/// were enabled. Only available with the `cdp-websocket` feature.
+///
+/// Use
+/// [ChromeDevTools::connect_events()](struct.ChromeDevTools.html#method.connect_events)
+/// to create one. Events are collected on a background thread from the moment
+/// the stream is created, so none are missed while the caller is busy.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+///
+/// let events = ChromeDevTools::new(driver.session()).connect_events(&["Network", "Runtime"])?;
+/// driver.get("http://webappdemo")?;
+/// let response = events.wait_for(
+///     |e| e.method == "Network.responseReceived",
+///     Duration::from_secs(10),
+/// )?;
+/// println!("status {}", response.params["response"]["status"]);
+/// #     Ok(())
+/// # }
+/// ```
+pub struct CdpEvents {
+    connection: CdpConnection,
+    session_id: String,
+    receiver: Receiver<Value>,
+}
+
+impl std::fmt::Debug for CdpEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CdpEvents").field("session_id", &self.session_id).finish()
+    }
+}
+
+/// Find the DevTools target of the current window. chromedriver uses the
+/// target id as the window handle, with a `CDwindow-` prefix in older
+/// versions.
+fn current_target_id(
+    session: &WebDriverSession,
+    connection: &CdpConnection,
+) -> WebDriverResult<String> {
+    let handle = session.current_window_handle()?.to_string();
+    let handle = handle.strip_prefix("CDwindow-").unwrap_or(&handle);
+    let pages: Vec<_> = connection.targets()?.into_iter().filter(|t| t.is_page()).collect();
+    match pages.iter().find(|t| t.target_id.eq_ignore_ascii_case(handle)) {
+        Some(target) => Ok(target.target_id.clone()),
+        // Not chromedriver, so guess the page that is already being driven.
+        None => pages
+            .iter()
+            .find(|t| t.attached)
+            .or_else(|| pages.first())
+            .map(|t| t.target_id.clone())
+            .ok_or_else(|| {
+                WebDriverError::UnknownResponse("no DevTools page target found".to_string())
+            }),
+    }
+}
+
+impl CdpEvents {
+    pub(crate) fn connect(
+        session: &WebDriverSession,
+        connection: CdpConnection,
+        domains: &[&str],
+    ) -> WebDriverResult<Self> {
+        let target_id = current_target_id(session, &connection)?;
+        let target = connection.attach_to_target(&target_id)?;
+        let session_id = target.session_id().to_string();
+
+        // Listen before enabling, as enabling a domain may replay events.
+        let prefixes: Vec<String> = domains.iter().map(|d| format!("{}.", d)).collect();
+        let listen_session_id = session_id.clone();
+        let receiver = connection.listen(Box::new(move |message| {
+            let method = message["method"].as_str().unwrap_or_default();
+            message["sessionId"].as_str() == Some(&listen_session_id)
+                && prefixes.iter().any(|p| method.starts_with(p.as_str()))
+        }));
+        for domain in domains {
+            target.execute_cdp(&format!("{}.enable", domain))?;
+        }
+
+        Ok(Self {
+            connection,
+            session_id,
+            receiver,
+        })
+    }
+
+    /// Execute the specified command with the specified parameter(s) in the
+    /// page that events are received from, e.g. to enable another domain.
+    pub fn execute_cdp_with_params(&self, cmd: &str, cmd_args: Value) -> WebDriverResult<Value> {
+        self.connection.send(Some(&self.session_id), cmd, cmd_args)
+    }
+
+    /// Wait for the next event, for as long as it takes. Returns a
+    /// FatalError if the connection is closed.
+    pub fn next_event(&self) -> WebDriverResult<CdpEvent> {
+        self.receiver
+            .recv()
+            .map(to_event)
+            .map_err(|_| WebDriverError::FatalError("DevTools connection closed".to_string()))
+    }
+
+    /// Return the next event if one has already arrived.
+    pub fn try_next_event(&self) -> Option<CdpEvent> {
+        self.receiver.try_recv().ok().map(to_event)
+    }
+
+    /// Wait for the next event, returning a Timeout error if none arrives
+    /// within the timeout.
+    pub fn next_event_timeout(&self, timeout: Duration) -> WebDriverResult<CdpEvent> {
+        self.receiver.recv_timeout(timeout).map(to_event).map_err(|e| match e {
+            RecvTimeoutError::Timeout => {
+                WebDriverError::Timeout("no DevTools event received".to_string())
+            }
+            RecvTimeoutError::Disconnected => {
+                WebDriverError::FatalError("DevTools connection closed".to_string())
+            }
+        })
+    }
+
+    /// Wait for an event that matches the predicate, discarding any others.
+    pub fn wait_for<F>(&self, predicate: F, timeout: Duration) -> WebDriverResult<CdpEvent>
+    where
+        F: Fn(&CdpEvent) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let event = self.next_event_timeout(remaining).map_err(|e| match e {
+                WebDriverError::Timeout(_) => {
+                    WebDriverError::Timeout("no DevTools event matched the predicate".to_string())
+                }
+                e => e,
+            })?;
+            if predicate(&event) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Return all events that have already arrived.
+    pub fn drain(&self) -> Vec<CdpEvent> {
+        self.receiver.try_iter().map(to_event).collect()
+    }
+
+    /// Call the callback with every event on a background thread, until the
+    /// returned listener is stopped or dropped, or the connection closes.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let events = ChromeDevTools::new(driver.session()).connect_events(&["Runtime"])?;
+    /// let listener = events.on_event(|event| {
+    ///     if event.method == "Runtime.consoleAPICalled" {
+    ///         println!("console.{}: {}", event.params["type"], event.params["args"]);
+    ///     }
+    /// });
+    /// driver.get("http://webappdemo")?;
+    /// listener.stop();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn on_event<F>(self, mut callback: F) -> CdpEventListener
+    where
+        F: FnMut(&CdpEvent) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    match self.receiver.recv_timeout(LISTENER_POLL_INTERVAL) {
+                        Ok(message) => callback(&to_event(message)),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })
+        };
+        CdpEventListener {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+/// Calls a callback with DevTools events on a background thread. See
+/// [CdpEvents::on_event()](struct.CdpEvents.html#method.on_event).
+///
+/// The connection is closed when the listener is stopped or dropped.
+#[derive(Debug)]
+pub struct CdpEventListener {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CdpEventListener {
+    /// Stop calling the callback, and wait for it to return if it is
+    /// running.
+    pub fn stop(self) {}
+}
+
+impl Drop for CdpEventListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
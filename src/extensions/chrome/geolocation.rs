@@ -0,0 +1,133 @@
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+
+/// Options for
+/// [ChromeDevTools::simulate_route()](struct.ChromeDevTools.html#method.simulate_route).
+#[derive(Debug, Clone)]
+pub struct RouteOptions {
+    /// The accuracy reported with each position, in metres.
+    pub accuracy: f64,
+    /// Start again from the first point after the last one, rather than
+    /// staying at the last point until the closure returns.
+    pub repeat: bool,
+    /// Whether to clear the geolocation override when the closure returns.
+    /// Otherwise the browser stays at the last position reached.
+    pub clear_when_done: bool,
+}
+
+impl Default for RouteOptions {
+    fn default() -> Self {
+        Self {
+            accuracy: 10.0,
+            repeat: false,
+            clear_when_done: true,
+        }
+    }
+}
+
+impl RouteOptions {
+    /// Create new RouteOptions with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the accuracy reported with each position, in metres.
+    pub fn with_accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Set whether to start again from the first point after the last one.
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Set whether to clear the geolocation override when the closure returns.
+    pub fn with_clear_when_done(mut self, clear_when_done: bool) -> Self {
+        self.clear_when_done = clear_when_done;
+        self
+    }
+}
+
+/// Check that each point is a valid position.
+fn check_route(route: &[(f64, f64, Duration)]) -> WebDriverResult<()> {
+    if route.is_empty() {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+            "route must have at least one point",
+        )));
+    }
+    for &(latitude, longitude, _) in route {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "invalid position ({}, {}) in route",
+                latitude, longitude
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Move along the route until told to stop, or until the last point is
+/// reached when not repeating. Each point is held for its dwell time.
+fn follow_route(
+    dev_tools: &ChromeDevTools,
+    route: &[(f64, f64, Duration)],
+    options: &RouteOptions,
+    stop: Receiver<()>,
+) -> WebDriverResult<()> {
+    loop {
+        for &(latitude, longitude, dwell) in route {
+            dev_tools.set_geolocation(latitude, longitude, options.accuracy)?;
+            match stop.recv_timeout(dwell) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => return Ok(()),
+            }
+        }
+        if !options.repeat {
+            return Ok(());
+        }
+    }
+}
+
+pub(crate) fn simulate_route<F, T>(
+    dev_tools: &ChromeDevTools,
+    route: &[(f64, f64, Duration)],
+    options: &RouteOptions,
+    f: F,
+) -> WebDriverResult<T>
+where
+    F: FnOnce() -> WebDriverResult<T>,
+{
+    check_route(route)?;
+    // Set the first position before the closure starts, so that the page
+    // never sees the real location.
+    let (latitude, longitude, _) = route[0];
+    dev_tools.set_geolocation(latitude, longitude, options.accuracy)?;
+
+    let (sender, stop) = channel();
+    let (result, route_result) = thread::scope(|scope| {
+        let handle = scope.spawn(|| follow_route(dev_tools, route, options, stop));
+        let result = f();
+        // Dropping the sender wakes the thread if it is still dwelling.
+        drop(sender);
+        let route_result = handle.join().unwrap_or_else(|_| {
+            Err(WebDriverError::FatalError("geolocation route thread panicked".to_string()))
+        });
+        (result, route_result)
+    });
+
+    if options.clear_when_done {
+        let cleared = dev_tools.clear_geolocation();
+        if result.is_ok() && route_result.is_ok() {
+            cleared?;
+        }
+    }
+    let value = result?;
+    route_result?;
+    Ok(value)
+}
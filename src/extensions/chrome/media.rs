@@ -0,0 +1,137 @@
+use std::path::Path;
+
+use serde_json::json;
+use thirtyfour::ChromeCapabilities;
+
+use crate::common::capabilities::desiredcapabilities::Capabilities;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// Extra settings for [ChromeCapabilities](../../struct.ChromeCapabilities.html).
+pub trait ChromeCapabilitiesExt {
+    /// Replace the camera and microphone with fake devices, so that pages
+    /// using `getUserMedia()` can be tested without real hardware.
+    ///
+    /// With `fake_ui`, the permission prompt is also accepted automatically.
+    /// The fake camera shows a test pattern and the fake microphone plays a
+    /// beep, unless a video file (`.y4m` or `.mjpeg`) or an audio file
+    /// (`.wav`) is given to play instead.
+    ///
+    /// The files are read by the browser, so they must be on the machine
+    /// that runs it. Relative paths are resolved against the current
+    /// directory. Returns an error if a file does not exist or has the
+    /// wrong format.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeCapabilitiesExt;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.use_fake_media(true, None, None)?;
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn use_fake_media(
+        &mut self,
+        fake_ui: bool,
+        video_file: Option<&Path>,
+        audio_file: Option<&Path>,
+    ) -> WebDriverResult<()>;
+
+    /// Turn off the browser's own dialogs and prompts, which WebDriver
+    /// cannot see or dismiss and which can leave the session hanging: the
+    /// print dialog, the save-password bubble, the translate bar, the
+    /// download prompt, the default-browser and search engine prompts, the
+    /// first-run experience and the OS keychain prompt. Other arguments and
+    /// preferences already set are kept.
+    ///
+    /// With the print dialog turned off, `window.print()` prints straight to
+    /// the default printer, if there is one.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::ChromeCapabilitiesExt;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::chrome();
+    /// caps.suppress_native_ui()?;
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn suppress_native_ui(&mut self) -> WebDriverResult<()>;
+}
+
+impl ChromeCapabilitiesExt for ChromeCapabilities {
+    fn use_fake_media(
+        &mut self,
+        fake_ui: bool,
+        video_file: Option<&Path>,
+        audio_file: Option<&Path>,
+    ) -> WebDriverResult<()> {
+        let video_file = video_file.map(|p| media_file(p, &["y4m", "mjpeg"])).transpose()?;
+        let audio_file = audio_file.map(|p| media_file(p, &["wav"])).transpose()?;
+
+        self.add_chrome_arg("--use-fake-device-for-media-stream")?;
+        if fake_ui {
+            self.add_chrome_arg("--use-fake-ui-for-media-stream")?;
+        }
+        if let Some(path) = video_file {
+            self.add_chrome_arg(&format!("--use-file-for-fake-video-capture={}", path))?;
+        }
+        if let Some(path) = audio_file {
+            self.add_chrome_arg(&format!("--use-file-for-fake-audio-capture={}", path))?;
+        }
+        Ok(())
+    }
+
+    fn suppress_native_ui(&mut self) -> WebDriverResult<()> {
+        for arg in &[
+            "--no-first-run",
+            "--no-default-browser-check",
+            "--disable-search-engine-choice-screen",
+            "--kiosk-printing",
+            // Keep saved data out of the OS keychain, which prompts to unlock it.
+            "--password-store=basic",
+            "--use-mock-keychain",
+        ] {
+            self.add_chrome_arg(arg)?;
+        }
+        self.update(json!({
+            "goog:chromeOptions": {
+                "prefs": {
+                    "credentials_enable_service": false,
+                    "profile.password_manager_enabled": false,
+                    "profile.password_manager_leak_detection": false,
+                    "translate.enabled": false,
+                    "download.prompt_for_download": false,
+                }
+            }
+        }));
+        Ok(())
+    }
+}
+
+/// Check that the file exists and has one of the extensions, and return its
+/// absolute path.
+fn media_file(path: &Path, extensions: &[&str]) -> WebDriverResult<String> {
+    let extension =
+        path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+    if !extensions.contains(&extension.as_str()) {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "fake media file {} must be a .{} file",
+            path.display(),
+            extensions.join(" or .")
+        ))));
+    }
+    match path.canonicalize() {
+        Ok(path) if path.is_file() => Ok(path.to_string_lossy().into_owned()),
+        _ => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "fake media file {} does not exist",
+            path.display()
+        )))),
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::{ExtensionCommand, RequestMethod};
+
+use crate::common::capabilities::desiredcapabilities::Capabilities;
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::extensions::chrome::{ChromeDevTools, Waterfall};
+use crate::json::convert_json;
+use crate::query::ElementPoller;
+use crate::text::wildcard_matches;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// Read (and clear) the chromedriver performance log.
+struct GetPerformanceLog;
+
+impl ExtensionCommand for GetPerformanceLog {
+    fn parameters_json(&self) -> Option<Value> {
+        Some(json!({ "type": "performance" }))
+    }
+
+    fn method(&self) -> RequestMethod {
+        RequestMethod::Post
+    }
+
+    fn endpoint(&self) -> String {
+        String::from("/se/log")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LogEntry {
+    message: String,
+    timestamp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogMessage {
+    message: NetworkEventData,
+}
+
+#[derive(Debug, Deserialize)]
+struct NetworkEventData {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A single Chrome DevTools Protocol `Network.*` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkEvent {
+    /// The CDP event name, e.g. `Network.responseReceived`.
+    pub method: String,
+    /// The CDP event parameters.
+    pub params: Value,
+    /// The time the event was logged, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl NetworkEvent {
+    /// The CDP request id this event relates to, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.params["requestId"].as_str()
+    }
+}
+
+/// The body of a network response, as returned by `Network.getResponseBody`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseBody {
+    /// The body, which is base64-encoded if `base64_encoded` is true.
+    pub body: String,
+    /// True if the body was sent as base64.
+    pub base64_encoded: bool,
+}
+
+impl ResponseBody {
+    /// The raw bytes of the body.
+    pub fn bytes(&self) -> WebDriverResult<Vec<u8>> {
+        if self.base64_encoded {
+            Ok(base64::decode(&self.body)?)
+        } else {
+            Ok(self.body.clone().into_bytes())
+        }
+    }
+
+    /// The body as text.
+    pub fn text(&self) -> WebDriverResult<String> {
+        if self.base64_encoded {
+            String::from_utf8(self.bytes()?).map_err(|e| {
+                WebDriverError::UnknownResponse(format!("response body is not UTF-8: {}", e))
+            })
+        } else {
+            Ok(self.body.clone())
+        }
+    }
+
+    /// Parse the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> WebDriverResult<T> {
+        Ok(serde_json::from_str(&self.text()?)?)
+    }
+}
+
+/// A network response observed by the [NetworkMonitor](struct.NetworkMonitor.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkResponse {
+    /// The CDP request id.
+    pub request_id: String,
+    /// The response URL.
+    pub url: String,
+    /// The HTTP status code.
+    pub status: u16,
+    /// The HTTP status text.
+    pub status_text: String,
+    /// The response headers.
+    pub headers: HashMap<String, String>,
+    /// The response MIME type.
+    pub mime_type: String,
+    /// The resource type, e.g. `XHR`, `Fetch` or `Document`.
+    pub resource_type: String,
+    /// The response body. This is None if the request failed before the
+    /// body was received, or the browser no longer has the body available.
+    pub body: Option<ResponseBody>,
+}
+
+impl NetworkResponse {
+    fn from_event(event: &NetworkEvent) -> Self {
+        let response = &event.params["response"];
+        let headers = response["headers"]
+            .as_object()
+            .map(|x| {
+                x.iter()
+                    .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            request_id: event.request_id().unwrap_or_default().to_string(),
+            url: response["url"].as_str().unwrap_or_default().to_string(),
+            status: response["status"].as_u64().unwrap_or_default() as u16,
+            status_text: response["statusText"].as_str().unwrap_or_default().to_string(),
+            headers,
+            mime_type: response["mimeType"].as_str().unwrap_or_default().to_string(),
+            resource_type: event.params["type"].as_str().unwrap_or_default().to_string(),
+            body: None,
+        }
+    }
+
+    /// Return the value of the specified header. Header names are compared
+    /// case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// Parse the response body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> WebDriverResult<T> {
+        match &self.body {
+            Some(body) => body.json(),
+            None => Err(WebDriverError::NotFound(
+                format!("response body for {}", self.url),
+                "the response body is not available".to_string(),
+            )),
+        }
+    }
+}
+
+/// A message received on an `EventSource` (server-sent events) stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventSourceMessage {
+    /// The CDP request id of the EventSource connection.
+    pub request_id: String,
+    /// The event type. This is `message` unless the server specified one.
+    pub event_name: String,
+    /// The event id, or an empty string if the server did not specify one.
+    pub event_id: String,
+    /// The message data.
+    pub data: String,
+    /// The time the message was received, in seconds, as reported by the browser.
+    pub timestamp: f64,
+}
+
+impl EventSourceMessage {
+    fn from_event(event: &NetworkEvent) -> Self {
+        let params = &event.params;
+        Self {
+            request_id: event.request_id().unwrap_or_default().to_string(),
+            event_name: params["eventName"].as_str().unwrap_or_default().to_string(),
+            event_id: params["eventId"].as_str().unwrap_or_default().to_string(),
+            data: params["data"].as_str().unwrap_or_default().to_string(),
+            timestamp: params["timestamp"].as_f64().unwrap_or_default(),
+        }
+    }
+
+    /// Parse the message data as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> WebDriverResult<T> {
+        Ok(serde_json::from_str(&self.data)?)
+    }
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    events: Vec<NetworkEvent>,
+    consumed: HashSet<String>,
+    consumed_messages: HashSet<usize>,
+}
+
+/// Monitor network activity in Chromium-based browsers.
+///
+/// The NetworkMonitor reads the chromedriver performance log, so performance
+/// logging must be enabled in the capabilities before the session is started,
+/// using [NetworkMonitor::enable_logging()](#method.enable_logging).
+///
+/// Reading the performance log clears it, so only one NetworkMonitor should be
+/// used per session at a time. Events logged before the NetworkMonitor was
+/// created are included unless `clear()` is called first.
+///
+/// # Example
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::chrome::NetworkMonitor;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let mut caps = DesiredCapabilities::chrome();
+/// NetworkMonitor::enable_logging(&mut caps)?;
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.get("http://localhost:8000/users")?;
+///
+/// let monitor = NetworkMonitor::new(driver.session());
+/// monitor.clear()?;
+/// driver.find_element(By::Id("load-users"))?.click()?;
+/// let response = monitor.wait_for_response("*/api/users*", Duration::from_secs(10))?;
+/// assert_eq!(response.status, 200);
+/// let users: serde_json::Value = response.json()?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct NetworkMonitor<'a> {
+    session: &'a WebDriverSession,
+    state: Mutex<MonitorState>,
+}
+
+impl<'a> NetworkMonitor<'a> {
+    /// Create a new NetworkMonitor for the specified session.
+    pub fn new(session: &'a WebDriverSession) -> Self {
+        Self {
+            session,
+            state: Mutex::new(MonitorState::default()),
+        }
+    }
+
+    /// Enable the chromedriver performance log, which the NetworkMonitor
+    /// requires. This must be done before the session is started.
+    pub fn enable_logging<C: Capabilities>(capabilities: &mut C) -> WebDriverResult<()> {
+        capabilities.add_subkey("goog:loggingPrefs", "performance", "ALL")
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MonitorState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Read any new `Network.*` events from the performance log.
+    fn refresh(&self) -> WebDriverResult<()> {
+        let entries: Vec<LogEntry> =
+            convert_json("Get Log", &self.session.extension_command(GetPerformanceLog)?)?;
+        let mut state = self.lock();
+        for entry in entries {
+            if let Ok(msg) = serde_json::from_str::<LogMessage>(&entry.message) {
+                if msg.message.method.starts_with("Network.") {
+                    state.events.push(NetworkEvent {
+                        method: msg.message.method,
+                        params: msg.message.params,
+                        timestamp: entry.timestamp,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Discard all events received so far, including any still in the
+    /// performance log.
+    pub fn clear(&self) -> WebDriverResult<()> {
+        self.refresh()?;
+        let mut state = self.lock();
+        state.events.clear();
+        state.consumed.clear();
+        state.consumed_messages.clear();
+        Ok(())
+    }
+
+    /// Return all `Network.*` events received so far.
+    pub fn events(&self) -> WebDriverResult<Vec<NetworkEvent>> {
+        self.refresh()?;
+        Ok(self.lock().events.clone())
+    }
+
+    /// Build a waterfall of per-request phase timings from the events
+    /// received so far.
+    pub fn waterfall(&self) -> WebDriverResult<Waterfall> {
+        Ok(Waterfall::from_events(&self.events()?))
+    }
+
+    /// Return all messages received on `EventSource` streams so far.
+    pub fn event_source_messages(&self) -> WebDriverResult<Vec<EventSourceMessage>> {
+        self.refresh()?;
+        Ok(self
+            .lock()
+            .events
+            .iter()
+            .filter(|e| e.method == "Network.eventSourceMessageReceived")
+            .map(EventSourceMessage::from_event)
+            .collect())
+    }
+
+    /// Wait for an `EventSource` message of the specified type for which the
+    /// predicate returns true, and return it.
+    ///
+    /// Each message is only returned once, so calling this repeatedly returns
+    /// successive matching messages.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::chrome::NetworkMonitor;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let mut caps = DesiredCapabilities::chrome();
+    /// #     NetworkMonitor::enable_logging(&mut caps)?;
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/orders")?;
+    /// let monitor = NetworkMonitor::new(driver.session());
+    /// let message = monitor.wait_for_event(
+    ///     "order-update",
+    ///     |msg| msg.data.contains("shipped"),
+    ///     Duration::from_secs(10),
+    /// )?;
+    /// let update: serde_json::Value = message.json()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn wait_for_event<F>(
+        &self,
+        event_type: &str,
+        mut predicate: F,
+        timeout: Duration,
+    ) -> WebDriverResult<EventSourceMessage>
+    where
+        F: FnMut(&EventSourceMessage) -> bool,
+    {
+        let poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(100));
+        let description = format!("no matching EventSource message of type {}", event_type);
+        poller.poll(&description, || {
+            self.refresh()?;
+            let mut state = self.lock();
+            let found = state
+                .events
+                .iter()
+                .enumerate()
+                .filter(|(i, e)| {
+                    e.method == "Network.eventSourceMessageReceived"
+                        && !state.consumed_messages.contains(i)
+                })
+                .map(|(i, e)| (i, EventSourceMessage::from_event(e)))
+                .find(|(_, msg)| msg.event_name == event_type && predicate(msg));
+            Ok(found.map(|(i, msg)| {
+                state.consumed_messages.insert(i);
+                msg
+            }))
+        })
+    }
+
+    /// Fetch the body of the response for the specified request id, using
+    /// `Network.getResponseBody`.
+    pub fn response_body(&self, request_id: &str) -> WebDriverResult<ResponseBody> {
+        let dev_tools = ChromeDevTools::new(self.session);
+        let v = dev_tools.execute_cdp_with_params(
+            "Network.getResponseBody",
+            json!({ "requestId": request_id }),
+        )?;
+        convert_json("Network.getResponseBody", &v)
+    }
+
+    /// Wait for a response whose URL matches the specified pattern to finish
+    /// loading, and return it along with its body.
+    ///
+    /// The pattern is matched against the full URL, and `*` matches any
+    /// sequence of characters. Each response is only returned once, so calling
+    /// this repeatedly returns successive matching responses, in the order
+    /// they finish loading.
+    pub fn wait_for_response(
+        &self,
+        url_pattern: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<NetworkResponse> {
+        let poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(100));
+        let description = format!("no response matching {}", url_pattern);
+        let (mut response, loaded) = poller.poll(&description, || {
+            self.refresh()?;
+            let mut state = self.lock();
+            // Responses are taken in the order they finish, so those that are
+            // still loading are skipped until they finish.
+            let found = state
+                .events
+                .iter()
+                .filter(|x| {
+                    x.method == "Network.loadingFinished" || x.method == "Network.loadingFailed"
+                })
+                .find_map(|finished| {
+                    let request_id = finished.request_id()?;
+                    if state.consumed.contains(request_id) {
+                        return None;
+                    }
+                    let e = state.events.iter().find(|e| {
+                        e.method == "Network.responseReceived"
+                            && e.request_id() == Some(request_id)
+                            && wildcard_matches(
+                                url_pattern,
+                                e.params["response"]["url"].as_str().unwrap_or_default(),
+                                true,
+                            )
+                    })?;
+                    Some((
+                        NetworkResponse::from_event(e),
+                        finished.method == "Network.loadingFinished",
+                    ))
+                });
+            let (response, loaded) = match found {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            state.consumed.insert(response.request_id.clone());
+            Ok(Some((response, loaded)))
+        })?;
+
+        if loaded {
+            response.body = self.response_body(&response.request_id).ok();
+        }
+        Ok(response)
+    }
+}
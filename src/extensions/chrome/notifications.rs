@@ -0,0 +1,71 @@
+use serde::Deserialize;
+
+/// A notification shown by the page, as captured by
+/// [ChromeDevTools::capture_notifications()](struct.ChromeDevTools.html#method.capture_notifications).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedNotification {
+    /// The notification title.
+    pub title: String,
+    /// The notification body, if any.
+    #[serde(default)]
+    pub body: String,
+    /// The notification tag, if any.
+    #[serde(default)]
+    pub tag: String,
+    /// The URL of the notification icon, if any.
+    #[serde(default)]
+    pub icon: String,
+    /// Whether the notification was shown via a service worker registration
+    /// rather than the `Notification` constructor.
+    #[serde(default)]
+    pub service_worker: bool,
+    /// The time the notification was shown, in milliseconds since the Unix
+    /// epoch.
+    pub timestamp: f64,
+}
+
+/// The key under which captured notifications are kept in session storage,
+/// so that they survive navigation within an origin.
+pub(crate) const NOTIFICATIONS_KEY: &str = "__thirtyfour_notifications";
+
+/// Wraps the `Notification` constructor and `showNotification()` so that
+/// each notification is recorded before it is shown.
+pub(crate) const NOTIFICATION_HOOK_SCRIPT: &str = r#"
+(() => {
+    if (window.__thirtyfourNotificationHook || !window.Notification) return;
+    window.__thirtyfourNotificationHook = true;
+    const key = "__thirtyfour_notifications";
+    const record = (title, options, serviceWorker) => {
+        options = options || {};
+        try {
+            const captured = JSON.parse(sessionStorage.getItem(key) || "[]");
+            captured.push({
+                title: String(title),
+                body: String(options.body || ""),
+                tag: String(options.tag || ""),
+                icon: String(options.icon || ""),
+                serviceWorker,
+                timestamp: Date.now(),
+            });
+            sessionStorage.setItem(key, JSON.stringify(captured));
+        } catch (e) {}
+    };
+    const Original = window.Notification;
+    const Wrapped = function Notification(title, options) {
+        record(title, options, false);
+        return new Original(title, options);
+    };
+    Wrapped.prototype = Original.prototype;
+    Object.defineProperty(Wrapped, "permission", { get: () => Original.permission });
+    Wrapped.requestPermission = (...args) => Original.requestPermission(...args);
+    window.Notification = Wrapped;
+    if (window.ServiceWorkerRegistration) {
+        const show = ServiceWorkerRegistration.prototype.showNotification;
+        ServiceWorkerRegistration.prototype.showNotification = function (title, options) {
+            record(title, options, true);
+            return show.call(this, title, options);
+        };
+    }
+})();
+"#;
@@ -0,0 +1,124 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::json::convert_json_from;
+
+/// A mirror of a JavaScript value, as returned by `Runtime.evaluate` and
+/// `Runtime.callFunctionOn`.
+///
+/// Primitive values are returned by value. Objects are returned by
+/// reference: use the `object_id` with
+/// [ChromeDevTools::call_function_on()](struct.ChromeDevTools.html#method.call_function_on)
+/// to keep working with them, and release them with
+/// [ChromeDevTools::release_object()](struct.ChromeDevTools.html#method.release_object)
+/// when done.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteObject {
+    /// The object type, e.g. `object`, `function`, `string` or `undefined`.
+    #[serde(rename = "type")]
+    pub object_type: String,
+    /// The object subtype, e.g. `array`, `node`, `null` or `promise`.
+    #[serde(default)]
+    pub subtype: Option<String>,
+    /// The object's class name, e.g. `HTMLDivElement`.
+    #[serde(default)]
+    pub class_name: Option<String>,
+    /// The value, for primitive values and objects returned by value.
+    #[serde(default)]
+    pub value: Option<Value>,
+    /// The value as a string, for values that can't be represented in JSON,
+    /// such as `NaN`, `-0` and bigints.
+    #[serde(default)]
+    pub unserializable_value: Option<String>,
+    /// A description of the value, e.g. `Array(3)`.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The id of the object, for objects returned by reference.
+    #[serde(default)]
+    pub object_id: Option<String>,
+}
+
+impl RemoteObject {
+    /// Returns true if the value is `undefined`.
+    pub fn is_undefined(&self) -> bool {
+        self.object_type == "undefined"
+    }
+
+    /// Returns true if the value is `null`.
+    pub fn is_null(&self) -> bool {
+        self.subtype.as_deref() == Some("null")
+    }
+
+    /// Convert the value into the specified type. Objects returned by
+    /// reference have no value, so this only works for primitive values.
+    pub fn convert<T: DeserializeOwned>(&self) -> WebDriverResult<T> {
+        match &self.value {
+            Some(v) => convert_json_from("the remote object value", v),
+            None if self.is_null() => convert_json_from("the remote object value", &Value::Null),
+            None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "remote object {} has no value",
+                self.description.as_deref().unwrap_or(&self.object_type)
+            )))),
+        }
+    }
+}
+
+/// An argument for
+/// [ChromeDevTools::call_function_on()](struct.ChromeDevTools.html#method.call_function_on).
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallArgument {
+    /// A JSON value.
+    Value(Value),
+    /// A reference to a remote object by its id.
+    ObjectId(String),
+    /// A value that can't be represented in JSON, such as `NaN` or `1n`.
+    Unserializable(String),
+}
+
+impl CallArgument {
+    /// Pass a reference to the specified remote object. Primitive values are
+    /// passed by value.
+    pub fn object(object: &RemoteObject) -> Self {
+        match (&object.object_id, &object.unserializable_value) {
+            (Some(id), _) => CallArgument::ObjectId(id.clone()),
+            (None, Some(v)) => CallArgument::Unserializable(v.clone()),
+            (None, None) => CallArgument::Value(object.value.clone().unwrap_or(Value::Null)),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        match self {
+            CallArgument::Value(v) => json!({ "value": v }),
+            CallArgument::ObjectId(id) => json!({ "objectId": id }),
+            CallArgument::Unserializable(v) => json!({ "unserializableValue": v }),
+        }
+    }
+}
+
+impl From<Value> for CallArgument {
+    fn from(value: Value) -> Self {
+        CallArgument::Value(value)
+    }
+}
+
+impl From<&RemoteObject> for CallArgument {
+    fn from(object: &RemoteObject) -> Self {
+        CallArgument::object(object)
+    }
+}
+
+/// Convert the result of `Runtime.evaluate` or `Runtime.callFunctionOn` into
+/// a RemoteObject, turning thrown exceptions into errors.
+pub(crate) fn remote_object_result(v: Value) -> WebDriverResult<RemoteObject> {
+    if let Some(details) = v.get("exceptionDetails") {
+        let message = details["exception"]["description"]
+            .as_str()
+            .or_else(|| details["text"].as_str())
+            .unwrap_or("uncaught exception");
+        return Err(WebDriverError::JavascriptError(WebDriverErrorInfo::new(message)));
+    }
+    convert_json_from("the remote object", &v["result"])
+}
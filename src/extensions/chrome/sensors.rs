@@ -0,0 +1,114 @@
+use serde_json::{json, Value};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+
+/// The orientation reported by `deviceorientation` events, in degrees.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::extensions::chrome::DeviceOrientation;
+///
+/// // A phone held upright, facing north.
+/// let upright = DeviceOrientation::new(0.0, 90.0, 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceOrientation {
+    /// Rotation around the z axis, from 0 to 360.
+    pub alpha: f64,
+    /// Rotation around the x axis (front to back tilt), from -180 to 180.
+    pub beta: f64,
+    /// Rotation around the y axis (left to right tilt), from -90 to 90.
+    pub gamma: f64,
+}
+
+impl DeviceOrientation {
+    /// Create a new DeviceOrientation.
+    pub fn new(alpha: f64, beta: f64, gamma: f64) -> Self {
+        Self {
+            alpha,
+            beta,
+            gamma,
+        }
+    }
+}
+
+/// A sensor of the Generic Sensor API, which also drives the
+/// `devicemotion` and `deviceorientation` events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorType {
+    /// Orientation relative to the Earth's reference frame, as a quaternion.
+    AbsoluteOrientation,
+    /// Acceleration including gravity, in m/s².
+    Accelerometer,
+    /// Illuminance, in lux.
+    AmbientLight,
+    /// Gravity alone, in m/s².
+    Gravity,
+    /// Angular velocity, in rad/s.
+    Gyroscope,
+    /// Acceleration excluding gravity, in m/s².
+    LinearAcceleration,
+    /// Magnetic field, in µT.
+    Magnetometer,
+    /// Orientation relative to a stationary reference frame, as a quaternion.
+    RelativeOrientation,
+}
+
+impl SensorType {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            SensorType::AbsoluteOrientation => "absolute-orientation",
+            SensorType::Accelerometer => "accelerometer",
+            SensorType::AmbientLight => "ambient-light",
+            SensorType::Gravity => "gravity",
+            SensorType::Gyroscope => "gyroscope",
+            SensorType::LinearAcceleration => "linear-acceleration",
+            SensorType::Magnetometer => "magnetometer",
+            SensorType::RelativeOrientation => "relative-orientation",
+        }
+    }
+}
+
+/// A reading for a sensor override. The kind of reading must suit the
+/// sensor: `Single` for ambient light, `Quaternion` for the orientation
+/// sensors, and `Xyz` for the others.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorReading {
+    /// A single value.
+    Single(f64),
+    /// A value along each axis.
+    Xyz(f64, f64, f64),
+    /// An orientation as the quaternion (x, y, z, w).
+    Quaternion(f64, f64, f64, f64),
+}
+
+impl SensorReading {
+    /// Convert the reading to its CDP form, checking that it suits the sensor.
+    pub(crate) fn to_json(self, sensor: SensorType) -> WebDriverResult<Value> {
+        let reading = match (sensor, self) {
+            (SensorType::AmbientLight, SensorReading::Single(value)) => {
+                json!({ "single": { "value": value } })
+            }
+            (SensorType::AbsoluteOrientation, SensorReading::Quaternion(x, y, z, w))
+            | (SensorType::RelativeOrientation, SensorReading::Quaternion(x, y, z, w)) => {
+                json!({ "quaternion": { "x": x, "y": y, "z": z, "w": w } })
+            }
+            (SensorType::AmbientLight, _)
+            | (SensorType::AbsoluteOrientation, _)
+            | (SensorType::RelativeOrientation, _) => {
+                return Err(mismatch(sensor, &self));
+            }
+            (_, SensorReading::Xyz(x, y, z)) => json!({ "xyz": { "x": x, "y": y, "z": z } }),
+            _ => return Err(mismatch(sensor, &self)),
+        };
+        Ok(reading)
+    }
+}
+
+fn mismatch(sensor: SensorType, reading: &SensorReading) -> WebDriverError {
+    WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+        "reading {:?} does not suit the {} sensor",
+        reading,
+        sensor.name()
+    )))
+}
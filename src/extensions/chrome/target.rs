@@ -0,0 +1,256 @@
+use serde::Deserialize;
+#[cfg(feature = "cdp-websocket")]
+use serde_json::{json, Value};
+#[cfg(feature = "cdp-websocket")]
+use std::time::Duration;
+
+#[cfg(feature = "cdp-websocket")]
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+#[cfg(feature = "cdp-websocket")]
+use crate::json::convert_json;
+#[cfg(feature = "cdp-websocket")]
+use crate::websocket::{EventFilter, WsClient};
+#[cfg(feature = "cdp-websocket")]
+use crate::WebDriverSession;
+#[cfg(feature = "cdp-websocket")]
+use std::sync::mpsc::Receiver;
+
+/// Information about a CDP target, such as a page, an out-of-process iframe
+/// or a worker, as returned by `Target.getTargets`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetInfo {
+    /// The target id.
+    pub target_id: String,
+    /// The target type, e.g. `page`, `iframe`, `service_worker` or `worker`.
+    #[serde(rename = "type")]
+    pub target_type: String,
+    /// The target title.
+    #[serde(default)]
+    pub title: String,
+    /// The target URL.
+    #[serde(default)]
+    pub url: String,
+    /// Whether any client is attached to the target.
+    #[serde(default)]
+    pub attached: bool,
+    /// The id of the target that opened this one, e.g. for popups.
+    #[serde(default)]
+    pub opener_id: Option<String>,
+    /// The browser context the target belongs to.
+    #[serde(default)]
+    pub browser_context_id: Option<String>,
+}
+
+impl TargetInfo {
+    /// Returns true if the target is a page, i.e. a tab or popup window.
+    pub fn is_page(&self) -> bool {
+        self.target_type == "page"
+    }
+
+    /// Returns true if the target is an out-of-process iframe.
+    pub fn is_iframe(&self) -> bool {
+        self.target_type == "iframe"
+    }
+
+    /// Returns true if the target is a dedicated, shared or service worker.
+    pub fn is_worker(&self) -> bool {
+        self.target_type.ends_with("worker")
+    }
+}
+
+/// Find the browser-level DevTools websocket URL for the session.
+#[cfg(feature = "cdp-websocket")]
+pub(crate) fn browser_websocket_url(session: &WebDriverSession) -> WebDriverResult<String> {
+    let caps = session.capabilities();
+    // Selenium Grid proxies the websocket and advertises it directly.
+    if let Some(url) = caps["se:cdp"].as_str() {
+        return Ok(url.to_string());
+    }
+
+    let address = caps["goog:chromeOptions"]["debuggerAddress"]
+        .as_str()
+        .or_else(|| caps["ms:edgeOptions"]["debuggerAddress"].as_str())
+        .ok_or_else(|| {
+            WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "the session capabilities do not include a DevTools debuggerAddress",
+            ))
+        })?;
+    let version: Value =
+        reqwest::blocking::get(format!("http://{}/json/version", address))?.json()?;
+    match version["webSocketDebuggerUrl"].as_str() {
+        Some(url) => Ok(url.to_string()),
+        None => Err(WebDriverError::UnknownResponse(format!(
+            "no webSocketDebuggerUrl in DevTools version info: {}",
+            version
+        ))),
+    }
+}
+
+/// A direct connection to the browser's DevTools websocket. Only available
+/// with the `cdp-websocket` feature.
+///
+/// Unlike [ChromeDevTools](struct.ChromeDevTools.html), which sends every
+/// command to the top-level page via chromedriver, a CdpConnection can attach
+/// to any target, so commands can be run in out-of-process iframes, service
+/// workers and popup windows.
+///
+/// Use [ChromeDevTools::connect()](struct.ChromeDevTools.html#method.connect)
+/// to create one.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::chrome::ChromeDevTools;
+/// use serde_json::json;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.get("http://webappdemo")?;
+///
+/// let cdp = ChromeDevTools::new(driver.session()).connect()?;
+/// for target in cdp.targets()?.iter().filter(|t| t.is_iframe()) {
+///     let frame = cdp.attach_to_target(&target.target_id)?;
+///     let ret = frame.execute_cdp_with_params(
+///         "Runtime.evaluate",
+///         json!({"expression": "document.title", "returnByValue": true}),
+///     )?;
+///     println!("{}: {}", target.url, ret["result"]["value"]);
+///     frame.detach()?;
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(feature = "cdp-websocket")]
+pub struct CdpConnection {
+    client: WsClient,
+    command_timeout: Duration,
+}
+
+#[cfg(feature = "cdp-websocket")]
+impl std::fmt::Debug for CdpConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CdpConnection").field("command_timeout", &self.command_timeout).finish()
+    }
+}
+
+#[cfg(feature = "cdp-websocket")]
+impl CdpConnection {
+    /// Connect to the specified DevTools websocket URL.
+    pub fn connect(url: &str) -> WebDriverResult<Self> {
+        Ok(Self {
+            client: WsClient::connect(url)?,
+            command_timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Set how long to wait for the response to a command. The default is 30 seconds.
+    pub fn set_command_timeout(&mut self, timeout: Duration) {
+        self.command_timeout = timeout;
+    }
+
+    pub(crate) fn send(
+        &self,
+        session_id: Option<&str>,
+        cmd: &str,
+        cmd_args: Value,
+    ) -> WebDriverResult<Value> {
+        let mut message = json!({"method": cmd, "params": cmd_args});
+        if let Some(session_id) = session_id {
+            message["sessionId"] = Value::from(session_id);
+        }
+        let response = self.client.request(message, self.command_timeout)?;
+        if let Some(error) = response.get("error") {
+            return Err(WebDriverError::UnknownResponse(format!(
+                "{} failed: {}",
+                cmd,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Receive all future events that match the filter.
+    pub(crate) fn listen(&self, filter: EventFilter) -> Receiver<Value> {
+        self.client.listen(filter)
+    }
+
+    /// Execute the specified browser-level command without parameters.
+    pub fn execute_cdp(&self, cmd: &str) -> WebDriverResult<Value> {
+        self.execute_cdp_with_params(cmd, json!({}))
+    }
+
+    /// Execute the specified browser-level command with the specified parameter(s).
+    pub fn execute_cdp_with_params(&self, cmd: &str, cmd_args: Value) -> WebDriverResult<Value> {
+        self.send(None, cmd, cmd_args)
+    }
+
+    /// List all targets: pages, iframes, workers and so on.
+    pub fn targets(&self) -> WebDriverResult<Vec<TargetInfo>> {
+        let v = self.execute_cdp("Target.getTargets")?;
+        convert_json("Target.getTargets", &v["targetInfos"])
+    }
+
+    /// Attach to the specified target, returning a session that runs
+    /// commands in that target.
+    pub fn attach_to_target(&self, target_id: &str) -> WebDriverResult<TargetSession<'_>> {
+        let v = self.execute_cdp_with_params(
+            "Target.attachToTarget",
+            json!({"targetId": target_id, "flatten": true}),
+        )?;
+        match v["sessionId"].as_str() {
+            Some(session_id) => Ok(TargetSession {
+                connection: self,
+                target_id: target_id.to_string(),
+                session_id: session_id.to_string(),
+            }),
+            None => Err(WebDriverError::UnknownResponse(format!(
+                "Target.attachToTarget returned no sessionId: {}",
+                v
+            ))),
+        }
+    }
+}
+
+/// A CDP session attached to a single target. See
+/// [CdpConnection::attach_to_target()](struct.CdpConnection.html#method.attach_to_target).
+#[cfg(feature = "cdp-websocket")]
+#[derive(Debug)]
+pub struct TargetSession<'c> {
+    connection: &'c CdpConnection,
+    target_id: String,
+    session_id: String,
+}
+
+#[cfg(feature = "cdp-websocket")]
+impl<'c> TargetSession<'c> {
+    /// The id of the attached target.
+    pub fn target_id(&self) -> &str {
+        &self.target_id
+    }
+
+    /// The CDP session id.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Execute the specified command in this target without parameters.
+    pub fn execute_cdp(&self, cmd: &str) -> WebDriverResult<Value> {
+        self.execute_cdp_with_params(cmd, json!({}))
+    }
+
+    /// Execute the specified command in this target with the specified parameter(s).
+    pub fn execute_cdp_with_params(&self, cmd: &str, cmd_args: Value) -> WebDriverResult<Value> {
+        self.connection.send(Some(&self.session_id), cmd, cmd_args)
+    }
+
+    /// Detach from the target.
+    pub fn detach(self) -> WebDriverResult<()> {
+        self.connection.execute_cdp_with_params(
+            "Target.detachFromTarget",
+            json!({ "sessionId": self.session_id }),
+        )?;
+        Ok(())
+    }
+}
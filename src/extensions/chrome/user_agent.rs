@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// A browser brand and version, as reported in the `Sec-CH-UA` client hints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Brand {
+    /// The brand name, e.g. `Google Chrome`.
+    pub brand: String,
+    /// The brand version, e.g. `120` or `120.0.6099.109`.
+    pub version: String,
+}
+
+impl Brand {
+    /// Create a new Brand.
+    pub fn new(brand: &str, version: &str) -> Self {
+        Self {
+            brand: brand.to_string(),
+            version: version.to_string(),
+        }
+    }
+}
+
+/// User agent client hints, reported via `navigator.userAgentData` and the
+/// `Sec-CH-UA-*` headers.
+///
+/// Sites increasingly read the client hints rather than the user agent
+/// string, so overriding one without the other is easily detected.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::extensions::chrome::{Brand, UserAgentMetadata};
+///
+/// let metadata = UserAgentMetadata {
+///     platform: "Android".to_string(),
+///     mobile: true,
+///     brands: vec![Brand::new("Chromium", "120"), Brand::new("Google Chrome", "120")],
+///     model: "Pixel 7".to_string(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgentMetadata {
+    /// The brands, with major versions only.
+    pub brands: Vec<Brand>,
+    /// The brands, with full versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_version_list: Option<Vec<Brand>>,
+    /// The platform, e.g. `Windows`, `macOS`, `Linux` or `Android`.
+    pub platform: String,
+    /// The platform version, e.g. `10.0.0`.
+    pub platform_version: String,
+    /// The CPU architecture, e.g. `x86` or `arm`.
+    pub architecture: String,
+    /// The device model, for mobile devices.
+    pub model: String,
+    /// Whether the device is a mobile device.
+    pub mobile: bool,
+    /// The CPU bitness, e.g. `64`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitness: Option<String>,
+}
@@ -0,0 +1,435 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::WebDriverResult;
+use crate::extensions::chrome::NetworkEvent;
+
+/// Phase timings for a single request, in milliseconds.
+///
+/// Phases are None if they did not apply to the request, e.g. the DNS and
+/// connect phases are None when an existing connection was reused.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestTiming {
+    /// The CDP request id.
+    pub request_id: String,
+    /// The request URL.
+    pub url: String,
+    /// The HTTP method.
+    pub method: String,
+    /// The resource type, e.g. `XHR`, `Fetch` or `Document`.
+    pub resource_type: String,
+    /// The HTTP status code, if a response was received.
+    pub status: Option<u16>,
+    /// When the request started, relative to the first request in the waterfall.
+    pub start_ms: f64,
+    /// Time spent resolving the host name.
+    pub dns_ms: Option<f64>,
+    /// Time spent establishing the connection, including the TLS handshake.
+    pub connect_ms: Option<f64>,
+    /// Time spent on the TLS handshake.
+    pub tls_ms: Option<f64>,
+    /// Time from sending the request until the response headers were received.
+    pub ttfb_ms: Option<f64>,
+    /// Time spent downloading the response body.
+    pub download_ms: Option<f64>,
+    /// Time from the start of the request until it finished or failed.
+    pub total_ms: Option<f64>,
+    /// The number of bytes received over the network, including headers.
+    pub bytes: u64,
+    /// True if the request failed.
+    pub failed: bool,
+}
+
+/// Summary statistics for a [Waterfall](struct.Waterfall.html).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaterfallSummary {
+    /// The number of requests.
+    pub request_count: usize,
+    /// The number of failed requests.
+    pub failed_count: usize,
+    /// The total number of bytes received.
+    pub total_bytes: u64,
+    /// Time from the start of the first request until the last request finished.
+    pub duration_ms: f64,
+    /// The slowest requests, slowest first.
+    pub slowest: Vec<RequestTiming>,
+}
+
+/// Per-request phase timings built from the events captured by a
+/// [NetworkMonitor](struct.NetworkMonitor.html).
+///
+/// # Example
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::chrome::NetworkMonitor;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let mut caps = DesiredCapabilities::chrome();
+/// NetworkMonitor::enable_logging(&mut caps)?;
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// let monitor = NetworkMonitor::new(driver.session());
+/// driver.get("http://webappdemo")?;
+///
+/// let waterfall = monitor.waterfall()?;
+/// let summary = waterfall.summary(5);
+/// println!("{} requests, {} bytes", summary.request_count, summary.total_bytes);
+/// let csv = waterfall.to_csv();
+/// assert!(csv.starts_with("request_id,url,"));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Waterfall {
+    /// All requests, in the order they started.
+    pub requests: Vec<RequestTiming>,
+}
+
+#[derive(Default)]
+struct RequestEvents<'a> {
+    sent: Option<&'a Value>,
+    response: Option<&'a Value>,
+    finished: Option<&'a Value>,
+    failed: Option<&'a Value>,
+}
+
+/// Return the duration between two timing offsets, or None if either is unset.
+fn phase(start: f64, end: f64) -> Option<f64> {
+    if start >= 0.0 && end >= start {
+        Some(end - start)
+    } else {
+        None
+    }
+}
+
+impl Waterfall {
+    /// Build a waterfall from CDP `Network.*` events.
+    pub fn from_events(events: &[NetworkEvent]) -> Self {
+        let mut order = Vec::new();
+        let mut by_id: HashMap<&str, RequestEvents> = HashMap::new();
+        for event in events {
+            let id = match event.request_id() {
+                Some(x) => x,
+                None => continue,
+            };
+            let entry = by_id.entry(id).or_insert_with(|| {
+                order.push(id);
+                RequestEvents::default()
+            });
+            match event.method.as_str() {
+                // Redirects reuse the request id; keep the first request.
+                "Network.requestWillBeSent" => {
+                    entry.sent.get_or_insert(&event.params);
+                }
+                "Network.responseReceived" => entry.response = Some(&event.params),
+                "Network.loadingFinished" => entry.finished = Some(&event.params),
+                "Network.loadingFailed" => entry.failed = Some(&event.params),
+                _ => {}
+            }
+        }
+
+        let mut requests: Vec<RequestTiming> = order
+            .into_iter()
+            .filter_map(|id| {
+                let events = &by_id[id];
+                let sent = events.sent?;
+                let start = sent["timestamp"].as_f64().unwrap_or_default() * 1000.0;
+                let response = events.response.map(|x| &x["response"]);
+                let timing = response.map(|x| &x["timing"]).filter(|x| x.is_object());
+                let end = events
+                    .finished
+                    .or(events.failed)
+                    .and_then(|x| x["timestamp"].as_f64())
+                    .map(|x| x * 1000.0);
+
+                let t = |name: &str| timing.and_then(|x| x[name].as_f64()).unwrap_or(-1.0);
+                let request_time =
+                    timing.and_then(|x| x["requestTime"].as_f64()).map(|x| x * 1000.0);
+                let download_ms = match (request_time, end, events.finished) {
+                    (Some(request_time), Some(end), Some(_)) => {
+                        phase(request_time + t("receiveHeadersEnd"), end)
+                    }
+                    _ => None,
+                };
+
+                Some(RequestTiming {
+                    request_id: id.to_string(),
+                    url: sent["request"]["url"].as_str().unwrap_or_default().to_string(),
+                    method: sent["request"]["method"].as_str().unwrap_or_default().to_string(),
+                    resource_type: sent["type"].as_str().unwrap_or_default().to_string(),
+                    status: response.and_then(|x| x["status"].as_u64()).map(|x| x as u16),
+                    start_ms: start,
+                    dns_ms: phase(t("dnsStart"), t("dnsEnd")),
+                    connect_ms: phase(t("connectStart"), t("connectEnd")),
+                    tls_ms: phase(t("sslStart"), t("sslEnd")),
+                    ttfb_ms: phase(t("sendStart"), t("receiveHeadersEnd")),
+                    download_ms,
+                    total_ms: end.and_then(|end| phase(start, end)),
+                    bytes: events
+                        .finished
+                        .and_then(|x| x["encodedDataLength"].as_f64())
+                        .unwrap_or_default() as u64,
+                    failed: events.failed.is_some(),
+                })
+            })
+            .collect();
+
+        requests.sort_by(|a, b| a.start_ms.partial_cmp(&b.start_ms).unwrap_or(Ordering::Equal));
+        if let Some(first) = requests.first().map(|x| x.start_ms) {
+            for request in &mut requests {
+                request.start_ms -= first;
+            }
+        }
+
+        Self {
+            requests,
+        }
+    }
+
+    /// Return the specified number of slowest requests, slowest first.
+    /// Requests that did not finish are not included.
+    pub fn slowest(&self, count: usize) -> Vec<RequestTiming> {
+        let mut requests: Vec<&RequestTiming> =
+            self.requests.iter().filter(|x| x.total_ms.is_some()).collect();
+        requests.sort_by(|a, b| b.total_ms.partial_cmp(&a.total_ms).unwrap_or(Ordering::Equal));
+        requests.into_iter().take(count).cloned().collect()
+    }
+
+    /// Summarize the waterfall, including the specified number of slowest requests.
+    pub fn summary(&self, slowest: usize) -> WaterfallSummary {
+        WaterfallSummary {
+            request_count: self.requests.len(),
+            failed_count: self.requests.iter().filter(|x| x.failed).count(),
+            total_bytes: self.requests.iter().map(|x| x.bytes).sum(),
+            duration_ms: self
+                .requests
+                .iter()
+                .map(|x| x.start_ms + x.total_ms.unwrap_or_default())
+                .fold(0.0, f64::max),
+            slowest: self.slowest(slowest),
+        }
+    }
+
+    /// Export the waterfall as CSV, with one row per request.
+    pub fn to_csv(&self) -> String {
+        fn field(s: &str) -> String {
+            if s.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_string()
+            }
+        }
+        fn ms(v: Option<f64>) -> String {
+            v.map(|x| format!("{:.3}", x)).unwrap_or_default()
+        }
+
+        let mut csv = String::from(
+            "request_id,url,method,resource_type,status,start_ms,dns_ms,connect_ms,tls_ms,\
+             ttfb_ms,download_ms,total_ms,bytes,failed\n",
+        );
+        for r in &self.requests {
+            let row = [
+                field(&r.request_id),
+                field(&r.url),
+                field(&r.method),
+                field(&r.resource_type),
+                r.status.map(|x| x.to_string()).unwrap_or_default(),
+                ms(Some(r.start_ms)),
+                ms(r.dns_ms),
+                ms(r.connect_ms),
+                ms(r.tls_ms),
+                ms(r.ttfb_ms),
+                ms(r.download_ms),
+                ms(r.total_ms),
+                r.bytes.to_string(),
+                r.failed.to_string(),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Export the waterfall and a summary of the 10 slowest requests as JSON.
+    pub fn to_json(&self) -> WebDriverResult<String> {
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "requests": self.requests,
+            "summary": self.summary(10),
+        }))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(method: &str, params: Value) -> NetworkEvent {
+        NetworkEvent {
+            method: method.to_string(),
+            params,
+            timestamp: 0,
+        }
+    }
+
+    fn sent(id: &str, url: &str, timestamp: f64) -> NetworkEvent {
+        event(
+            "Network.requestWillBeSent",
+            json!({
+                "requestId": id,
+                "timestamp": timestamp,
+                "type": "Document",
+                "request": { "url": url, "method": "GET" }
+            }),
+        )
+    }
+
+    fn finished(id: &str, timestamp: f64, bytes: u64) -> NetworkEvent {
+        event(
+            "Network.loadingFinished",
+            json!({ "requestId": id, "timestamp": timestamp, "encodedDataLength": bytes }),
+        )
+    }
+
+    #[test]
+    fn phases() {
+        let events = vec![
+            sent("1", "https://example.com/", 100.5),
+            event(
+                "Network.responseReceived",
+                json!({
+                    "requestId": "1",
+                    "response": {
+                        "status": 200,
+                        "timing": {
+                            "requestTime": 100.5,
+                            "dnsStart": 0.0,
+                            "dnsEnd": 5.0,
+                            "connectStart": 5.0,
+                            "connectEnd": 30.0,
+                            "sslStart": 10.0,
+                            "sslEnd": 30.0,
+                            "sendStart": 31.0,
+                            "receiveHeadersEnd": 80.0
+                        }
+                    }
+                }),
+            ),
+            finished("1", 100.75, 1234),
+        ];
+        let waterfall = Waterfall::from_events(&events);
+        assert_eq!(waterfall.requests.len(), 1);
+        let r = &waterfall.requests[0];
+        assert_eq!(r.url, "https://example.com/");
+        assert_eq!(r.method, "GET");
+        assert_eq!(r.resource_type, "Document");
+        assert_eq!(r.status, Some(200));
+        assert_eq!(r.start_ms, 0.0);
+        assert_eq!(r.dns_ms, Some(5.0));
+        assert_eq!(r.connect_ms, Some(25.0));
+        assert_eq!(r.tls_ms, Some(20.0));
+        assert_eq!(r.ttfb_ms, Some(49.0));
+        assert_eq!(r.download_ms, Some(170.0));
+        assert_eq!(r.total_ms, Some(250.0));
+        assert_eq!(r.bytes, 1234);
+        assert!(!r.failed);
+    }
+
+    #[test]
+    fn reused_connection_and_failures() {
+        let events = vec![
+            sent("2", "https://example.com/app.js", 11.0),
+            sent("1", "https://example.com/", 10.0),
+            event(
+                "Network.responseReceived",
+                json!({
+                    "requestId": "1",
+                    "response": {
+                        "status": 304,
+                        "timing": {
+                            "requestTime": 10.0,
+                            "dnsStart": -1.0,
+                            "dnsEnd": -1.0,
+                            "connectStart": -1.0,
+                            "connectEnd": -1.0,
+                            "sslStart": -1.0,
+                            "sslEnd": -1.0,
+                            "sendStart": 1.0,
+                            "receiveHeadersEnd": 2.0
+                        }
+                    }
+                }),
+            ),
+            finished("1", 10.5, 100),
+            event("Network.loadingFailed", json!({ "requestId": "2", "timestamp": 12.0 })),
+            // Never finished, and events without a request are ignored.
+            sent("3", "https://example.com/slow", 12.0),
+            event("Network.responseReceived", json!({ "requestId": "4" })),
+            event("Network.dataReceived", json!({})),
+        ];
+        let waterfall = Waterfall::from_events(&events);
+        let ids: Vec<&str> = waterfall.requests.iter().map(|x| x.request_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        let starts: Vec<f64> = waterfall.requests.iter().map(|x| x.start_ms).collect();
+        assert_eq!(starts, vec![0.0, 1000.0, 2000.0]);
+
+        let r = &waterfall.requests[0];
+        assert_eq!((r.dns_ms, r.connect_ms, r.tls_ms), (None, None, None));
+        assert_eq!(r.ttfb_ms, Some(1.0));
+
+        let r = &waterfall.requests[1];
+        assert!(r.failed);
+        assert_eq!(r.status, None);
+        assert_eq!(r.total_ms, Some(1000.0));
+        assert_eq!(r.download_ms, None);
+        assert_eq!(r.bytes, 0);
+
+        let r = &waterfall.requests[2];
+        assert_eq!(r.total_ms, None);
+
+        let summary = waterfall.summary(5);
+        assert_eq!(summary.request_count, 3);
+        assert_eq!(summary.failed_count, 1);
+        assert_eq!(summary.total_bytes, 100);
+        assert_eq!(summary.duration_ms, 2000.0);
+        let slowest: Vec<&str> = summary.slowest.iter().map(|x| x.request_id.as_str()).collect();
+        assert_eq!(slowest, vec!["2", "1"]);
+    }
+
+    #[test]
+    fn redirects_keep_first_request() {
+        let events = vec![
+            sent("1", "http://example.com/old", 20.0),
+            sent("1", "https://example.com/new", 20.25),
+            finished("1", 21.0, 10),
+        ];
+        let waterfall = Waterfall::from_events(&events);
+        assert_eq!(waterfall.requests.len(), 1);
+        assert_eq!(waterfall.requests[0].url, "http://example.com/old");
+        assert_eq!(waterfall.requests[0].total_ms, Some(1000.0));
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let events = vec![
+            sent("1", "https://example.com/a,b", 1.0),
+            sent("2", "https://example.com/\"quoted\"", 1.0),
+            finished("2", 1.5, 7),
+        ];
+        let csv = Waterfall::from_events(&events).to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "request_id,url,method,resource_type,status,start_ms,dns_ms,connect_ms,tls_ms,\
+             ttfb_ms,download_ms,total_ms,bytes,failed"
+        );
+        assert_eq!(lines[1], "1,\"https://example.com/a,b\",GET,Document,,0.000,,,,,,,0,false");
+        assert_eq!(
+            lines[2],
+            "2,\"https://example.com/\"\"quoted\"\"\",GET,Document,,0.000,,,,,,500.000,7,false"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use serde_json::json;
+use thirtyfour::FirefoxCapabilities;
+
+use crate::common::capabilities::desiredcapabilities::Capabilities;
+
+/// Extra settings for [FirefoxCapabilities](../../struct.FirefoxCapabilities.html).
+pub trait FirefoxCapabilitiesExt {
+    /// Allow pages to use the camera and microphone without a permission
+    /// prompt, optionally replacing them with fake devices so that no real
+    /// hardware is needed. Other preferences already set are kept.
+    ///
+    /// Firefox cannot grant these permissions once the session has started,
+    /// so this must be done beforehand.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::firefox::FirefoxCapabilitiesExt;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// caps.allow_media(true);
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn allow_media(&mut self, fake_devices: bool);
+
+    /// Turn off the browser's own dialogs and prompts, which WebDriver
+    /// cannot see or dismiss and which can leave the session hanging: the
+    /// print dialog, the save-password prompt, the translations popup, the
+    /// download prompt, the default-browser check and the first-run and
+    /// privacy notice pages. Other preferences already set are kept.
+    ///
+    /// With the print dialog turned off, `window.print()` prints straight to
+    /// the default printer, if there is one.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::firefox::FirefoxCapabilitiesExt;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// caps.suppress_native_ui();
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn suppress_native_ui(&mut self);
+}
+
+impl FirefoxCapabilitiesExt for FirefoxCapabilities {
+    fn allow_media(&mut self, fake_devices: bool) {
+        self.update(json!({
+            "moz:firefoxOptions": {
+                "prefs": {
+                    "media.navigator.permission.disabled": true,
+                    "media.navigator.streams.fake": fake_devices,
+                }
+            }
+        }));
+    }
+
+    fn suppress_native_ui(&mut self) {
+        self.update(json!({
+            "moz:firefoxOptions": {
+                "prefs": {
+                    "print.always_print_silent": true,
+                    "print.show_print_progress": false,
+                    "signon.rememberSignons": false,
+                    "browser.translations.enable": false,
+                    "browser.translations.automaticallyPopup": false,
+                    "browser.download.useDownloadDir": true,
+                    "browser.download.always_ask_before_handling_new_types": false,
+                    "browser.shell.checkDefaultBrowser": false,
+                    "browser.startup.homepage_override.mstone": "ignore",
+                    "browser.aboutwelcome.enabled": false,
+                    "datareporting.policy.dataSubmissionPolicyBypassNotification": true,
+                    "toolkit.telemetry.reportingpolicy.firstRun": false,
+                }
+            }
+        }));
+    }
+}
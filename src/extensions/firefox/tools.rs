@@ -0,0 +1,189 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use base64::decode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thirtyfour::{ExtensionCommand, RequestMethod};
+
+use crate::error::WebDriverResult;
+use crate::json::convert_json;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// The context that commands are run in, as set with
+/// [FirefoxTools::set_context()](struct.FirefoxTools.html#method.set_context).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirefoxContext {
+    /// The browser chrome, i.e. the privileged UI around the page. Scripts
+    /// run with chrome privileges, so this requires the
+    /// `-remote-allow-system-access` argument on recent versions of Firefox.
+    Chrome,
+    /// The content of the page. This is the default.
+    Content,
+}
+
+/// The geckodriver `moz:` extension commands.
+enum FirefoxCommand {
+    InstallAddon {
+        path: String,
+        temporary: Option<bool>,
+    },
+    UninstallAddon(String),
+    FullScreenshot,
+    GetContext,
+    SetContext(FirefoxContext),
+}
+
+impl ExtensionCommand for FirefoxCommand {
+    fn parameters_json(&self) -> Option<Value> {
+        match self {
+            FirefoxCommand::InstallAddon {
+                path,
+                temporary,
+            } => Some(json!({ "path": path, "temporary": temporary })),
+            FirefoxCommand::UninstallAddon(id) => Some(json!({ "id": id })),
+            FirefoxCommand::SetContext(context) => Some(json!({ "context": context })),
+            FirefoxCommand::FullScreenshot | FirefoxCommand::GetContext => None,
+        }
+    }
+
+    fn method(&self) -> RequestMethod {
+        match self {
+            FirefoxCommand::FullScreenshot | FirefoxCommand::GetContext => RequestMethod::Get,
+            _ => RequestMethod::Post,
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        let endpoint = match self {
+            FirefoxCommand::InstallAddon {
+                ..
+            } => "/moz/addon/install",
+            FirefoxCommand::UninstallAddon(_) => "/moz/addon/uninstall",
+            FirefoxCommand::FullScreenshot => "/moz/screenshot/full",
+            FirefoxCommand::GetContext | FirefoxCommand::SetContext(_) => "/moz/context",
+        };
+        String::from(endpoint)
+    }
+}
+
+/// The FirefoxTools struct allows you to use the Firefox-specific commands
+/// provided by geckodriver.
+///
+/// # Example
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::extensions::firefox::FirefoxTools;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::firefox();
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+///
+/// let tools = FirefoxTools::new(driver.session());
+/// driver.get("http://webappdemo")?;
+/// tools.full_screenshot_as_png_file(std::path::Path::new("page.png"))?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FirefoxTools<'a> {
+    pub session: &'a WebDriverSession,
+}
+
+impl<'a> FirefoxTools<'a> {
+    /// Create a new FirefoxTools struct.
+    pub fn new(session: &'a WebDriverSession) -> Self {
+        Self {
+            session,
+        }
+    }
+
+    /// Install the addon at the specified path, which must be on the
+    /// machine running geckodriver. Temporary addons do not need to be
+    /// signed, and are removed when the browser closes. Returns the id of
+    /// the installed addon.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::firefox::FirefoxTools;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::firefox();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let tools = FirefoxTools::new(driver.session());
+    /// let id = tools.install_addon("/path/to/addon.xpi", Some(true))?;
+    /// tools.uninstall_addon(&id)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn install_addon(&self, path: &str, temporary: Option<bool>) -> WebDriverResult<String> {
+        let v = self.session.extension_command(FirefoxCommand::InstallAddon {
+            path: path.to_string(),
+            temporary,
+        })?;
+        convert_json("Install Addon", &v)
+    }
+
+    /// Uninstall the addon with the specified id.
+    pub fn uninstall_addon(&self, id: &str) -> WebDriverResult<()> {
+        self.session.extension_command(FirefoxCommand::UninstallAddon(id.to_string()))?;
+        Ok(())
+    }
+
+    /// Take a screenshot of the whole page, not just the part in the
+    /// viewport, and return it as base64-encoded PNG.
+    pub fn full_screenshot_as_base64(&self) -> WebDriverResult<String> {
+        let v = self.session.extension_command(FirefoxCommand::FullScreenshot)?;
+        convert_json("Take Full Screenshot", &v)
+    }
+
+    /// Take a screenshot of the whole page, not just the part in the
+    /// viewport, and return it as PNG bytes.
+    pub fn full_screenshot(&self) -> WebDriverResult<Vec<u8>> {
+        let s = self.full_screenshot_as_base64()?;
+        Ok(decode(&s)?)
+    }
+
+    /// Take a screenshot of the whole page, not just the part in the
+    /// viewport, and write it to the specified PNG file.
+    pub fn full_screenshot_as_png_file(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.full_screenshot()?;
+        let mut file = File::create(path)?;
+        file.write_all(&png)?;
+        Ok(())
+    }
+
+    /// Get the context that commands are currently run in.
+    pub fn context(&self) -> WebDriverResult<FirefoxContext> {
+        let v = self.session.extension_command(FirefoxCommand::GetContext)?;
+        convert_json("Get Context", &v)
+    }
+
+    /// Set the context that commands are run in, e.g. to find elements in
+    /// and run scripts against the browser UI. Remember to set it back to
+    /// `Content` afterwards.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::extensions::firefox::{FirefoxContext, FirefoxTools};
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::firefox();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let tools = FirefoxTools::new(driver.session());
+    /// tools.set_context(FirefoxContext::Chrome)?;
+    /// let urlbar = driver.find_element(By::Id("urlbar-input"))?;
+    /// tools.set_context(FirefoxContext::Content)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_context(&self, context: FirefoxContext) -> WebDriverResult<()> {
+        self.session.extension_command(FirefoxCommand::SetContext(context))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::error::WebDriverResult;
+
+/// Content types by file extension. Anything else is served as
+/// `application/octet-stream`.
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("ico", "image/x-icon"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("wasm", "application/wasm"),
+    ("pdf", "application/pdf"),
+];
+
+#[derive(Debug, Clone)]
+struct Route {
+    content_type: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct Content {
+    routes: HashMap<String, Route>,
+    root: Option<PathBuf>,
+}
+
+fn lock(content: &Mutex<Content>) -> MutexGuard<'_, Content> {
+    content.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A tiny HTTP server for test fixtures, serving a directory and in-memory
+/// routes on a random port. Only available with the `fixture-server`
+/// feature.
+///
+/// The server only supports `GET` and `HEAD` requests, and is meant for
+/// loading test pages rather than for performance. It stops when dropped.
+/// Set it on a WebDriver with
+/// [WebDriver::set_fixture_server()](struct.GenericWebDriver.html#method.set_fixture_server)
+/// to stop it along with the driver, and to load fixtures with
+/// [get_fixture()](trait.WebDriverCommands.html#method.get_fixture).
+///
+/// By default the server listens on `127.0.0.1`, which is only reachable by
+/// a browser on the same machine. For a browser in a container or on a
+/// Selenium Grid node, bind to another address with `bind()` and set the
+/// host name the browser should use with `with_public_host()`.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::FixtureServer;
+///
+/// # fn main() -> thirtyfour_sync::error::WebDriverResult<()> {
+/// let server = FixtureServer::start()?.serve_dir("tests/fixtures");
+/// server.add_html("/widget.html", "<button>Click me</button>");
+/// println!("fixtures at {}", server.url("/widget.html"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FixtureServer {
+    address: SocketAddr,
+    base_url: String,
+    content: Arc<Mutex<Content>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FixtureServer {
+    /// Start a server on `127.0.0.1`, on a random free port.
+    pub fn start() -> WebDriverResult<Self> {
+        Self::bind("127.0.0.1:0")
+    }
+
+    /// Start a server on the specified address, e.g. `0.0.0.0:0` to accept
+    /// connections from other machines on a random free port.
+    pub fn bind(address: &str) -> WebDriverResult<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let content = Arc::new(Mutex::new(Content::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let content = content.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let content = content.clone();
+                        thread::spawn(move || handle_connection(stream, &content));
+                    }
+                }
+            })
+        };
+        Ok(Self {
+            address,
+            base_url: format!("http://{}", address),
+            content,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Use the specified host name, instead of the address the server is
+    /// bound to, in the URLs returned by `base_url()` and `url()`.
+    pub fn with_public_host(mut self, host: &str) -> Self {
+        self.base_url = format!("http://{}:{}", host, self.address.port());
+        self
+    }
+
+    /// Serve the files in the specified directory, for paths that have no
+    /// in-memory route. A request for a directory serves its `index.html`.
+    pub fn serve_dir<P: AsRef<Path>>(self, dir: P) -> Self {
+        lock(&self.content).root = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Serve the specified body at the specified path, replacing any
+    /// existing route for that path. Routes can be added while the server is
+    /// running.
+    pub fn add_route<B: Into<Vec<u8>>>(&self, path: &str, content_type: &str, body: B) {
+        let route = Route {
+            content_type: content_type.to_string(),
+            body: body.into(),
+        };
+        lock(&self.content).routes.insert(normalize_path(path), route);
+    }
+
+    /// Serve the specified HTML document at the specified path.
+    pub fn add_html(&self, path: &str, html: &str) {
+        self.add_route(path, "text/html; charset=utf-8", html);
+    }
+
+    /// The base URL of the server, e.g. `http://127.0.0.1:34567`, with no
+    /// trailing slash.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The URL of the specified path on the server.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, normalize_path(path))
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake up the accept loop so that it sees the stop flag.
+        let mut wake = self.address;
+        if wake.ip().is_unspecified() {
+            wake.set_ip([127, 0, 0, 1].into());
+        }
+        if TcpStream::connect(wake).is_ok() {
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)?;
+            // `from_str_radix()` would also accept a sign, as in `%+1`.
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|x| x.to_str()).unwrap_or_default();
+    CONTENT_TYPES
+        .iter()
+        .find(|(x, _)| x.eq_ignore_ascii_case(extension))
+        .map(|(_, t)| *t)
+        .unwrap_or("application/octet-stream")
+}
+
+/// Find the response for the specified path, from the routes or the
+/// directory.
+fn find(content: &Mutex<Content>, path: &str) -> Option<Route> {
+    let (route, root) = {
+        let content = lock(content);
+        (content.routes.get(path).cloned(), content.root.clone())
+    };
+    if route.is_some() {
+        return route;
+    }
+
+    let mut file = root?;
+    let relative = Path::new(path.trim_start_matches('/'));
+    // Refuse to serve anything outside the directory.
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    file.push(relative);
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    let body = fs::read(&file).ok()?;
+    Some(Route {
+        content_type: content_type(&file).to_string(),
+        body,
+    })
+}
+
+fn handle_connection(stream: TcpStream, content: &Mutex<Content>) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Skip the headers; requests with bodies are not supported.
+    let mut header = String::new();
+    while reader.read_line(&mut header).map(|n| n > 2).unwrap_or(false) {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let (status, route) = if method != "GET" && method != "HEAD" {
+        ("405 Method Not Allowed", None)
+    } else {
+        match percent_decode(path).and_then(|p| find(content, &p)) {
+            Some(route) => ("200 OK", Some(route)),
+            None => ("404 Not Found", None),
+        }
+    };
+    let route = route.unwrap_or_else(|| Route {
+        content_type: "text/plain; charset=utf-8".to_string(),
+        body: status.as_bytes().to_vec(),
+    });
+
+    let mut stream = &stream;
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        route.content_type,
+        route.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    if method != "HEAD" {
+        let _ = stream.write_all(&route.body);
+    }
+    let _ = stream.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode_escapes() {
+        assert_eq!(percent_decode("/a%20b.html").as_deref(), Some("/a b.html"));
+        assert_eq!(percent_decode("/%2e%2E/x").as_deref(), Some("/../x"));
+        assert_eq!(percent_decode("/a%2fb").as_deref(), Some("/a/b"));
+        assert_eq!(percent_decode("/caf%C3%A9").as_deref(), Some("/caf\u{e9}"));
+    }
+
+    #[test]
+    fn percent_decode_rejects_malformed_escapes() {
+        assert_eq!(percent_decode("/a%"), None);
+        assert_eq!(percent_decode("/a%2"), None);
+        assert_eq!(percent_decode("/a%zz"), None);
+        assert_eq!(percent_decode("/a%+1"), None);
+        assert_eq!(percent_decode("/a%-1"), None);
+        assert_eq!(percent_decode("/a%\u{e9}"), None);
+        assert_eq!(percent_decode("/a%ff"), None);
+    }
+
+    fn fixture_dir() -> (PathBuf, Mutex<Content>) {
+        let dir =
+            std::env::temp_dir().join(format!("thirtyfour-fixture-server-{}", std::process::id()));
+        let root = dir.join("root");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(dir.join("secret.txt"), "secret").unwrap();
+        fs::write(root.join("sub").join("page.html"), "page").unwrap();
+        let content = Content {
+            routes: HashMap::new(),
+            root: Some(root),
+        };
+        (dir, Mutex::new(content))
+    }
+
+    fn serve(content: &Mutex<Content>, target: &str) -> Option<Vec<u8>> {
+        percent_decode(target).and_then(|p| find(content, &p)).map(|r| r.body)
+    }
+
+    #[test]
+    fn find_stays_inside_the_directory() {
+        let (dir, content) = fixture_dir();
+        assert_eq!(serve(&content, "/sub/page.html"), Some(b"page".to_vec()));
+        // An encoded slash inside a segment still names a file in the directory.
+        assert_eq!(serve(&content, "/sub%2fpage.html"), Some(b"page".to_vec()));
+        assert_eq!(serve(&content, "/../secret.txt"), None);
+        assert_eq!(serve(&content, "/%2e%2e/secret.txt"), None);
+        assert_eq!(serve(&content, "/sub/%2E%2E/%2e%2e/secret.txt"), None);
+        assert_eq!(serve(&content, "/sub%2f..%2f..%2fsecret.txt"), None);
+        assert_eq!(serve(&content, "/./sub/page.html"), None);
+        // Encoded absolute paths are served relative to the directory.
+        let secret = dir.join("secret.txt");
+        let absolute = secret.to_str().unwrap().replace('/', "%2f");
+        assert_eq!(serve(&content, &format!("/{}", absolute)), None);
+        assert_eq!(serve(&content, "/%2fsub%2fpage.html"), Some(b"page".to_vec()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use log::error;
+
+use crate::error::{no_such_element, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::query::without_implicit_wait;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession, WebElement};
+
+/// The selector for frame elements. Frame paths count frames in the order
+/// that this selector returns them.
+const FRAME_SELECTOR: &str = "iframe, frame";
+
+/// How deeply nested frames are searched.
+const MAX_FRAME_DEPTH: usize = 8;
+
+/// The frames to switch through, starting from the top-level page, to reach
+/// a nested browsing context. Each entry is the position of the frame among
+/// the `<iframe>` and `<frame>` elements of its parent document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FramePath {
+    indexes: Vec<usize>,
+}
+
+impl FramePath {
+    /// Create a FramePath from the position of each frame within its parent.
+    /// An empty path refers to the top-level page.
+    pub fn new(indexes: Vec<usize>) -> Self {
+        Self {
+            indexes,
+        }
+    }
+
+    /// The position of each frame within its parent document.
+    pub fn indexes(&self) -> &[usize] {
+        &self.indexes
+    }
+
+    /// Returns true if the path refers to the top-level page.
+    pub fn is_top_level(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Switch to the top-level page, then through each frame in the path.
+    pub(crate) fn switch_to(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+        session.switch_to().default_content()?;
+        for &index in &self.indexes {
+            let frames = session.find_elements(By::Css(FRAME_SELECTOR))?;
+            match frames.get(index) {
+                Some(frame) => session.switch_to().frame_element(frame)?,
+                None => {
+                    return Err(no_such_element(&format!(
+                        "frame {} of path {} no longer exists",
+                        index, self
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FramePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "top")?;
+        for index in &self.indexes {
+            write!(f, " > frame[{}]", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the session switched into a frame. When the guard is dropped, the
+/// session switches back to the top-level page.
+///
+/// Returned by
+/// [WebDriver::enter_frame()](trait.WebDriverCommands.html#method.enter_frame).
+#[derive(Debug)]
+pub struct FrameGuard<'a> {
+    session: &'a WebDriverSession,
+    path: FramePath,
+    active: bool,
+}
+
+impl<'a> FrameGuard<'a> {
+    pub(crate) fn enter(session: &'a WebDriverSession, path: FramePath) -> WebDriverResult<Self> {
+        path.switch_to(session)?;
+        Ok(Self {
+            session,
+            path,
+            active: true,
+        })
+    }
+
+    /// The path of the frame that this guard switched into.
+    pub fn path(&self) -> &FramePath {
+        &self.path
+    }
+
+    /// Switch back to the top-level page now, returning any error rather
+    /// than logging it.
+    pub fn leave(mut self) -> WebDriverResult<()> {
+        self.active = false;
+        self.session.switch_to().default_content()
+    }
+}
+
+impl<'a> Drop for FrameGuard<'a> {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        if let Err(e) = self.session.switch_to().default_content() {
+            error!("Failed to switch back to the top-level page: {:?}", e);
+        }
+    }
+}
+
+/// An element found by
+/// [WebDriver::find_element_anywhere()](trait.WebDriverCommands.html#method.find_element_anywhere),
+/// together with the frame that contains it.
+///
+/// The session stays switched into that frame, so that the element can be
+/// used, until this is dropped.
+#[derive(Debug)]
+pub struct FoundElement<'a> {
+    /// The element.
+    pub element: WebElement<'a>,
+    /// The guard that keeps the session switched into the element's frame.
+    pub guard: FrameGuard<'a>,
+}
+
+impl<'a> FoundElement<'a> {
+    /// The frames to switch through to reach the element. Use it with
+    /// [WebDriver::enter_frame()](trait.WebDriverCommands.html#method.enter_frame)
+    /// to return to the element's frame later.
+    pub fn path(&self) -> &FramePath {
+        self.guard.path()
+    }
+}
+
+/// Search the current browsing context and then, depth first, each of its
+/// frames. Returns the path to the first frame with a match, leaving the
+/// session switched into it.
+fn search<'a>(
+    session: &'a WebDriverSession,
+    by: &By,
+    path: &mut Vec<usize>,
+    searched: &mut usize,
+) -> WebDriverResult<Option<WebElement<'a>>> {
+    *searched += 1;
+    if let Some(elem) = session.find_elements(by.clone())?.into_iter().next() {
+        return Ok(Some(elem));
+    }
+    if path.len() >= MAX_FRAME_DEPTH {
+        return Ok(None);
+    }
+
+    let count = session.find_elements(By::Css(FRAME_SELECTOR))?.len();
+    for index in 0..count {
+        // Frames are found again each time, since switching back to the
+        // parent may invalidate the previous elements.
+        let frames = session.find_elements(By::Css(FRAME_SELECTOR))?;
+        let frame = match frames.get(index) {
+            Some(x) => x,
+            None => break,
+        };
+        if session.switch_to().frame_element(frame).is_err() {
+            continue;
+        }
+        path.push(index);
+        if let Some(elem) = search(session, by, path, searched)? {
+            return Ok(Some(elem));
+        }
+        path.pop();
+        session.switch_to().parent_frame()?;
+    }
+    Ok(None)
+}
+
+pub(crate) fn find_element_anywhere<'a>(
+    session: &'a WebDriverSession,
+    by: By,
+) -> WebDriverResult<FoundElement<'a>> {
+    session.switch_to().default_content()?;
+    let mut path = Vec::new();
+    let mut searched = 0;
+    let found = without_implicit_wait(session, || search(session, &by, &mut path, &mut searched));
+    match found {
+        Ok(Some(element)) => Ok(FoundElement {
+            element,
+            guard: FrameGuard {
+                session,
+                path: FramePath::new(path),
+                active: true,
+            },
+        }),
+        Ok(None) => {
+            session.switch_to().default_content()?;
+            let message = format!(
+                "no element matched {} in the page or its frames ({} documents searched)",
+                by, searched
+            );
+            Err(no_such_element(&with_page_context(session, &message)))
+        }
+        Err(e) => {
+            session.switch_to().default_content().ok();
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::webelement::convert_element_sync;
+use crate::{ScriptArgs, WebDriverSession, WebElement};
+
+/// The number of buttons on a gamepad with the standard mapping.
+const STANDARD_BUTTONS: usize = 17;
+
+/// The number of axes on a gamepad with the standard mapping.
+const STANDARD_AXES: usize = 4;
+
+/// The buttons and axes of a fake gamepad, for
+/// [WebDriver::connect_gamepad()](trait.WebDriverCommands.html#method.connect_gamepad).
+///
+/// Buttons are indexed as in the W3C standard mapping, e.g. 0 is the bottom
+/// face button (A on an Xbox controller) and 12 to 15 are the d-pad. Axes 0
+/// and 1 are the left stick, and 2 and 3 the right stick.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::GamepadState;
+///
+/// // Hold A while pushing the left stick fully right.
+/// let state = GamepadState::standard().with_button(0, 1.0).with_axis(0, 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GamepadState {
+    /// The value of each button, from 0.0 (released) to 1.0 (fully
+    /// pressed). Buttons at 0.5 or more are reported as pressed.
+    pub buttons: Vec<f64>,
+    /// The value of each axis, from -1.0 to 1.0.
+    pub axes: Vec<f64>,
+}
+
+impl Default for GamepadState {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl GamepadState {
+    /// A gamepad with the standard mapping, with all buttons released and
+    /// the sticks centred.
+    pub fn standard() -> Self {
+        Self {
+            buttons: vec![0.0; STANDARD_BUTTONS],
+            axes: vec![0.0; STANDARD_AXES],
+        }
+    }
+
+    /// Set the value of a button, adding buttons if needed.
+    pub fn with_button(mut self, index: usize, value: f64) -> Self {
+        if self.buttons.len() <= index {
+            self.buttons.resize(index + 1, 0.0);
+        }
+        self.buttons[index] = value;
+        self
+    }
+
+    /// Set the value of an axis, adding axes if needed.
+    pub fn with_axis(mut self, index: usize, value: f64) -> Self {
+        if self.axes.len() <= index {
+            self.axes.resize(index + 1, 0.0);
+        }
+        self.axes[index] = value;
+        self
+    }
+}
+
+/// Replaces `navigator.getGamepads()` with fake gamepads, and connects,
+/// updates or disconnects one of them, firing the `gamepadconnected` and
+/// `gamepaddisconnected` events as a real gamepad would. Returns false if
+/// the gamepad to update is not connected.
+const GAMEPAD_SCRIPT: &str = r#"
+    const [action, index, id, state] = arguments;
+    let pads = window.__thirtyfourGamepads;
+    if (!pads) {
+        pads = window.__thirtyfourGamepads = [];
+        Object.defineProperty(Navigator.prototype, "getGamepads", {
+            value: () => {
+                const list = [];
+                for (let i = 0; i < Math.max(4, pads.length); i++) {
+                    list.push(pads[i] && pads[i].connected ? snapshot(pads[i]) : null);
+                }
+                return list;
+            },
+            configurable: true,
+            writable: true,
+        });
+    }
+    function snapshot(pad) {
+        return {
+            id: pad.id,
+            index: pad.index,
+            connected: pad.connected,
+            mapping: "standard",
+            timestamp: pad.timestamp,
+            axes: pad.axes.slice(),
+            buttons: pad.buttons.map((value) => ({
+                pressed: value >= 0.5,
+                touched: value > 0,
+                value,
+            })),
+            vibrationActuator: null,
+        };
+    }
+    const fire = (type, pad) => {
+        const event = new Event(type);
+        Object.defineProperty(event, "gamepad", { value: snapshot(pad) });
+        window.dispatchEvent(event);
+    };
+
+    const pad = pads[index];
+    if (action === "connect") {
+        pads[index] = {
+            id,
+            index,
+            connected: true,
+            timestamp: performance.now(),
+            buttons: state.buttons,
+            axes: state.axes,
+        };
+        fire("gamepadconnected", pads[index]);
+        return true;
+    }
+    if (!pad || !pad.connected) return false;
+    if (action === "update") {
+        pad.buttons = state.buttons;
+        pad.axes = state.axes;
+        pad.timestamp = performance.now();
+    } else {
+        pad.connected = false;
+        fire("gamepaddisconnected", pad);
+    }
+    return true;
+"#;
+
+fn gamepad_action(
+    session: &WebDriverSession,
+    action: &str,
+    index: u32,
+    id: &str,
+    state: Option<&GamepadState>,
+) -> WebDriverResult<()> {
+    let mut args = ScriptArgs::new();
+    args.push(action)?;
+    args.push(index)?;
+    args.push(id)?;
+    args.push(state)?;
+    let done: bool = session.execute_script_with_args(GAMEPAD_SCRIPT, &args)?.convert()?;
+    if !done {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "no fake gamepad is connected at index {}",
+            index
+        ))));
+    }
+    Ok(())
+}
+
+pub(crate) fn connect_gamepad(
+    session: &WebDriverSession,
+    index: u32,
+    id: &str,
+    state: &GamepadState,
+) -> WebDriverResult<()> {
+    gamepad_action(session, "connect", index, id, Some(state))
+}
+
+pub(crate) fn set_gamepad_state(
+    session: &WebDriverSession,
+    index: u32,
+    state: &GamepadState,
+) -> WebDriverResult<()> {
+    gamepad_action(session, "update", index, "", Some(state))
+}
+
+pub(crate) fn disconnect_gamepad(session: &WebDriverSession, index: u32) -> WebDriverResult<()> {
+    gamepad_action(session, "disconnect", index, "", None)
+}
+
+pub(crate) fn pointer_lock_element(
+    session: &WebDriverSession,
+) -> WebDriverResult<Option<WebElement<'_>>> {
+    let value = session.execute_script("return document.pointerLockElement;")?.value().clone();
+    match value {
+        Value::Null => Ok(None),
+        v => convert_element_sync(session, &v).map(Some),
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+/// The center of an element, in both viewport and page coordinates.
+/// See [WebElement::center()](struct.WebElement.html#method.center).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementCenter {
+    /// The x coordinate relative to the viewport, as used by action chains.
+    pub viewport_x: f64,
+    /// The y coordinate relative to the viewport, as used by action chains.
+    pub viewport_y: f64,
+    /// The x coordinate relative to the top-left of the document.
+    pub page_x: f64,
+    /// The y coordinate relative to the top-left of the document.
+    pub page_y: f64,
+}
+
+/// The element's bounding client rect along with the viewport size and
+/// scroll position, fetched in a single script.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ViewportGeometry {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    viewport_width: f64,
+    viewport_height: f64,
+    scroll_x: f64,
+    scroll_y: f64,
+}
+
+pub(crate) const VIEWPORT_GEOMETRY_SCRIPT: &str = r#"
+    const r = arguments[0].getBoundingClientRect();
+    return {
+        left: r.left,
+        top: r.top,
+        width: r.width,
+        height: r.height,
+        viewportWidth: document.documentElement.clientWidth || window.innerWidth,
+        viewportHeight: document.documentElement.clientHeight || window.innerHeight,
+        scrollX: window.scrollX,
+        scrollY: window.scrollY
+    };
+"#;
+
+impl ViewportGeometry {
+    pub(crate) fn center(&self) -> ElementCenter {
+        let viewport_x = self.left + self.width / 2.0;
+        let viewport_y = self.top + self.height / 2.0;
+        ElementCenter {
+            viewport_x,
+            viewport_y,
+            page_x: viewport_x + self.scroll_x,
+            page_y: viewport_y + self.scroll_y,
+        }
+    }
+
+    /// The fraction of the element's area that is inside the viewport.
+    pub(crate) fn intersection_ratio(&self) -> f64 {
+        let area = self.width * self.height;
+        if area <= 0.0 {
+            return 0.0;
+        }
+        let visible_width = (self.left + self.width).min(self.viewport_width) - self.left.max(0.0);
+        let visible_height = (self.top + self.height).min(self.viewport_height) - self.top.max(0.0);
+        (visible_width.max(0.0) * visible_height.max(0.0) / area).min(1.0)
+    }
+
+    /// Returns true if any part of the element is inside the viewport.
+    pub(crate) fn is_in_viewport(&self) -> bool {
+        self.left < self.viewport_width
+            && self.top < self.viewport_height
+            && self.left + self.width > 0.0
+            && self.top + self.height > 0.0
+    }
+}
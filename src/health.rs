@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use crate::common::command::Command;
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// Options for
+/// [WebDriver::health_check()](trait.WebDriverCommands.html#method.health_check).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthCheckOptions {
+    navigate: bool,
+}
+
+impl HealthCheckOptions {
+    /// Create a new HealthCheckOptions that checks the server and the
+    /// session, without navigating.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also check that the browser can load `about:blank`. This navigates
+    /// away from the current page.
+    pub fn with_navigation(mut self, navigate: bool) -> Self {
+        self.navigate = navigate;
+        self
+    }
+}
+
+/// The outcome of a single health check probe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthProbe {
+    /// How long the probe took.
+    pub latency: Duration,
+    /// The error message, if the probe failed.
+    pub error: Option<String>,
+}
+
+impl HealthProbe {
+    fn run<T, F>(f: F) -> (Self, Option<T>)
+    where
+        F: FnOnce() -> WebDriverResult<T>,
+    {
+        let started = Instant::now();
+        let result = f();
+        let latency = started.elapsed();
+        match result {
+            Ok(x) => (
+                Self {
+                    latency,
+                    error: None,
+                },
+                Some(x),
+            ),
+            Err(e) => (
+                Self {
+                    latency,
+                    error: Some(e.to_string()),
+                },
+                None,
+            ),
+        }
+    }
+
+    /// Returns true if the probe succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The result of
+/// [WebDriver::health_check()](trait.WebDriverCommands.html#method.health_check).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthReport {
+    /// The request to the server's `/status` endpoint.
+    pub status: HealthProbe,
+    /// Whether the server reported that it is ready to create new sessions.
+    pub server_ready: bool,
+    /// The message reported by the server, if any.
+    pub server_message: String,
+    /// A request within the session, to check that it is still alive.
+    pub session: HealthProbe,
+    /// Loading `about:blank`, if navigation was enabled.
+    pub navigation: Option<HealthProbe>,
+}
+
+impl HealthReport {
+    /// Returns true if the server and session responded, and navigation
+    /// succeeded if it was checked.
+    ///
+    /// Whether the server is ready for new sessions is not considered, since
+    /// a server that is busy with this session may not accept any more.
+    pub fn is_healthy(&self) -> bool {
+        self.status.is_ok()
+            && self.session.is_ok()
+            && self.navigation.as_ref().map(|n| n.is_ok()).unwrap_or(true)
+    }
+}
+
+pub(crate) fn health_check(
+    session: &WebDriverSession,
+    options: &HealthCheckOptions,
+) -> HealthReport {
+    let (status, value) = HealthProbe::run(|| session.cmd(Command::Status));
+    let value = value.unwrap_or_default();
+    let (session_probe, _) = HealthProbe::run(|| session.current_window_handle());
+    let navigation = if options.navigate {
+        Some(HealthProbe::run(|| session.cmd(Command::NavigateTo("about:blank".to_string()))).0)
+    } else {
+        None
+    };
+
+    HealthReport {
+        status,
+        server_ready: value["value"]["ready"].as_bool().unwrap_or(false),
+        server_message: value["value"]["message"].as_str().unwrap_or_default().to_string(),
+        session: session_probe,
+        navigation,
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use thirtyfour::http::connection_async::WebDriverHttpClientAsync;
+use thirtyfour::http::reqwest_async::ReqwestDriverAsync;
+use thirtyfour::RequestData;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::webdriver::GenericWebDriver;
+
+/// A WebDriver that sends its requests using the async `thirtyfour` client.
+/// Only available with the `async-transport` feature.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::http::async_sync::AsyncBackedWebDriver;
+///
+/// fn main() -> WebDriverResult<()> {
+///     let caps = DesiredCapabilities::chrome();
+///     let driver = AsyncBackedWebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+///     driver.get("http://webappdemo")?;
+///     Ok(())
+/// }
+/// ```
+pub type AsyncBackedWebDriver = GenericWebDriver<AsyncBackedDriverSync<ReqwestDriverAsync>>;
+
+/// Synchronous connection to the remote WebDriver server, implemented with an
+/// async `thirtyfour` HTTP client running on an internal runtime.
+///
+/// This keeps the blocking API of this crate while sending requests exactly
+/// as the async crate does. Like the other sync clients, it must not be used
+/// from within an async task.
+#[derive(Debug)]
+pub struct AsyncBackedDriverSync<C: WebDriverHttpClientAsync> {
+    client: C,
+    runtime: Runtime,
+}
+
+impl<C: WebDriverHttpClientAsync> WebDriverHttpClientSync for AsyncBackedDriverSync<C> {
+    fn create(remote_server_addr: &str) -> WebDriverResult<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("thirtyfour-sync-transport")
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                WebDriverError::FatalError(format!("failed to start the async runtime: {}", e))
+            })?;
+        // Some clients expect to be created within the runtime.
+        let client = {
+            let _guard = runtime.enter();
+            C::create(remote_server_addr)?
+        };
+        Ok(Self {
+            client,
+            runtime,
+        })
+    }
+
+    /// Set the HTTP client request timeout.
+    fn set_request_timeout(&mut self, timeout: Duration) {
+        self.client.set_request_timeout(timeout);
+    }
+
+    /// Execute the specified command and return the data as serde_json::Value.
+    fn execute(&self, request_data: RequestData) -> WebDriverResult<serde_json::Value> {
+        self.runtime.block_on(self.client.execute(request_data))
+    }
+}
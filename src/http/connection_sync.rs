@@ -1,8 +1,16 @@
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::io;
+use std::sync::Arc;
+use std::thread;
 
-use crate::error::WebDriverResult;
-use std::time::Duration;
-use thirtyfour::RequestData;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::error_info::error_info;
+use crate::http::config::HttpClientConfig;
+#[cfg(feature = "fault-injection")]
+use crate::http::fault_sync::FaultInjector;
+use crate::legacy::normalize_error_body;
+use std::time::{Duration, SystemTime};
+use thirtyfour::{RequestData, RequestMethod};
 
 /// Trait for executing HTTP requests to selenium/webdriver.
 /// As long as you have some struct that implements WebDriverHttpClientSync,
@@ -17,7 +25,238 @@ pub trait WebDriverHttpClientSync: Debug + Send + Sync {
     where
         Self: Sized;
 
+    /// Create a client that applies the specified config to every request.
+    /// Clients that do not support custom headers return an error unless the
+    /// config is empty.
+    fn create_with_config(
+        remote_server_addr: &str,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self>
+    where
+        Self: Sized,
+    {
+        if !config.is_empty() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "this HTTP client does not support custom headers or TLS options",
+            )));
+        }
+        Self::create(remote_server_addr)
+    }
+
     fn set_request_timeout(&mut self, timeout: Duration);
 
     fn execute(&self, request_data: RequestData) -> WebDriverResult<serde_json::Value>;
+
+    /// The time in the `Date` header of the most recent response, if the
+    /// server sent one. Clients that do not record it return None.
+    fn last_response_date(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// The fault rules for a client that injects faults. Only available with
+    /// the `fault-injection` feature.
+    #[cfg(feature = "fault-injection")]
+    fn fault_injector(&self) -> Option<FaultInjector> {
+        None
+    }
+
+    /// Inject faults according to the specified rules, e.g. to share the
+    /// rules of another connection. Clients that do not inject faults
+    /// ignore this. Only available with the `fault-injection` feature.
+    #[cfg(feature = "fault-injection")]
+    fn set_fault_injector(&mut self, _faults: FaultInjector) {}
+}
+
+/// The error for an HTTP error response. Errors that are not WebDriver
+/// errors, such as an HTML page from a proxy, are reported as unknown errors
+/// so that they still carry the HTTP status.
+pub(crate) fn error_response(status: u16, mut body: serde_json::Value) -> WebDriverError {
+    normalize_error_body(&mut body);
+    match WebDriverError::parse(status, body) {
+        WebDriverError::UnknownResponse(message) => {
+            let mut info = WebDriverErrorInfo::new(&message);
+            info.status = status;
+            WebDriverError::UnknownError(info)
+        }
+        error => error,
+    }
+}
+
+type RequestFilter = Arc<dyn Fn(&RequestData) -> bool + Send + Sync>;
+
+/// Returns true if the request can safely be sent again: GET and DELETE
+/// requests, and requests to find elements.
+pub fn is_idempotent(request: &RequestData) -> bool {
+    match request.method {
+        RequestMethod::Get | RequestMethod::Delete => true,
+        RequestMethod::Post => {
+            request.url.ends_with("/element") || request.url.ends_with("/elements")
+        }
+    }
+}
+
+/// Decides when a request that failed because of a transient problem
+/// between the client and the WebDriver server is sent again, such as a
+/// 502 from a load balancer in front of Selenium Grid or a connection reset.
+///
+/// This is separate from [RetryPolicy](../../struct.RetryPolicy.html), which
+/// retries whole operations that failed because of the state of the page.
+///
+/// By default a request is attempted up to 3 times, and is retried only if it
+/// is idempotent (see `is_idempotent()`) and it failed because the connection
+/// failed or the server returned HTTP status 502, 503 or 504. The wait
+/// between attempts starts at 500ms and doubles after each attempt, up to 5
+/// seconds. Requests that timed out are not retried.
+///
+/// Set it with
+/// [WebDriver::set_retry_policy()](../../struct.GenericWebDriver.html#method.set_retry_policy).
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::http::connection_sync::HttpRetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = HttpRetryPolicy::new()
+///     .with_max_attempts(5)
+///     .with_backoff(Duration::from_secs(1), Duration::from_secs(10))
+///     .retry_requests(|request| request.url.ends_with("/execute/sync"));
+/// ```
+#[derive(Clone)]
+pub struct HttpRetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    statuses: Vec<u16>,
+    filters: Vec<RequestFilter>,
+}
+
+impl fmt::Debug for HttpRetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpRetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("statuses", &self.statuses)
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+impl Default for HttpRetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpRetryPolicy {
+    /// Create a new HttpRetryPolicy that retries idempotent requests.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            statuses: vec![502, 503, 504],
+            filters: vec![Arc::new(is_idempotent)],
+        }
+    }
+
+    /// Set the maximum number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the wait before the first retry, and the maximum wait between
+    /// attempts. The wait doubles after each attempt.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set the HTTP statuses that are retried, replacing the default of 502,
+    /// 503 and 504.
+    pub fn with_statuses(mut self, statuses: &[u16]) -> Self {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Also retry requests for which the specified function returns true.
+    pub fn retry_requests<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RequestData) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Arc::new(f));
+        self
+    }
+
+    /// Return true if the specified request may be sent again.
+    pub fn is_retryable_request(&self, request: &RequestData) -> bool {
+        self.filters.iter().any(|f| f(request))
+    }
+
+    /// Return true if the error is transient. Errors from the server are
+    /// transient if their HTTP status is one of the retried statuses.
+    pub fn is_transient(&self, error: &WebDriverError) -> bool {
+        match error {
+            WebDriverError::ReqwestError(e) => {
+                (e.is_connect() || e.is_request()) && !e.is_timeout()
+            }
+            WebDriverError::IOError(e) => {
+                !matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::InvalidData)
+            }
+            _ => error_info(error).is_some_and(|info| self.statuses.contains(&info.status)),
+        }
+    }
+
+    /// Send the request using the specified function, retrying it according
+    /// to this policy. Returns the last error if every attempt fails.
+    pub(crate) fn execute<F>(
+        &self,
+        request: RequestData,
+        mut send: F,
+    ) -> WebDriverResult<serde_json::Value>
+    where
+        F: FnMut(RequestData) -> WebDriverResult<serde_json::Value>,
+    {
+        if !self.is_retryable_request(&request) {
+            return send(request);
+        }
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match send(request.clone()) {
+                Err(e) if attempt < self.max_attempts && self.is_transient(&e) => {
+                    log::warn!(
+                        "retrying {:?} {} after attempt {} failed with a transient error",
+                        request.method,
+                        request.url,
+                        attempt
+                    );
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+                x => return x,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_statuses_of_error_responses() {
+        let policy = HttpRetryPolicy::new();
+        let html = serde_json::Value::Null;
+        assert!(policy.is_transient(&error_response(502, html.clone())));
+        assert!(!policy.is_transient(&error_response(500, html)));
+        let body = serde_json::json!({
+            "value": { "error": "unknown error", "message": "", "stacktrace": "" }
+        });
+        assert!(policy.is_transient(&error_response(503, body.clone())));
+        assert!(!policy.is_transient(&error_response(500, body)));
+    }
 }
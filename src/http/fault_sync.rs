@@ -0,0 +1,368 @@
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use serde_json::json;
+use thirtyfour::{RequestData, RequestMethod};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::http::config::HttpClientConfig;
+use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::http::reqwest_sync::ReqwestDriverSync;
+use crate::webdriver::GenericWebDriver;
+
+/// A WebDriver whose requests can be made to fail on purpose, for testing
+/// how automation copes with a misbehaving server. Only available with the
+/// `fault-injection` feature.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::http::fault_sync::{Fault, FaultRule, FaultyWebDriver};
+/// use std::time::Duration;
+///
+/// fn main() -> WebDriverResult<()> {
+///     let caps = DesiredCapabilities::chrome();
+///     let driver = FaultyWebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+///     driver.faults().add(
+///         FaultRule::new(Fault::Delay(Duration::from_secs(2))).with_probability(0.2),
+///     );
+///     driver.faults().add(FaultRule::new(Fault::DropResponse).matching("/element").times(1));
+///     driver.get("http://webappdemo")?;
+///     Ok(())
+/// }
+/// ```
+pub type FaultyWebDriver = GenericWebDriver<FaultyDriverSync<ReqwestDriverSync>>;
+
+/// A fault to inject into a request.
+#[derive(Debug, Clone)]
+pub enum Fault {
+    /// Wait for the specified duration before sending the request, as if the
+    /// server were slow to respond.
+    Delay(Duration),
+    /// Fail without sending the request, as if the connection were dropped
+    /// before the server received it.
+    DropRequest,
+    /// Send the request, then fail as if the connection were dropped before
+    /// the response arrived. The command still takes effect in the browser.
+    DropResponse,
+    /// Fail without sending the request, with the specified HTTP status and
+    /// WebDriver error code, e.g. `500` and `unknown error`.
+    ErrorResponse {
+        status: u16,
+        error: String,
+    },
+}
+
+impl Fault {
+    /// An error response with the specified HTTP status and WebDriver error
+    /// code.
+    pub fn error_response(status: u16, error: &str) -> Self {
+        Fault::ErrorResponse {
+            status,
+            error: error.to_string(),
+        }
+    }
+}
+
+/// When to inject a fault.
+///
+/// A rule applies to the requests it matches, which is all of them by
+/// default. Of those, it skips the first `after()` requests, then injects the
+/// fault into every `every()`th request with the specified probability, up to
+/// `times()` times in total.
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    fault: Fault,
+    path: Option<String>,
+    method: Option<RequestMethod>,
+    probability: f64,
+    after: u64,
+    every: u64,
+    times: Option<u64>,
+}
+
+impl FaultRule {
+    /// Inject the specified fault into every request.
+    pub fn new(fault: Fault) -> Self {
+        Self {
+            fault,
+            path: None,
+            method: None,
+            probability: 1.0,
+            after: 0,
+            every: 1,
+            times: None,
+        }
+    }
+
+    /// Only match requests whose URL contains the specified text, e.g.
+    /// `/element` or `/execute/sync`.
+    pub fn matching(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    /// Only match requests with the specified HTTP method.
+    pub fn for_method(mut self, method: RequestMethod) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Inject the fault with the specified probability, from 0.0 to 1.0.
+    pub fn with_probability(mut self, probability: f64) -> Self {
+        self.probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Skip the first `count` matching requests.
+    pub fn after(mut self, count: u64) -> Self {
+        self.after = count;
+        self
+    }
+
+    /// Only inject the fault into every `n`th matching request.
+    pub fn every(mut self, n: u64) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// Inject the fault at most `count` times.
+    pub fn times(mut self, count: u64) -> Self {
+        self.times = Some(count);
+        self
+    }
+
+    // `Option::is_none_or()` needs Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    fn matches(&self, request: &RequestData) -> bool {
+        let method_matches = matches!(
+            (&self.method, &request.method),
+            (None, _)
+                | (Some(RequestMethod::Get), RequestMethod::Get)
+                | (Some(RequestMethod::Post), RequestMethod::Post)
+                | (Some(RequestMethod::Delete), RequestMethod::Delete)
+        );
+        method_matches && self.path.as_ref().map_or(true, |p| request.url.contains(p.as_str()))
+    }
+}
+
+#[derive(Debug)]
+struct RuleState {
+    rule: FaultRule,
+    matched: u64,
+    injected: u64,
+}
+
+#[derive(Debug)]
+struct FaultState {
+    rules: Vec<RuleState>,
+    enabled: bool,
+    injected: u64,
+    rng: u64,
+}
+
+impl FaultState {
+    /// A xorshift generator; good enough for choosing when to inject faults,
+    /// and reproducible with `set_seed()`.
+    fn next_random(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// The faults to inject into the specified request.
+    // `u64::is_multiple_of()` needs Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    fn faults_for(&mut self, request: &RequestData) -> Vec<Fault> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut faults = Vec::new();
+        for i in 0..self.rules.len() {
+            let state = &mut self.rules[i];
+            if !state.rule.matches(request) {
+                continue;
+            }
+            state.matched += 1;
+            let rule = &state.rule;
+            if state.matched <= rule.after
+                || (state.matched - rule.after) % rule.every != 0
+                || rule.times.is_some_and(|t| state.injected >= t)
+            {
+                continue;
+            }
+            let probability = rule.probability;
+            if probability < 1.0 && self.next_random() >= probability {
+                continue;
+            }
+            let state = &mut self.rules[i];
+            state.injected += 1;
+            faults.push(state.rule.fault.clone());
+        }
+        self.injected += faults.len() as u64;
+        faults
+    }
+}
+
+/// The set of fault rules for a
+/// [FaultyDriverSync](struct.FaultyDriverSync.html) connection. Clones share
+/// the same rules, so faults can be changed while the WebDriver is in use.
+/// The extra connections a WebDriver opens for batches share its rules too.
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaultInjector {
+    /// Create a FaultInjector with no rules.
+    pub fn new() -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let state = FaultState {
+            rules: Vec::new(),
+            enabled: true,
+            injected: 0,
+            rng: 0,
+        };
+        let injector = Self {
+            state: Arc::new(Mutex::new(state)),
+        };
+        injector.set_seed(seed as u64);
+        injector
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FaultState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add a rule. Rules are checked in the order they were added, and more
+    /// than one can apply to the same request: delays add up, and the first
+    /// failure wins.
+    pub fn add(&self, rule: FaultRule) {
+        self.lock().rules.push(RuleState {
+            rule,
+            matched: 0,
+            injected: 0,
+        });
+    }
+
+    /// Remove all rules.
+    pub fn clear(&self) {
+        self.lock().rules.clear();
+    }
+
+    /// Pause or resume injecting faults, without removing the rules.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.lock().enabled = enabled;
+    }
+
+    /// Seed the random number generator used for probabilities, to make a
+    /// run reproducible.
+    pub fn set_seed(&self, seed: u64) {
+        // Xorshift gets stuck at zero.
+        self.lock().rng = seed | 1;
+    }
+
+    /// The number of faults injected so far.
+    pub fn injected_count(&self) -> u64 {
+        self.lock().injected
+    }
+}
+
+/// Synchronous connection to the remote WebDriver server that wraps another
+/// connection and injects faults into its requests, according to the rules
+/// in its [FaultInjector](struct.FaultInjector.html). It starts with no
+/// rules, so the session is created normally.
+#[derive(Debug)]
+pub struct FaultyDriverSync<C: WebDriverHttpClientSync> {
+    client: C,
+    faults: FaultInjector,
+}
+
+impl<C: WebDriverHttpClientSync> WebDriverHttpClientSync for FaultyDriverSync<C> {
+    fn create(remote_server_addr: &str) -> WebDriverResult<Self> {
+        Ok(Self {
+            client: C::create(remote_server_addr)?,
+            faults: FaultInjector::new(),
+        })
+    }
+
+    fn create_with_config(
+        remote_server_addr: &str,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self> {
+        Ok(Self {
+            client: C::create_with_config(remote_server_addr, config)?,
+            faults: FaultInjector::new(),
+        })
+    }
+
+    /// Set the HTTP client request timeout.
+    fn set_request_timeout(&mut self, timeout: Duration) {
+        self.client.set_request_timeout(timeout);
+    }
+
+    /// Execute the specified command, injecting any faults that apply.
+    fn execute(&self, request_data: RequestData) -> WebDriverResult<serde_json::Value> {
+        let faults = self.faults.lock().faults_for(&request_data);
+        let mut drop_response = false;
+        for fault in faults {
+            warn!("Injecting {:?} into {}", fault, request_data.url);
+            match fault {
+                Fault::Delay(delay) => std::thread::sleep(delay),
+                Fault::DropRequest => return Err(dropped("before sending the request")),
+                Fault::DropResponse => drop_response = true,
+                Fault::ErrorResponse {
+                    status,
+                    error,
+                } => {
+                    let body = json!({
+                        "value": {
+                            "error": error,
+                            "message": "injected fault",
+                            "stacktrace": "",
+                        }
+                    });
+                    return Err(WebDriverError::parse(status, body));
+                }
+            }
+        }
+        let result = self.client.execute(request_data);
+        if drop_response {
+            return Err(dropped("before receiving the response"));
+        }
+        result
+    }
+
+    fn last_response_date(&self) -> Option<SystemTime> {
+        self.client.last_response_date()
+    }
+
+    fn fault_injector(&self) -> Option<FaultInjector> {
+        Some(self.faults.clone())
+    }
+
+    fn set_fault_injector(&mut self, faults: FaultInjector) {
+        self.faults = faults;
+    }
+}
+
+fn dropped(when: &str) -> WebDriverError {
+    let message = format!("injected fault: connection dropped {}", when);
+    WebDriverError::IOError(io::Error::new(io::ErrorKind::ConnectionReset, message))
+}
+
+impl<C: WebDriverHttpClientSync + 'static> GenericWebDriver<FaultyDriverSync<C>> {
+    /// The fault rules for this WebDriver's connection.
+    pub fn faults(&self) -> FaultInjector {
+        self.session.fault_injector().expect("FaultyDriverSync always has a FaultInjector")
+    }
+}
@@ -1,28 +1,112 @@
 use std::fmt::Debug;
 
-use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::http::config::{HttpClientConfig, TlsOptions};
+use crate::http::connection_sync::{error_response, WebDriverHttpClientSync};
 use crate::{
     common::connection_common::reqwest_support::build_reqwest_headers,
-    error::{WebDriverError, WebDriverResult},
+    error::{WebDriverError, WebDriverErrorInfo, WebDriverResult},
 };
-use std::time::Duration;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 use thirtyfour::{RequestData, RequestMethod};
 
+fn invalid_header<E: std::fmt::Display>(name: &str, e: E) -> WebDriverError {
+    let msg = format!("invalid header {}: {}", name, e);
+    WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&msg))
+}
+
+fn invalid_tls<E: std::fmt::Display>(what: &str, e: E) -> WebDriverError {
+    let msg = format!("invalid {}: {}", what, e);
+    WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&msg))
+}
+
+/// Apply the TLS options to the client builder.
+fn configure_tls(
+    mut builder: reqwest::blocking::ClientBuilder,
+    tls: &TlsOptions,
+) -> WebDriverResult<reqwest::blocking::ClientBuilder> {
+    for pem in tls.root_certificates() {
+        let certs = reqwest::Certificate::from_pem_bundle(pem)
+            .map_err(|e| invalid_tls("root certificate", e))?;
+        if certs.is_empty() {
+            return Err(invalid_tls("root certificate", "no certificates found in the PEM data"));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder = builder.tls_built_in_root_certs(tls.builtin_roots());
+
+    if let Some(identity) = tls.client_identity() {
+        #[cfg(any(feature = "reqwest-native-tls", feature = "reqwest-native-tls-vendored"))]
+        {
+            let identity =
+                reqwest::Identity::from_pkcs8_pem(identity.certificate_pem(), identity.key_pem())
+                    .map_err(|e| invalid_tls("client certificate or key", e))?;
+            builder = builder.identity(identity);
+        }
+        #[cfg(all(
+            feature = "reqwest-rustls-tls",
+            not(any(feature = "reqwest-native-tls", feature = "reqwest-native-tls-vendored"))
+        ))]
+        {
+            let pem = [identity.certificate_pem(), b"\n", identity.key_pem()].concat();
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| invalid_tls("client certificate or key", e))?;
+            builder = builder.use_rustls_tls().identity(identity);
+        }
+        #[cfg(not(any(
+            feature = "reqwest-native-tls",
+            feature = "reqwest-native-tls-vendored",
+            feature = "reqwest-rustls-tls"
+        )))]
+        {
+            let _ = identity;
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "client certificates need the reqwest-native-tls or reqwest-rustls-tls feature",
+            )));
+        }
+    }
+    Ok(builder)
+}
+
 /// Synchronous connection to the remote WebDriver server.
 #[derive(Debug)]
 pub struct ReqwestDriverSync {
     url: String,
     client: reqwest::blocking::Client,
     timeout: Duration,
+    config: HttpClientConfig,
+    last_response_date: Mutex<Option<SystemTime>>,
+}
+
+impl ReqwestDriverSync {
+    /// Create a connection that uses the specified TLS options. To use them
+    /// for a WebDriver, add them to an
+    /// [HttpClientConfig](../config/struct.HttpClientConfig.html) instead.
+    pub fn create_with_tls(remote_server_addr: &str, tls: TlsOptions) -> WebDriverResult<Self> {
+        Self::create_with_config(remote_server_addr, &HttpClientConfig::new().with_tls(tls))
+    }
 }
 
 impl WebDriverHttpClientSync for ReqwestDriverSync {
     fn create(remote_server_addr: &str) -> WebDriverResult<Self> {
+        Self::create_with_config(remote_server_addr, &HttpClientConfig::new())
+    }
+
+    fn create_with_config(
+        remote_server_addr: &str,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self> {
         let headers = build_reqwest_headers(remote_server_addr)?;
+        let builder = reqwest::blocking::Client::builder().default_headers(headers);
         Ok(ReqwestDriverSync {
             url: remote_server_addr.trim_end_matches('/').to_owned(),
-            client: reqwest::blocking::Client::builder().default_headers(headers).build()?,
+            client: configure_tls(builder, config.tls())?.build()?,
             timeout: Duration::from_secs(120),
+            config: config.clone(),
+            last_response_date: Mutex::new(None),
         })
     }
 
@@ -40,21 +124,42 @@ impl WebDriverHttpClientSync for ReqwestDriverSync {
             RequestMethod::Delete => self.client.delete(&url),
         };
         request = request.timeout(self.timeout);
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.config.request_headers(&request_data) {
+            let name =
+                HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid_header(&name, e))?;
+            let value =
+                HeaderValue::from_str(&value).map_err(|e| invalid_header(name.as_str(), e))?;
+            headers.append(name, value);
+        }
+        // These replace any headers with the same name, including the default
+        // headers and basic authentication from the URL.
+        request = request.headers(headers);
 
         if let Some(x) = request_data.body {
             request = request.json(&x);
         }
 
         let resp = request.send()?;
+        let date = resp
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|d| d.to_str().ok())
+            .and_then(|d| httpdate::parse_http_date(d).ok());
+        *self.last_response_date.lock().unwrap_or_else(|e| e.into_inner()) = date;
 
         match resp.status().as_u16() {
             200..=399 => Ok(resp.json()?),
             400..=599 => {
                 let status = resp.status().as_u16();
                 let body: serde_json::Value = resp.json().unwrap_or(serde_json::Value::Null);
-                Err(WebDriverError::parse(status, body))
+                Err(error_response(status, body))
             }
             _ => unreachable!(),
         }
     }
+
+    fn last_response_date(&self) -> Option<SystemTime> {
+        *self.last_response_date.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
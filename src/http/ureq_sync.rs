@@ -0,0 +1,148 @@
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use thirtyfour::{RequestData, RequestMethod};
+use url::Url;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::http::config::HttpClientConfig;
+use crate::http::connection_sync::{error_response, WebDriverHttpClientSync};
+use crate::webdriver::GenericWebDriver;
+
+/// A WebDriver that sends its requests with the lightweight, blocking `ureq`
+/// client. Only available with the `ureq-transport` feature.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::WebDriverUreq;
+///
+/// fn main() -> WebDriverResult<()> {
+///     let caps = DesiredCapabilities::chrome();
+///     let driver = WebDriverUreq::new("http://localhost:4444/wd/hub", &caps)?;
+///     driver.get("http://webappdemo")?;
+///     Ok(())
+/// }
+/// ```
+pub type WebDriverUreq = GenericWebDriver<UreqDriverSync>;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Synchronous connection to the remote WebDriver server, implemented with
+/// `ureq`.
+///
+/// This sends the same requests as
+/// [ReqwestDriverSync](../reqwest_sync/struct.ReqwestDriverSync.html),
+/// including basic authentication from the credentials in the server URL,
+/// without starting an async runtime. HTTPS uses `rustls`.
+///
+/// `reqwest` is still compiled, as the `thirtyfour` crate depends on it, so
+/// this does not reduce compile times or binary size. It does avoid creating
+/// a reqwest client, and the tokio runtime behind it, for WebDriver requests.
+#[derive(Debug)]
+pub struct UreqDriverSync {
+    url: String,
+    agent: ureq::Agent,
+    authorization: Option<String>,
+    timeout: Duration,
+    config: HttpClientConfig,
+    last_response_date: Mutex<Option<SystemTime>>,
+}
+
+// `io::Error::other()` needs Rust 1.74.
+#[allow(clippy::io_other_error)]
+fn transport_error(e: ureq::Transport) -> WebDriverError {
+    WebDriverError::IOError(io::Error::new(io::ErrorKind::Other, e.to_string()))
+}
+
+impl WebDriverHttpClientSync for UreqDriverSync {
+    fn create(remote_server_addr: &str) -> WebDriverResult<Self> {
+        Self::create_with_config(remote_server_addr, &HttpClientConfig::new())
+    }
+
+    fn create_with_config(
+        remote_server_addr: &str,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self> {
+        if !config.tls().is_empty() {
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "the ureq transport does not support TLS options",
+            )));
+        }
+        let mut url = Url::parse(remote_server_addr).map_err(|e| {
+            WebDriverError::FatalError(format!("invalid server URL {}: {}", remote_server_addr, e))
+        })?;
+        let authorization = match (url.username(), url.password()) {
+            ("", _) | (_, None) => None,
+            (username, Some(password)) => {
+                Some(format!("Basic {}", base64::encode(format!("{}:{}", username, password))))
+            }
+        };
+        // The credentials are sent in the Authorization header instead.
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        Ok(UreqDriverSync {
+            url: url.as_str().trim_end_matches('/').to_owned(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(&format!("thirtyfour/{} (rust)", VERSION))
+                .build(),
+            authorization,
+            timeout: Duration::from_secs(120),
+            config: config.clone(),
+            last_response_date: Mutex::new(None),
+        })
+    }
+
+    /// Set the HTTP client request timeout.
+    fn set_request_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Execute the specified command and return the data as serde_json::Value.
+    fn execute(&self, request_data: RequestData) -> WebDriverResult<serde_json::Value> {
+        let url = self.url.clone() + &request_data.url;
+        let method = match request_data.method {
+            RequestMethod::Get => "GET",
+            RequestMethod::Post => "POST",
+            RequestMethod::Delete => "DELETE",
+        };
+        let mut request = self
+            .agent
+            .request(method, &url)
+            .timeout(self.timeout)
+            .set("Accept", "application/json")
+            .set("Content-Type", "application/json;charset=UTF-8");
+        if let Some(authorization) = &self.authorization {
+            request = request.set("Authorization", authorization);
+        }
+        for (name, value) in self.config.request_headers(&request_data) {
+            request = request.set(&name, &value);
+        }
+
+        let result = match request_data.body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+        let (resp, is_error) = match result {
+            Ok(resp) => (resp, false),
+            Err(ureq::Error::Status(_, resp)) => (resp, true),
+            Err(ureq::Error::Transport(e)) => return Err(transport_error(e)),
+        };
+        let date = resp.header("Date").and_then(|d| httpdate::parse_http_date(d).ok());
+        *self.last_response_date.lock().unwrap_or_else(|e| e.into_inner()) = date;
+
+        if is_error {
+            let status = resp.status();
+            let body: serde_json::Value = resp.into_json().unwrap_or(serde_json::Value::Null);
+            Err(error_response(status, body))
+        } else {
+            Ok(resp.into_json()?)
+        }
+    }
+
+    fn last_response_date(&self) -> Option<SystemTime> {
+        *self.last_response_date.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
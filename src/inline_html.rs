@@ -0,0 +1,40 @@
+use base64::encode;
+
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession};
+
+/// Documents larger than this are written into `about:blank` instead of
+/// being loaded from a `data:` URL, since browsers limit the length of URLs.
+const MAX_DATA_URL_HTML: usize = 512 * 1024;
+
+/// Replaces the current document with the markup passed as an argument, and
+/// waits for it to finish loading.
+const WRITE_DOCUMENT_SCRIPT: &str = r#"
+    const [html, done] = arguments;
+    document.open();
+    document.write(html);
+    document.close();
+    if (document.readyState === "complete") {
+        done();
+    } else {
+        window.addEventListener("load", () => done(), { once: true });
+    }
+"#;
+
+/// The `data:` URL for an HTML document.
+fn html_data_url(html: &str) -> String {
+    format!("data:text/html;charset=utf-8;base64,{}", encode(html))
+}
+
+pub(crate) fn load_html(session: &WebDriverSession, html: &str) -> WebDriverResult<()> {
+    if html.len() <= MAX_DATA_URL_HTML {
+        return session.get(html_data_url(html));
+    }
+
+    session.get("about:blank")?;
+    let mut args = ScriptArgs::new();
+    args.push(html)?;
+    session.execute_async_script_with_args(WRITE_DOCUMENT_SCRIPT, &args)?;
+    session.wait_for_ready()
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::WebDriverSession;
+
+/// The error state of the WebDriverTimeout error returned when an element
+/// did not become interactable within the interaction timeout. See
+/// `is_interaction_timeout()`.
+pub const INTERACTION_TIMEOUT: &str = "interaction timeout";
+
+/// How often an interaction is attempted again while waiting for the element.
+const INTERACTION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Returns true if the error was returned because an element did not become
+/// interactable within the interaction timeout.
+pub fn is_interaction_timeout(error: &WebDriverError) -> bool {
+    matches!(error, WebDriverError::WebDriverTimeout(info) if info.error == INTERACTION_TIMEOUT)
+}
+
+/// Returns true if the error was returned because a request to the WebDriver
+/// server did not complete within the HTTP request timeout, e.g. because the
+/// server is unresponsive.
+///
+/// See [WebDriver::set_request_timeout()](../struct.GenericWebDriver.html#method.set_request_timeout).
+pub fn is_http_timeout(error: &WebDriverError) -> bool {
+    match error {
+        WebDriverError::ReqwestError(e) => e.is_timeout(),
+        WebDriverError::IOError(e) => e.kind() == std::io::ErrorKind::TimedOut,
+        _ => false,
+    }
+}
+
+/// Returns true if the error means the element could not be interacted with
+/// yet, but might be soon, e.g. because it is hidden, disabled, covered by
+/// another element or still animating into place.
+fn is_not_actionable(error: &WebDriverError) -> bool {
+    matches!(
+        error,
+        WebDriverError::ElementNotInteractable(_)
+            | WebDriverError::ElementClickIntercepted(_)
+            | WebDriverError::InvalidElementState(_)
+            | WebDriverError::MoveTargetOutOfBounds(_)
+    )
+}
+
+/// Perform the interaction, attempting it again until the element is
+/// actionable or the timeout is reached. With no timeout, it is attempted
+/// once.
+///
+/// The timeout does not cut short a request that is in progress, which is
+/// limited by the HTTP request timeout instead.
+pub(crate) fn interact<T, F>(
+    session: &WebDriverSession,
+    timeout: Option<Duration>,
+    action: &str,
+    mut f: F,
+) -> WebDriverResult<T>
+where
+    F: FnMut() -> WebDriverResult<T>,
+{
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return f(),
+    };
+    let start = Instant::now();
+    loop {
+        match f() {
+            Err(e) if is_not_actionable(&e) => {
+                if start.elapsed() >= timeout {
+                    let message = format!(
+                        "could not {} the element within the interaction timeout of {:?}: {}",
+                        action, timeout, e
+                    );
+                    let mut info = WebDriverErrorInfo::new(&with_page_context(session, &message));
+                    info.error = INTERACTION_TIMEOUT.to_string();
+                    return Err(WebDriverError::WebDriverTimeout(info));
+                }
+                std::thread::sleep(INTERACTION_INTERVAL);
+            }
+            result => return result,
+        }
+    }
+}
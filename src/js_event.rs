@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{no_such_element, WebDriverError, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession};
+
+/// How much longer than the wait itself the script timeout must be, so that
+/// the script reports its own timeout rather than being cut off.
+const SCRIPT_TIMEOUT_MARGIN: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsEventResult {
+    #[serde(default)]
+    missing: bool,
+    #[serde(default)]
+    timed_out: bool,
+    #[serde(default)]
+    detail: Value,
+}
+
+/// Waits for the next event with the specified name on the target, which is
+/// a CSS selector, `window` or `document`, and returns the event's `detail`
+/// as JSON.
+const WAIT_FOR_EVENT_SCRIPT: &str = r#"
+    const [selector, eventName, timeoutMs] = arguments;
+    const done = arguments[arguments.length - 1];
+    const target = selector === "window"
+        ? window
+        : selector === "document" ? document : document.querySelector(selector);
+    if (!target) {
+        done({ missing: true });
+        return;
+    }
+    const listener = (event) => {
+        clearTimeout(timer);
+        let detail = event.detail === undefined ? null : event.detail;
+        try {
+            detail = JSON.parse(JSON.stringify(detail));
+        } catch (e) {
+            detail = String(detail);
+        }
+        done({ detail: detail === undefined ? null : detail });
+    };
+    const timer = setTimeout(() => {
+        target.removeEventListener(eventName, listener);
+        done({ timedOut: true });
+    }, timeoutMs);
+    target.addEventListener(eventName, listener, { once: true });
+"#;
+
+pub(crate) fn wait_for_js_event(
+    session: &WebDriverSession,
+    target: &str,
+    event_name: &str,
+    timeout: Duration,
+) -> WebDriverResult<Value> {
+    // Make sure the script timeout is long enough for the wait.
+    let script_timeout = session.get_timeouts()?.script();
+    let extend = script_timeout.is_some_and(|t| t < timeout + SCRIPT_TIMEOUT_MARGIN);
+    if extend {
+        session.set_script_timeout(timeout + SCRIPT_TIMEOUT_MARGIN)?;
+    }
+
+    let mut args = ScriptArgs::new();
+    args.push(target)?;
+    args.push(event_name)?;
+    args.push(timeout.as_millis() as u64)?;
+    let result = session
+        .execute_async_script_with_args(WAIT_FOR_EVENT_SCRIPT, &args)
+        .and_then(|ret| ret.convert::<JsEventResult>());
+
+    if let (true, Some(t)) = (extend, script_timeout) {
+        session.set_script_timeout(t)?;
+    }
+    let result = result?;
+    if result.missing {
+        return Err(no_such_element(&format!(
+            "no element matches {:?} to listen for {:?} events",
+            target, event_name
+        )));
+    }
+    if result.timed_out {
+        let message = format!("no {:?} event on {:?} within {:?}", event_name, target, timeout);
+        return Err(WebDriverError::Timeout(with_page_context(session, &message)));
+    }
+    Ok(result.detail)
+}
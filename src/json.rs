@@ -0,0 +1,47 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// The maximum length of the payload included in conversion errors.
+const MAX_SNIPPET_LEN: usize = 200;
+
+fn snippet(value: &Value) -> String {
+    let s = value.to_string();
+    match s.char_indices().nth(MAX_SNIPPET_LEN) {
+        Some((i, _)) => format!("{}...", &s[..i]),
+        None => s,
+    }
+}
+
+/// Convert the JSON value into the specified type. If this fails, the error
+/// names the command or other source of the value, the path to the field
+/// that did not match, and the start of the value.
+pub(crate) fn convert_json_from<T>(source: &str, value: &Value) -> WebDriverResult<T>
+where
+    T: DeserializeOwned,
+{
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let path = e.path().to_string();
+        let location = if path == "." {
+            String::new()
+        } else {
+            format!(" at `{}`", path)
+        };
+        WebDriverError::UnknownResponse(format!(
+            "failed to parse {}{}: {}\n    payload: {}",
+            source,
+            location,
+            e.inner(),
+            snippet(value)
+        ))
+    })
+}
+
+/// Convert the JSON value returned by the specified WebDriver command.
+pub(crate) fn convert_json<T>(command: &str, value: &Value) -> WebDriverResult<T>
+where
+    T: DeserializeOwned,
+{
+    convert_json_from(&format!("the response to {}", command), value)
+}
@@ -0,0 +1,353 @@
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use thirtyfour::{RequestData, RequestMethod};
+
+use crate::common::command::MAGIC_ELEMENTID;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::http::connection_sync::WebDriverHttpClientSync;
+
+/// The key used for element references by the JSON Wire Protocol.
+const LEGACY_ELEMENTID: &str = "ELEMENT";
+
+/// Map a JSON Wire Protocol status code to the equivalent W3C error code.
+fn legacy_error_code(status: i64) -> &'static str {
+    match status {
+        6 => "invalid session id",
+        7 => "no such element",
+        8 => "no such frame",
+        9 => "unknown command",
+        10 => "stale element reference",
+        11 | 60 => "element not interactable",
+        12 | 15 => "invalid element state",
+        17 => "javascript error",
+        19 | 32 | 51 | 52 => "invalid selector",
+        21 => "timeout",
+        23 => "no such window",
+        24 => "invalid cookie domain",
+        25 => "unable to set cookie",
+        26 => "unexpected alert open",
+        27 => "no such alert",
+        28 => "script timeout",
+        29 | 61 => "invalid argument",
+        33 => "session not created",
+        34 => "move target out of bounds",
+        62 => "no such cookie",
+        63 => "unable to capture screen",
+        64 => "element click intercepted",
+        _ => "unknown error",
+    }
+}
+
+/// Returns the JSON Wire Protocol status code of the response, if it has one
+/// and it indicates an error.
+fn legacy_error_status(body: &Value) -> Option<i64> {
+    body["status"].as_i64().filter(|s| *s != 0)
+}
+
+/// Add the W3C error code to a JSON Wire Protocol error body, so that it is
+/// recognised as the right kind of error. W3C error bodies are unchanged.
+pub(crate) fn normalize_error_body(body: &mut Value) {
+    if body["value"]["error"].is_string() || body["state"].is_string() {
+        return;
+    }
+    if let Some(status) = legacy_error_status(body) {
+        if !body["value"].is_object() {
+            body["value"] = json!({ "message": body["value"].to_string() });
+        }
+        body["value"]["error"] = Value::from(legacy_error_code(status));
+    }
+}
+
+/// Add W3C element references alongside JSON Wire Protocol ones, anywhere in
+/// the value.
+fn normalize_element_refs(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if map.len() == 1 && map.get(LEGACY_ELEMENTID).map(Value::is_string).unwrap_or(false) {
+                let id = map[LEGACY_ELEMENTID].clone();
+                map.insert(MAGIC_ELEMENTID.to_string(), id);
+                return;
+            }
+            if let Some(expiry) = cookie_expiry(map) {
+                map.insert("expiry".to_string(), Value::from(expiry));
+            }
+            map.values_mut().for_each(normalize_element_refs);
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_element_refs),
+        _ => {}
+    }
+}
+
+/// Some drivers return cookie expiry times with a fractional part.
+fn cookie_expiry(map: &Map<String, Value>) -> Option<i64> {
+    if !map.contains_key("name") || !map.contains_key("value") {
+        return None;
+    }
+    match map.get("expiry") {
+        Some(Value::Number(n)) if n.is_f64() => n.as_f64().map(|x| x as i64),
+        _ => None,
+    }
+}
+
+/// Convert a response from a driver that predates the W3C spec into the
+/// shape expected by this crate.
+///
+/// Errors reported with a status code in a successful HTTP response are
+/// returned as errors, JSON Wire Protocol element references are also given
+/// W3C keys, and fractional cookie expiry times are truncated.
+pub(crate) fn normalize_response(mut response: Value) -> WebDriverResult<Value> {
+    if legacy_error_status(&response).is_some() {
+        normalize_error_body(&mut response);
+        return Err(WebDriverError::parse(500, response));
+    }
+    if let Some(value) = response.get_mut("value") {
+        normalize_element_refs(value);
+    }
+    Ok(response)
+}
+
+/// Add JSON Wire Protocol element references alongside W3C ones, anywhere in
+/// the request body.
+fn add_legacy_element_refs(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(id) = map.get(MAGIC_ELEMENTID).filter(|id| id.is_string()).cloned() {
+                map.entry(LEGACY_ELEMENTID).or_insert(id);
+            }
+            map.values_mut().for_each(add_legacy_element_refs);
+        }
+        Value::Array(items) => items.iter_mut().for_each(add_legacy_element_refs),
+        _ => {}
+    }
+}
+
+fn unsupported(what: &str) -> WebDriverError {
+    WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(&format!(
+        "{} is not supported by the JSON Wire Protocol",
+        what
+    )))
+}
+
+/// Sends JSON Wire Protocol requests for a single session.
+struct JsonWire<'a> {
+    conn: &'a dyn WebDriverHttpClientSync,
+    prefix: &'a str,
+}
+
+impl<'a> JsonWire<'a> {
+    fn send(
+        &self,
+        method: RequestMethod,
+        path: &str,
+        body: Option<Value>,
+    ) -> WebDriverResult<Value> {
+        let mut request = RequestData::new(method, format!("{}{}", self.prefix, path));
+        if let Some(mut body) = body {
+            add_legacy_element_refs(&mut body);
+            request = request.add_body(body);
+        }
+        normalize_response(self.conn.execute(request)?)
+    }
+
+    fn get(&self, path: &str) -> WebDriverResult<Value> {
+        self.send(RequestMethod::Get, path, None)
+    }
+
+    fn post(&self, path: &str, body: Value) -> WebDriverResult<Value> {
+        self.send(RequestMethod::Post, path, Some(body))
+    }
+
+    /// Combine separate position and size requests into a W3C rect.
+    fn rect(&self, position_path: &str, size_path: &str) -> WebDriverResult<Value> {
+        let position = self.get(position_path)?;
+        let size = self.get(size_path)?;
+        Ok(json!({ "value": {
+            "x": position["value"]["x"],
+            "y": position["value"]["y"],
+            "width": size["value"]["width"],
+            "height": size["value"]["height"],
+        }}))
+    }
+
+    fn set_window_rect(&self, rect: &Value) -> WebDriverResult<Value> {
+        if !rect["x"].is_null() && !rect["y"].is_null() {
+            self.post("/window/current/position", json!({ "x": rect["x"], "y": rect["y"] }))?;
+        }
+        if !rect["width"].is_null() && !rect["height"].is_null() {
+            self.post(
+                "/window/current/size",
+                json!({ "width": rect["width"], "height": rect["height"] }),
+            )?;
+        }
+        self.rect("/window/current/position", "/window/current/size")
+    }
+
+    fn set_timeouts(&self, timeouts: &Value) -> WebDriverResult<Value> {
+        for (key, name) in
+            &[("implicit", "implicit"), ("pageLoad", "page load"), ("script", "script")]
+        {
+            if let Some(ms) = timeouts[*key].as_u64() {
+                self.post("/timeouts", json!({ "type": name, "ms": ms }))?;
+            }
+        }
+        Ok(json!({ "value": null }))
+    }
+
+    fn named_cookie(&self, name: &str) -> WebDriverResult<Value> {
+        let cookies = self.get("/cookie")?;
+        let cookie = cookies["value"]
+            .as_array()
+            .and_then(|c| c.iter().find(|c| c["name"].as_str() == Some(name)).cloned());
+        match cookie {
+            Some(c) => Ok(json!({ "value": c })),
+            None => Err(WebDriverError::NoSuchCookie(WebDriverErrorInfo::new(&format!(
+                "no cookie named {:?}",
+                name
+            )))),
+        }
+    }
+
+    /// Perform W3C actions one tick at a time using the legacy mouse and
+    /// keyboard endpoints.
+    fn perform_actions(&self, body: &Value) -> WebDriverResult<Value> {
+        let sources = body["actions"].as_array().cloned().unwrap_or_default();
+        let ticks = sources.iter().filter_map(|s| s["actions"].as_array()).map(|a| a.len()).max();
+        for tick in 0..ticks.unwrap_or(0) {
+            for source in &sources {
+                if let Some(action) = source["actions"].get(tick) {
+                    self.perform_action(action)?;
+                }
+            }
+        }
+        Ok(json!({ "value": null }))
+    }
+
+    fn perform_action(&self, action: &Value) -> WebDriverResult<()> {
+        match action["type"].as_str().unwrap_or_default() {
+            "pause" => {
+                thread::sleep(Duration::from_millis(action["duration"].as_u64().unwrap_or(0)));
+            }
+            "keyDown" => {
+                self.post("/keys", json!({ "value": [action["value"]] }))?;
+            }
+            "keyUp" => {
+                // Legacy modifier keys are toggled, so they must be sent again
+                // to release them. Other keys are released automatically.
+                let is_modifier = action["value"]
+                    .as_str()
+                    .and_then(|v| v.chars().next())
+                    .map(|c| ('\u{E008}'..='\u{E00B}').contains(&c) || c == '\u{E03D}')
+                    .unwrap_or(false);
+                if is_modifier {
+                    self.post("/keys", json!({ "value": [action["value"]] }))?;
+                }
+            }
+            "pointerDown" => {
+                self.post("/buttondown", json!({ "button": action["button"] }))?;
+            }
+            "pointerUp" => {
+                self.post("/buttonup", json!({ "button": action["button"] }))?;
+            }
+            "pointerMove" => self.pointer_move(action)?,
+            other => return Err(unsupported(&format!("the {} action", other))),
+        }
+        Ok(())
+    }
+
+    fn pointer_move(&self, action: &Value) -> WebDriverResult<()> {
+        let x = action["x"].as_i64().unwrap_or(0);
+        let y = action["y"].as_i64().unwrap_or(0);
+        let origin = &action["origin"];
+        let body = match origin[MAGIC_ELEMENTID].as_str() {
+            Some(id) if x == 0 && y == 0 => json!({ "element": id }),
+            Some(id) => {
+                // W3C offsets are from the center of the element, but legacy
+                // offsets are from its top left corner.
+                let size = self.get(&format!("/element/{}/size", id))?;
+                let width = size["value"]["width"].as_f64().unwrap_or(0.0);
+                let height = size["value"]["height"].as_f64().unwrap_or(0.0);
+                json!({
+                    "element": id,
+                    "xoffset": (width / 2.0) as i64 + x,
+                    "yoffset": (height / 2.0) as i64 + y,
+                })
+            }
+            None if origin.as_str() == Some("pointer") => json!({ "xoffset": x, "yoffset": y }),
+            None => return Err(unsupported("moving the pointer relative to the viewport")),
+        };
+        self.post("/moveto", body)?;
+        Ok(())
+    }
+}
+
+/// Execute a W3C request against a server that only speaks the JSON Wire
+/// Protocol, translating it into the equivalent legacy request or requests.
+/// Requests with no translation are sent unchanged.
+pub(crate) fn execute_json_wire(
+    conn: &dyn WebDriverHttpClientSync,
+    mut request: RequestData,
+) -> WebDriverResult<Value> {
+    let url = request.url.clone();
+    let split = url
+        .strip_prefix("/session/")
+        .and_then(|rest| rest.find('/'))
+        .map(|i| i + "/session/".len());
+    let (prefix, path) = match split {
+        Some(i) => url.split_at(i),
+        None => {
+            if let Some(body) = &mut request.body {
+                add_legacy_element_refs(body);
+            }
+            return normalize_response(conn.execute(request)?);
+        }
+    };
+
+    let wire = JsonWire {
+        conn,
+        prefix,
+    };
+    let body = request.body.take().unwrap_or(Value::Null);
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match (&request.method, segments.as_slice()) {
+        (RequestMethod::Get, ["window"]) => wire.get("/window_handle"),
+        (RequestMethod::Get, ["window", "handles"]) => wire.get("/window_handles"),
+        (RequestMethod::Post, ["window"]) => {
+            wire.post("/window", json!({ "name": body["handle"], "handle": body["handle"] }))
+        }
+        (RequestMethod::Get, ["window", "rect"]) => {
+            wire.rect("/window/current/position", "/window/current/size")
+        }
+        (RequestMethod::Post, ["window", "rect"]) => wire.set_window_rect(&body),
+        (RequestMethod::Post, ["window", "maximize"]) => {
+            wire.post("/window/current/maximize", json!({}))
+        }
+        (RequestMethod::Get, ["element", "active"]) => wire.post("/element/active", json!({})),
+        (RequestMethod::Get, ["element", id, "rect"]) => {
+            wire.rect(&format!("/element/{}/location", id), &format!("/element/{}/size", id))
+        }
+        (RequestMethod::Get, ["element", id, "property", name]) => {
+            wire.get(&format!("/element/{}/attribute/{}", id, name))
+        }
+        (RequestMethod::Post, ["execute", "sync"]) => wire.post("/execute", body),
+        (RequestMethod::Post, ["execute", "async"]) => wire.post("/execute_async", body),
+        (RequestMethod::Get, ["alert", "text"]) => wire.get("/alert_text"),
+        (RequestMethod::Post, ["alert", "text"]) => wire.post("/alert_text", body),
+        (RequestMethod::Post, ["alert", "accept"]) => wire.post("/accept_alert", json!({})),
+        (RequestMethod::Post, ["alert", "dismiss"]) => wire.post("/dismiss_alert", json!({})),
+        (RequestMethod::Get, ["timeouts"]) => Err(unsupported("getting the timeouts")),
+        (RequestMethod::Post, ["timeouts"]) => wire.set_timeouts(&body),
+        (RequestMethod::Get, ["cookie", name]) => wire.named_cookie(name),
+        (RequestMethod::Post, ["actions"]) => wire.perform_actions(&body),
+        (RequestMethod::Delete, ["actions"]) => Ok(json!({ "value": null })),
+        _ => {
+            let body = if body.is_null() {
+                None
+            } else {
+                Some(body)
+            };
+            wire.send(request.method, path, body)
+        }
+    }
+}
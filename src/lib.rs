@@ -31,6 +31,36 @@
 //! - **reqwest-native-tls-vendored**: Enables the `vendored` feature of `native-tls`.
 //! - **reqwest-rustls-tls**: Enables TLS functionality provided by `rustls`.
 //!
+//! Optional features:
+//! - **async-transport**: Enables `AsyncBackedWebDriver`, which sends requests using the
+//!   async `thirtyfour` HTTP client on an internal runtime.
+//! - **bidi**: Enables the [bidi](bidi/index.html) module, a WebDriver BiDi client for
+//!   log, network and browsing context events, and the [console](console/index.html)
+//!   module for streaming console messages.
+//! - **cdp-websocket**: Enables `ChromeDevTools::connect()`, a direct DevTools connection
+//!   that can run commands in iframes, workers and popups, and
+//!   `ChromeDevTools::connect_events()`, for receiving DevTools events, and
+//!   `ChromeDevTools::connect_console()`, for streaming console messages.
+//! - **fault-injection**: Enables `FaultyWebDriver`, which injects delays, dropped
+//!   connections and error responses into requests, for testing retry and recovery logic.
+//! - **fixture-server**: Enables `FixtureServer`, a tiny local HTTP server for serving test
+//!   pages from a directory or from memory, and `WebDriver::set_fixture_server()`.
+//! - **locators-toml**: Enables `Locators::from_toml()`, for loading named locators from
+//!   TOML files.
+//! - **ocr**: Enables `WebDriver::find_text_on_screen()`, which finds text on a screenshot
+//!   using the `tesseract` executable, for canvas-rendered content and embedded PDFs.
+//! - **screenshot-scaling**: Enables `ScreenshotOptions::with_device_pixel_ratio()`, which
+//!   resizes screenshots so that they match between HiDPI and standard displays.
+//! - **stealth**: Enables the [stealth](stealth/index.html) module, which applies the
+//!   well-known mitigations for naive automation detection.
+//! - **tracing**: Emits a `webdriver.command` span from the `tracing` crate for every
+//!   command, with the endpoint, session id, duration and result status.
+//! - **ureq-transport**: Enables `WebDriverUreq`, which sends requests using the blocking
+//!   `ureq` client instead of `reqwest`. `reqwest` is still compiled, as the `thirtyfour`
+//!   crate depends on it.
+//! - **visual-locator**: Enables `WebDriver::find_by_image()`, which locates a template
+//!   image on a screenshot for canvas-based UIs.
+//!
 //! ## Examples
 //!
 //! The following example assumes you have a selenium server running
@@ -84,6 +114,7 @@
 
 #![forbid(unsafe_code)]
 #![allow(clippy::needless_doctest_main)]
+#![allow(clippy::result_large_err)]
 
 /// Re-export common types.
 pub use thirtyfour::common::{
@@ -103,8 +134,42 @@ pub use thirtyfour::error;
 pub use thirtyfour::SessionId;
 
 pub use alert::Alert;
+pub use auth_state::{AuthExportOptions, AuthState};
+pub use browser_info::BrowserInfo;
+pub use clock::ClockReport;
+pub use color::Color;
+#[cfg(feature = "fixture-server")]
+pub use fixture_server::FixtureServer;
+pub use frames::{FoundElement, FrameGuard, FramePath};
+pub use gamepad::GamepadState;
+pub use geometry::ElementCenter;
+pub use health::{HealthCheckOptions, HealthProbe, HealthReport};
+#[cfg(feature = "ureq-transport")]
+pub use http::ureq_sync::WebDriverUreq;
+pub use listener::WebDriverEventListener;
+pub use locators::Locators;
+pub use media::MediaDevice;
+pub use menu::MenuOptions;
+pub use metadata::SessionMetadata;
+#[cfg(feature = "ocr")]
+pub use ocr::TextMatch;
+pub use page_info::PageInfo;
+pub use paginate::Paginator;
+pub use pinned::PinnedElement;
+pub use print::PrintOptions;
+pub use retry::RetryPolicy;
+pub use screenshot::{Screenshot, ScreenshotOptions};
+pub use script_args::ScriptArgsExt;
+pub use scroll::{ScrollOptions, ScrollReport, ScrollStep, ScrollUntil};
+pub use secret::SecretString;
 pub use session::WebDriverSession;
+pub use speech::SpokenUtterance;
 pub use switch_to::SwitchTo;
+pub use transaction::{TransactionReport, TransactionReporter};
+pub use upload::UploadOptions;
+pub use viewport::{Device, Orientation, Preset};
+#[cfg(feature = "visual-locator")]
+pub use visual::ImageMatch;
 pub use webdriver::GenericWebDriver;
 pub use webdriver::WebDriver;
 pub use webdrivercommands::WebDriverCommands;
@@ -113,6 +178,7 @@ pub use webelement::WebElement;
 pub mod prelude {
     pub use crate::alert::Alert;
     pub use crate::error::WebDriverResult;
+    pub use crate::script_args::ScriptArgsExt;
     pub use crate::switch_to::SwitchTo;
     pub use crate::webdriver::WebDriver;
     pub use crate::webdrivercommands::{ScriptRetSync, WebDriverCommands};
@@ -122,23 +188,179 @@ pub mod prelude {
 
 mod action_chain;
 mod alert;
+mod auth_state;
+/// Sending independent read-only commands together, to save round trips.
+pub mod batch;
+/// A synchronous WebDriver BiDi client, for browser events on Firefox and Chrome.
+#[cfg(feature = "bidi")]
+pub mod bidi;
+/// Conversions between handles in this crate and the async `thirtyfour` crate.
+pub mod bridge;
+mod browser_info;
+mod canvas;
+mod clock;
+mod color;
+/// Streaming and filtering browser console messages.
+#[cfg(any(feature = "bidi", feature = "cdp-websocket"))]
+pub mod console;
+/// Recognising page crashes and out-of-memory errors, as distinct from failed commands.
+pub mod crash;
+/// Breadth-first site crawling using one or more browser sessions.
+pub mod crawler;
+mod error_info;
+#[cfg(feature = "fixture-server")]
+mod fixture_server;
+mod frames;
+mod gamepad;
+mod geometry;
+mod health;
+mod inline_html;
+/// Waiting for elements to become interactable, separately from HTTP timeouts.
+pub mod interaction;
+mod js_event;
+mod json;
+mod legacy;
+mod listener;
+mod locators;
+mod media;
+mod menu;
+mod metadata;
 pub mod http {
+    #[cfg(feature = "async-transport")]
+    pub mod async_sync;
+    pub mod config;
     pub mod connection_sync;
+    #[cfg(feature = "fault-injection")]
+    pub mod fault_sync;
     pub mod reqwest_sync;
+    #[cfg(feature = "ureq-transport")]
+    pub mod ureq_sync;
+}
+/// Synthetic monitoring of user flows, run on an interval.
+pub mod monitoring;
+/// Readable narration of the actions performed in a session.
+pub mod narrator;
+#[cfg(feature = "ocr")]
+mod ocr;
+mod offline;
+mod page_info;
+mod page_source;
+mod paginate;
+/// Running the same closure concurrently in several browser sessions.
+pub mod parallel;
+/// Elements pinned to the page they were found on.
+pub mod pinned;
+/// Robots.txt support and per-host rate limiting for navigations.
+pub mod politeness;
+mod print;
+/// Polling support for waiting on elements and other conditions.
+pub mod query {
+    mod conditions;
+    mod element_query;
+    mod eventually;
+    mod implicit;
+    mod poller;
+    mod ready;
+    mod settle;
+    mod waiter;
+
+    pub use conditions::Condition;
+    pub use element_query::ElementQuery;
+    pub(crate) use eventually::poll_assertion;
+    pub(crate) use implicit::{find_elements_nowait, without_implicit_wait};
+    pub use poller::ElementPoller;
+    pub use ready::{DocumentReady, JsCondition, NetworkIdle, ReadyStrategy};
+    pub(crate) use settle::wait_for_page_settled;
+    pub use waiter::ElementWaiter;
 }
+mod retry;
+/// Multi-user scenarios with named roles, barriers and message passing.
+pub mod scenario;
+mod screenshot;
+mod script_args;
+mod scroll;
+mod secret;
+/// Utilities for building XPath expressions and CSS selectors safely.
+pub mod selectors;
 mod session;
+mod speech;
+/// Mitigations for naive automation detection in Chromium-based browsers.
+#[cfg(feature = "stealth")]
+pub mod stealth;
+mod style;
+mod suggest;
 mod switch_to;
+#[cfg(feature = "tracing")]
+mod telemetry;
+mod text;
+mod transaction;
+mod upload;
+mod viewport;
+#[cfg(feature = "visual-locator")]
+mod visual;
 mod webdriver;
 mod webdrivercommands;
 mod webelement;
+#[cfg(any(feature = "bidi", feature = "cdp-websocket"))]
+mod websocket;
+/// Helpers for common third-party UI widgets.
+pub mod widgets {
+    /// Setting dates in date picker widgets.
+    pub mod datepicker;
+    /// Selecting options in `<select>` elements.
+    pub mod select;
+}
+mod zoom;
 
 /// Extensions for specific browsers.
 pub mod extensions {
     /// Extensions for working with Chromium-based browsers.
     pub mod chrome {
+        mod battery;
+        mod browser_version;
+        mod cache_storage;
+        mod connection;
         mod devtools;
+        #[cfg(feature = "cdp-websocket")]
+        mod events;
+        mod geolocation;
+        mod media;
+        mod network;
+        mod notifications;
+        mod runtime;
+        mod sensors;
+        mod target;
+        mod user_agent;
+        mod waterfall;
 
+        pub use battery::BatteryState;
+        pub use browser_version::BrowserVersion;
+        pub use cache_storage::{Cache, CacheEntry, CacheHeader, CacheStorage};
+        pub use connection::{ConnectionType, EffectiveConnectionType};
         pub use devtools::ChromeDevTools;
+        #[cfg(feature = "cdp-websocket")]
+        pub use events::{CdpEvent, CdpEventListener, CdpEvents};
+        pub use geolocation::RouteOptions;
+        pub use media::ChromeCapabilitiesExt;
+        pub use network::{
+            EventSourceMessage, NetworkEvent, NetworkMonitor, NetworkResponse, ResponseBody,
+        };
+        pub use notifications::CapturedNotification;
+        pub use runtime::{CallArgument, RemoteObject};
+        pub use sensors::{DeviceOrientation, SensorReading, SensorType};
+        pub use target::TargetInfo;
+        #[cfg(feature = "cdp-websocket")]
+        pub use target::{CdpConnection, TargetSession};
         pub use thirtyfour::extensions::chrome::NetworkConditions;
+        pub use user_agent::{Brand, UserAgentMetadata};
+        pub use waterfall::{RequestTiming, Waterfall, WaterfallSummary};
+    }
+    /// Extensions for working with Firefox.
+    pub mod firefox {
+        mod media;
+        mod tools;
+
+        pub use media::FirefoxCapabilitiesExt;
+        pub use tools::{FirefoxContext, FirefoxTools};
     }
 }
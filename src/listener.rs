@@ -0,0 +1,142 @@
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
+
+use serde_json::Value;
+use thirtyfour::RequestData;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::{By, WebDriverSession, WebElement};
+
+/// Hooks that are called as a session sends commands, e.g. to capture a
+/// screenshot whenever a command fails or to record metrics, without
+/// wrapping every call site.
+///
+/// Register a listener with
+/// [WebDriver::register_listener()](struct.GenericWebDriver.html#method.register_listener).
+/// Every method does nothing by default, so implement only the hooks you
+/// need. Hooks are called on the thread that sends the command, and are
+/// given the session so that they can send commands of their own. Commands
+/// sent by a hook are not reported to the listeners.
+///
+/// Secrets sent with
+/// [WebElement::send_secret()](struct.WebElement.html#method.send_secret)
+/// are redacted from the requests and errors passed to the hooks.
+///
+/// # Example
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::common::requestdata::RequestData;
+/// use thirtyfour_sync::error::WebDriverError;
+/// use thirtyfour_sync::{WebDriverEventListener, WebDriverSession};
+/// use std::time::Duration;
+///
+/// #[derive(Debug)]
+/// struct ScreenshotOnError;
+///
+/// impl WebDriverEventListener for ScreenshotOnError {
+///     fn after_command(
+///         &self,
+///         _session: &WebDriverSession,
+///         request: &RequestData,
+///         _result: &WebDriverResult<serde_json::Value>,
+///         duration: Duration,
+///     ) {
+///         println!("command.duration:{}|ms|#url:{}", duration.as_millis(), request.url);
+///     }
+///
+///     fn on_error(&self, session: &WebDriverSession, _request: &RequestData, _error: &WebDriverError) {
+///         let _ = session.screenshot(&std::env::temp_dir().join("failure.png"));
+///     }
+/// }
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.register_listener(Box::new(ScreenshotOnError));
+/// driver.get("http://webappdemo")?;
+/// #     Ok(())
+/// # }
+/// ```
+pub trait WebDriverEventListener: fmt::Debug + Send + Sync {
+    /// Called before a command is sent.
+    fn before_command(&self, _session: &WebDriverSession, _request: &RequestData) {}
+
+    /// Called after a command completes, whether or not it succeeded.
+    fn after_command(
+        &self,
+        _session: &WebDriverSession,
+        _request: &RequestData,
+        _result: &WebDriverResult<Value>,
+        _duration: Duration,
+    ) {
+    }
+
+    /// Called after a command fails, after `after_command()`.
+    fn on_error(
+        &self,
+        _session: &WebDriverSession,
+        _request: &RequestData,
+        _error: &WebDriverError,
+    ) {
+    }
+
+    /// Called before finding an element or elements, from the page or from
+    /// another element.
+    fn before_find(&self, _session: &WebDriverSession, _by: &By) {}
+
+    /// Called before clicking an element.
+    fn before_click(&self, _session: &WebDriverSession, _element: &WebElement) {}
+
+    /// Called after an element was clicked successfully.
+    fn after_click(&self, _session: &WebDriverSession, _element: &WebElement) {}
+}
+
+thread_local! {
+    /// Set while listeners are being called, so that commands sent by a
+    /// listener are not reported to the listeners.
+    static NOTIFYING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Resets NOTIFYING, even if a listener panics.
+struct NotifyingGuard;
+
+impl Drop for NotifyingGuard {
+    fn drop(&mut self) {
+        NOTIFYING.with(|n| n.set(false));
+    }
+}
+
+/// Call the function for each listener registered with the session, unless
+/// this is a command sent by a listener.
+pub(crate) fn notify<F>(session: &WebDriverSession, f: F)
+where
+    F: Fn(&dyn WebDriverEventListener),
+{
+    if session.listeners().is_empty() || NOTIFYING.with(|n| n.replace(true)) {
+        return;
+    }
+    let _guard = NotifyingGuard;
+    for listener in session.listeners() {
+        f(listener.as_ref());
+    }
+}
+
+/// A copy of the request with any secrets redacted.
+pub(crate) fn redacted_request(session: &WebDriverSession, request: &RequestData) -> RequestData {
+    let mut request = request.clone();
+    request.url = session.redact(&request.url);
+    if let Some(body) = &request.body {
+        let text = body.to_string();
+        let redacted = session.redact(&text);
+        if redacted != text {
+            let mut body = serde_json::from_str(&redacted).unwrap_or(Value::String(redacted));
+            // Typed text is also sent one character at a time.
+            if let (Some(text), true) = (body["text"].as_str(), body["value"].is_array()) {
+                body["value"] = text.chars().map(|c| Value::String(c.to_string())).collect();
+            }
+            request.body = Some(body);
+        }
+    }
+    request
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::By;
+
+/// The kinds of locator, with the keys used for them in locator files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LocatorKind {
+    Id,
+    XPath,
+    LinkText,
+    PartialLinkText,
+    Name,
+    Tag,
+    ClassName,
+    Css,
+}
+
+const LOCATOR_KINDS: &[(&str, LocatorKind)] = &[
+    ("id", LocatorKind::Id),
+    ("xpath", LocatorKind::XPath),
+    ("link_text", LocatorKind::LinkText),
+    ("partial_link_text", LocatorKind::PartialLinkText),
+    ("name", LocatorKind::Name),
+    ("tag", LocatorKind::Tag),
+    ("class_name", LocatorKind::ClassName),
+    ("css", LocatorKind::Css),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Locator {
+    kind: LocatorKind,
+    value: String,
+}
+
+impl Locator {
+    fn from_by(by: &By) -> Self {
+        let (kind, value) = match by {
+            By::Id(x) => (LocatorKind::Id, x),
+            By::XPath(x) => (LocatorKind::XPath, x),
+            By::LinkText(x) => (LocatorKind::LinkText, x),
+            By::PartialLinkText(x) => (LocatorKind::PartialLinkText, x),
+            By::Name(x) => (LocatorKind::Name, x),
+            By::Tag(x) => (LocatorKind::Tag, x),
+            By::ClassName(x) => (LocatorKind::ClassName, x),
+            By::Css(x) => (LocatorKind::Css, x),
+        };
+        Self {
+            kind,
+            value: value.to_string(),
+        }
+    }
+
+    fn by(&self) -> By<'_> {
+        let value = self.value.as_str();
+        match self.kind {
+            LocatorKind::Id => By::Id(value),
+            LocatorKind::XPath => By::XPath(value),
+            LocatorKind::LinkText => By::LinkText(value),
+            LocatorKind::PartialLinkText => By::PartialLinkText(value),
+            LocatorKind::Name => By::Name(value),
+            LocatorKind::Tag => By::Tag(value),
+            LocatorKind::ClassName => By::ClassName(value),
+            LocatorKind::Css => By::Css(value),
+        }
+    }
+}
+
+fn invalid(message: String) -> WebDriverError {
+    WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&message))
+}
+
+/// A registry of locators with logical names such as `login.submit`, so
+/// that the selectors used by a test suite are kept in one place.
+///
+/// Locators can be added in code or loaded from JSON, or from TOML with the
+/// `locators-toml` feature. Each locator is an object with a single key
+/// naming the kind of locator: `id`, `xpath`, `link_text`,
+/// `partial_link_text`, `name`, `tag`, `class_name` or `css`. Any other
+/// object is a namespace, whose locators are named with its key and a dot.
+///
+/// ```toml
+/// [login]
+/// username = { id = "username" }
+/// submit = { css = "form#login button[type=submit]" }
+/// ```
+///
+/// Set the registry on a WebDriver with
+/// [WebDriver::set_locators()](struct.GenericWebDriver.html#method.set_locators)
+/// to find elements by name using
+/// [find_named()](trait.WebDriverCommands.html#method.find_named). Errors
+/// from finding a named locator mention its name.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::{By, Locators};
+///
+/// # fn main() -> thirtyfour_sync::error::WebDriverResult<()> {
+/// let mut locators = Locators::from_json(
+///     r#"{ "login": { "username": { "id": "username" }, "submit": { "css": "button" } } }"#,
+/// )?;
+/// locators.insert("login.password", By::Name("password"));
+/// assert!(matches!(locators.get("login.submit")?, By::Css("button")));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Locators {
+    locators: BTreeMap<String, Locator>,
+}
+
+impl Locators {
+    /// Create a new, empty Locators registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load locators from a JSON string.
+    pub fn from_json(json: &str) -> WebDriverResult<Self> {
+        Self::from_value(&serde_json::from_str(json)?)
+    }
+
+    /// Load locators from a TOML string.
+    #[cfg(feature = "locators-toml")]
+    pub fn from_toml(toml: &str) -> WebDriverResult<Self> {
+        let value: Value = toml::from_str(toml)
+            .map_err(|e| invalid(format!("failed to parse locators: {}", e)))?;
+        Self::from_value(&value)
+    }
+
+    /// Load locators from a file, which is parsed as TOML if its name ends
+    /// with `.toml` and as JSON otherwise.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> WebDriverResult<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|x| x == "toml") {
+            #[cfg(feature = "locators-toml")]
+            return Self::from_toml(&text);
+            #[cfg(not(feature = "locators-toml"))]
+            return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "loading locators from TOML requires the locators-toml feature",
+            )));
+        }
+        Self::from_json(&text)
+    }
+
+    fn from_value(value: &Value) -> WebDriverResult<Self> {
+        let mut locators = Self::new();
+        locators.add_namespace("", value)?;
+        Ok(locators)
+    }
+
+    fn add_namespace(&mut self, prefix: &str, value: &Value) -> WebDriverResult<()> {
+        let entries = value.as_object().ok_or_else(|| {
+            invalid(format!("locator namespace {:?} must be an object, not {}", prefix, value))
+        })?;
+        for (key, value) in entries {
+            let name = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            let locator = match value.as_object() {
+                Some(x) if x.len() == 1 => x.iter().next().and_then(|(kind, selector)| {
+                    LOCATOR_KINDS.iter().find(|(k, _)| k == kind).map(|(_, kind)| (*kind, selector))
+                }),
+                _ => None,
+            };
+            match locator {
+                Some((kind, Value::String(selector))) => {
+                    self.locators.insert(
+                        name,
+                        Locator {
+                            kind,
+                            value: selector.clone(),
+                        },
+                    );
+                }
+                Some((_, selector)) => {
+                    return Err(invalid(format!(
+                        "locator {:?} must be a string, not {}",
+                        name, selector
+                    )))
+                }
+                None => self.add_namespace(&name, value)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a locator, replacing any existing locator with the same name.
+    pub fn insert(&mut self, name: &str, by: By) {
+        self.locators.insert(name.to_string(), Locator::from_by(&by));
+    }
+
+    /// Add all the locators from another registry, replacing any with the
+    /// same names.
+    pub fn extend(&mut self, other: Locators) {
+        self.locators.extend(other.locators);
+    }
+
+    /// Get the locator with the specified name.
+    pub fn get(&self, name: &str) -> WebDriverResult<By<'_>> {
+        match self.locators.get(name) {
+            Some(locator) => Ok(locator.by()),
+            None => Err(invalid(format!("no locator named {:?}", name))),
+        }
+    }
+
+    /// The names of all the locators, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.locators.keys().map(|x| x.as_str())
+    }
+
+    /// The number of locators.
+    pub fn len(&self) -> usize {
+        self.locators.len()
+    }
+
+    /// Returns true if there are no locators.
+    pub fn is_empty(&self) -> bool {
+        self.locators.is_empty()
+    }
+}
+
+/// Get a named locator from the registry set on the session.
+pub(crate) fn named_locator<'a>(
+    locators: Option<&'a Locators>,
+    name: &str,
+) -> WebDriverResult<By<'a>> {
+    match locators {
+        Some(locators) => locators.get(name),
+        None => Err(invalid(format!(
+            "cannot find locator {:?} because no Locators are set; use set_locators()",
+            name
+        ))),
+    }
+}
+
+/// Mention the name of the locator in an error from finding it.
+pub(crate) fn with_locator_name(name: &str, by: &By, error: WebDriverError) -> WebDriverError {
+    match error {
+        WebDriverError::NoSuchElement(mut info) => {
+            info.value.message = format!("locator {:?} ({}): {}", name, by, info.value.message);
+            WebDriverError::NoSuchElement(info)
+        }
+        e => e,
+    }
+}
@@ -0,0 +1,155 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::{ExtensionCommand, RequestMethod};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::page_info::assertion_failed;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// A camera, microphone or speaker, as reported by
+/// `navigator.mediaDevices.enumerateDevices()`.
+///
+/// Labels are only reported once the page has been granted access to a
+/// device of that kind.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaDevice {
+    /// The device id, which is specific to the origin.
+    pub device_id: String,
+    /// The kind of device: `videoinput`, `audioinput` or `audiooutput`.
+    pub kind: String,
+    /// The device name, e.g. `FaceTime HD Camera`.
+    pub label: String,
+    /// The id shared by devices that belong to the same physical device.
+    pub group_id: String,
+}
+
+/// The W3C Permissions extension command, to set a permission for the
+/// current origin.
+struct SetPermission {
+    name: &'static str,
+}
+
+impl ExtensionCommand for SetPermission {
+    fn parameters_json(&self) -> Option<Value> {
+        Some(json!({ "descriptor": { "name": self.name }, "state": "granted" }))
+    }
+
+    fn method(&self) -> RequestMethod {
+        RequestMethod::Post
+    }
+
+    fn endpoint(&self) -> String {
+        String::from("/permissions")
+    }
+}
+
+/// Lists the media devices, or none if the API is unavailable, e.g. on an
+/// insecure origin.
+const ENUMERATE_DEVICES_SCRIPT: &str = r#"
+    const done = arguments[arguments.length - 1];
+    if (!navigator.mediaDevices || !navigator.mediaDevices.enumerateDevices) {
+        done([]);
+        return;
+    }
+    navigator.mediaDevices.enumerateDevices().then(
+        (devices) => done(devices.map((d) => ({
+            deviceId: d.deviceId,
+            kind: d.kind,
+            label: d.label,
+            groupId: d.groupId,
+        }))),
+        () => done([])
+    );
+"#;
+
+/// Finds the live camera and microphone tracks attached to `<video>` and
+/// `<audio>` elements, and matches them to the devices they come from.
+const SELECTED_DEVICES_SCRIPT: &str = r#"
+    const done = arguments[arguments.length - 1];
+    const tracks = [];
+    for (const e of document.querySelectorAll("video, audio")) {
+        const stream = e.srcObject;
+        if (!stream || typeof stream.getTracks !== "function") continue;
+        for (const track of stream.getTracks()) {
+            if (track.readyState === "live" && !tracks.includes(track)) tracks.push(track);
+        }
+    }
+    const enumerate = navigator.mediaDevices && navigator.mediaDevices.enumerateDevices
+        ? navigator.mediaDevices.enumerateDevices()
+        : Promise.resolve([]);
+    enumerate.then((devices) => devices, () => []).then((devices) => {
+        done(tracks.map((track) => {
+            const settings = track.getSettings ? track.getSettings() : {};
+            const kind = track.kind === "video" ? "videoinput" : "audioinput";
+            const device = devices.find((d) => d.kind === kind && d.deviceId === settings.deviceId);
+            return {
+                deviceId: settings.deviceId || "",
+                kind,
+                label: device ? device.label : track.label,
+                groupId: settings.groupId || (device ? device.groupId : ""),
+            };
+        }));
+    });
+"#;
+
+/// Grant the camera and microphone permissions. Chromium-based browsers
+/// grant them for every origin, using DevTools. Other browsers are asked via
+/// the W3C Permissions extension command, which applies to the current
+/// origin.
+pub(crate) fn grant_media_permissions(session: &WebDriverSession) -> WebDriverResult<()> {
+    if session.browser_info().is_chromium() {
+        let dev_tools = ChromeDevTools::new(session);
+        for name in &["videoCapture", "audioCapture"] {
+            dev_tools.execute_cdp_with_params(
+                "Browser.setPermission",
+                json!({ "permission": { "name": name }, "setting": "granted" }),
+            )?;
+        }
+        return Ok(());
+    }
+
+    for name in &["camera", "microphone"] {
+        session
+            .extension_command(SetPermission {
+                name,
+            })
+            .map_err(|e| {
+                WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(&format!(
+                    "the browser could not grant the {} permission ({}); on Firefox, use \
+                     FirefoxCapabilitiesExt::allow_media() before starting the session",
+                    name, e
+                )))
+            })?;
+    }
+    Ok(())
+}
+
+pub(crate) fn media_devices(session: &WebDriverSession) -> WebDriverResult<Vec<MediaDevice>> {
+    session.execute_async_script(ENUMERATE_DEVICES_SCRIPT)?.convert()
+}
+
+pub(crate) fn selected_media_devices(
+    session: &WebDriverSession,
+) -> WebDriverResult<Vec<MediaDevice>> {
+    session.execute_async_script(SELECTED_DEVICES_SCRIPT)?.convert()
+}
+
+pub(crate) fn assert_media_device_selected(
+    session: &WebDriverSession,
+    label: &str,
+) -> WebDriverResult<()> {
+    let selected = selected_media_devices(session)?;
+    if !selected.iter().any(|d| d.label.contains(label)) {
+        let labels: Vec<&str> = selected.iter().map(|d| d.label.as_str()).collect();
+        let message = format!(
+            "no live media track from a device labelled {:?}; selected devices: {:?}",
+            label, labels
+        );
+        let item = format!("media device {:?}", label);
+        return Err(assertion_failed(session, item, &message));
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::query::{find_elements_nowait, ElementPoller};
+use crate::selectors::xpath_literal;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession, WebElement};
+
+/// Options for
+/// [WebDriver::navigate_menu_with_options()](trait.WebDriverCommands.html#method.navigate_menu_with_options).
+#[derive(Debug, Clone)]
+pub struct MenuOptions {
+    /// How long to keep the pointer on each menu before moving to the next
+    /// level, so that menus which wait for hover intent open.
+    pub dwell: Duration,
+    /// Controls how long to wait for each level of the menu to appear.
+    pub poller: ElementPoller,
+}
+
+impl Default for MenuOptions {
+    fn default() -> Self {
+        Self {
+            dwell: Duration::from_millis(300),
+            poller: ElementPoller::TimeoutWithInterval(
+                Duration::from_secs(10),
+                Duration::from_millis(100),
+            ),
+        }
+    }
+}
+
+impl MenuOptions {
+    /// Create new MenuOptions with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how long to hover over each menu before moving on.
+    pub fn with_dwell(mut self, dwell: Duration) -> Self {
+        self.dwell = dwell;
+        self
+    }
+
+    /// Set the poller used while waiting for each level to appear.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+}
+
+/// Find the elements matching the XPath that are currently displayed.
+/// Elements that go stale while being checked are skipped.
+pub(crate) fn visible_elements<'a>(
+    session: &'a WebDriverSession,
+    xpath: &str,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    Ok(find_elements_nowait(session, By::XPath(xpath))?
+        .into_iter()
+        .filter(|e| matches!(e.is_displayed(), Ok(true)))
+        .collect())
+}
+
+/// Wait for a visible menu entry with the specified text. Links, buttons
+/// and elements with a `menuitem` role are preferred over other elements
+/// whose own text matches.
+fn wait_for_entry<'a>(
+    session: &'a WebDriverSession,
+    label: &str,
+    poller: &ElementPoller,
+) -> WebDriverResult<WebElement<'a>> {
+    let text = xpath_literal(label);
+    let entries = format!(
+        "//*[self::a or self::button or starts-with(@role, 'menuitem')][normalize-space(.)={}]",
+        text
+    );
+    let others = format!("//*[normalize-space(text())={}]", text);
+    let description = format!("menu entry {:?} did not appear", label);
+    let result = poller.poll(&description, || {
+        if let Some(entry) = visible_elements(session, &entries)?.into_iter().next() {
+            return Ok(Some(entry));
+        }
+        Ok(visible_elements(session, &others)?.into_iter().next())
+    });
+    match result {
+        Err(WebDriverError::Timeout(_)) => {
+            Err(WebDriverError::Timeout(with_page_context(session, &description)))
+        }
+        x => x,
+    }
+}
+
+pub(crate) fn navigate_menu(
+    session: &WebDriverSession,
+    labels: &[&str],
+    options: &MenuOptions,
+) -> WebDriverResult<()> {
+    let (leaf, parents) = match labels.split_last() {
+        Some(x) => x,
+        None => {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "menu path must not be empty",
+            )))
+        }
+    };
+    for label in parents {
+        let entry = wait_for_entry(session, label, &options.poller)?;
+        session.action_chain().move_to_element_center(&entry).perform()?;
+        thread::sleep(options.dwell);
+    }
+    let entry = wait_for_entry(session, leaf, &options.poller)?;
+    // Click with the pointer, so that the menu sees it move onto the entry
+    // rather than leave.
+    session.action_chain().move_to_element_center(&entry).click().perform()
+}
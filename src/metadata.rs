@@ -0,0 +1,156 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{Capabilities, WebDriverSession};
+
+/// Tags describing a session, such as the test name and build id, so that
+/// logs, reports and cloud recordings can be matched up with the test that
+/// produced them.
+///
+/// Set tags on a running session with
+/// [WebDriver::set_session_metadata()](trait.WebDriverCommands.html#method.set_session_metadata).
+/// Tags are included in transaction reports, in the page context of error
+/// messages, and are forwarded to the cloud providers that support renaming
+/// a running session. Selenium Grid only takes the session name when the
+/// session is created, so use `add_to_capabilities()` for that.
+///
+/// The `name` and `build` keys have special meaning to cloud providers. Other
+/// keys are sent as tags where supported.
+///
+/// # Example
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::SessionMetadata;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let metadata = SessionMetadata::new().with("name", "login test").with("build", "1234");
+/// let mut caps = DesiredCapabilities::chrome();
+/// metadata.add_to_capabilities(&mut caps)?;
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.set_session_metadata("name", "login test")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionMetadata {
+    entries: Vec<(String, String)>,
+}
+
+impl SessionMetadata {
+    /// Create an empty SessionMetadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the specified key, replacing any existing value.
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Set the specified key, replacing any existing value. Keys keep the
+    /// order in which they were first set.
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.entries.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    /// Get the value of the specified key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// The tags, in the order in which they were first set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns true if no tags are set.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add the session name to the capabilities as `se:name`, which
+    /// Selenium Grid shows in its UI and uses to name session recordings.
+    pub fn add_to_capabilities<C: Capabilities>(&self, caps: &mut C) -> WebDriverResult<()> {
+        if let Some(name) = self.get("name") {
+            caps.add("se:name", name)?;
+        }
+        Ok(())
+    }
+
+    /// The tags other than `name` and `build`, as `key:value` strings.
+    fn tags(&self) -> Vec<String> {
+        self.iter()
+            .filter(|(k, _)| *k != "name" && *k != "build")
+            .map(|(k, v)| format!("{}:{}", k, v))
+            .collect()
+    }
+}
+
+impl fmt::Display for SessionMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "{}", entries.join(", "))
+    }
+}
+
+/// The cloud providers that accept session metadata while the session is
+/// running, detected from the session capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cloud {
+    SauceLabs,
+    BrowserStack,
+    LambdaTest,
+}
+
+fn detect_cloud(caps: &Value) -> Option<Cloud> {
+    let keys = caps.as_object()?.keys();
+    for key in keys {
+        if key.starts_with("sauce:") {
+            return Some(Cloud::SauceLabs);
+        } else if key.starts_with("bstack:") {
+            return Some(Cloud::BrowserStack);
+        } else if key == "LT:Options" {
+            return Some(Cloud::LambdaTest);
+        }
+    }
+    None
+}
+
+/// The scripts that tell the cloud provider about the specified key.
+fn cloud_scripts(cloud: Cloud, metadata: &SessionMetadata, key: &str) -> Vec<String> {
+    let value = metadata.get(key).unwrap_or_default();
+    match (cloud, key) {
+        (Cloud::SauceLabs, "name") => vec![format!("sauce:job-name={}", value)],
+        (Cloud::SauceLabs, "build") => vec![format!("sauce:job-build={}", value)],
+        (Cloud::SauceLabs, _) => vec![format!("sauce:job-tags={}", metadata.tags().join(","))],
+        (Cloud::BrowserStack, "name") => {
+            let command = json!({"action": "setSessionName", "arguments": {"name": value}});
+            vec![format!("browserstack_executor: {}", command)]
+        }
+        (Cloud::LambdaTest, "name") => vec![format!("lambda-name={}", value)],
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn set_session_metadata(
+    session: &WebDriverSession,
+    key: &str,
+    value: &str,
+) -> WebDriverResult<()> {
+    let metadata = session.update_metadata(|m| m.set(key, value));
+    log::info!("session {} metadata: {}", session.session_id(), metadata);
+
+    if let Some(cloud) = detect_cloud(session.capabilities()) {
+        for script in cloud_scripts(cloud, &metadata, key) {
+            session.execute_script(&script)?;
+        }
+    }
+    Ok(())
+}
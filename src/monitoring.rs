@@ -0,0 +1,377 @@
+use std::fmt;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{PageInfo, RetryPolicy, WebDriver};
+
+type FlowFn = Arc<dyn Fn(&WebDriver) -> WebDriverResult<()> + Send + Sync>;
+
+/// The classification of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The flow succeeded at the first attempt, within the degraded threshold.
+    Up,
+    /// The flow succeeded, but only after a retry or slower than the
+    /// degraded threshold.
+    Degraded,
+    /// The flow failed or timed out on every attempt.
+    Down,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CheckStatus::Up => "up",
+            CheckStatus::Degraded => "degraded",
+            CheckStatus::Down => "down",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The outcome of a single check, including any artifacts captured when the
+/// flow failed.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// The name of the monitor.
+    pub name: String,
+    /// When the check started.
+    pub started: SystemTime,
+    /// The classification of the check.
+    pub status: CheckStatus,
+    /// The time taken by the last attempt.
+    pub duration: Duration,
+    /// The number of attempts made, including the first one.
+    pub attempts: u32,
+    /// The error from the last attempt, if it failed.
+    pub error: Option<String>,
+    /// A screenshot taken when the flow failed, as PNG bytes. This is None
+    /// if the flow succeeded, or the browser could not be reached.
+    pub screenshot: Option<Vec<u8>>,
+    /// The page the browser was on when the flow failed.
+    pub page: Option<PageInfo>,
+}
+
+/// Receives the result of a check whenever the status of a
+/// [Monitor](struct.Monitor.html) changes, e.g. to page someone.
+///
+/// The first check is only alerted if it is not up, and a return to up is
+/// alerted as a recovery. Closures taking the same arguments can be used as
+/// alert sinks.
+pub trait AlertSink: Send {
+    /// Handle a change of status. `previous` is None for the first check.
+    fn alert(&mut self, previous: Option<CheckStatus>, result: &CheckResult);
+}
+
+impl<F> AlertSink for F
+where
+    F: FnMut(Option<CheckStatus>, &CheckResult) + Send,
+{
+    fn alert(&mut self, previous: Option<CheckStatus>, result: &CheckResult) {
+        self(previous, result)
+    }
+}
+
+/// An AlertSink that logs status changes, as a warning for degraded and as
+/// an error for down.
+#[derive(Debug, Clone, Default)]
+pub struct LogAlertSink;
+
+impl AlertSink for LogAlertSink {
+    fn alert(&mut self, previous: Option<CheckStatus>, result: &CheckResult) {
+        let from = previous.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string());
+        let error = result.error.as_deref().unwrap_or("no error");
+        match result.status {
+            CheckStatus::Up => {
+                log::info!("monitor {} is up again (was {})", result.name, from)
+            }
+            CheckStatus::Degraded => log::warn!(
+                "monitor {} is degraded (was {}): took {:?} over {} attempts",
+                result.name,
+                from,
+                result.duration,
+                result.attempts
+            ),
+            CheckStatus::Down => {
+                log::error!("monitor {} is down (was {}): {}", result.name, from, error)
+            }
+        }
+    }
+}
+
+/// Runs a user flow on an interval, as a synthetic monitoring check, and
+/// alerts when it starts failing or slows down.
+///
+/// Each check runs the flow in a browser session with a timeout, retrying
+/// transient errors according to a [RetryPolicy](../struct.RetryPolicy.html),
+/// and classifies the result as up, degraded or down. The session is kept
+/// between checks while they succeed, and replaced after a failure. When a
+/// check fails, a screenshot and the current page are captured and included
+/// in the result.
+///
+/// A flow that times out cannot be interrupted, so it is left running on its
+/// own thread with its session, which is closed when the flow returns.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::monitoring::{LogAlertSink, Monitor};
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let mut monitor = Monitor::new("buttons", "http://localhost:4444/wd/hub", &caps, |driver| {
+///     driver.get("http://webappdemo")?;
+///     driver.find_element(By::Id("button1"))?.click()?;
+///     driver.find_element(By::Id("button-result"))?.assert_text_eq("Button 1 clicked")?;
+///     Ok(())
+/// })?
+/// .with_interval(Duration::from_secs(300))
+/// .with_degraded_after(Duration::from_secs(5))
+/// .with_alert_sink(LogAlertSink);
+///
+/// let result = monitor.check();
+/// println!("{}: {} in {:?}", result.name, result.status, result.duration);
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Monitor {
+    name: String,
+    server_url: String,
+    capabilities: Value,
+    flow: FlowFn,
+    interval: Duration,
+    timeout: Duration,
+    degraded_after: Option<Duration>,
+    retry_policy: RetryPolicy,
+    sinks: Vec<Box<dyn AlertSink>>,
+    driver: Option<WebDriver>,
+    last_status: Option<CheckStatus>,
+}
+
+impl fmt::Debug for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Monitor")
+            .field("name", &self.name)
+            .field("server_url", &self.server_url)
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("degraded_after", &self.degraded_after)
+            .field("retry_policy", &self.retry_policy)
+            .field("sinks", &self.sinks.len())
+            .field("last_status", &self.last_status)
+            .finish()
+    }
+}
+
+/// The outcome of a single attempt.
+struct Attempt {
+    duration: Duration,
+    result: WebDriverResult<()>,
+    driver: Option<WebDriver>,
+}
+
+impl Monitor {
+    /// Create a new Monitor with the specified name, that will run the flow
+    /// in sessions started on the specified server using the specified
+    /// capabilities.
+    ///
+    /// By default the flow runs every minute with a timeout of 30 seconds,
+    /// is never classified as degraded for being slow, and is retried
+    /// according to `RetryPolicy::new()`.
+    pub fn new<C, F>(
+        name: &str,
+        server_url: &str,
+        capabilities: C,
+        flow: F,
+    ) -> WebDriverResult<Self>
+    where
+        C: Serialize,
+        F: Fn(&WebDriver) -> WebDriverResult<()> + Send + Sync + 'static,
+    {
+        Ok(Self {
+            name: name.to_string(),
+            server_url: server_url.to_string(),
+            capabilities: serde_json::to_value(capabilities)?,
+            flow: Arc::new(flow),
+            interval: Duration::from_secs(60),
+            timeout: Duration::from_secs(30),
+            degraded_after: None,
+            retry_policy: RetryPolicy::new(),
+            sinks: Vec::new(),
+            driver: None,
+            last_status: None,
+        })
+    }
+
+    /// Set the time between the starts of consecutive checks.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the timeout for each attempt, including starting a session.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Classify a check as degraded if its successful attempt takes longer
+    /// than this.
+    pub fn with_degraded_after(mut self, threshold: Duration) -> Self {
+        self.degraded_after = Some(threshold);
+        self
+    }
+
+    /// Set the policy for retrying transient errors. Timeouts are reported
+    /// as `WebDriverError::Timeout`, and are only retried if the policy says
+    /// so.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Add a sink to be alerted when the status changes.
+    pub fn with_alert_sink<S: AlertSink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// The name of this monitor.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The status of the most recent check, if any.
+    pub fn last_status(&self) -> Option<CheckStatus> {
+        self.last_status
+    }
+
+    /// Run the flow once, on a new thread, with a timeout.
+    fn attempt(&mut self) -> Attempt {
+        let (sender, receiver) = channel();
+        let driver = self.driver.take();
+        let server_url = self.server_url.clone();
+        let capabilities = self.capabilities.clone();
+        let flow = self.flow.clone();
+        let start = Instant::now();
+        thread::spawn(move || {
+            let driver = match driver {
+                Some(driver) => Ok(driver),
+                None => WebDriver::new(&server_url, &capabilities),
+            };
+            let (driver, result) = match driver {
+                Ok(driver) => {
+                    let result = flow(&driver);
+                    (Some(driver), result)
+                }
+                Err(e) => (None, Err(e)),
+            };
+            // If the check timed out, the driver is dropped here instead.
+            let _ = sender.send((driver, result));
+        });
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok((driver, result)) => Attempt {
+                duration: start.elapsed(),
+                result,
+                driver,
+            },
+            Err(RecvTimeoutError::Timeout) => Attempt {
+                duration: start.elapsed(),
+                result: Err(WebDriverError::Timeout(format!(
+                    "monitor {} timed out after {:?}",
+                    self.name, self.timeout
+                ))),
+                driver: None,
+            },
+            Err(RecvTimeoutError::Disconnected) => Attempt {
+                duration: start.elapsed(),
+                result: Err(WebDriverError::FatalError(format!(
+                    "monitor {} flow panicked",
+                    self.name
+                ))),
+                driver: None,
+            },
+        }
+    }
+
+    /// Run a single check now, alerting the sinks if the status changed.
+    pub fn check(&mut self) -> CheckResult {
+        let started = SystemTime::now();
+        let mut attempts = 0;
+        let mut duration = Duration::default();
+        let mut failed_driver = None;
+        let policy = self.retry_policy.clone();
+        let outcome = policy.run(|| {
+            attempts += 1;
+            let attempt = self.attempt();
+            duration = attempt.duration;
+            match attempt.result {
+                Ok(()) => Ok(attempt.driver),
+                Err(e) => {
+                    // Keep the session of the last failed attempt for artifacts.
+                    failed_driver = attempt.driver;
+                    Err(e)
+                }
+            }
+        });
+
+        let mut result = CheckResult {
+            name: self.name.clone(),
+            started,
+            status: CheckStatus::Up,
+            duration,
+            attempts,
+            error: None,
+            screenshot: None,
+            page: None,
+        };
+        match outcome {
+            Ok(driver) => {
+                let slow = self.degraded_after.is_some_and(|t| duration > t);
+                if attempts > 1 || slow {
+                    result.status = CheckStatus::Degraded;
+                }
+                self.driver = driver;
+            }
+            Err(e) => {
+                result.status = CheckStatus::Down;
+                result.error = Some(e.to_string());
+                // Capture what we can, then start afresh next time.
+                if let Some(driver) = failed_driver {
+                    result.screenshot = driver.screenshot_as_png().ok();
+                    result.page = driver.page_info().ok();
+                }
+            }
+        }
+
+        if self.last_status != Some(result.status)
+            && (self.last_status.is_some() || result.status != CheckStatus::Up)
+        {
+            for sink in &mut self.sinks {
+                sink.alert(self.last_status, &result);
+            }
+        }
+        self.last_status = Some(result.status);
+        result
+    }
+
+    /// Run a check every interval, forever. Use
+    /// [check()](#method.check) in your own loop to stop early or to record
+    /// every result.
+    pub fn run(&mut self) -> ! {
+        loop {
+            let start = Instant::now();
+            self.check();
+            thread::sleep(self.interval.saturating_sub(start.elapsed()));
+        }
+    }
+}
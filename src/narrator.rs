@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use thirtyfour::{RequestData, RequestMethod};
+
+use crate::error::WebDriverResult;
+use crate::http::connection_sync::WebDriverHttpClientSync;
+
+/// The maximum number of characters of element text or typed text quoted in
+/// a sentence.
+const MAX_QUOTE_LEN: usize = 40;
+
+/// Records the actions performed in a session as plain sentences, such as
+/// `clicked 'Save' button (css=.btn-save)` or `navigated to
+/// https://example.com/checkout`, for attaching to bug reports.
+///
+/// Only actions are narrated: navigation, clicks, typing, window and frame
+/// switches, alerts and cookies. Queries such as reading text are left
+/// out. Elements are described by the locator used to find them and, for
+/// clicks and typing, their tag name and text, which costs two extra
+/// requests per interaction. Secrets sent with
+/// [WebElement::send_secret()](../struct.WebElement.html#method.send_secret)
+/// are redacted.
+///
+/// Each sentence is numbered, kept in memory and, if a writer was given,
+/// written as a line as soon as the action completes.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::narrator::Narrator;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.set_narrator(Some(Narrator::new()));
+/// driver.get("http://webappdemo")?;
+/// driver.find_element(By::Id("button1"))?.click()?;
+/// for step in driver.session().narrator().unwrap().sentences() {
+///     println!("{}", step);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Narrator {
+    writer: Option<Mutex<Box<dyn Write + Send>>>,
+    sentences: Mutex<Vec<String>>,
+    locators: Mutex<HashMap<String, String>>,
+}
+
+impl fmt::Debug for Narrator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Narrator")
+            .field("sentences", &self.sentences.lock().unwrap_or_else(|e| e.into_inner()).len())
+            .finish()
+    }
+}
+
+impl Default for Narrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a request does, worked out before it is sent.
+pub(crate) struct Narration {
+    sentence: Option<String>,
+    locator: Option<String>,
+}
+
+impl Narrator {
+    /// Create a new Narrator that keeps the sentences in memory only.
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            sentences: Mutex::new(Vec::new()),
+            locators: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a new Narrator that also writes each sentence to the writer.
+    pub fn with_writer<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Some(Mutex::new(Box::new(writer))),
+            ..Self::new()
+        }
+    }
+
+    /// Create a new Narrator that also writes each sentence to the file,
+    /// replacing any existing file.
+    pub fn to_file<P: AsRef<Path>>(path: P) -> WebDriverResult<Self> {
+        Ok(Self::with_writer(File::create(path)?))
+    }
+
+    /// The sentences recorded so far.
+    pub fn sentences(&self) -> Vec<String> {
+        self.sentences.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Work out what the request does, before it is sent.
+    pub(crate) fn narrate(
+        &self,
+        conn: &dyn WebDriverHttpClientSync,
+        request: &RequestData,
+    ) -> Narration {
+        let path = match request.url.strip_prefix("/session/").and_then(|rest| rest.find('/')) {
+            Some(i) => &request.url["/session/".len() + i..],
+            None => "",
+        };
+        let prefix = &request.url[..request.url.len() - path.len()];
+        let body = request.body.as_ref().unwrap_or(&Value::Null);
+        let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        let element = |id: &str| self.describe_element(conn, prefix, id);
+
+        let mut locator = None;
+        let sentence = match (&request.method, segments.as_slice()) {
+            (RequestMethod::Delete, [""]) => Some("ended the session".to_string()),
+            (RequestMethod::Post, ["url"]) => {
+                Some(format!("navigated to {}", body["url"].as_str().unwrap_or_default()))
+            }
+            (RequestMethod::Post, ["back"]) => Some("went back".to_string()),
+            (RequestMethod::Post, ["forward"]) => Some("went forward".to_string()),
+            (RequestMethod::Post, ["refresh"]) => Some("refreshed the page".to_string()),
+            (RequestMethod::Post, ["element"])
+            | (RequestMethod::Post, ["elements"])
+            | (RequestMethod::Post, ["element", _, "element"])
+            | (RequestMethod::Post, ["element", _, "elements"]) => {
+                locator = Some(format_locator(body));
+                None
+            }
+            (RequestMethod::Post, ["element", id, "click"]) => {
+                Some(format!("clicked {}", element(id)))
+            }
+            (RequestMethod::Post, ["element", id, "clear"]) => {
+                Some(format!("cleared {}", element(id)))
+            }
+            (RequestMethod::Post, ["element", id, "value"]) => Some(format!(
+                "typed {} into {}",
+                quote(&keys_text(body["text"].as_str().unwrap_or_default())),
+                element(id)
+            )),
+            (RequestMethod::Post, ["window"]) => Some("switched to another window".to_string()),
+            (RequestMethod::Post, ["window", "new"]) => Some("opened a new window".to_string()),
+            (RequestMethod::Delete, ["window"]) => Some("closed the window".to_string()),
+            (RequestMethod::Post, ["frame"]) => Some(if body["id"].is_null() {
+                "switched to the main page".to_string()
+            } else {
+                "switched to a frame".to_string()
+            }),
+            (RequestMethod::Post, ["frame", "parent"]) => {
+                Some("switched to the parent frame".to_string())
+            }
+            (RequestMethod::Post, ["alert", "accept"]) => Some("accepted the alert".to_string()),
+            (RequestMethod::Post, ["alert", "dismiss"]) => Some("dismissed the alert".to_string()),
+            (RequestMethod::Post, ["alert", "text"]) => Some(format!(
+                "typed {} into the prompt",
+                quote(body["text"].as_str().unwrap_or_default())
+            )),
+            (RequestMethod::Post, ["cookie"]) => Some(format!(
+                "added cookie {}",
+                quote(body["cookie"]["name"].as_str().unwrap_or_default())
+            )),
+            (RequestMethod::Delete, ["cookie"]) => Some("deleted all cookies".to_string()),
+            (RequestMethod::Delete, ["cookie", name]) => {
+                Some(format!("deleted cookie {}", quote(name)))
+            }
+            (RequestMethod::Post, ["actions"]) => {
+                Some("performed mouse and keyboard actions".to_string())
+            }
+            _ => None,
+        };
+        Narration {
+            sentence,
+            locator,
+        }
+    }
+
+    /// Record the outcome of a request. The redact function is applied to
+    /// the sentence before it is stored.
+    pub(crate) fn record<F>(&self, narration: Narration, result: &WebDriverResult<Value>, redact: F)
+    where
+        F: Fn(&str) -> String,
+    {
+        if let (Some(locator), Ok(value)) = (narration.locator, result) {
+            let mut locators = self.locators.lock().unwrap_or_else(|e| e.into_inner());
+            let refs = match &value["value"] {
+                Value::Array(elements) => elements.iter().collect(),
+                element => vec![element],
+            };
+            for element in refs {
+                if let Some(id) = element_id(element) {
+                    locators.insert(id.to_string(), locator.clone());
+                }
+            }
+        }
+
+        let sentence = match (narration.sentence, result) {
+            (Some(s), Ok(_)) => s,
+            (Some(s), Err(e)) => {
+                let error = e.to_string();
+                format!("{}, but it failed: {}", s, error.lines().next().unwrap_or_default())
+            }
+            (None, _) => return,
+        };
+        let sentence = redact(&sentence);
+
+        let mut sentences = self.sentences.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(writer) = &self.writer {
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(e) = writeln!(writer, "{}. {}", sentences.len() + 1, sentence)
+                .and_then(|_| writer.flush())
+            {
+                log::warn!("failed to write narration: {}", e);
+            }
+        }
+        sentences.push(sentence);
+    }
+
+    /// Describe an element, e.g. `'Save' button (css=.btn-save)`.
+    fn describe_element(
+        &self,
+        conn: &dyn WebDriverHttpClientSync,
+        prefix: &str,
+        id: &str,
+    ) -> String {
+        let get = |what: &str| {
+            conn.execute(RequestData::new(
+                RequestMethod::Get,
+                format!("{}/element/{}/{}", prefix, id, what),
+            ))
+            .ok()
+            .and_then(|v| v["value"].as_str().map(|s| s.trim().to_string()))
+            .unwrap_or_default()
+        };
+        let kind = match get("name").to_lowercase().as_str() {
+            "" => "element".to_string(),
+            "a" => "link".to_string(),
+            "input" | "textarea" => "field".to_string(),
+            "select" => "dropdown".to_string(),
+            "img" => "image".to_string(),
+            tag => tag.to_string(),
+        };
+        let text = get("text");
+        let mut description = if text.is_empty() {
+            kind
+        } else {
+            format!("{} {}", quote(text.lines().next().unwrap_or_default()), kind)
+        };
+        if let Some(locator) = self.locators.lock().unwrap_or_else(|e| e.into_inner()).get(id) {
+            description.push_str(&format!(" ({})", locator));
+        }
+        description
+    }
+}
+
+fn element_id(value: &Value) -> Option<&str> {
+    value.as_object()?.values().next()?.as_str()
+}
+
+/// Format a W3C locator as e.g. `css=.btn-save`, recognising the CSS
+/// selectors that `By::Id` and `By::Name` are translated into.
+fn format_locator(body: &Value) -> String {
+    let using = body["using"].as_str().unwrap_or_default();
+    let value = body["value"].as_str().unwrap_or_default();
+    let (using, value) = match using {
+        "css selector" => {
+            let attribute = |name: &str| {
+                value
+                    .strip_prefix(&format!("[{}=\"", name))
+                    .and_then(|rest| rest.strip_suffix("\"]"))
+                    .filter(|x| !x.contains('"'))
+            };
+            if let Some(id) = attribute("id") {
+                ("id", id)
+            } else if let Some(name) = attribute("name") {
+                ("name", name)
+            } else {
+                ("css", value)
+            }
+        }
+        "link text" => ("link", value),
+        "partial link text" => ("partial link", value),
+        "tag name" => ("tag", value),
+        other => (other, value),
+    };
+    format!("{}={}", using, value)
+}
+
+/// Replace the special keys in typed text with readable names.
+fn keys_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{e003}' => "[Backspace]".to_string(),
+            '\u{e004}' => "[Tab]".to_string(),
+            '\u{e006}' | '\u{e007}' => "[Enter]".to_string(),
+            '\u{e00c}' => "[Escape]".to_string(),
+            '\u{e000}'..='\u{f8ff}' => "[key]".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn quote(text: &str) -> String {
+    match text.char_indices().nth(MAX_QUOTE_LEN) {
+        Some((i, _)) => format!("'{}...'", &text[..i]),
+        None => format!("'{}'", text),
+    }
+}
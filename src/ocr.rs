@@ -0,0 +1,193 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::text::normalize_text;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::webelement::convert_element_sync;
+use crate::{WebDriverSession, WebElement};
+
+/// The tesseract executable used for OCR.
+const TESSERACT: &str = "tesseract";
+
+/// Text found on the screen by OCR.
+///
+/// Coordinates are in CSS pixels relative to the top-left of the viewport,
+/// so they can be used directly with action chains.
+#[derive(Debug, Clone)]
+pub struct TextMatch<'a> {
+    session: &'a WebDriverSession,
+    /// The recognized text of the matched words.
+    pub text: String,
+    /// The recognized text of the whole line containing the match. This is
+    /// useful for reading a value that follows a label, such as `"Total: $42"`.
+    pub line: String,
+    /// The left edge of the matched words.
+    pub left: f64,
+    /// The top edge of the matched words.
+    pub top: f64,
+    /// The width of the matched words.
+    pub width: f64,
+    /// The height of the matched words.
+    pub height: f64,
+    /// The lowest OCR confidence of the matched words, from 0 to 100.
+    pub confidence: f64,
+}
+
+impl<'a> TextMatch<'a> {
+    /// The center of the matched words.
+    pub fn center(&self) -> (f64, f64) {
+        (self.left + self.width / 2.0, self.top + self.height / 2.0)
+    }
+
+    /// The element at the center of the matched words, as returned by
+    /// `document.elementFromPoint()`.
+    pub fn element(&self) -> WebDriverResult<Option<WebElement<'a>>> {
+        let (x, y) = self.center();
+        let ret = self
+            .session
+            .execute_script(&format!("return document.elementFromPoint({}, {});", x, y))?;
+        if ret.value().is_null() {
+            return Ok(None);
+        }
+        convert_element_sync(self.session, ret.value()).map(Some)
+    }
+
+    /// Click the center of the matched words.
+    pub fn click(&self) -> WebDriverResult<()> {
+        let (x, y) = self.center();
+        self.session.action_chain().move_to(x as i32, y as i32).click().perform()
+    }
+}
+
+/// A single word recognized by tesseract, in screenshot pixels.
+#[derive(Debug, Clone)]
+struct Word {
+    line: (u32, u32, u32),
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    confidence: f64,
+    text: String,
+}
+
+/// Parse tesseract's TSV output into words.
+fn parse_tsv(tsv: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        // Only level 5 rows are words.
+        if cols.len() < 12 || cols[0] != "5" || cols[11].trim().is_empty() {
+            continue;
+        }
+        let num = |i: usize| cols[i].trim().parse::<f64>().unwrap_or(0.0);
+        words.push(Word {
+            line: (num(2) as u32, num(3) as u32, num(4) as u32),
+            left: num(6),
+            top: num(7),
+            width: num(8),
+            height: num(9),
+            confidence: num(10),
+            text: cols[11].trim().to_string(),
+        });
+    }
+    words
+}
+
+/// Run tesseract over the PNG image and return the recognized words.
+fn recognize(png_data: &[u8]) -> WebDriverResult<Vec<Word>> {
+    let mut child = Command::new(TESSERACT)
+        .args(["stdin", "stdout", "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| WebDriverError::FatalError(format!("failed to run {}: {}", TESSERACT, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(png_data)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(WebDriverError::FatalError(format!(
+            "{} failed: {}",
+            TESSERACT,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Find every occurrence of the needle within the words of each line.
+fn find_in_words(words: &[Word], needle: &str) -> Vec<(String, String, Vec<Word>)> {
+    let needle = normalize_text(needle);
+    let mut found = Vec::new();
+    if needle.is_empty() {
+        return found;
+    }
+
+    let mut start = 0;
+    while start < words.len() {
+        let end = words[start..]
+            .iter()
+            .position(|w| w.line != words[start].line)
+            .map(|n| start + n)
+            .unwrap_or_else(|| words.len());
+        let line_words = &words[start..end];
+
+        // Record where each word starts in the joined line text.
+        let mut line = String::new();
+        let mut offsets = Vec::with_capacity(line_words.len());
+        for word in line_words {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            offsets.push(line.len());
+            line.push_str(&word.text);
+        }
+
+        for (match_start, _) in line.match_indices(&needle) {
+            let match_end = match_start + needle.len();
+            let matched: Vec<Word> = line_words
+                .iter()
+                .zip(&offsets)
+                .filter(|(w, &o)| o < match_end && o + w.text.len() > match_start)
+                .map(|(w, _)| w.clone())
+                .collect();
+            let text = matched.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+            found.push((text, line.clone(), matched));
+        }
+        start = end;
+    }
+    found
+}
+
+pub(crate) fn find_text_on_screen<'a>(
+    session: &'a WebDriverSession,
+    text: &str,
+) -> WebDriverResult<Vec<TextMatch<'a>>> {
+    let words = recognize(&session.screenshot_as_png()?)?;
+    let ratio: f64 = session.execute_script("return window.devicePixelRatio || 1;")?.convert()?;
+
+    let matches = find_in_words(&words, text)
+        .into_iter()
+        .map(|(text, line, matched)| {
+            let left = matched.iter().map(|w| w.left).fold(f64::INFINITY, f64::min);
+            let top = matched.iter().map(|w| w.top).fold(f64::INFINITY, f64::min);
+            let right = matched.iter().map(|w| w.left + w.width).fold(0.0, f64::max);
+            let bottom = matched.iter().map(|w| w.top + w.height).fold(0.0, f64::max);
+            let confidence = matched.iter().map(|w| w.confidence).fold(100.0, f64::min);
+            TextMatch {
+                session,
+                text,
+                line,
+                left: left / ratio,
+                top: top / ratio,
+                width: (right - left) / ratio,
+                height: (bottom - top) / ratio,
+                confidence,
+            }
+        })
+        .collect();
+    Ok(matches)
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::extensions::chrome::{ChromeDevTools, NetworkConditions};
+use crate::query::ElementPoller;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OnlineState {
+    on_line: bool,
+    online_events: u64,
+    offline_events: u64,
+}
+
+const ONLINE_STATE_SCRIPT: &str = r#"
+    if (!window.__thirtyfourOnline) {
+        const state = { online: 0, offline: 0 };
+        window.addEventListener("online", () => { state.online++; });
+        window.addEventListener("offline", () => { state.offline++; });
+        window.__thirtyfourOnline = state;
+    }
+    return {
+        onLine: navigator.onLine,
+        onlineEvents: window.__thirtyfourOnline.online,
+        offlineEvents: window.__thirtyfourOnline.offline
+    };
+"#;
+
+fn online_state(session: &WebDriverSession) -> WebDriverResult<OnlineState> {
+    session.execute_script(ONLINE_STATE_SCRIPT)?.convert()
+}
+
+/// Switch the browser's network emulation on or off, keeping any other
+/// network conditions, then wait for the page to receive the corresponding
+/// `online` or `offline` event.
+///
+/// If the page is already in the requested state, no event is expected.
+pub(crate) fn set_offline(session: &WebDriverSession, offline: bool) -> WebDriverResult<()> {
+    let before = online_state(session)?;
+
+    // Keep any throttling that was set, and only toggle the offline flag.
+    // chromedriver returns an unknown error if no conditions were set yet.
+    let dev_tools = ChromeDevTools::new(session);
+    let mut conditions = match dev_tools.get_network_conditions() {
+        Ok(x) => x,
+        Err(WebDriverError::UnknownError(_)) => NetworkConditions::new(),
+        Err(e) => return Err(e),
+    };
+    conditions.offline = offline;
+    dev_tools.set_network_conditions(&conditions)?;
+
+    if before.on_line != offline {
+        return Ok(());
+    }
+
+    let description = if offline {
+        "page did not receive the offline event"
+    } else {
+        "page did not receive the online event"
+    };
+    ElementPoller::default().poll(description, || {
+        let state = online_state(session)?;
+        let fired = if offline {
+            state.offline_events > before.offline_events
+        } else {
+            state.online_events > before.online_events
+        };
+        Ok(if fired && state.on_line != offline {
+            Some(())
+        } else {
+            None
+        })
+    })
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// The URL, title and ready state of the current page.
+/// See [WebDriver::page_info()](trait.WebDriverCommands.html#method.page_info).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    /// The current URL.
+    pub url: String,
+    /// The page title.
+    pub title: String,
+    /// The value of `document.readyState`: `loading`, `interactive` or `complete`.
+    pub ready_state: String,
+}
+
+impl fmt::Display for PageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (title: {:?}, readyState: {})", self.url, self.title, self.ready_state)
+    }
+}
+
+pub(crate) fn page_info(session: &WebDriverSession) -> WebDriverResult<PageInfo> {
+    session
+        .execute_script(
+            "return {url: location.href, title: document.title, readyState: document.readyState};",
+        )?
+        .convert()
+}
+
+/// Append the current page to an error message, if it can be fetched, and
+/// the session metadata if any is set.
+pub(crate) fn with_page_context(session: &WebDriverSession, message: &str) -> String {
+    let mut message = match page_info(session) {
+        Ok(info) => format!("{}\n    on page: {}", message, info),
+        Err(_) => message.to_string(),
+    };
+    let metadata = session.metadata();
+    if !metadata.is_empty() {
+        message.push_str(&format!("\n    in session: {}", metadata));
+    }
+    message
+}
+
+/// The error for a failed assertion about the specified item, with the
+/// current page appended to the message.
+pub(crate) fn assertion_failed(
+    session: &WebDriverSession,
+    item: String,
+    message: &str,
+) -> WebDriverError {
+    WebDriverError::NotFound(item, with_page_context(session, message))
+}
@@ -0,0 +1,65 @@
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// Serializes the document like `outerHTML`, but also serializes each open
+/// shadow root as a declarative shadow DOM `<template>` at the start of its
+/// host element. The legacy `shadowroot` attribute is written alongside
+/// `shadowrootmode` for older parsers.
+const PAGE_SOURCE_DEEP_SCRIPT: &str = r#"
+    const HTML_NS = "http://www.w3.org/1999/xhtml";
+    const VOID = new Set([
+        "area", "base", "br", "col", "embed", "hr", "img", "input",
+        "link", "meta", "param", "source", "track", "wbr",
+    ]);
+    const RAW_TEXT = new Set([
+        "script", "style", "xmp", "iframe", "noembed", "noframes", "noscript", "plaintext",
+    ]);
+    const escapeText = (s) =>
+        s.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;")
+            .replace(/\u00a0/g, "&nbsp;");
+    const escapeAttr = (s) =>
+        s.replace(/&/g, "&amp;").replace(/"/g, "&quot;").replace(/\u00a0/g, "&nbsp;");
+    const children = (node) => Array.from(node.childNodes, serialize).join("");
+    function serialize(node) {
+        switch (node.nodeType) {
+            case Node.ELEMENT_NODE: {
+                const tag = node.localName;
+                const html = node.namespaceURI === HTML_NS;
+                let out = "<" + tag;
+                for (const attr of node.attributes) {
+                    out += " " + attr.name + '="' + escapeAttr(attr.value) + '"';
+                }
+                out += ">";
+                if (html && VOID.has(tag)) return out;
+                const root = node.shadowRoot;
+                if (root) {
+                    out += '<template shadowrootmode="' + root.mode + '" shadowroot="' + root.mode + '"'
+                        + (root.delegatesFocus ? " shadowrootdelegatesfocus" : "")
+                        + ">" + children(root) + "</template>";
+                }
+                if (html && tag === "template") {
+                    out += children(node.content);
+                } else if (html && RAW_TEXT.has(tag)) {
+                    out += Array.from(node.childNodes, (n) => n.textContent).join("");
+                } else {
+                    out += children(node);
+                }
+                return out + "</" + tag + ">";
+            }
+            case Node.TEXT_NODE:
+                return escapeText(node.data);
+            case Node.COMMENT_NODE:
+                return "<!--" + node.data + "-->";
+            case Node.DOCUMENT_TYPE_NODE:
+                return "<!DOCTYPE " + node.name + ">";
+            default:
+                return "";
+        }
+    }
+    return (document.doctype ? serialize(document.doctype) : "") + serialize(document.documentElement);
+"#;
+
+pub(crate) fn page_source_deep(session: &WebDriverSession) -> WebDriverResult<String> {
+    session.execute_script(PAGE_SOURCE_DEEP_SCRIPT)?.convert()
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::error::WebDriverResult;
+use crate::query::{find_elements_nowait, wait_for_page_settled, ElementPoller};
+use crate::{By, WebDriverSession};
+
+/// Step through the pages of a paginated listing by clicking a "next" button.
+///
+/// The Paginator is typically created via
+/// [WebDriver::paginate()](trait.WebDriverCommands.html#method.paginate).
+///
+/// After each click, the Paginator waits for the old page content to go stale
+/// and for the new page to settle before continuing. Pagination stops when the
+/// "next" button is no longer present, displayed or enabled, or when the
+/// maximum number of pages has been reached.
+///
+/// By default the whole document (`<html>`) is expected to be replaced when
+/// moving to the next page. For single-page apps that only re-render part of
+/// the page, use `with_content()` to specify the element that gets replaced.
+#[derive(Debug, Clone)]
+pub struct Paginator<'a> {
+    session: &'a WebDriverSession,
+    next_button: By<'a>,
+    content: By<'a>,
+    max_pages: Option<usize>,
+    poller: ElementPoller,
+    quiet_period: Duration,
+    page: usize,
+}
+
+impl<'a> Paginator<'a> {
+    /// Create a new Paginator that uses the specified selector to locate the
+    /// "next" button.
+    pub fn new(session: &'a WebDriverSession, next_button: By<'a>) -> Self {
+        Self {
+            session,
+            next_button,
+            content: By::Tag("html"),
+            max_pages: None,
+            poller: ElementPoller::default(),
+            quiet_period: Duration::from_millis(250),
+            page: 0,
+        }
+    }
+
+    /// Stop after the specified number of pages (including the first page).
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Specify the element that is replaced when moving to the next page.
+    pub fn with_content(mut self, content: By<'a>) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Set the poller used while waiting for the next page to load.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+
+    /// The current page number, starting at 1. Returns 0 if `next_page()`
+    /// has not been called yet.
+    pub fn page_number(&self) -> usize {
+        self.page
+    }
+
+    /// Move to the next page and return its page number, or None if there
+    /// are no more pages.
+    ///
+    /// The first call does not click anything and simply returns page 1,
+    /// so that this can be used as the condition of a `while let` loop.
+    pub fn next_page(&mut self) -> WebDriverResult<Option<usize>> {
+        if self.page == 0 {
+            self.page = 1;
+            return Ok(Some(self.page));
+        }
+
+        if let Some(max_pages) = self.max_pages {
+            if self.page >= max_pages {
+                return Ok(None);
+            }
+        }
+
+        let button = match find_elements_nowait(self.session, self.next_button.clone())?
+            .into_iter()
+            .next()
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let aria_disabled = button.get_attribute("aria-disabled")?;
+        if !button.is_clickable()? || aria_disabled.as_deref() == Some("true") {
+            return Ok(None);
+        }
+
+        let old_content = find_elements_nowait(self.session, self.content.clone())?;
+        button.click()?;
+        if let Some(old) = old_content.first() {
+            self.poller.poll("previous page content did not go stale", || {
+                Ok(if old.is_present()? {
+                    None
+                } else {
+                    Some(())
+                })
+            })?;
+        }
+        wait_for_page_settled(self.session, self.quiet_period, &self.poller)?;
+
+        self.page += 1;
+        Ok(Some(self.page))
+    }
+
+    /// Call the closure once for each page, including the current page,
+    /// and return all of the results.
+    pub fn for_each_page<T, F>(mut self, mut f: F) -> WebDriverResult<Vec<T>>
+    where
+        F: FnMut(&'a WebDriverSession, usize) -> WebDriverResult<T>,
+    {
+        let mut results = Vec::new();
+        while let Some(page) = self.next_page()? {
+            results.push(f(self.session, page)?);
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::WebDriver;
+
+/// The outcome for a single worker.
+#[derive(Debug)]
+pub struct WorkerResult<T> {
+    /// The index of the worker, from 0.
+    pub worker: usize,
+    /// How long it took to start the session.
+    pub startup: Duration,
+    /// How long the closure ran for, after the synchronized start.
+    pub elapsed: Duration,
+    /// The value returned by the closure, or the error from starting the
+    /// session or running the closure.
+    pub result: WebDriverResult<T>,
+}
+
+/// The aggregated results of a [Parallel](struct.Parallel.html) run.
+#[derive(Debug)]
+pub struct ParallelReport<T> {
+    /// The results for each worker, in worker order.
+    pub workers: Vec<WorkerResult<T>>,
+    /// The total time from the synchronized start until the last worker
+    /// finished.
+    pub elapsed: Duration,
+}
+
+impl<T> ParallelReport<T> {
+    /// The number of workers that succeeded.
+    pub fn successes(&self) -> usize {
+        self.workers.iter().filter(|w| w.result.is_ok()).count()
+    }
+
+    /// The number of workers that failed.
+    pub fn failures(&self) -> usize {
+        self.workers.len() - self.successes()
+    }
+
+    /// The mean time the closure ran for, over all workers.
+    pub fn mean_elapsed(&self) -> Duration {
+        match self.workers.len() {
+            0 => Duration::default(),
+            n => self.workers.iter().map(|w| w.elapsed).sum::<Duration>() / n as u32,
+        }
+    }
+
+    /// The longest time the closure ran for, over all workers.
+    pub fn max_elapsed(&self) -> Duration {
+        self.workers.iter().map(|w| w.elapsed).max().unwrap_or_default()
+    }
+
+    /// Return the values from every worker, or the first error.
+    pub fn into_results(self) -> WebDriverResult<Vec<T>> {
+        self.workers.into_iter().map(|w| w.result).collect()
+    }
+}
+
+/// Runs the same closure concurrently in several browser sessions, for
+/// simple browser-level load and concurrency tests.
+///
+/// Every session is started on its own worker thread. Once all workers have
+/// started their sessions, they begin running the closure at the same time.
+/// Each worker's result and timings are collected into a
+/// [ParallelReport](struct.ParallelReport.html). Sessions are closed when
+/// their worker finishes.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::parallel::Parallel;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let report = Parallel::new("http://localhost:4444/wd/hub", &caps)?
+///     .with_sessions(4)
+///     .run(|driver, worker| {
+///         driver.get("http://webappdemo")?;
+///         driver.find_element(By::Id("button1"))?.click()?;
+///         Ok(worker)
+///     });
+/// println!("{} of 4 succeeded, slowest took {:?}", report.successes(), report.max_elapsed());
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Parallel {
+    server_url: String,
+    capabilities: Value,
+    sessions: usize,
+}
+
+impl fmt::Debug for Parallel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parallel")
+            .field("server_url", &self.server_url)
+            .field("capabilities", &self.capabilities)
+            .field("sessions", &self.sessions)
+            .finish()
+    }
+}
+
+impl Parallel {
+    /// Create a new Parallel that will start sessions on the specified server
+    /// using the specified capabilities.
+    pub fn new<C>(server_url: &str, capabilities: C) -> WebDriverResult<Self>
+    where
+        C: Serialize,
+    {
+        Ok(Self {
+            server_url: server_url.to_string(),
+            capabilities: serde_json::to_value(capabilities)?,
+            sessions: 2,
+        })
+    }
+
+    /// Set the number of concurrent sessions. The default is 2.
+    pub fn with_sessions(mut self, sessions: usize) -> Self {
+        self.sessions = sessions.max(1);
+        self
+    }
+
+    /// Start the sessions and run the closure in each of them concurrently.
+    /// The closure receives the session and the index of the worker.
+    ///
+    /// A worker whose session fails to start still waits for the others, so
+    /// the remaining workers start together. Its error is reported in its
+    /// result.
+    pub fn run<T, F>(self, f: F) -> ParallelReport<T>
+    where
+        T: Send + 'static,
+        F: Fn(&WebDriver, usize) -> WebDriverResult<T> + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        // The extra party is this thread, which records the start time.
+        let barrier = Arc::new(Barrier::new(self.sessions + 1));
+        let capabilities = Arc::new(self.capabilities);
+
+        let mut handles = Vec::new();
+        for worker in 0..self.sessions {
+            let f = f.clone();
+            let barrier = barrier.clone();
+            let capabilities = capabilities.clone();
+            let server_url = self.server_url.clone();
+            handles.push(thread::spawn(move || {
+                let started = Instant::now();
+                let driver = WebDriver::new(&server_url, &*capabilities);
+                let startup = started.elapsed();
+                barrier.wait();
+
+                let started = Instant::now();
+                let result = driver.and_then(|driver| {
+                    panic::catch_unwind(AssertUnwindSafe(|| f(&driver, worker))).unwrap_or_else(
+                        |_| {
+                            Err(WebDriverError::FatalError(format!(
+                                "parallel worker {} panicked",
+                                worker
+                            )))
+                        },
+                    )
+                });
+                WorkerResult {
+                    worker,
+                    startup,
+                    elapsed: started.elapsed(),
+                    result,
+                }
+            }));
+        }
+
+        barrier.wait();
+        let started = Instant::now();
+        let workers = handles
+            .into_iter()
+            .enumerate()
+            .map(|(worker, handle)| {
+                handle.join().unwrap_or_else(|_| WorkerResult {
+                    worker,
+                    startup: Duration::default(),
+                    elapsed: Duration::default(),
+                    result: Err(WebDriverError::FatalError(format!(
+                        "parallel worker {} panicked",
+                        worker
+                    ))),
+                })
+            })
+            .collect();
+
+        ParallelReport {
+            workers,
+            elapsed: started.elapsed(),
+        }
+    }
+}
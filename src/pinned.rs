@@ -0,0 +1,198 @@
+use std::sync::Mutex;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession, WebElement};
+
+/// The error state of the StaleElementReference error returned by a
+/// [PinnedElement](struct.PinnedElement.html) that was invalidated by a
+/// navigation. See `is_invalidated_by_navigation()`.
+pub const ELEMENT_INVALIDATED_BY_NAVIGATION: &str = "element invalidated by navigation";
+
+/// Returns true if the error was returned by a
+/// [PinnedElement](struct.PinnedElement.html) because the page navigated
+/// since the element was found.
+pub fn is_invalidated_by_navigation(error: &WebDriverError) -> bool {
+    matches!(error, WebDriverError::StaleElementReference(info)
+        if info.error == ELEMENT_INVALIDATED_BY_NAVIGATION)
+}
+
+/// Marks the current document with a random id, so that a new document can
+/// be told apart from the one an element was found in.
+const DOCUMENT_ID_SCRIPT: &str = r#"
+if (!window.__thirtyfourDocumentId) {
+    window.__thirtyfourDocumentId = Math.random().toString(36).slice(2);
+}
+return window.__thirtyfourDocumentId;
+"#;
+
+fn document_id(session: &WebDriverSession) -> WebDriverResult<String> {
+    let ret = session.execute_script(DOCUMENT_ID_SCRIPT)?;
+    ret.convert()
+}
+
+/// What a [PinnedElement](struct.PinnedElement.html) does when the page has
+/// navigated since the element was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnNavigation {
+    /// Return an error that `is_invalidated_by_navigation()` recognises.
+    Invalidate,
+    /// Find the element again using the same selector.
+    Reresolve,
+}
+
+#[derive(Debug)]
+struct Pin<'a> {
+    element: WebElement<'a>,
+    navigations: u64,
+    document_id: String,
+}
+
+/// An element that knows which page it was found on, so that using it after
+/// a navigation gives a clear error, or finds it again, instead of a
+/// confusing StaleElementReference or NoSuchElement error later on.
+///
+/// Create one with
+/// [WebDriver::pin_element()](trait.WebDriverCommands.html#method.pin_element).
+///
+/// Navigations made with `get()`, `back()`, `forward()` and `refresh()` are
+/// noticed straight away. Navigations caused by the page itself, such as a
+/// click on a link or a redirect, are noticed when using the element fails
+/// because it went stale and the document has changed.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::pinned::{is_invalidated_by_navigation, OnNavigation};
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.get("http://webappdemo")?;
+/// let button = driver.pin_element(By::Id("button1"))?;
+/// button.click()?;
+/// driver.refresh()?;
+/// let err = button.click().unwrap_err();
+/// assert!(is_invalidated_by_navigation(&err));
+///
+/// let button = driver.pin_element(By::Id("button1"))?.on_navigation(OnNavigation::Reresolve);
+/// driver.refresh()?;
+/// button.click()?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PinnedElement<'a> {
+    session: &'a WebDriverSession,
+    by: By<'a>,
+    on_navigation: OnNavigation,
+    pin: Mutex<Pin<'a>>,
+}
+
+impl<'a> PinnedElement<'a> {
+    pub(crate) fn new(session: &'a WebDriverSession, by: By<'a>) -> WebDriverResult<Self> {
+        let pin = Self::find(session, &by)?;
+        Ok(Self {
+            session,
+            by,
+            on_navigation: OnNavigation::Invalidate,
+            pin: Mutex::new(pin),
+        })
+    }
+
+    fn find(session: &'a WebDriverSession, by: &By<'a>) -> WebDriverResult<Pin<'a>> {
+        let navigations = session.navigation_count();
+        let document_id = document_id(session)?;
+        let element = session.find_element(by.clone())?;
+        Ok(Pin {
+            element: WebElement::new(session, element.element_id),
+            navigations,
+            document_id,
+        })
+    }
+
+    fn invalidated(&self) -> WebDriverError {
+        let message = format!(
+            "element found by {} was invalidated because the page navigated since it was found",
+            self.by
+        );
+        let mut info = WebDriverErrorInfo::new(&message);
+        info.error = ELEMENT_INVALIDATED_BY_NAVIGATION.to_string();
+        WebDriverError::StaleElementReference(info)
+    }
+
+    /// Set what happens when the element is used after a navigation. The
+    /// default is `OnNavigation::Invalidate`.
+    pub fn on_navigation(mut self, on_navigation: OnNavigation) -> Self {
+        self.on_navigation = on_navigation;
+        self
+    }
+
+    /// Handle a navigation, either by returning an error or by finding the
+    /// element again.
+    fn handle_navigation(&self, pin: &mut Pin<'a>) -> WebDriverResult<()> {
+        match self.on_navigation {
+            OnNavigation::Invalidate => Err(self.invalidated()),
+            OnNavigation::Reresolve => {
+                *pin = Self::find(self.session, &self.by)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns true if the page has navigated since the element was found,
+    /// as far as is known without sending a command.
+    pub fn navigated(&self) -> bool {
+        let pin = self.pin.lock().unwrap_or_else(|e| e.into_inner());
+        pin.navigations != self.session.navigation_count()
+    }
+
+    /// Get the element, handling any known navigation first.
+    pub fn get(&self) -> WebDriverResult<WebElement<'a>> {
+        let mut pin = self.pin.lock().unwrap_or_else(|e| e.into_inner());
+        if pin.navigations != self.session.navigation_count() {
+            self.handle_navigation(&mut pin)?;
+        }
+        Ok(pin.element.clone())
+    }
+
+    /// Call the closure with the element. If the element has gone stale
+    /// because the page navigated, this is handled as set with
+    /// `on_navigation()`, calling the closure again after finding the element
+    /// again.
+    pub fn with<T, F>(&self, mut f: F) -> WebDriverResult<T>
+    where
+        F: FnMut(&WebElement<'a>) -> WebDriverResult<T>,
+    {
+        let element = self.get()?;
+        match f(&element) {
+            Err(WebDriverError::StaleElementReference(info)) => {
+                let mut pin = self.pin.lock().unwrap_or_else(|e| e.into_inner());
+                if document_id(self.session)? == pin.document_id {
+                    // The element was removed from the page, not navigated away from.
+                    return Err(WebDriverError::StaleElementReference(info));
+                }
+                self.handle_navigation(&mut pin)?;
+                let element = pin.element.clone();
+                drop(pin);
+                f(&element)
+            }
+            result => result,
+        }
+    }
+
+    /// Click the element.
+    pub fn click(&self) -> WebDriverResult<()> {
+        self.with(|elem| elem.click())
+    }
+
+    /// Get the text of the element.
+    pub fn text(&self) -> WebDriverResult<String> {
+        self.with(|elem| elem.text())
+    }
+
+    /// Type the specified keys into the element.
+    pub fn send_keys(&self, keys: &str) -> WebDriverResult<()> {
+        self.with(|elem| elem.send_keys(keys))
+    }
+}
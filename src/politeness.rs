@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::text::wildcard_matches;
+
+/// The error state of the InvalidArgument error returned by `get()` when
+/// `robots.txt` disallows the URL. See `is_disallowed()`.
+pub const DISALLOWED_BY_ROBOTS_TXT: &str = "disallowed by robots.txt";
+
+/// Returns true if the error was returned because the
+/// [Politeness](struct.Politeness.html) policy's `robots.txt` check
+/// disallowed the navigation.
+pub fn is_disallowed(error: &WebDriverError) -> bool {
+    matches!(error, WebDriverError::InvalidArgument(info) if info.error == DISALLOWED_BY_ROBOTS_TXT)
+}
+
+/// The parsed contents of a `robots.txt` file.
+///
+/// Rules are matched as described in RFC 9309: the most specific matching
+/// rule wins, and `Allow` wins over `Disallow` when both match equally.
+/// The `*` wildcard and `$` end-of-path anchor are supported, as is the
+/// non-standard `Crawl-delay` directive.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::politeness::RobotsTxt;
+/// use std::time::Duration;
+///
+/// let robots = RobotsTxt::parse(
+///     "User-agent: *\n\
+///      Disallow: /private/\n\
+///      Allow: /private/public.html\n\
+///      Crawl-delay: 2\n",
+/// );
+/// assert!(robots.is_allowed("mybot", "/index.html"));
+/// assert!(!robots.is_allowed("mybot", "/private/secret.html"));
+/// assert!(robots.is_allowed("mybot", "/private/public.html"));
+/// assert_eq!(robots.crawl_delay("mybot"), Some(Duration::from_secs(2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<RobotsGroup>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RobotsGroup {
+    agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+impl RobotsTxt {
+    /// Parse the contents of a `robots.txt` file. Lines that cannot be
+    /// understood are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<RobotsGroup> = Vec::new();
+        let mut current: Option<RobotsGroup> = None;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next().unwrap_or_default().trim().to_lowercase();
+            let value = match parts.next() {
+                Some(x) => x.trim(),
+                None => continue,
+            };
+
+            match key.as_str() {
+                "user-agent" => {
+                    let in_agent_list = current
+                        .as_ref()
+                        .map(|g| g.rules.is_empty() && g.crawl_delay.is_none())
+                        .unwrap_or(false);
+                    if !in_agent_list {
+                        groups.extend(current.take());
+                        current = Some(RobotsGroup::default());
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    // An empty Disallow means everything is allowed.
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group.rules.push(RobotsRule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    if let (Some(group), Ok(secs)) = (current.as_mut(), value.parse::<f64>()) {
+                        // Negative, NaN and out of range delays are ignored.
+                        if let Ok(delay) = Duration::try_from_secs_f64(secs) {
+                            group.crawl_delay = Some(delay);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        groups.extend(current);
+
+        Self {
+            groups,
+        }
+    }
+
+    /// A `robots.txt` that disallows everything. This is used when the file
+    /// cannot be fetched because the server is unavailable.
+    fn disallow_all() -> Self {
+        Self::parse("User-agent: *\nDisallow: /\n")
+    }
+
+    /// Return the groups that apply to the specified user agent. Groups naming
+    /// the user agent take precedence over the `*` groups.
+    fn groups_for(&self, user_agent: &str) -> Vec<&RobotsGroup> {
+        let user_agent = user_agent.to_lowercase();
+        let best = self
+            .groups
+            .iter()
+            .flat_map(|g| g.agents.iter())
+            .filter(|a| a.as_str() != "*" && user_agent.contains(a.as_str()))
+            .max_by_key(|a| a.len())
+            .cloned()
+            .unwrap_or_else(|| "*".to_string());
+        self.groups.iter().filter(|g| g.agents.contains(&best)).collect()
+    }
+
+    /// Return true if the specified user agent may fetch the specified path.
+    /// The path should include the query string, if any.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        let mut best: Option<&RobotsRule> = None;
+        for rule in self.groups_for(user_agent).into_iter().flat_map(|g| g.rules.iter()) {
+            if !pattern_matches(&rule.pattern, path) {
+                continue;
+            }
+            best = match best {
+                Some(b)
+                    if b.pattern.len() > rule.pattern.len()
+                        || (b.pattern.len() == rule.pattern.len() && b.allow) =>
+                {
+                    Some(b)
+                }
+                _ => Some(rule),
+            };
+        }
+        best.map(|x| x.allow).unwrap_or(true)
+    }
+
+    /// Return the crawl delay requested for the specified user agent, if any.
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent).into_iter().filter_map(|g| g.crawl_delay).max()
+    }
+}
+
+/// Match a robots.txt path pattern, supporting `*` wildcards and a trailing
+/// `$` to anchor the end of the path.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix('$') {
+        Some(pattern) => wildcard_matches(pattern, path, true),
+        None => wildcard_matches(pattern, path, false),
+    }
+}
+
+#[derive(Debug, Default)]
+struct PolitenessState {
+    client: Option<reqwest::blocking::Client>,
+    robots: HashMap<String, Arc<RobotsTxt>>,
+    next_slot: HashMap<String, Instant>,
+}
+
+/// Politeness rules applied to navigations, for scraping sites you do not own.
+///
+/// Once a Politeness policy has been set on a WebDriver using
+/// [WebDriver::set_politeness()](../struct.GenericWebDriver.html#method.set_politeness),
+/// every call to `get()` will first:
+///
+/// 1. Fetch (and cache) the `robots.txt` file for the target origin and
+///    return an `InvalidArgument` error if the URL is disallowed. Use
+///    [is_disallowed()](fn.is_disallowed.html) to recognise it.
+/// 2. Wait until enough time has passed since the last navigation to the same
+///    host. The delay is the larger of the configured minimum delay and the
+///    `Crawl-delay` requested by the site.
+///
+/// If `robots.txt` returns a 4xx status, everything is allowed. If it cannot
+/// be fetched at all or returns a 5xx status, everything is disallowed.
+///
+/// Clones of a Politeness share the same cache and rate limits, so the same
+/// policy can be applied to several sessions at once.
+///
+/// Only navigations made via `get()` are checked. Clicking links, submitting
+/// forms and navigating back or forward are not affected.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::politeness::Politeness;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.set_politeness(Some(
+///     Politeness::new("mybot").with_min_delay(Duration::from_secs(1)),
+/// ));
+/// driver.get("http://webappdemo")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Politeness {
+    user_agent: String,
+    min_delay: Duration,
+    ignore_robots_txt: bool,
+    state: Arc<Mutex<PolitenessState>>,
+}
+
+impl Politeness {
+    /// Create a new Politeness policy. The user agent is used both to fetch
+    /// `robots.txt` and to select the rules that apply.
+    pub fn new(user_agent: &str) -> Self {
+        Self {
+            user_agent: user_agent.to_string(),
+            min_delay: Duration::from_secs(1),
+            ignore_robots_txt: false,
+            state: Arc::new(Mutex::new(PolitenessState::default())),
+        }
+    }
+
+    /// Set the minimum delay between navigations to the same host.
+    /// The default is 1 second.
+    pub fn with_min_delay(mut self, min_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// Ignore `robots.txt` rules and crawl delays. Only the minimum delay
+    /// between navigations to the same host is still enforced.
+    ///
+    /// This is intended for sites you have permission to crawl.
+    pub fn ignore_robots_txt(mut self, ignore: bool) -> Self {
+        self.ignore_robots_txt = ignore;
+        self
+    }
+
+    /// Return true if `robots.txt` allows navigating to the specified URL.
+    /// Non-http(s) URLs are always allowed.
+    pub fn is_allowed(&self, url: &str) -> WebDriverResult<bool> {
+        let url = match parse_http_url(url)? {
+            Some(x) => x,
+            None => return Ok(true),
+        };
+        Ok(match self.robots_for(&url)? {
+            Some(robots) => robots.is_allowed(&self.user_agent, &path_and_query(&url)),
+            None => true,
+        })
+    }
+
+    /// Check that navigating to the specified URL is allowed, and wait until
+    /// it is this host's turn. This is called automatically by `get()` for
+    /// sessions that have a Politeness policy set.
+    pub fn before_navigation(&self, url: &str) -> WebDriverResult<()> {
+        let parsed = match parse_http_url(url)? {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+
+        let mut delay = self.min_delay;
+        if let Some(robots) = self.robots_for(&parsed)? {
+            if !robots.is_allowed(&self.user_agent, &path_and_query(&parsed)) {
+                let mut info = WebDriverErrorInfo::new(&format!(
+                    "navigation to {} is disallowed by robots.txt",
+                    url
+                ));
+                info.error = DISALLOWED_BY_ROBOTS_TXT.to_string();
+                return Err(WebDriverError::InvalidArgument(info));
+            }
+            if let Some(crawl_delay) = robots.crawl_delay(&self.user_agent) {
+                delay = delay.max(crawl_delay);
+            }
+        }
+
+        let host = parsed.host_str().unwrap_or_default().to_string();
+        let slot = {
+            let mut state = self.lock();
+            let now = Instant::now();
+            let slot = state.next_slot.get(&host).copied().filter(|x| *x > now).unwrap_or(now);
+            state.next_slot.insert(host, slot + delay);
+            slot
+        };
+        let now = Instant::now();
+        if slot > now {
+            thread::sleep(slot - now);
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PolitenessState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The `robots.txt` rules for the URL's origin, or None if they are
+    /// ignored.
+    fn robots_for(&self, url: &Url) -> WebDriverResult<Option<Arc<RobotsTxt>>> {
+        if self.ignore_robots_txt {
+            return Ok(None);
+        }
+
+        let origin = url.origin().ascii_serialization();
+        if let Some(robots) = self.lock().robots.get(&origin) {
+            return Ok(Some(robots.clone()));
+        }
+
+        let robots = Arc::new(self.fetch_robots_txt(&origin)?);
+        self.lock().robots.insert(origin, robots.clone());
+        Ok(Some(robots))
+    }
+
+    /// The HTTP client used to fetch `robots.txt`, which is shared by clones
+    /// of this policy.
+    fn client(&self) -> WebDriverResult<reqwest::blocking::Client> {
+        let mut state = self.lock();
+        if let Some(client) = &state.client {
+            return Ok(client.clone());
+        }
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        state.client = Some(client.clone());
+        Ok(client)
+    }
+
+    fn fetch_robots_txt(&self, origin: &str) -> WebDriverResult<RobotsTxt> {
+        let client = self.client()?;
+        let resp = match client.get(format!("{}/robots.txt", origin)).send() {
+            Ok(x) => x,
+            Err(_) => return Ok(RobotsTxt::disallow_all()),
+        };
+
+        let status = resp.status();
+        if status.is_success() {
+            Ok(resp
+                .text()
+                .map(|x| RobotsTxt::parse(&x))
+                .unwrap_or_else(|_| RobotsTxt::disallow_all()))
+        } else if status.is_client_error() {
+            Ok(RobotsTxt::default())
+        } else {
+            Ok(RobotsTxt::disallow_all())
+        }
+    }
+}
+
+/// The path of the URL including the query string, as robots.txt rules match.
+fn path_and_query(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn parse_http_url(url: &str) -> WebDriverResult<Option<Url>> {
+    let url = Url::parse(url).map_err(|e| {
+        WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "invalid URL {}: {}",
+            url, e
+        )))
+    })?;
+    Ok(match url.scheme() {
+        "http" | "https" => Some(url),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_prefix() {
+        assert!(pattern_matches("/private", "/private"));
+        assert!(pattern_matches("/private", "/private/secret.html"));
+        assert!(pattern_matches("/", "/anything"));
+        assert!(!pattern_matches("/private", "/public"));
+        assert!(!pattern_matches("/private/", "/private"));
+    }
+
+    #[test]
+    fn pattern_end_anchor() {
+        assert!(pattern_matches("/index.html$", "/index.html"));
+        assert!(!pattern_matches("/index.html$", "/index.html?page=2"));
+        assert!(pattern_matches("/*.pdf$", "/docs/report.pdf"));
+        assert!(!pattern_matches("/*.pdf$", "/docs/report.pdf.html"));
+        assert!(pattern_matches("/$", "/"));
+        assert!(!pattern_matches("/$", "/index.html"));
+    }
+
+    #[test]
+    fn pattern_wildcard() {
+        assert!(pattern_matches("/*/edit", "/posts/1/edit"));
+        assert!(pattern_matches("/*?", "/search?q=rust"));
+        assert!(!pattern_matches("/*?", "/search"));
+        assert!(pattern_matches("/a*b*c", "/axxbyyc"));
+        assert!(!pattern_matches("/a*b*c", "/axxcyyb"));
+        assert!(pattern_matches("*", "/anything"));
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        let robots = RobotsTxt::parse(
+            "User-agent: *\n\
+             Allow: /shop\n\
+             Disallow: /shop/cart\n\
+             Allow: /shop/cart/help\n",
+        );
+        assert!(robots.is_allowed("bot", "/shop/items"));
+        assert!(!robots.is_allowed("bot", "/shop/cart/1"));
+        assert!(robots.is_allowed("bot", "/shop/cart/help.html"));
+    }
+
+    #[test]
+    fn allow_wins_ties() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow: /page\nAllow: /page\n");
+        assert!(robots.is_allowed("bot", "/page"));
+        let robots = RobotsTxt::parse("User-agent: *\nAllow: /page\nDisallow: /page\n");
+        assert!(robots.is_allowed("bot", "/page"));
+        let robots = RobotsTxt::parse("User-agent: *\nAllow: /a*\nDisallow: /ab\n");
+        assert!(robots.is_allowed("bot", "/abc"));
+    }
+
+    #[test]
+    fn empty_disallow_and_robots_txt() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow:\n");
+        assert!(robots.is_allowed("bot", "/anything"));
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow: /\n");
+        assert!(!robots.is_allowed("bot", "/anything"));
+        assert!(robots.is_allowed("bot", "/robots.txt"));
+        assert!(RobotsTxt::parse("").is_allowed("bot", "/anything"));
+    }
+
+    #[test]
+    fn user_agent_groups() {
+        let robots = RobotsTxt::parse(
+            "# comment\n\
+             User-agent: *\n\
+             Disallow: /\n\
+             \n\
+             User-agent: GoodBot\n\
+             User-agent: otherbot\n\
+             Disallow: /private # trailing comment\n\
+             Crawl-delay: 0.5\n\
+             \n\
+             User-agent: goodbot-images\n\
+             Disallow: /photos\n",
+        );
+        // Groups naming the agent replace the `*` group, ignoring case.
+        assert!(robots.is_allowed("Mozilla/5.0 (compatible; goodbot/1.0)", "/index.html"));
+        assert!(!robots.is_allowed("goodbot", "/private/x"));
+        assert!(robots.is_allowed("OtherBot", "/index.html"));
+        assert!(!robots.is_allowed("unknown", "/index.html"));
+        // The most specific agent name is used.
+        assert!(robots.is_allowed("goodbot-images", "/private/x"));
+        assert!(!robots.is_allowed("goodbot-images", "/photos/1.jpg"));
+
+        assert_eq!(robots.crawl_delay("goodbot"), Some(Duration::from_millis(500)));
+        assert_eq!(robots.crawl_delay("unknown"), None);
+    }
+
+    #[test]
+    fn groups_for_the_same_agent_are_merged() {
+        let robots = RobotsTxt::parse(
+            "User-agent: bot\nDisallow: /a\n\nUser-agent: other\nDisallow: /b\n\n\
+             User-agent: bot\nDisallow: /c\n",
+        );
+        assert!(!robots.is_allowed("bot", "/a"));
+        assert!(robots.is_allowed("bot", "/b"));
+        assert!(!robots.is_allowed("bot", "/c"));
+    }
+
+    #[test]
+    fn invalid_lines_are_ignored() {
+        let robots = RobotsTxt::parse(
+            "Disallow: /before-any-group\nnonsense\nUser-agent: *\nCrawl-delay: soon\n",
+        );
+        assert!(robots.is_allowed("bot", "/before-any-group"));
+        assert_eq!(robots.crawl_delay("bot"), None);
+    }
+
+    #[test]
+    fn out_of_range_crawl_delays_are_ignored() {
+        for delay in &["-1", "NaN", "inf", "1e20", "1e300"] {
+            let robots = RobotsTxt::parse(&format!("User-agent: *\nCrawl-delay: {}\n", delay));
+            assert_eq!(robots.crawl_delay("bot"), None, "{}", delay);
+        }
+    }
+
+    #[test]
+    fn path_includes_query() {
+        let url = Url::parse("http://example.com/search?q=rust").unwrap();
+        assert_eq!(path_and_query(&url), "/search?q=rust");
+        let url = Url::parse("http://example.com").unwrap();
+        assert_eq!(path_and_query(&url), "/");
+    }
+}
@@ -0,0 +1,208 @@
+use base64::decode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::{ExtensionCommand, RequestMethod};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::json::convert_json;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession, WebElement};
+
+/// CSS pixels per centimetre, as used by browsers when printing.
+const PX_PER_CM: f64 = 96.0 / 2.54;
+
+/// The W3C Print Page command.
+struct PrintPage(Value);
+
+impl ExtensionCommand for PrintPage {
+    fn parameters_json(&self) -> Option<Value> {
+        Some(self.0.clone())
+    }
+
+    fn method(&self) -> RequestMethod {
+        RequestMethod::Post
+    }
+
+    fn endpoint(&self) -> String {
+        String::from("/print")
+    }
+}
+
+/// Options for printing the page, with sizes in centimetres as in the
+/// WebDriver specification. The defaults match the specification: US
+/// Letter, portrait, with 1cm margins and no backgrounds.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::PrintOptions;
+///
+/// // A4 landscape.
+/// let options = PrintOptions::new().with_page_size(21.0, 29.7).with_landscape(true);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    page_width: f64,
+    page_height: f64,
+    margin: f64,
+    landscape: bool,
+    background: bool,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            page_width: 21.59,
+            page_height: 27.94,
+            margin: 1.0,
+            landscape: false,
+            background: false,
+        }
+    }
+}
+
+impl PrintOptions {
+    /// Create a new PrintOptions with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the paper size in portrait orientation, in centimetres.
+    pub fn with_page_size(mut self, width: f64, height: f64) -> Self {
+        self.page_width = width;
+        self.page_height = height;
+        self
+    }
+
+    /// Set the margin on all four sides, in centimetres.
+    pub fn with_margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Print in landscape orientation.
+    pub fn with_landscape(mut self, landscape: bool) -> Self {
+        self.landscape = landscape;
+        self
+    }
+
+    /// Print background colours and images.
+    pub fn with_background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// The height of the printable area of each page, in CSS pixels.
+    fn content_height_px(&self) -> f64 {
+        let height = if self.landscape {
+            self.page_width
+        } else {
+            self.page_height
+        };
+        (height - 2.0 * self.margin) * PX_PER_CM
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "orientation": if self.landscape { "landscape" } else { "portrait" },
+            "background": self.background,
+            "page": { "width": self.page_width, "height": self.page_height },
+            "margin": {
+                "top": self.margin,
+                "bottom": self.margin,
+                "left": self.margin,
+                "right": self.margin
+            }
+        })
+    }
+}
+
+/// Print the current page to PDF using the W3C Print Page command.
+pub(crate) fn print_to_pdf(
+    session: &WebDriverSession,
+    options: &PrintOptions,
+) -> WebDriverResult<Vec<u8>> {
+    let v = session.extension_command(PrintPage(options.to_json()))?;
+    let s: String = convert_json("Print Page", &v)?;
+    Ok(decode(&s)?)
+}
+
+/// Count the pages in a PDF by counting its page objects.
+pub(crate) fn pdf_page_count(pdf: &[u8]) -> usize {
+    let mut count = 0;
+    for pattern in [&b"/Type/Page"[..], &b"/Type /Page"[..]].iter() {
+        count += pdf
+            .windows(pattern.len() + 1)
+            .filter(|w| w.starts_with(pattern) && !w[pattern.len()].is_ascii_alphanumeric())
+            .count();
+    }
+    count
+}
+
+/// Switch CSS media emulation between `print` and the default `screen`.
+pub(crate) fn emulate_print_media(
+    session: &WebDriverSession,
+    enabled: bool,
+) -> WebDriverResult<()> {
+    if !session.browser_info().is_chromium() {
+        return Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+            "print media emulation requires a Chromium-based browser",
+        )));
+    }
+    let media = if enabled {
+        "print"
+    } else {
+        ""
+    };
+    ChromeDevTools::new(session)
+        .execute_cdp_with_params("Emulation.setEmulatedMedia", json!({ "media": media }))?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrintExtent {
+    top: f64,
+    bottom: f64,
+    avoid_break: bool,
+}
+
+/// Returns the top and bottom of the element relative to the document, and
+/// whether it or an ancestor asks not to be split across pages.
+const PRINT_EXTENT_SCRIPT: &str = r#"
+    const elem = arguments[0];
+    const r = elem.getBoundingClientRect();
+    let avoidBreak = false;
+    for (let e = elem; e && e.nodeType === 1; e = e.parentElement) {
+        const s = getComputedStyle(e);
+        if (s.breakInside === "avoid" || s.breakInside === "avoid-page" ||
+            s.pageBreakInside === "avoid") {
+            avoidBreak = true;
+            break;
+        }
+    }
+    return { top: r.top + window.scrollY, bottom: r.bottom + window.scrollY, avoidBreak };
+"#;
+
+/// Estimate the first and last page (counting from 1) that the element
+/// appears on when printed.
+pub(crate) fn print_page_span(
+    elem: &WebElement,
+    options: &PrintOptions,
+) -> WebDriverResult<(u32, u32)> {
+    let mut args = ScriptArgs::new();
+    args.push(elem.clone())?;
+    let extent: PrintExtent =
+        elem.session().execute_script_with_args(PRINT_EXTENT_SCRIPT, &args)?.convert()?;
+
+    let page_height = options.content_height_px();
+    let first = (extent.top / page_height).floor().max(0.0) as u32;
+    // An element ending exactly on a page boundary does not continue onto
+    // the next page.
+    let last = ((extent.bottom - 0.5) / page_height).floor().max(first as f64) as u32;
+    if last > first && extent.avoid_break && extent.bottom - extent.top <= page_height {
+        // The browser moves the whole element onto the next page.
+        return Ok((first + 2, first + 2));
+    }
+    Ok((first + 1, last + 1))
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::error::WebDriverResult;
+use crate::query::find_elements_nowait;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession};
+
+type ConditionFn<'a> = Box<dyn Fn(&WebDriverSession) -> WebDriverResult<bool> + 'a>;
+
+/// A condition on the state of the page, for use with
+/// [WebDriver::wait_for_any()](../trait.WebDriverCommands.html#method.wait_for_any).
+///
+/// Each condition is checked once per poll attempt and should return quickly.
+/// Conditions that look up elements do not use the implicit wait.
+pub struct Condition<'a> {
+    description: String,
+    check: ConditionFn<'a>,
+}
+
+impl<'a> fmt::Debug for Condition<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condition").field("description", &self.description).finish()
+    }
+}
+
+impl<'a> Condition<'a> {
+    /// Create a condition from a closure. The description is used in error
+    /// messages if the condition is never met.
+    pub fn new<F>(description: &str, f: F) -> Self
+    where
+        F: Fn(&WebDriverSession) -> WebDriverResult<bool> + 'a,
+    {
+        Self {
+            description: description.to_string(),
+            check: Box::new(f),
+        }
+    }
+
+    /// The condition is met when at least one element matches the selector.
+    pub fn element_present(by: By<'a>) -> Self {
+        let description = format!("element present: {:?}", by);
+        Self::new(&description, move |session| {
+            Ok(!find_elements_nowait(session, by.clone())?.is_empty())
+        })
+    }
+
+    /// The condition is met when at least one element matching the selector
+    /// is displayed.
+    pub fn element_displayed(by: By<'a>) -> Self {
+        let description = format!("element displayed: {:?}", by);
+        Self::new(&description, move |session| {
+            for elem in find_elements_nowait(session, by.clone())? {
+                if elem.is_displayed()? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+    }
+
+    /// The condition is met when the page title contains the specified text.
+    pub fn title_contains(text: &str) -> Self {
+        let text = text.to_string();
+        Self::new(&format!("title contains {:?}", text), move |session| {
+            Ok(session.title()?.contains(&text))
+        })
+    }
+
+    /// The condition is met when the current URL contains the specified text.
+    pub fn url_contains(text: &str) -> Self {
+        let text = text.to_string();
+        Self::new(&format!("URL contains {:?}", text), move |session| {
+            Ok(session.current_url()?.contains(&text))
+        })
+    }
+
+    /// The description of this condition.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Check the condition once.
+    pub fn check(&self, session: &WebDriverSession) -> WebDriverResult<bool> {
+        (self.check)(session)
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::{no_such_element, WebDriverError, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::query::{without_implicit_wait, ElementPoller};
+use crate::text::normalize_text;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession, WebElement};
+
+/// A filter applied to the elements found by an ElementQuery.
+#[derive(Debug, Clone)]
+enum ElementFilter {
+    Text(String),
+    TextContaining(String),
+    Attribute(String, Option<String>),
+    Displayed,
+    Enabled,
+}
+
+impl ElementFilter {
+    fn matches(&self, elem: &WebElement) -> WebDriverResult<bool> {
+        match self {
+            ElementFilter::Text(text) => Ok(&elem.normalized_text()? == text),
+            ElementFilter::TextContaining(text) => Ok(elem.normalized_text()?.contains(text)),
+            ElementFilter::Attribute(name, value) => {
+                let actual = elem.get_attribute(name)?;
+                Ok(match value {
+                    Some(value) => actual.as_ref() == Some(value),
+                    None => actual.is_some(),
+                })
+            }
+            ElementFilter::Displayed => elem.is_displayed(),
+            ElementFilter::Enabled => elem.is_enabled(),
+        }
+    }
+}
+
+impl fmt::Display for ElementFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementFilter::Text(text) => write!(f, "with text {:?}", text),
+            ElementFilter::TextContaining(text) => write!(f, "with text containing {:?}", text),
+            ElementFilter::Attribute(name, Some(value)) => {
+                write!(f, "with attribute {}={:?}", name, value)
+            }
+            ElementFilter::Attribute(name, None) => write!(f, "with attribute {}", name),
+            ElementFilter::Displayed => write!(f, "displayed"),
+            ElementFilter::Enabled => write!(f, "enabled"),
+        }
+    }
+}
+
+/// A builder for finding elements, polling until they appear.
+///
+/// Create one with
+/// [WebDriver::query()](../trait.WebDriverCommands.html#method.query) or
+/// [WebElement::query()](../struct.WebElement.html#method.query). The query
+/// waits for the page to be ready, then polls using the default
+/// [ElementPoller](enum.ElementPoller.html) unless another timeout is set.
+/// On each attempt every selector is tried in order, and the elements found
+/// are filtered. The implicit wait is not used.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://webappdemo")?;
+/// let button = driver
+///     .query(By::Id("no-such-element"))
+///     .or(By::Css("div[data-section='section-buttons'] #button1"))
+///     .displayed()
+///     .with_timeout(Duration::from_secs(5))
+///     .first()?;
+/// button.click()?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElementQuery<'a> {
+    session: &'a WebDriverSession,
+    parent: Option<WebElement<'a>>,
+    selectors: Vec<By<'a>>,
+    filters: Vec<ElementFilter>,
+    poller: ElementPoller,
+}
+
+impl<'a> ElementQuery<'a> {
+    pub(crate) fn new(
+        session: &'a WebDriverSession,
+        parent: Option<WebElement<'a>>,
+        by: By<'a>,
+    ) -> Self {
+        Self {
+            session,
+            parent,
+            selectors: vec![by],
+            filters: Vec::new(),
+            poller: ElementPoller::default(),
+        }
+    }
+
+    /// Also find elements matching another selector.
+    pub fn or(mut self, by: By<'a>) -> Self {
+        self.selectors.push(by);
+        self
+    }
+
+    /// Only match elements whose text equals the specified text, ignoring
+    /// differences in whitespace.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.filters.push(ElementFilter::Text(normalize_text(text)));
+        self
+    }
+
+    /// Only match elements whose text contains the specified text, ignoring
+    /// differences in whitespace.
+    pub fn with_text_containing(mut self, text: &str) -> Self {
+        self.filters.push(ElementFilter::TextContaining(normalize_text(text)));
+        self
+    }
+
+    /// Only match elements with the specified attribute value.
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.filters.push(ElementFilter::Attribute(name.to_string(), Some(value.to_string())));
+        self
+    }
+
+    /// Only match elements that have the specified attribute, whatever its
+    /// value.
+    pub fn with_attribute_present(mut self, name: &str) -> Self {
+        self.filters.push(ElementFilter::Attribute(name.to_string(), None));
+        self
+    }
+
+    /// Only match elements that are displayed.
+    pub fn displayed(mut self) -> Self {
+        self.filters.push(ElementFilter::Displayed);
+        self
+    }
+
+    /// Only match elements that are enabled.
+    pub fn enabled(mut self) -> Self {
+        self.filters.push(ElementFilter::Enabled);
+        self
+    }
+
+    /// Poll up to the specified timeout, every 500ms.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(500));
+        self
+    }
+
+    /// Poll up to the specified timeout, with the specified interval
+    /// between attempts.
+    pub fn with_timeout_and_interval(mut self, timeout: Duration, interval: Duration) -> Self {
+        self.poller = ElementPoller::TimeoutWithInterval(timeout, interval);
+        self
+    }
+
+    /// Use the specified poller.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+
+    /// Check only once, without polling.
+    pub fn nowait(self) -> Self {
+        self.with_poller(ElementPoller::NoWait)
+    }
+
+    fn description(&self) -> String {
+        let mut description =
+            self.selectors.iter().map(|by| by.to_string()).collect::<Vec<String>>().join(" or ");
+        for filter in &self.filters {
+            description.push_str(&format!(", {}", filter));
+        }
+        description
+    }
+
+    /// Find the elements matching any selector and every filter. Elements
+    /// that go stale while being filtered are left out.
+    fn find(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
+        let mut found = Vec::new();
+        for by in &self.selectors {
+            let elems = match &self.parent {
+                Some(parent) => parent
+                    .find_elements(by.clone())?
+                    .into_iter()
+                    .map(|e| WebElement::new(self.session, e.element_id))
+                    .collect(),
+                None => self.session.find_elements(by.clone())?,
+            };
+            'elems: for elem in elems {
+                for filter in &self.filters {
+                    match filter.matches(&elem) {
+                        Ok(true) => {}
+                        Ok(false) | Err(WebDriverError::StaleElementReference(_)) => {
+                            continue 'elems
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                found.push(elem);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Poll until at least one element matches, returning all of the
+    /// matching elements, or a Timeout error.
+    fn poll(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
+        self.session.wait_for_ready()?;
+        let description = format!("no element matched {}", self.description());
+        without_implicit_wait(self.session, || {
+            self.poller.poll(&description, || {
+                let found = self.find()?;
+                Ok(if found.is_empty() {
+                    None
+                } else {
+                    Some(found)
+                })
+            })
+        })
+    }
+
+    /// Return the first matching element, or a NoSuchElement error if none
+    /// matches before the timeout.
+    pub fn first(&self) -> WebDriverResult<WebElement<'a>> {
+        match self.poll() {
+            Ok(elems) => Ok(elems.into_iter().next().expect("poll returns at least one element")),
+            Err(WebDriverError::Timeout(_)) => {
+                let description = format!("no element matched {}", self.description());
+                Err(no_such_element(&with_page_context(self.session, &description)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return all the matching elements, as soon as at least one matches.
+    /// Returns an empty Vec if none matches before the timeout.
+    pub fn all(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
+        match self.poll() {
+            Err(WebDriverError::Timeout(_)) => Ok(Vec::new()),
+            x => x,
+        }
+    }
+
+    /// Return true as soon as an element matches, or false if none matches
+    /// before the timeout.
+    pub fn exists(&self) -> WebDriverResult<bool> {
+        Ok(!self.all()?.is_empty())
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use crate::error::WebDriverResult;
+use crate::query::poller::PollerWithState;
+use crate::query::ElementPoller;
+
+/// How often waiting assertions check again.
+const ASSERTION_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Call `check` until it passes or the timeout is reached. `check` returns
+/// Ok(Ok(())) if the assertion passes, or a description of what it observed
+/// instead. Errors are returned immediately.
+///
+/// Returns None if the assertion passed, or the number of attempts and the
+/// last observation if it never did.
+pub(crate) fn poll_assertion<F>(
+    timeout: Duration,
+    mut check: F,
+) -> WebDriverResult<Option<(u32, String)>>
+where
+    F: FnMut() -> WebDriverResult<Result<(), String>>,
+{
+    let mut state =
+        PollerWithState::new(ElementPoller::TimeoutWithInterval(timeout, ASSERTION_INTERVAL));
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let observed = match check()? {
+            Ok(()) => return Ok(None),
+            Err(observed) => observed,
+        };
+        if !state.tick() {
+            return Ok(Some((attempts, observed)));
+        }
+    }
+}
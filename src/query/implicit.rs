@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use crate::error::WebDriverResult;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, TimeoutConfiguration, WebDriverSession, WebElement};
+
+/// Run the specified closure with the implicit wait timeout temporarily set to 0,
+/// restoring the previous value afterwards.
+///
+/// This allows checking whether elements are present without waiting for the
+/// full implicit wait timeout when they are not.
+pub(crate) fn without_implicit_wait<T, F>(session: &WebDriverSession, f: F) -> WebDriverResult<T>
+where
+    F: FnOnce() -> WebDriverResult<T>,
+{
+    let previous = session.get_timeouts()?.implicit();
+    if previous == Some(Duration::new(0, 0)) {
+        return f();
+    }
+
+    session.set_implicit_wait_timeout(Duration::new(0, 0))?;
+    let result = f();
+    session.set_timeouts(TimeoutConfiguration::new(None, None, previous))?;
+    result
+}
+
+/// Find all elements matching the selector, without waiting for the implicit wait timeout.
+pub(crate) fn find_elements_nowait<'a>(
+    session: &'a WebDriverSession,
+    by: By,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    without_implicit_wait(session, || session.find_elements(by))
+}
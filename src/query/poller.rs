@@ -0,0 +1,114 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// Parameters used to determine the polling rate when waiting for elements
+/// or other conditions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ElementPoller {
+    /// No polling. Only a single attempt will be made.
+    NoWait,
+    /// Poll up to the specified timeout, with the specified interval being the
+    /// minimum time elapsed between the start of each poll attempt.
+    /// If the previous poll attempt took longer than the interval, the next will
+    /// start immediately. Once the timeout is reached, a Timeout error will be
+    /// returned regardless of the actual number of polling attempts completed.
+    TimeoutWithInterval(Duration, Duration),
+    /// Poll once every interval, up to the maximum number of polling attempts.
+    /// If the previous poll attempt took longer than the interval, the next will
+    /// start immediately.
+    NumTriesWithInterval(u32, Duration),
+    /// Poll up to the specified timeout, with the specified interval being the
+    /// minimum time elapsed between the start of each poll attempt, but make
+    /// at least the specified number of attempts even if the timeout is reached.
+    TimeoutWithIntervalAndMinTries(Duration, Duration, u32),
+}
+
+impl Default for ElementPoller {
+    fn default() -> Self {
+        ElementPoller::TimeoutWithInterval(Duration::from_secs(20), Duration::from_millis(500))
+    }
+}
+
+impl ElementPoller {
+    /// Call `f` repeatedly until it returns `Some(T)` or the poller gives up.
+    ///
+    /// The `description` is used in the Timeout error message.
+    pub fn poll<T, F>(&self, description: &str, mut f: F) -> WebDriverResult<T>
+    where
+        F: FnMut() -> WebDriverResult<Option<T>>,
+    {
+        let mut state = PollerWithState::new(self.clone());
+        loop {
+            if let Some(x) = f()? {
+                return Ok(x);
+            }
+
+            if !state.tick() {
+                return Err(WebDriverError::Timeout(format!(
+                    "{} (gave up after {} attempts in {:?})",
+                    description,
+                    state.cur_tries,
+                    state.started.elapsed()
+                )));
+            }
+        }
+    }
+}
+
+/// Tracks the progress of a single polling operation.
+#[derive(Debug)]
+pub(crate) struct PollerWithState {
+    poller: ElementPoller,
+    started: Instant,
+    cur_tries: u32,
+}
+
+impl PollerWithState {
+    pub fn new(poller: ElementPoller) -> Self {
+        Self {
+            poller,
+            started: Instant::now(),
+            cur_tries: 0,
+        }
+    }
+
+    /// Sleep until the next poll attempt is due.
+    ///
+    /// Returns false if no more attempts should be made.
+    pub fn tick(&mut self) -> bool {
+        self.cur_tries += 1;
+
+        let (timeout, interval, min_tries) = match self.poller {
+            ElementPoller::NoWait => return false,
+            ElementPoller::TimeoutWithInterval(timeout, interval) => (Some(timeout), interval, 0),
+            ElementPoller::NumTriesWithInterval(max_tries, interval) => {
+                if self.cur_tries >= max_tries {
+                    return false;
+                }
+                (None, interval, 0)
+            }
+            ElementPoller::TimeoutWithIntervalAndMinTries(timeout, interval, min_tries) => {
+                (Some(timeout), interval, min_tries)
+            }
+        };
+
+        if let Some(t) = timeout {
+            if self.started.elapsed() >= t && self.cur_tries >= min_tries {
+                return false;
+            }
+        }
+
+        // The interval is the minimum time between the start of each attempt.
+        let minimum_elapsed = interval * self.cur_tries;
+        let actual_elapsed = self.started.elapsed();
+        if actual_elapsed < minimum_elapsed {
+            thread::sleep(minimum_elapsed - actual_elapsed);
+        }
+
+        true
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::WebDriverResult;
+use crate::query::{wait_for_page_settled, ElementPoller};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// Determines when a page is ready to be interacted with.
+///
+/// A ReadyStrategy can be set on a WebDriver using
+/// [WebDriver::set_ready_strategy()](../struct.GenericWebDriver.html#method.set_ready_strategy).
+/// It is then consulted after every navigation made via `get()`, `back()`,
+/// `forward()` and `refresh()`, and can be invoked manually with
+/// [WebDriver::wait_for_ready()](../trait.WebDriverCommands.html#method.wait_for_ready).
+///
+/// Implement this trait to define readiness for your own app.
+///
+/// # Example
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::query::ReadyStrategy;
+/// use thirtyfour_sync::WebDriverSession;
+///
+/// #[derive(Debug)]
+/// struct SpinnerGone;
+///
+/// impl ReadyStrategy for SpinnerGone {
+///     fn wait_until_ready(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+///         while !session.find_elements(By::ClassName("spinner"))?.is_empty() {
+///             std::thread::sleep(std::time::Duration::from_millis(100));
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// driver.set_ready_strategy(SpinnerGone);
+/// driver.get("http://webappdemo")?;
+/// #     Ok(())
+/// # }
+/// ```
+pub trait ReadyStrategy: fmt::Debug + Send + Sync {
+    /// Block until the page is ready, or return an error if it never becomes ready.
+    fn wait_until_ready(&self, session: &WebDriverSession) -> WebDriverResult<()>;
+}
+
+/// Wait until `document.readyState` is `complete`.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentReady {
+    poller: ElementPoller,
+}
+
+impl DocumentReady {
+    /// Create a new DocumentReady strategy using the default poller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the poller used while waiting.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+}
+
+impl ReadyStrategy for DocumentReady {
+    fn wait_until_ready(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+        self.poller.poll("document did not finish loading", || {
+            let state: String = session.execute_script("return document.readyState;")?.convert()?;
+            Ok(if state == "complete" {
+                Some(())
+            } else {
+                None
+            })
+        })
+    }
+}
+
+/// Wait until the document has loaded and there have been no DOM mutations
+/// and no new network requests for the specified quiet period.
+///
+/// If the page does not go quiet before the poller gives up, it is
+/// considered ready anyway. Pages that poll the server continuously would
+/// otherwise never become ready.
+#[derive(Debug, Clone)]
+pub struct NetworkIdle {
+    quiet_period: Duration,
+    poller: ElementPoller,
+}
+
+impl NetworkIdle {
+    /// Create a new NetworkIdle strategy with the specified quiet period.
+    pub fn new(quiet_period: Duration) -> Self {
+        Self {
+            quiet_period,
+            poller: ElementPoller::TimeoutWithInterval(
+                Duration::from_secs(10),
+                Duration::from_millis(100),
+            ),
+        }
+    }
+
+    /// Set the poller used while waiting.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+}
+
+impl ReadyStrategy for NetworkIdle {
+    fn wait_until_ready(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+        wait_for_page_settled(session, self.quiet_period, &self.poller)?;
+        Ok(())
+    }
+}
+
+/// Wait until a JavaScript expression, such as `window.appReady`, is truthy.
+#[derive(Debug, Clone)]
+pub struct JsCondition {
+    expression: String,
+    poller: ElementPoller,
+}
+
+impl JsCondition {
+    /// Create a new JsCondition strategy for the specified expression.
+    pub fn new(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            poller: ElementPoller::default(),
+        }
+    }
+
+    /// Set the poller used while waiting.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+}
+
+impl ReadyStrategy for JsCondition {
+    fn wait_until_ready(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+        let script = format!("return !!({});", self.expression);
+        let description = format!("{} did not become true", self.expression);
+        self.poller.poll(&description, || {
+            let ready: bool = session.execute_script(&script)?.convert()?;
+            Ok(if ready {
+                Some(())
+            } else {
+                None
+            })
+        })
+    }
+}
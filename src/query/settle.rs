@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::query::ElementPoller;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivityStats {
+    ready_state: String,
+    since_mutation: f64,
+    resources: u64,
+}
+
+const ACTIVITY_SCRIPT: &str = r#"
+    if (!window.__thirtyfourActivity) {
+        const activity = { lastMutation: Date.now() };
+        new MutationObserver(() => { activity.lastMutation = Date.now(); }).observe(
+            document, { childList: true, subtree: true, attributes: true, characterData: true }
+        );
+        window.__thirtyfourActivity = activity;
+    }
+    return {
+        readyState: document.readyState,
+        sinceMutation: Date.now() - window.__thirtyfourActivity.lastMutation,
+        resources: performance.getEntriesByType("resource").length
+    };
+"#;
+
+/// Wait until the document has loaded and there has been no DOM or network
+/// activity for the specified quiet period.
+///
+/// Returns false if the poller gave up before the page settled.
+pub(crate) fn wait_for_page_settled(
+    session: &WebDriverSession,
+    quiet_period: Duration,
+    poller: &ElementPoller,
+) -> WebDriverResult<bool> {
+    let mut resources = None;
+    let mut last_resource_change = Instant::now();
+    let settled = poller.poll("page did not settle", || {
+        let stats: ActivityStats = session.execute_script(ACTIVITY_SCRIPT)?.convert()?;
+        if resources != Some(stats.resources) {
+            resources = Some(stats.resources);
+            last_resource_change = Instant::now();
+        }
+
+        let quiet = Duration::from_millis(stats.since_mutation.max(0.0) as u64) >= quiet_period
+            && last_resource_change.elapsed() >= quiet_period;
+        Ok(if stats.ready_state == "complete" && quiet {
+            Some(())
+        } else {
+            None
+        })
+    });
+
+    match settled {
+        Ok(()) => Ok(true),
+        Err(WebDriverError::Timeout(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
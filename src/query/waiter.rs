@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::page_info::with_page_context;
+use crate::query::ElementPoller;
+use crate::text::normalize_text;
+use crate::WebElement;
+
+/// Waits for a WebElement to reach a particular state, such as displayed or
+/// clickable. Create one with
+/// [WebElement::wait_until()](../struct.WebElement.html#method.wait_until).
+///
+/// Each condition method polls until the condition is met, then returns Ok.
+/// If the condition is still not met after the timeout, a Timeout error is
+/// returned describing the condition and the current page. The default
+/// timeout is 20 seconds, polling every 500ms, and either can be overridden
+/// for a single wait.
+///
+/// Errors from checking the condition are returned straight away. In
+/// particular, waiting for any condition other than `stale()` fails with a
+/// StaleElementReference error if the element goes stale.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://webappdemo")?;
+/// let elem = driver.find_element(By::Id("button1"))?;
+/// elem.wait_until().clickable()?;
+/// elem.click()?;
+/// let result = driver.find_element(By::Id("button-result"))?;
+/// result
+///     .wait_until()
+///     .with_timeout(Duration::from_secs(5))
+///     .with_interval(Duration::from_millis(100))
+///     .has_text("Button 1 clicked")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElementWaiter<'e, 'a> {
+    element: &'e WebElement<'a>,
+    timeout: Duration,
+    interval: Duration,
+}
+
+impl<'e, 'a> ElementWaiter<'e, 'a> {
+    pub(crate) fn new(element: &'e WebElement<'a>) -> Self {
+        let (timeout, interval) = match ElementPoller::default() {
+            ElementPoller::TimeoutWithInterval(timeout, interval) => (timeout, interval),
+            _ => (Duration::from_secs(20), Duration::from_millis(500)),
+        };
+        Self {
+            element,
+            timeout,
+            interval,
+        }
+    }
+
+    /// Wait up to the specified timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait the specified interval between checks of the condition.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Wait until the specified closure returns true. The description is
+    /// used in the Timeout error if it never does.
+    pub fn condition<F>(&self, description: &str, mut f: F) -> WebDriverResult<()>
+    where
+        F: FnMut(&WebElement<'a>) -> WebDriverResult<bool>,
+    {
+        let poller = ElementPoller::TimeoutWithInterval(self.timeout, self.interval);
+        let result = poller.poll(description, || {
+            Ok(if f(self.element)? {
+                Some(())
+            } else {
+                None
+            })
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                let message =
+                    format!("timed out waiting for element {} to be {}", self.element, description);
+                Err(WebDriverError::Timeout(with_page_context(self.element.session(), &message)))
+            }
+            x => x,
+        }
+    }
+
+    /// Wait until the element is displayed.
+    pub fn displayed(&self) -> WebDriverResult<()> {
+        self.condition("displayed", |elem| elem.is_displayed())
+    }
+
+    /// Wait until the element is not displayed.
+    pub fn not_displayed(&self) -> WebDriverResult<()> {
+        self.condition("hidden", |elem| Ok(!elem.is_displayed()?))
+    }
+
+    /// Wait until the element is enabled.
+    pub fn enabled(&self) -> WebDriverResult<()> {
+        self.condition("enabled", |elem| elem.is_enabled())
+    }
+
+    /// Wait until the element is disabled.
+    pub fn not_enabled(&self) -> WebDriverResult<()> {
+        self.condition("disabled", |elem| Ok(!elem.is_enabled()?))
+    }
+
+    /// Wait until the element is clickable, i.e. displayed and enabled.
+    pub fn clickable(&self) -> WebDriverResult<()> {
+        self.condition("clickable", |elem| elem.is_clickable())
+    }
+
+    /// Wait until the element is selected.
+    pub fn selected(&self) -> WebDriverResult<()> {
+        self.condition("selected", |elem| elem.is_selected())
+    }
+
+    /// Wait until the element is not selected.
+    pub fn not_selected(&self) -> WebDriverResult<()> {
+        self.condition("deselected", |elem| Ok(!elem.is_selected()?))
+    }
+
+    /// Wait until the element goes stale, e.g. because it was removed or
+    /// the page navigated away.
+    pub fn stale(&self) -> WebDriverResult<()> {
+        self.condition("stale", |elem| Ok(!elem.is_present()?))
+    }
+
+    /// Wait until the text of the element equals the specified text,
+    /// ignoring differences in whitespace.
+    pub fn has_text(&self, text: &str) -> WebDriverResult<()> {
+        let text = normalize_text(text);
+        self.condition(&format!("showing text {:?}", text), |elem| {
+            Ok(elem.normalized_text()? == text)
+        })
+    }
+
+    /// Wait until the text of the element contains the specified text,
+    /// ignoring differences in whitespace.
+    pub fn contains_text(&self, text: &str) -> WebDriverResult<()> {
+        let text = normalize_text(text);
+        self.condition(&format!("showing text containing {:?}", text), |elem| {
+            Ok(elem.normalized_text()?.contains(&text))
+        })
+    }
+
+    /// Wait until the element has the specified attribute value.
+    pub fn has_attribute(&self, name: &str, value: &str) -> WebDriverResult<()> {
+        self.condition(&format!("given attribute {}={:?}", name, value), |elem| {
+            Ok(elem.get_attribute(name)?.as_deref() == Some(value))
+        })
+    }
+
+    /// Wait until the element has the specified class.
+    pub fn has_class(&self, class_name: &str) -> WebDriverResult<()> {
+        self.condition(&format!("given class {:?}", class_name), |elem| {
+            let classes = elem.class_name()?.unwrap_or_default();
+            Ok(classes.split_whitespace().any(|c| c == class_name))
+        })
+    }
+}
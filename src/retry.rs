@@ -0,0 +1,129 @@
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+type RetryFilter = Arc<dyn Fn(&WebDriverError) -> bool + Send + Sync>;
+
+/// Decides when a failed operation should be attempted again, and how long
+/// to wait between attempts.
+///
+/// By default an operation is attempted up to 3 times, and is retried only
+/// if it fails because an element went stale or a click was intercepted.
+/// The wait between attempts starts at 100ms and doubles after each attempt,
+/// up to 2 seconds.
+///
+/// See [WebDriver::retrying()](trait.WebDriverCommands.html#method.retrying).
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::error::WebDriverError;
+/// use thirtyfour_sync::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new()
+///     .with_max_attempts(5)
+///     .with_backoff(Duration::from_millis(200), Duration::from_secs(5))
+///     .retry_on(|e| matches!(e, WebDriverError::ElementNotInteractable(_)));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    filters: Vec<RetryFilter>,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new RetryPolicy that retries stale element and intercepted
+    /// click errors.
+    pub fn new() -> Self {
+        Self::none().retry_on(|e| {
+            matches!(
+                e,
+                WebDriverError::StaleElementReference(_)
+                    | WebDriverError::ElementClickIntercepted(_)
+            )
+        })
+    }
+
+    /// Create a new RetryPolicy that retries no errors. Use
+    /// [retry_on()](#method.retry_on) to choose which errors to retry.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Set the maximum number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the wait before the first retry, and the maximum wait between
+    /// attempts. The wait doubles after each attempt.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Also retry errors for which the specified function returns true.
+    pub fn retry_on<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&WebDriverError) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Arc::new(f));
+        self
+    }
+
+    /// Return true if the specified error should be retried.
+    pub fn is_retryable(&self, error: &WebDriverError) -> bool {
+        self.filters.iter().any(|f| f(error))
+    }
+
+    /// Run the specified function, retrying it according to this policy.
+    /// Returns the last error if every attempt fails, or the first error
+    /// that is not retryable.
+    pub fn run<T, F>(&self, mut f: F) -> WebDriverResult<T>
+    where
+        F: FnMut() -> WebDriverResult<T>,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match f() {
+                Err(e) if attempt < self.max_attempts && self.is_retryable(&e) => {
+                    log::debug!("retrying after attempt {} failed: {}", attempt, e);
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+                x => return x,
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::json::convert_json_from;
+use crate::WebDriver;
+
+type RoleFn = Box<dyn FnOnce(&RoleContext) -> WebDriverResult<()> + Send>;
+
+/// How often blocked roles check whether another role has failed.
+const ABORT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A message sent from one role to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioMessage {
+    /// The name of the role that sent the message.
+    pub from: String,
+    /// The message body.
+    pub body: Value,
+}
+
+impl ScenarioMessage {
+    /// Convert the message body into the specified type.
+    pub fn convert<T: DeserializeOwned>(&self) -> WebDriverResult<T> {
+        convert_json_from("the scenario message", &self.body)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    barriers: HashMap<String, usize>,
+    failed: Option<String>,
+    error: Option<WebDriverError>,
+}
+
+struct Shared {
+    roles: usize,
+    timeout: Duration,
+    state: Mutex<State>,
+    cond: Condvar,
+    inboxes: Mutex<HashMap<String, Sender<ScenarioMessage>>>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn aborted(state: &State, role: &str) -> WebDriverError {
+        WebDriverError::FatalError(format!(
+            "role {} aborted because role {} failed",
+            role,
+            state.failed.as_deref().unwrap_or("unknown")
+        ))
+    }
+}
+
+/// The view of a running scenario from a single role.
+///
+/// Each role has its own WebDriver session, and runs on its own thread.
+pub struct RoleContext {
+    name: String,
+    driver: WebDriver,
+    shared: Arc<Shared>,
+    inbox: Receiver<ScenarioMessage>,
+}
+
+impl fmt::Debug for RoleContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoleContext").field("name", &self.name).finish()
+    }
+}
+
+impl RoleContext {
+    /// The name of this role.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The WebDriver session for this role.
+    pub fn driver(&self) -> &WebDriver {
+        &self.driver
+    }
+
+    /// Wait until every role has reached the barrier with the specified name.
+    ///
+    /// Each barrier name can only be used once per scenario. Returns a
+    /// Timeout error if the other roles do not arrive within the scenario
+    /// timeout, or an error if another role fails while waiting.
+    pub fn barrier(&self, name: &str) -> WebDriverResult<()> {
+        let deadline = Instant::now() + self.shared.timeout;
+        let mut state = self.shared.lock();
+        *state.barriers.entry(name.to_string()).or_insert(0) += 1;
+        self.shared.cond.notify_all();
+        loop {
+            if state.failed.is_some() {
+                return Err(Shared::aborted(&state, &self.name));
+            }
+            let arrived = state.barriers.get(name).copied().unwrap_or(0);
+            if arrived >= self.shared.roles {
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(WebDriverError::Timeout(format!(
+                    "role {} timed out at barrier {:?} ({} of {} roles arrived)",
+                    self.name, name, arrived, self.shared.roles
+                )));
+            }
+            state = self
+                .shared
+                .cond
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Send a message to the role with the specified name.
+    pub fn send<T: Serialize>(&self, to: &str, body: &T) -> WebDriverResult<()> {
+        let message = ScenarioMessage {
+            from: self.name.clone(),
+            body: serde_json::to_value(body)?,
+        };
+        let inboxes = self.shared.inboxes.lock().unwrap_or_else(|e| e.into_inner());
+        match inboxes.get(to) {
+            Some(inbox) => inbox.send(message).map_err(|_| {
+                WebDriverError::FatalError(format!("role {} has already finished", to))
+            }),
+            None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "no role named {:?}",
+                to
+            )))),
+        }
+    }
+
+    /// Wait for the next message sent to this role.
+    ///
+    /// Returns a Timeout error if no message arrives within the timeout, or
+    /// an error if another role fails while waiting.
+    pub fn recv(&self, timeout: Duration) -> WebDriverResult<ScenarioMessage> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            {
+                let state = self.shared.lock();
+                if state.failed.is_some() {
+                    return Err(Shared::aborted(&state, &self.name));
+                }
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.inbox.recv_timeout(remaining.min(ABORT_CHECK_INTERVAL)) {
+                Ok(message) => return Ok(message),
+                Err(RecvTimeoutError::Timeout) if remaining > ABORT_CHECK_INTERVAL => {}
+                Err(_) => {
+                    return Err(WebDriverError::Timeout(format!(
+                        "role {} received no message",
+                        self.name
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Coordinates several browser sessions with named roles, for tests where
+/// users interact with each other, such as chat or collaborative editing.
+///
+/// Each role gets its own WebDriver session and runs on its own thread. Roles
+/// can synchronize with [barrier()](struct.RoleContext.html#method.barrier)
+/// and exchange data with [send()](struct.RoleContext.html#method.send) and
+/// [recv()](struct.RoleContext.html#method.recv). If any role fails, the
+/// others are aborted at their next barrier or recv, and the first error is
+/// returned.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::scenario::Scenario;
+/// use std::time::Duration;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let caps = DesiredCapabilities::chrome();
+/// Scenario::new("http://localhost:4444/wd/hub", &caps)?
+///     .role("alice", |ctx| {
+///         ctx.driver().get("http://localhost:8000/chat")?;
+///         ctx.barrier("joined")?;
+///         ctx.driver().find_element(By::Id("message"))?.send_keys("hello bob")?;
+///         ctx.driver().find_element(By::Id("send"))?.click()?;
+///         ctx.send("bob", &"sent")?;
+///         Ok(())
+///     })
+///     .role("bob", |ctx| {
+///         ctx.driver().get("http://localhost:8000/chat")?;
+///         ctx.barrier("joined")?;
+///         ctx.recv(Duration::from_secs(5))?;
+///         let log = ctx.driver().find_element(By::Id("chat-log"))?;
+///         assert!(log.text()?.contains("hello bob"));
+///         Ok(())
+///     })
+///     .run()?;
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Scenario {
+    server_url: String,
+    capabilities: Value,
+    timeout: Duration,
+    roles: Vec<(String, RoleFn)>,
+}
+
+impl fmt::Debug for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scenario")
+            .field("server_url", &self.server_url)
+            .field("capabilities", &self.capabilities)
+            .field("timeout", &self.timeout)
+            .field("roles", &self.roles.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Scenario {
+    /// Create a new Scenario that will start sessions on the specified server
+    /// using the specified capabilities.
+    pub fn new<C>(server_url: &str, capabilities: C) -> WebDriverResult<Self>
+    where
+        C: Serialize,
+    {
+        Ok(Self {
+            server_url: server_url.to_string(),
+            capabilities: serde_json::to_value(capabilities)?,
+            timeout: Duration::from_secs(30),
+            roles: Vec::new(),
+        })
+    }
+
+    /// Set how long a role waits at a barrier for the other roles.
+    /// The default is 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Add a role with the specified name.
+    pub fn role<F>(mut self, name: &str, f: F) -> Self
+    where
+        F: FnOnce(&RoleContext) -> WebDriverResult<()> + Send + 'static,
+    {
+        self.roles.push((name.to_string(), Box::new(f)));
+        self
+    }
+
+    /// Start a session for every role and run them all concurrently.
+    ///
+    /// Returns the first error from any role. All sessions are closed when
+    /// the scenario finishes.
+    pub fn run(self) -> WebDriverResult<()> {
+        let shared = Arc::new(Shared {
+            roles: self.roles.len(),
+            timeout: self.timeout,
+            state: Mutex::new(State::default()),
+            cond: Condvar::new(),
+            inboxes: Mutex::new(HashMap::new()),
+        });
+
+        let mut contexts = Vec::new();
+        for (name, f) in self.roles {
+            let driver = WebDriver::new(&self.server_url, &self.capabilities)?;
+            let (sender, inbox) = channel();
+            shared.inboxes.lock().unwrap_or_else(|e| e.into_inner()).insert(name.clone(), sender);
+            let context = RoleContext {
+                name,
+                driver,
+                shared: shared.clone(),
+                inbox,
+            };
+            contexts.push((context, f));
+        }
+
+        let mut handles = Vec::new();
+        for (context, f) in contexts {
+            let name = context.name.clone();
+            let handle = thread::spawn(move || {
+                // Treat a panic like an error, so the other roles are aborted.
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| f(&context))).unwrap_or_else(|_| {
+                        Err(WebDriverError::FatalError(format!(
+                            "scenario role {} panicked",
+                            context.name
+                        )))
+                    });
+                if let Err(e) = result {
+                    let mut state = context.shared.lock();
+                    if state.failed.is_none() {
+                        state.failed = Some(context.name.clone());
+                        state.error = Some(e);
+                    }
+                    context.shared.cond.notify_all();
+                }
+            });
+            handles.push((name, handle));
+        }
+
+        for (name, handle) in handles {
+            handle.join().map_err(|_| {
+                WebDriverError::FatalError(format!("scenario role {} panicked", name))
+            })?;
+        }
+
+        let error = shared.lock().error.take();
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+#[cfg(feature = "screenshot-scaling")]
+use png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::query::find_elements_nowait;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, ScriptArgs, WebDriverSession};
+
+/// Covers each element passed as an argument with a black box. The boxes are
+/// fixed to the viewport, which is what a window screenshot captures.
+const REDACT_SCRIPT: &str = r#"
+for (const elem of arguments) {
+    const r = elem.getBoundingClientRect();
+    if (r.width === 0 || r.height === 0) {
+        continue;
+    }
+    const box = document.createElement("div");
+    box.setAttribute("data-thirtyfour-redaction", "");
+    box.style.cssText = "position: fixed; margin: 0; padding: 0; border: 0; " +
+        "background: #000; pointer-events: none; z-index: 2147483647; " +
+        `left: ${r.left}px; top: ${r.top}px; width: ${r.width}px; height: ${r.height}px;`;
+    document.documentElement.appendChild(box);
+}
+"#;
+
+const UNREDACT_SCRIPT: &str = r#"
+document.querySelectorAll("[data-thirtyfour-redaction]").forEach((e) => e.remove());
+"#;
+
+/// Options for
+/// [WebDriver::screenshot_with_options()](trait.WebDriverCommands.html#method.screenshot_with_options).
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::{By, ScreenshotOptions};
+///
+/// let options = ScreenshotOptions::new().redact(&[By::Name("input1"), By::Id("pagetextinput")]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScreenshotOptions<'a> {
+    redact: Vec<By<'a>>,
+    device_pixel_ratio: Option<f64>,
+}
+
+impl<'a> ScreenshotOptions<'a> {
+    /// Create a new ScreenshotOptions that takes a plain screenshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Black out every element matching any of the selectors, for example
+    /// fields containing personal data.
+    pub fn redact(mut self, selectors: &[By<'a>]) -> Self {
+        self.redact.extend_from_slice(selectors);
+        self
+    }
+
+    /// Resize the screenshot to the specified number of image pixels per
+    /// CSS pixel, whatever the device pixel ratio of the display. Use 1.0
+    /// to get the same image on HiDPI machines as on CI, so that baseline
+    /// images can be compared. Only available with the
+    /// `screenshot-scaling` feature.
+    ///
+    /// Pixels are averaged when scaling down, so the result is close to,
+    /// but not exactly the same as, a screenshot taken on a 1x display.
+    #[cfg(feature = "screenshot-scaling")]
+    pub fn with_device_pixel_ratio(mut self, ratio: f64) -> Self {
+        self.device_pixel_ratio = Some(ratio);
+        self
+    }
+}
+
+/// A screenshot, with its size in both image pixels and CSS pixels. See
+/// [WebDriver::screenshot_with_details()](trait.WebDriverCommands.html#method.screenshot_with_details).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screenshot {
+    /// The image, as PNG bytes.
+    pub png: Vec<u8>,
+    /// The width of the image, in image pixels.
+    pub width: u32,
+    /// The height of the image, in image pixels.
+    pub height: u32,
+    /// The `window.devicePixelRatio` reported by the browser, i.e. the
+    /// number of physical pixels per CSS pixel on the display.
+    pub device_pixel_ratio: f64,
+    /// The number of image pixels per CSS pixel. This is the device pixel
+    /// ratio unless the screenshot was resized.
+    pub scale: f64,
+}
+
+impl Screenshot {
+    /// The width of the image, in CSS pixels.
+    pub fn logical_width(&self) -> u32 {
+        (f64::from(self.width) / self.scale).round() as u32
+    }
+
+    /// The height of the image, in CSS pixels.
+    pub fn logical_height(&self) -> u32 {
+        (f64::from(self.height) / self.scale).round() as u32
+    }
+}
+
+/// Read the width and height from the header of a PNG image.
+fn png_size(png: &[u8]) -> WebDriverResult<(u32, u32)> {
+    if png.len() < 24 || &png[1..4] != b"PNG" {
+        return Err(WebDriverError::UnknownResponse("screenshot is not a PNG image".to_string()));
+    }
+    let read = |i: usize| u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]);
+    Ok((read(16), read(20)))
+}
+
+/// The device pixel ratio reported by the browser.
+fn device_pixel_ratio(session: &WebDriverSession) -> WebDriverResult<f64> {
+    session.execute_script("return window.devicePixelRatio || 1;")?.convert()
+}
+
+/// For each output pixel along one axis, the source pixels it covers and
+/// the weight of each, averaging the source area it spans.
+#[cfg(feature = "screenshot-scaling")]
+fn axis_weights(from: usize, to: usize) -> Vec<Vec<(usize, f64)>> {
+    let step = from as f64 / to as f64;
+    (0..to)
+        .map(|i| {
+            let start = i as f64 * step;
+            let end = (start + step).min(from as f64);
+            let mut weights = Vec::new();
+            let mut j = start.floor() as usize;
+            while (j as f64) < end && j < from {
+                let overlap = (end.min(j as f64 + 1.0) - start.max(j as f64)).max(0.0);
+                if overlap > 0.0 {
+                    weights.push((j, overlap / (end - start)));
+                }
+                j += 1;
+            }
+            weights
+        })
+        .collect()
+}
+
+/// Resize a PNG image to the specified size.
+#[cfg(feature = "screenshot-scaling")]
+fn resize_png(png: &[u8], width: u32, height: u32) -> WebDriverResult<Vec<u8>> {
+    let failed =
+        |e: String| WebDriverError::UnknownResponse(format!("failed to resize screenshot: {}", e));
+    let mut decoder = Decoder::new(png);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| failed(e.to_string()))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| failed(e.to_string()))?;
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => return Err(failed("unexpected indexed PNG".to_string())),
+    };
+    let (from_w, from_h) = (info.width as usize, info.height as usize);
+    let (to_w, to_h) = (width.max(1) as usize, height.max(1) as usize);
+
+    // Resize each row, then each column.
+    let xs = axis_weights(from_w, to_w);
+    let mut rows = vec![0.0; to_w * from_h * channels];
+    for (y, row) in buf.chunks(info.line_size).take(from_h).enumerate() {
+        for (x, weights) in xs.iter().enumerate() {
+            for c in 0..channels {
+                rows[(y * to_w + x) * channels + c] =
+                    weights.iter().map(|(j, w)| f64::from(row[j * channels + c]) * w).sum();
+            }
+        }
+    }
+    let ys = axis_weights(from_h, to_h);
+    let mut pixels = vec![0; to_w * to_h * channels];
+    for (y, weights) in ys.iter().enumerate() {
+        for i in 0..to_w * channels {
+            let value: f64 = weights.iter().map(|(j, w)| rows[j * to_w * channels + i] * w).sum();
+            pixels[y * to_w * channels + i] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, to_w as u32, to_h as u32);
+    encoder.set_color(info.color_type);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| failed(e.to_string()))?;
+    writer.write_image_data(&pixels).map_err(|e| failed(e.to_string()))?;
+    writer.finish().map_err(|e| failed(e.to_string()))?;
+    Ok(out)
+}
+
+/// Take a screenshot of the current window as PNG bytes, applying the
+/// redactions.
+fn redacted_screenshot(
+    session: &WebDriverSession,
+    options: &ScreenshotOptions,
+) -> WebDriverResult<Vec<u8>> {
+    if options.redact.is_empty() {
+        return session.screenshot_as_png();
+    }
+
+    let mut args = ScriptArgs::new();
+    for by in &options.redact {
+        for elem in find_elements_nowait(session, by.clone())? {
+            args.push(elem)?;
+        }
+    }
+    let result = session
+        .execute_script_with_args(REDACT_SCRIPT, &args)
+        .and_then(|_| session.screenshot_as_png());
+    // Always try to remove the boxes, even if the screenshot failed.
+    let cleanup = session.execute_script(UNREDACT_SCRIPT);
+    let png = result?;
+    cleanup?;
+    Ok(png)
+}
+
+/// Resize a screenshot to the specified number of image pixels per CSS pixel.
+#[cfg(feature = "screenshot-scaling")]
+fn rescale(screenshot: Screenshot, ratio: f64) -> WebDriverResult<Screenshot> {
+    if (ratio - screenshot.scale).abs() < f64::EPSILON {
+        return Ok(screenshot);
+    }
+    let resize = |size: u32| (f64::from(size) * ratio / screenshot.scale).round() as u32;
+    let (width, height) = (resize(screenshot.width), resize(screenshot.height));
+    Ok(Screenshot {
+        png: resize_png(&screenshot.png, width, height)?,
+        width,
+        height,
+        scale: ratio,
+        ..screenshot
+    })
+}
+
+/// Take a screenshot of the current window, applying the options, along
+/// with its dimensions.
+pub(crate) fn screenshot_with_details(
+    session: &WebDriverSession,
+    options: &ScreenshotOptions,
+) -> WebDriverResult<Screenshot> {
+    let device_pixel_ratio = device_pixel_ratio(session)?;
+    let png = redacted_screenshot(session, options)?;
+    let (width, height) = png_size(&png)?;
+    let screenshot = Screenshot {
+        png,
+        width,
+        height,
+        device_pixel_ratio,
+        scale: device_pixel_ratio,
+    };
+    #[cfg(feature = "screenshot-scaling")]
+    let screenshot = match options.device_pixel_ratio {
+        Some(ratio) => rescale(screenshot, ratio)?,
+        None => screenshot,
+    };
+    Ok(screenshot)
+}
+
+/// Take a screenshot of the current window as PNG bytes, applying the options.
+pub(crate) fn screenshot_with_options(
+    session: &WebDriverSession,
+    options: &ScreenshotOptions,
+) -> WebDriverResult<Vec<u8>> {
+    if options.device_pixel_ratio.is_none() {
+        return redacted_screenshot(session, options);
+    }
+    Ok(screenshot_with_details(session, options)?.png)
+}
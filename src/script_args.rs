@@ -0,0 +1,48 @@
+use serde::Serialize;
+use serde_json::json;
+use thirtyfour::ScriptArgs;
+
+use crate::common::command::MAGIC_ELEMENTID;
+use crate::error::WebDriverResult;
+use crate::WebElement;
+
+/// Builder methods for [ScriptArgs](struct.ScriptArgs.html), so that the
+/// arguments for a script can be written as one expression.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// #
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://webappdemo")?;
+/// let elem = driver.find_element(By::Id("button1"))?;
+/// let args = ScriptArgs::new().with_element(&elem).with("TESTING")?.with(&[1, 2, 3])?;
+/// let text: String = driver.execute_script_typed(
+///     "arguments[0].innerHTML = arguments[1]; return arguments[0].innerText;",
+///     &args,
+/// )?;
+/// assert_eq!(text, "TESTING");
+/// #     Ok(())
+/// # }
+/// ```
+pub trait ScriptArgsExt: Sized {
+    /// Add any value that can be serialized to JSON.
+    fn with<T: Serialize>(self, value: T) -> WebDriverResult<Self>;
+
+    /// Add an element, which the script receives as a DOM element.
+    fn with_element(self, element: &WebElement) -> Self;
+}
+
+impl ScriptArgsExt for ScriptArgs {
+    fn with<T: Serialize>(mut self, value: T) -> WebDriverResult<Self> {
+        self.push(value)?;
+        Ok(self)
+    }
+
+    fn with_element(mut self, element: &WebElement) -> Self {
+        self.push_value(json!({ MAGIC_ELEMENTID: element.element_id.to_string() }));
+        self
+    }
+}
@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::error::WebDriverResult;
+use crate::query::{find_elements_nowait, wait_for_page_settled, ElementPoller};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, WebDriverSession};
+
+/// The condition that ends a call to
+/// [WebDriver::scroll_until()](trait.WebDriverCommands.html#method.scroll_until).
+#[derive(Debug, Clone)]
+pub enum ScrollUntil<'a> {
+    /// Stop once a scroll step produces no new elements matching the selector.
+    NoNewElements(By<'a>),
+    /// Stop once the page cannot be scrolled any further, even after waiting
+    /// for more content to load.
+    PageEnd,
+    /// Stop once at least the specified number of elements match the selector.
+    Count(By<'a>, usize),
+}
+
+impl<'a> ScrollUntil<'a> {
+    fn selector(&self) -> Option<&By<'a>> {
+        match self {
+            ScrollUntil::NoNewElements(by) | ScrollUntil::Count(by, _) => Some(by),
+            ScrollUntil::PageEnd => None,
+        }
+    }
+}
+
+/// Options for
+/// [WebDriver::scroll_until()](trait.WebDriverCommands.html#method.scroll_until).
+#[derive(Debug, Clone)]
+pub struct ScrollOptions {
+    /// The maximum number of scroll steps to perform.
+    pub max_steps: usize,
+    /// The page is considered settled once no DOM mutations and no new network
+    /// resources have been observed for this long.
+    pub quiet_period: Duration,
+    /// Controls how long to wait for the page to settle after each step.
+    /// If the page does not settle in time, scrolling continues anyway.
+    pub poller: ElementPoller,
+}
+
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self {
+            max_steps: 50,
+            quiet_period: Duration::from_millis(500),
+            poller: ElementPoller::TimeoutWithInterval(
+                Duration::from_secs(10),
+                Duration::from_millis(100),
+            ),
+        }
+    }
+}
+
+impl ScrollOptions {
+    /// Create new ScrollOptions with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of scroll steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Set the quiet period used to decide when the page has settled.
+    pub fn with_quiet_period(mut self, quiet_period: Duration) -> Self {
+        self.quiet_period = quiet_period;
+        self
+    }
+
+    /// Set the poller used while waiting for the page to settle.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+}
+
+/// The outcome of a single scroll step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollStep {
+    /// The number of matching elements that appeared during this step.
+    /// Always 0 for `ScrollUntil::PageEnd`.
+    pub new_elements: usize,
+    /// The total number of matching elements after this step.
+    pub total_elements: usize,
+    /// The document scroll height after this step, in CSS pixels.
+    pub scroll_height: i64,
+    /// Whether the page settled before the poller gave up.
+    pub settled: bool,
+}
+
+/// Summary of a call to
+/// [WebDriver::scroll_until()](trait.WebDriverCommands.html#method.scroll_until).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollReport {
+    /// One entry per scroll step performed.
+    pub steps: Vec<ScrollStep>,
+    /// True if the stop condition was met, false if `max_steps` was reached first.
+    pub completed: bool,
+}
+
+impl ScrollReport {
+    /// The total number of matching elements after the last step.
+    pub fn total_elements(&self) -> usize {
+        self.steps.last().map(|x| x.total_elements).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScrollPosition {
+    scroll_y: f64,
+    inner_height: f64,
+    scroll_height: f64,
+}
+
+impl ScrollPosition {
+    fn at_end(&self) -> bool {
+        self.scroll_y + self.inner_height >= self.scroll_height - 1.0
+    }
+}
+
+fn scroll_position(session: &WebDriverSession, to_bottom: bool) -> WebDriverResult<ScrollPosition> {
+    let script = format!(
+        r#"
+        if ({}) {{
+            window.scrollTo(0, document.documentElement.scrollHeight);
+        }}
+        return {{
+            scrollY: window.scrollY,
+            innerHeight: window.innerHeight,
+            scrollHeight: document.documentElement.scrollHeight
+        }};
+        "#,
+        to_bottom
+    );
+    session.execute_script(&script)?.convert()
+}
+
+pub(crate) fn scroll_until(
+    session: &WebDriverSession,
+    until: ScrollUntil,
+    options: ScrollOptions,
+) -> WebDriverResult<ScrollReport> {
+    let count = |by: &By| -> WebDriverResult<usize> {
+        Ok(find_elements_nowait(session, by.clone())?.len())
+    };
+
+    let mut total = match until.selector() {
+        Some(by) => count(by)?,
+        None => 0,
+    };
+    let mut report = ScrollReport {
+        steps: Vec::new(),
+        completed: false,
+    };
+
+    if let ScrollUntil::Count(_, n) = until {
+        if total >= n {
+            report.completed = true;
+            return Ok(report);
+        }
+    }
+
+    for _ in 0..options.max_steps {
+        scroll_position(session, true)?;
+        let settled = wait_for_page_settled(session, options.quiet_period, &options.poller)?;
+        let position = scroll_position(session, false)?;
+
+        let previous = total;
+        if let Some(by) = until.selector() {
+            total = count(by)?;
+        }
+        let new_elements = total.saturating_sub(previous);
+        report.steps.push(ScrollStep {
+            new_elements,
+            total_elements: total,
+            scroll_height: position.scroll_height as i64,
+            settled,
+        });
+
+        let done = match until {
+            ScrollUntil::NoNewElements(_) => new_elements == 0,
+            ScrollUntil::PageEnd => position.at_end(),
+            ScrollUntil::Count(_, n) => total >= n,
+        };
+        if done {
+            report.completed = true;
+            break;
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+use crate::error_info::error_info_mut;
+
+/// The text that replaces secrets in redacted output.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// A string that should never appear in logs or error messages, such as a
+/// password.
+///
+/// The Debug output is redacted, and there is deliberately no Display or
+/// Serialize implementation. Use
+/// [WebElement::send_secret()](struct.WebElement.html#method.send_secret)
+/// to type it into an element.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::SecretString;
+///
+/// let password = SecretString::new("hunter2");
+/// assert_eq!(format!("{:?}", password), "SecretString([REDACTED])");
+/// assert_eq!(password.expose_secret(), "hunter2");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Create a new SecretString.
+    pub fn new(secret: &str) -> Self {
+        Self(secret.to_string())
+    }
+
+    /// Get the secret value. Take care not to log it.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString({})", REDACTED)
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+/// Replace every occurrence of the secrets in the text.
+pub(crate) fn redact_text(text: &str, secrets: &[SecretString]) -> String {
+    secrets
+        .iter()
+        .filter(|s| !s.0.is_empty())
+        .fold(text.to_string(), |text, s| text.replace(&s.0, REDACTED))
+}
+
+fn redact_info(info: &mut WebDriverErrorInfo, secrets: &[SecretString]) {
+    info.error = redact_text(&info.error, secrets);
+    info.value.message = redact_text(&info.value.message, secrets);
+    if let Some(stacktrace) = &mut info.value.stacktrace {
+        *stacktrace = redact_text(stacktrace, secrets);
+    }
+    if let Some(data) = &mut info.value.data {
+        if let Ok(v) = serde_json::from_str(&redact_text(&data.to_string(), secrets)) {
+            *data = v;
+        }
+    }
+}
+
+/// Remove the secrets from the error message.
+pub(crate) fn redact_error(mut error: WebDriverError, secrets: &[SecretString]) -> WebDriverError {
+    match &mut error {
+        WebDriverError::UnknownResponse(s)
+        | WebDriverError::Timeout(s)
+        | WebDriverError::FatalError(s) => *s = redact_text(s, secrets),
+        WebDriverError::NotFound(a, b) => {
+            *a = redact_text(a, secrets);
+            *b = redact_text(b, secrets);
+        }
+        error => {
+            if let Some(info) = error_info_mut(error) {
+                redact_info(info, secrets);
+            }
+        }
+    }
+    error
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+/// Escape a string for use as an identifier in a CSS selector, such as an id
+/// or class name. This follows the same rules as the browser's `CSS.escape()`.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::selectors::css_escape;
+///
+/// assert_eq!(css_escape("user.name"), "user\\.name");
+/// assert_eq!(css_escape("1st"), "\\31 st");
+/// let selector = format!("#{}", css_escape("order:42"));
+/// assert_eq!(selector, "#order\\:42");
+/// ```
+pub fn css_escape(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut escaped = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let code = c as u32;
+        if c == '\0' {
+            escaped.push('\u{fffd}');
+        } else if (0x01..=0x1f).contains(&code)
+            || code == 0x7f
+            || (i == 0 && c.is_ascii_digit())
+            || (i == 1 && c.is_ascii_digit() && chars[0] == '-')
+        {
+            escaped.push_str(&format!("\\{:x} ", code));
+        } else if i == 0 && c == '-' && chars.len() == 1 {
+            escaped.push_str("\\-");
+        } else if code >= 0x80 || c == '-' || c == '_' || c.is_ascii_alphanumeric() {
+            escaped.push(c);
+        } else {
+            escaped.push('\\');
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Quote a string for use as a value in a CSS attribute selector.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::selectors::css_string;
+///
+/// let selector = format!("input[placeholder={}]", css_string(r#"Say "hi""#));
+/// assert_eq!(selector, r#"input[placeholder="Say \"hi\""]"#);
+/// ```
+pub fn css_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\a "),
+            '\r' => quoted.push_str("\\d "),
+            '\0' => quoted.push('\u{fffd}'),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote a string as an XPath string literal.
+///
+/// XPath 1.0 has no escape sequences, so strings containing both single and
+/// double quotes are built using `concat()`.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::selectors::xpath_literal;
+///
+/// assert_eq!(xpath_literal("Save"), "\"Save\"");
+/// assert_eq!(xpath_literal(r#"Say "hi""#), r#"'Say "hi"'"#);
+/// assert_eq!(xpath_literal(r#"It's "ok""#), r#"concat("It's ", '"', "ok", '"')"#);
+/// ```
+pub fn xpath_literal(value: &str) -> String {
+    if !value.contains('"') {
+        return format!("\"{}\"", value);
+    }
+    if !value.contains('\'') {
+        return format!("'{}'", value);
+    }
+
+    let mut parts = Vec::new();
+    for (i, part) in value.split('"').enumerate() {
+        if i > 0 {
+            parts.push("'\"'".to_string());
+        }
+        if !part.is_empty() {
+            parts.push(format!("\"{}\"", part));
+        }
+    }
+    format!("concat({})", parts.join(", "))
+}
+
+/// Start building an XPath expression that matches elements anywhere in the
+/// document.
+///
+/// All values are quoted with [xpath_literal()](fn.xpath_literal.html), so it
+/// is safe to build expressions from user data.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::selectors::xpath;
+///
+/// let xp = xpath().tag("button").with_text_contains("Save").build();
+/// assert_eq!(xp, r#"//button[contains(normalize-space(.), "Save")]"#);
+///
+/// let xp = xpath()
+///     .tag("form")
+///     .with_attribute("name", "login")
+///     .descendant("input")
+///     .with_class("error")
+///     .build();
+/// assert_eq!(
+///     xp,
+///     r#"//form[@name="login"]//input[contains(concat(" ", normalize-space(@class), " "), " error ")]"#
+/// );
+/// ```
+///
+/// Use the expression with `By::XPath`:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::selectors::xpath;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://webappdemo")?;
+/// let xp = xpath().tag("button").with_text("BUTTON 1").build();
+/// let elem = driver.find_element(By::XPath(&xp))?;
+/// #     Ok(())
+/// # }
+/// ```
+pub fn xpath() -> XPathBuilder {
+    XPathBuilder::new()
+}
+
+/// A fluent builder for XPath expressions. See [xpath()](fn.xpath.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct XPathBuilder {
+    steps: Vec<XPathStep>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct XPathStep {
+    axis: &'static str,
+    tag: String,
+    predicates: Vec<String>,
+}
+
+impl Default for XPathBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XPathBuilder {
+    /// Create a new builder matching any element anywhere in the document.
+    pub fn new() -> Self {
+        Self {
+            steps: vec![XPathStep {
+                axis: "//",
+                tag: "*".to_string(),
+                predicates: Vec::new(),
+            }],
+        }
+    }
+
+    fn step(&mut self) -> &mut XPathStep {
+        self.steps.last_mut().expect("builder always has a step")
+    }
+
+    fn push_step(mut self, axis: &'static str, tag: &str) -> Self {
+        self.steps.push(XPathStep {
+            axis,
+            tag: tag.to_string(),
+            predicates: Vec::new(),
+        });
+        self
+    }
+
+    /// Match only elements with the specified tag name.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.step().tag = tag.to_string();
+        self
+    }
+
+    /// Match only elements with the specified id.
+    pub fn with_id(self, id: &str) -> Self {
+        self.with_attribute("id", id)
+    }
+
+    /// Match only elements that have the specified class.
+    pub fn with_class(self, class: &str) -> Self {
+        let class = format!(" {} ", class);
+        self.with_predicate(&format!(
+            "contains(concat(\" \", normalize-space(@class), \" \"), {})",
+            xpath_literal(&class)
+        ))
+    }
+
+    /// Match only elements whose attribute has the specified value.
+    pub fn with_attribute(self, name: &str, value: &str) -> Self {
+        self.with_predicate(&format!("@{}={}", name, xpath_literal(value)))
+    }
+
+    /// Match only elements that have the specified attribute.
+    pub fn has_attribute(self, name: &str) -> Self {
+        self.with_predicate(&format!("@{}", name))
+    }
+
+    /// Match only elements whose text, with whitespace normalized, equals the
+    /// specified text.
+    pub fn with_text(self, text: &str) -> Self {
+        self.with_predicate(&format!("normalize-space(.)={}", xpath_literal(text)))
+    }
+
+    /// Match only elements whose text, with whitespace normalized, contains
+    /// the specified text.
+    pub fn with_text_contains(self, text: &str) -> Self {
+        self.with_predicate(&format!("contains(normalize-space(.), {})", xpath_literal(text)))
+    }
+
+    /// Add a raw XPath predicate. The predicate is not escaped.
+    pub fn with_predicate(mut self, predicate: &str) -> Self {
+        self.step().predicates.push(predicate.to_string());
+        self
+    }
+
+    /// Match only the nth matching element, starting from 1.
+    pub fn index(self, n: usize) -> Self {
+        self.with_predicate(&n.to_string())
+    }
+
+    /// Continue with descendants of the matched elements that have the
+    /// specified tag name. Use `"*"` for any tag.
+    pub fn descendant(self, tag: &str) -> Self {
+        self.push_step("//", tag)
+    }
+
+    /// Continue with direct children of the matched elements that have the
+    /// specified tag name. Use `"*"` for any tag.
+    pub fn child(self, tag: &str) -> Self {
+        self.push_step("/", tag)
+    }
+
+    /// Continue with ancestors of the matched elements that have the specified
+    /// tag name. Use `"*"` for any tag. Ancestors are numbered from the
+    /// nearest, so `.ancestor("div").index(1)` matches the closest `<div>`.
+    pub fn ancestor(self, tag: &str) -> Self {
+        self.push_step("/ancestor::", tag)
+    }
+
+    /// Build the XPath expression.
+    pub fn build(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for XPathBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            write!(f, "{}{}", step.axis, step.tag)?;
+            for predicate in &step.predicates {
+                write!(f, "[{}]", predicate)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xpath_literal_quotes() {
+        assert_eq!(xpath_literal(""), r#""""#);
+        assert_eq!(xpath_literal("plain text"), r#""plain text""#);
+        assert_eq!(xpath_literal("It's"), r#""It's""#);
+        assert_eq!(xpath_literal(r#"Say "hi""#), r#"'Say "hi"'"#);
+        assert_eq!(xpath_literal(r#"""#), r#"'"'"#);
+    }
+
+    #[test]
+    fn xpath_literal_mixed_quotes() {
+        assert_eq!(xpath_literal(r#"It's "ok""#), r#"concat("It's ", '"', "ok", '"')"#);
+        assert_eq!(xpath_literal(r#""It's""#), r#"concat('"', "It's", '"')"#);
+        assert_eq!(xpath_literal(r#"'""'"#), r#"concat("'", '"', '"', "'")"#);
+    }
+
+    #[test]
+    fn css_escape_identifiers() {
+        assert_eq!(css_escape(""), "");
+        assert_eq!(css_escape("main-menu_2"), "main-menu_2");
+        assert_eq!(css_escape("a b"), "a\\ b");
+        assert_eq!(css_escape("-"), "\\-");
+        assert_eq!(css_escape("-1"), "-\\31 ");
+        assert_eq!(css_escape("\0"), "\u{fffd}");
+        assert_eq!(css_escape("tab\there"), "tab\\9 here");
+        assert_eq!(css_escape("café"), "café");
+    }
+
+    #[test]
+    fn css_string_quotes() {
+        assert_eq!(css_string(""), r#""""#);
+        assert_eq!(css_string("It's"), r#""It's""#);
+        assert_eq!(css_string(r#"a\b"#), r#""a\\b""#);
+        assert_eq!(css_string("line\nbreak"), r#""line\a break""#);
+    }
+
+    #[test]
+    fn builder_quotes_values() {
+        let xp = xpath().tag("a").with_text(r#"It's "here""#).build();
+        assert_eq!(xp, r#"//a[normalize-space(.)=concat("It's ", '"', "here", '"')]"#);
+        let xp = xpath().with_id("main").child("li").index(2).ancestor("ul").build();
+        assert_eq!(xp, r#"//*[@id="main"]/li[2]/ancestor::ul"#);
+    }
+}
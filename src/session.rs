@@ -1,17 +1,58 @@
+use crate::batch::{ConnectionFactory, MAX_BATCH_CONNECTIONS};
+use crate::common::command::Command;
 use crate::common::config::WebDriverConfig;
+use crate::crash::detect_crash;
 use crate::error::{WebDriverError, WebDriverResult};
-use crate::http::connection_sync::WebDriverHttpClientSync;
+#[cfg(feature = "fixture-server")]
+use crate::fixture_server::FixtureServer;
+use crate::http::connection_sync::{HttpRetryPolicy, WebDriverHttpClientSync};
+use crate::legacy::{execute_json_wire, normalize_response};
+use crate::listener::{notify, redacted_request, WebDriverEventListener};
+use crate::locators::Locators;
+use crate::metadata::SessionMetadata;
+use crate::narrator::Narrator;
+use crate::politeness::Politeness;
+use crate::query::ReadyStrategy;
+use crate::secret::{redact_error, redact_text, SecretString};
+use crate::transaction::TransactionReporter;
+use crate::viewport::Device;
 use crate::SessionId;
 use crate::WebDriverCommands;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use thirtyfour::common::command::FormatRequestData;
+use thirtyfour::{RequestData, RequestMethod};
 
 #[derive(Debug)]
 pub struct WebDriverSession {
     session_id: SessionId,
     conn: Arc<Mutex<dyn WebDriverHttpClientSync>>,
     config: WebDriverConfig,
+    capabilities: serde_json::Value,
+    politeness: Option<Politeness>,
+    ready_strategy: Option<Arc<dyn ReadyStrategy>>,
+    secrets: Mutex<Vec<SecretString>>,
+    metadata: Mutex<SessionMetadata>,
+    lenient_responses: bool,
+    json_wire_protocol: bool,
+    locator_suggestions: bool,
+    speech_stubs: Option<String>,
+    narrator: Option<Narrator>,
+    transaction_reporter: Option<Arc<dyn TransactionReporter>>,
+    locators: Option<Arc<Locators>>,
+    #[cfg(feature = "fixture-server")]
+    fixture_server: Option<Arc<FixtureServer>>,
+    commands: AtomicU64,
+    navigations: AtomicU64,
+    last_url: Mutex<Option<String>>,
+    current_device: Mutex<Option<Device>>,
+    connection_factory: Option<ConnectionFactory>,
+    spare_connections: Mutex<Vec<Box<dyn WebDriverHttpClientSync>>>,
+    request_timeout: Option<Duration>,
+    retry_policy: Option<HttpRetryPolicy>,
+    interaction_timeout: Option<Duration>,
+    listeners: Vec<Box<dyn WebDriverEventListener>>,
 }
 
 impl WebDriverSession {
@@ -20,6 +61,30 @@ impl WebDriverSession {
             session_id,
             conn,
             config: WebDriverConfig::new(),
+            capabilities: serde_json::Value::Null,
+            politeness: None,
+            ready_strategy: None,
+            secrets: Mutex::new(Vec::new()),
+            metadata: Mutex::new(SessionMetadata::new()),
+            lenient_responses: false,
+            json_wire_protocol: false,
+            locator_suggestions: false,
+            speech_stubs: None,
+            narrator: None,
+            transaction_reporter: None,
+            locators: None,
+            #[cfg(feature = "fixture-server")]
+            fixture_server: None,
+            commands: AtomicU64::new(0),
+            navigations: AtomicU64::new(0),
+            last_url: Mutex::new(None),
+            current_device: Mutex::new(None),
+            connection_factory: None,
+            spare_connections: Mutex::new(Vec::new()),
+            request_timeout: None,
+            retry_policy: None,
+            interaction_timeout: None,
+            listeners: Vec::new(),
         }
     }
 
@@ -35,24 +100,392 @@ impl WebDriverSession {
         &mut self.config
     }
 
+    /// The capabilities returned by the WebDriver server when the session was created.
+    pub fn capabilities(&self) -> &serde_json::Value {
+        &self.capabilities
+    }
+
+    pub(crate) fn set_capabilities(&mut self, capabilities: serde_json::Value) {
+        self.capabilities = capabilities;
+    }
+
+    pub fn politeness(&self) -> Option<&Politeness> {
+        self.politeness.as_ref()
+    }
+
+    pub fn set_politeness(&mut self, politeness: Option<Politeness>) {
+        self.politeness = politeness;
+    }
+
+    pub fn ready_strategy(&self) -> Option<&dyn ReadyStrategy> {
+        self.ready_strategy.as_deref()
+    }
+
+    pub fn set_ready_strategy(&mut self, strategy: Option<Arc<dyn ReadyStrategy>>) {
+        self.ready_strategy = strategy;
+    }
+
+    pub fn lenient_responses(&self) -> bool {
+        self.lenient_responses
+    }
+
+    pub fn set_lenient_responses(&mut self, lenient: bool) {
+        self.lenient_responses = lenient;
+    }
+
+    pub fn json_wire_protocol(&self) -> bool {
+        self.json_wire_protocol
+    }
+
+    pub fn set_json_wire_protocol(&mut self, enabled: bool) {
+        self.json_wire_protocol = enabled;
+    }
+
+    pub fn locator_suggestions(&self) -> bool {
+        self.locator_suggestions
+    }
+
+    pub fn set_locator_suggestions(&mut self, enabled: bool) {
+        self.locator_suggestions = enabled;
+    }
+
+    pub fn speech_stubs(&self) -> Option<&str> {
+        self.speech_stubs.as_deref()
+    }
+
+    pub fn set_speech_stubs(&mut self, identifier: Option<String>) {
+        self.speech_stubs = identifier;
+    }
+
+    pub fn retry_policy(&self) -> Option<&HttpRetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    pub fn set_retry_policy(&mut self, policy: Option<HttpRetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    pub fn interaction_timeout(&self) -> Option<Duration> {
+        self.interaction_timeout
+    }
+
+    pub fn set_interaction_timeout(&mut self, timeout: Option<Duration>) {
+        self.interaction_timeout = timeout;
+    }
+
+    pub(crate) fn listeners(&self) -> &[Box<dyn WebDriverEventListener>] {
+        &self.listeners
+    }
+
+    pub(crate) fn add_listener(&mut self, listener: Box<dyn WebDriverEventListener>) {
+        self.listeners.push(listener);
+    }
+
+    pub(crate) fn clear_listeners(&mut self) {
+        self.listeners.clear();
+    }
+
+    pub fn narrator(&self) -> Option<&Narrator> {
+        self.narrator.as_ref()
+    }
+
+    pub fn set_narrator(&mut self, narrator: Option<Narrator>) {
+        self.narrator = narrator;
+    }
+
+    pub fn transaction_reporter(&self) -> Option<&dyn TransactionReporter> {
+        self.transaction_reporter.as_deref()
+    }
+
+    pub fn set_transaction_reporter(&mut self, reporter: Option<Arc<dyn TransactionReporter>>) {
+        self.transaction_reporter = reporter;
+    }
+
+    pub fn locators(&self) -> Option<&Locators> {
+        self.locators.as_deref()
+    }
+
+    pub fn set_locators(&mut self, locators: Option<Arc<Locators>>) {
+        self.locators = locators;
+    }
+
+    #[cfg(feature = "fixture-server")]
+    pub fn fixture_server(&self) -> Option<&FixtureServer> {
+        self.fixture_server.as_deref()
+    }
+
+    #[cfg(feature = "fixture-server")]
+    pub fn set_fixture_server(&mut self, server: Option<Arc<FixtureServer>>) {
+        self.fixture_server = server;
+    }
+
+    /// The tags set with
+    /// [set_session_metadata()](trait.WebDriverCommands.html#method.set_session_metadata).
+    pub fn metadata(&self) -> SessionMetadata {
+        self.metadata.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Update the tags, returning the new tags.
+    pub(crate) fn update_metadata<F>(&self, f: F) -> SessionMetadata
+    where
+        F: FnOnce(&mut SessionMetadata),
+    {
+        let mut metadata = self.metadata.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut metadata);
+        metadata.clone()
+    }
+
+    /// The number of commands sent in this session so far.
+    pub fn command_count(&self) -> u64 {
+        self.commands.load(Ordering::Relaxed)
+    }
+
+    /// The number of navigations made with `get()`, `back()`, `forward()`
+    /// and `refresh()` in this session so far.
+    pub(crate) fn navigation_count(&self) -> u64 {
+        self.navigations.load(Ordering::Relaxed)
+    }
+
+    /// The URL most recently navigated to with `get()`, or returned by
+    /// `current_url()`.
+    pub(crate) fn last_url(&self) -> Option<String> {
+        self.last_url.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The device most recently set with `set_viewport()`.
+    pub(crate) fn current_device(&self) -> Option<Device> {
+        self.current_device.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn set_current_device(&self, device: Option<Device>) {
+        *self.current_device.lock().unwrap_or_else(|e| e.into_inner()) = device;
+    }
+
+    /// Remember a secret, so that it is redacted from errors and from the
+    /// output of [redact()](#method.redact).
+    pub(crate) fn add_secret(&self, secret: &SecretString) {
+        let mut secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+        if !secrets.contains(secret) {
+            secrets.push(secret.clone());
+        }
+    }
+
+    /// Replace any secrets sent with
+    /// [WebElement::send_secret()](struct.WebElement.html#method.send_secret)
+    /// in the specified text. Use this before logging page content or
+    /// anything else that might contain a secret.
+    pub fn redact(&self, text: &str) -> String {
+        redact_text(text, &self.secrets.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
     pub fn execute(
         &self,
         request: Box<dyn FormatRequestData + Send + Sync>,
+    ) -> WebDriverResult<serde_json::Value> {
+        let request = request.format_request(&self.session_id);
+        self.observe(request, |request| {
+            let conn =
+                self.conn.lock().map_err(|e| WebDriverError::UnknownResponse(e.to_string()))?;
+            self.execute_on(&*conn, request)
+        })
+    }
+
+    /// Execute a request without calling the listeners or detecting a page
+    /// crash, for commands sent while handling another command.
+    pub(crate) fn execute_quietly(
+        &self,
+        request: RequestData,
     ) -> WebDriverResult<serde_json::Value> {
         let conn = self.conn.lock().map_err(|e| WebDriverError::UnknownResponse(e.to_string()))?;
-        conn.execute(request.format_request(&self.session_id))
+        self.execute_on(&*conn, request)
+    }
+
+    /// Send the request with the specified function, calling the listeners
+    /// before and after. The function must not hold the connection lock
+    /// when it returns, so that listeners can send commands, and so that
+    /// the details of a page crash can be fetched.
+    fn observe<F>(&self, request: RequestData, send: F) -> WebDriverResult<serde_json::Value>
+    where
+        F: FnOnce(RequestData) -> WebDriverResult<serde_json::Value>,
+    {
+        let send = |request| send(request).map_err(|e| detect_crash(self, e));
+        #[cfg(feature = "tracing")]
+        let send = |request| crate::telemetry::traced(self, request, send);
+        if self.listeners.is_empty() {
+            return send(request);
+        }
+        let observed = redacted_request(self, &request);
+        notify(self, |l| l.before_command(self, &observed));
+        let start = Instant::now();
+        let result = send(request);
+        let duration = start.elapsed();
+        notify(self, |l| {
+            l.after_command(self, &observed, &result, duration);
+            if let Err(e) = &result {
+                l.on_error(self, &observed, e);
+            }
+        });
+        result
+    }
+
+    /// Execute a request on the specified connection, which is either the
+    /// session's own connection or a spare one.
+    fn execute_on(
+        &self,
+        conn: &dyn WebDriverHttpClientSync,
+        request: RequestData,
+    ) -> WebDriverResult<serde_json::Value> {
+        self.commands.fetch_add(1, Ordering::Relaxed);
+        if is_navigation(&request) {
+            self.navigations.fetch_add(1, Ordering::Relaxed);
+        }
+        let narration = self.narrator.as_ref().map(|n| n.narrate(conn, &request));
+        let is_url = request.url.ends_with("/url");
+        if is_url && matches!(request.method, RequestMethod::Post) {
+            let url = request.body.as_ref().and_then(|b| b["url"].as_str());
+            self.set_last_url(url);
+        }
+        let send = |request| {
+            if self.json_wire_protocol {
+                execute_json_wire(conn, request)
+            } else if self.lenient_responses {
+                conn.execute(request).and_then(normalize_response)
+            } else {
+                conn.execute(request)
+            }
+        };
+        let result = match &self.retry_policy {
+            Some(policy) => policy.execute(request, send),
+            None => send(request),
+        };
+        let result = result.map_err(|e| {
+            let secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+            if secrets.is_empty() {
+                e
+            } else {
+                redact_error(e, &secrets)
+            }
+        });
+        if let (true, Ok(v)) = (is_url, &result) {
+            self.set_last_url(v["value"].as_str());
+        }
+        if let (Some(narrator), Some(narration)) = (&self.narrator, narration) {
+            narrator.record(narration, &result, |text| self.redact(text));
+        }
+        result
+    }
+
+    fn set_last_url(&self, url: Option<&str>) {
+        if let Some(url) = url {
+            *self.last_url.lock().unwrap_or_else(|e| e.into_inner()) = Some(url.to_string());
+        }
+    }
+
+    /// The time in the `Date` header of the most recent response.
+    pub(crate) fn last_response_date(&self) -> Option<SystemTime> {
+        self.conn.lock().ok()?.last_response_date()
+    }
+
+    #[cfg(feature = "fault-injection")]
+    pub(crate) fn fault_injector(&self) -> Option<crate::http::fault_sync::FaultInjector> {
+        self.conn.lock().ok()?.fault_injector()
     }
 
     pub fn set_request_timeout(&mut self, timeout: Duration) -> WebDriverResult<()> {
         let mut conn =
             self.conn.lock().map_err(|e| WebDriverError::UnknownResponse(e.to_string()))?;
         conn.set_request_timeout(timeout);
+        self.request_timeout = Some(timeout);
+        let mut spares = self.spare_connections.lock().unwrap_or_else(|e| e.into_inner());
+        for spare in spares.iter_mut() {
+            spare.set_request_timeout(timeout);
+        }
         Ok(())
     }
+
+    /// Set how extra connections are created, for batches.
+    pub(crate) fn set_connection_factory(&mut self, factory: Option<ConnectionFactory>) {
+        self.connection_factory = factory;
+    }
+
+    /// Take a spare connection, creating one if there are none.
+    fn take_spare_connection(&self) -> Option<Box<dyn WebDriverHttpClientSync>> {
+        let spare = self.spare_connections.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        spare.or_else(|| {
+            let mut conn = self.connection_factory.as_ref()?.create().ok()?;
+            if let Some(timeout) = self.request_timeout {
+                conn.set_request_timeout(timeout);
+            }
+            Some(conn)
+        })
+    }
+
+    /// Execute the commands concurrently over several connections, returning
+    /// the results in the same order. Without a connection factory, the
+    /// commands are executed one at a time.
+    pub(crate) fn execute_batch(
+        &self,
+        commands: Vec<Command>,
+    ) -> Vec<WebDriverResult<serde_json::Value>> {
+        let count = commands.len();
+        let workers = count.min(MAX_BATCH_CONNECTIONS);
+        if workers <= 1 || self.connection_factory.is_none() {
+            return commands.into_iter().map(|c| self.execute(Box::new(c))).collect();
+        }
+
+        let requests: Vec<RequestData> =
+            commands.iter().map(|c| c.format_request(&self.session_id)).collect();
+        let queue = Mutex::new(requests.into_iter().enumerate());
+        let results = Mutex::new((0..count).map(|_| None).collect::<Vec<_>>());
+        std::thread::scope(|scope| {
+            for worker in 0..workers {
+                let (queue, results) = (&queue, &results);
+                scope.spawn(move || {
+                    // The first worker uses the session's own connection.
+                    let spare = if worker == 0 {
+                        None
+                    } else {
+                        self.take_spare_connection()
+                    };
+                    loop {
+                        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                        let (index, request) = match next {
+                            Some(x) => x,
+                            None => break,
+                        };
+                        let result = self.observe(request, |request| match &spare {
+                            Some(conn) => self.execute_on(conn.as_ref(), request),
+                            None => match self.conn.lock() {
+                                Ok(conn) => self.execute_on(&*conn, request),
+                                Err(e) => Err(WebDriverError::UnknownResponse(e.to_string())),
+                            },
+                        });
+                        results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+                    }
+                    if let Some(conn) = spare {
+                        self.spare_connections.lock().unwrap_or_else(|e| e.into_inner()).push(conn);
+                    }
+                });
+            }
+        });
+        results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .map(|r| r.expect("every batched command is executed"))
+            .collect()
+    }
 }
 
 impl WebDriverCommands for WebDriverSession {
     fn session(&self) -> &WebDriverSession {
-        &self
+        self
     }
 }
+
+/// Returns true if the request navigates the current browsing context.
+fn is_navigation(request: &RequestData) -> bool {
+    let navigation = ["/url", "/back", "/forward", "/refresh"];
+    matches!(request.method, RequestMethod::Post)
+        && navigation.iter().any(|path| request.url.ends_with(path))
+}
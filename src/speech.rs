@@ -0,0 +1,219 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession};
+
+/// Text spoken by the page with `speechSynthesis.speak()`, as recorded by
+/// the speech stubs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpokenUtterance {
+    /// The text that was spoken.
+    pub text: String,
+    /// The language of the utterance, if set, e.g. `en-GB`.
+    #[serde(default)]
+    pub lang: String,
+    /// The name of the voice, if one was chosen.
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// The speaking rate, where 1.0 is normal.
+    pub rate: f64,
+    /// The pitch, where 1.0 is normal.
+    pub pitch: f64,
+    /// The volume, from 0.0 to 1.0.
+    pub volume: f64,
+}
+
+/// Replaces `speechSynthesis` and `SpeechRecognition` with stubs. Spoken
+/// utterances are recorded, and recognition results are delivered to the
+/// listening recognizers by `__thirtyfourSpeech.recognize()`.
+const SPEECH_STUBS_SCRIPT: &str = r#"
+(() => {
+    if (window.__thirtyfourSpeech) return;
+    const fire = (target, type, props) => {
+        const event = new Event(type);
+        for (const [name, value] of Object.entries(props || {})) {
+            Object.defineProperty(event, name, { value });
+        }
+        target.dispatchEvent(event);
+        const handler = target["on" + type];
+        if (typeof handler === "function") handler.call(target, event);
+    };
+    const later = (f) => setTimeout(f, 0);
+    const speech = { spoken: [], listening: new Set() };
+
+    class Utterance extends EventTarget {
+        constructor(text) {
+            super();
+            this.text = text === undefined ? "" : String(text);
+            this.lang = "";
+            this.voice = null;
+            this.rate = 1;
+            this.pitch = 1;
+            this.volume = 1;
+        }
+    }
+    const voice = {
+        name: "Stub Voice",
+        lang: "en-US",
+        voiceURI: "stub",
+        default: true,
+        localService: true,
+    };
+    const synthesis = new EventTarget();
+    Object.assign(synthesis, {
+        speaking: false,
+        pending: false,
+        paused: false,
+        onvoiceschanged: null,
+        getVoices: () => [voice],
+        speak(utterance) {
+            speech.spoken.push({
+                text: String(utterance.text),
+                lang: String(utterance.lang || ""),
+                voice: utterance.voice ? String(utterance.voice.name) : null,
+                rate: Number(utterance.rate),
+                pitch: Number(utterance.pitch),
+                volume: Number(utterance.volume),
+            });
+            synthesis.speaking = true;
+            later(() => {
+                fire(utterance, "start");
+                fire(utterance, "end");
+                synthesis.speaking = false;
+            });
+        },
+        cancel() { synthesis.speaking = false; },
+        pause() { synthesis.paused = true; },
+        resume() { synthesis.paused = false; },
+    });
+
+    class Recognition extends EventTarget {
+        constructor() {
+            super();
+            this.continuous = false;
+            this.interimResults = false;
+            this.lang = "";
+            this.maxAlternatives = 1;
+            this.results = [];
+        }
+        start() {
+            if (speech.listening.has(this)) {
+                throw new DOMException("recognition has already started", "InvalidStateError");
+            }
+            speech.listening.add(this);
+            this.results = [];
+            later(() => { fire(this, "start"); fire(this, "audiostart"); });
+        }
+        stop() { this._end(); }
+        abort() { this._end(); }
+        _end() {
+            if (!speech.listening.delete(this)) return;
+            later(() => { fire(this, "audioend"); fire(this, "end"); });
+        }
+        _deliver(transcript, confidence, isFinal) {
+            if (!isFinal && !this.interimResults) return;
+            const alternative = { transcript, confidence };
+            const result = Object.assign([alternative], { isFinal, item: (i) => result[i] });
+            const index = this.results.length;
+            if (isFinal) this.results.push(result);
+            const results = Object.assign(this.results.slice(0, index).concat([result]), {
+                item: (i) => results[i],
+            });
+            fire(this, "result", { resultIndex: index, results });
+            if (isFinal && !this.continuous) this._end();
+        }
+    }
+
+    speech.recognize = (transcript, confidence, isFinal) => {
+        const listening = Array.from(speech.listening);
+        for (const recognition of listening) recognition._deliver(transcript, confidence, isFinal);
+        return listening.length;
+    };
+    window.__thirtyfourSpeech = speech;
+    const define = (name, value) =>
+        Object.defineProperty(window, name, { value, configurable: true, writable: true });
+    define("speechSynthesis", synthesis);
+    define("SpeechSynthesisUtterance", Utterance);
+    define("SpeechRecognition", Recognition);
+    define("webkitSpeechRecognition", Recognition);
+})();
+"#;
+
+const SPOKEN_SCRIPT: &str = r#"
+    return window.__thirtyfourSpeech ? window.__thirtyfourSpeech.spoken : null;
+"#;
+
+const RECOGNIZE_SCRIPT: &str = r#"
+    const [transcript, confidence, isFinal] = arguments;
+    return window.__thirtyfourSpeech
+        ? window.__thirtyfourSpeech.recognize(transcript, confidence, isFinal)
+        : null;
+"#;
+
+fn not_installed() -> WebDriverError {
+    WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+        "the speech stubs are not installed in the current page",
+    ))
+}
+
+pub(crate) fn install_speech_stubs(session: &WebDriverSession) -> WebDriverResult<()> {
+    session.execute_script(SPEECH_STUBS_SCRIPT)?;
+    Ok(())
+}
+
+/// Install the stubs in the current page, and register them to be installed
+/// in every page loaded afterwards. Returns the identifier of the registered
+/// script.
+pub(crate) fn register_speech_stubs(session: &WebDriverSession) -> WebDriverResult<String> {
+    let v = ChromeDevTools::new(session).execute_cdp_with_params(
+        "Page.addScriptToEvaluateOnNewDocument",
+        json!({ "source": SPEECH_STUBS_SCRIPT }),
+    )?;
+    install_speech_stubs(session)?;
+    Ok(v["identifier"].as_str().unwrap_or_default().to_string())
+}
+
+/// Stop installing the stubs in pages loaded afterwards.
+pub(crate) fn unregister_speech_stubs(
+    session: &WebDriverSession,
+    identifier: &str,
+) -> WebDriverResult<()> {
+    ChromeDevTools::new(session).execute_cdp_with_params(
+        "Page.removeScriptToEvaluateOnNewDocument",
+        json!({ "identifier": identifier }),
+    )?;
+    Ok(())
+}
+
+pub(crate) fn spoken_utterances(
+    session: &WebDriverSession,
+) -> WebDriverResult<Vec<SpokenUtterance>> {
+    let spoken: Option<Vec<SpokenUtterance>> = session.execute_script(SPOKEN_SCRIPT)?.convert()?;
+    spoken.ok_or_else(not_installed)
+}
+
+pub(crate) fn recognize_speech(
+    session: &WebDriverSession,
+    transcript: &str,
+    confidence: f64,
+    is_final: bool,
+) -> WebDriverResult<()> {
+    let mut args = ScriptArgs::new();
+    args.push(transcript)?;
+    args.push(confidence)?;
+    args.push(is_final)?;
+    let listening: Option<usize> =
+        session.execute_script_with_args(RECOGNIZE_SCRIPT, &args)?.convert()?;
+    match listening {
+        None => Err(not_installed()),
+        Some(0) => Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(&format!(
+            "no speech recognition is listening for {:?}",
+            transcript
+        )))),
+        Some(_) => Ok(()),
+    }
+}
@@ -0,0 +1,159 @@
+use serde_json::json;
+use thirtyfour::ChromeCapabilities;
+
+use crate::error::WebDriverResult;
+use crate::extensions::chrome::{Brand, ChromeDevTools, UserAgentMetadata};
+use crate::WebDriverSession;
+
+/// The script injected into every new document. `__LANGUAGES__` is replaced
+/// with the configured languages.
+const STEALTH_SCRIPT: &str = r#"
+(() => {
+    const define = (obj, prop, value) => {
+        try {
+            Object.defineProperty(obj, prop, { get: () => value, configurable: true });
+        } catch (e) {}
+    };
+    define(Navigator.prototype, "webdriver", undefined);
+    define(Navigator.prototype, "languages", Object.freeze(__LANGUAGES__));
+    if (navigator.plugins.length === 0) {
+        const names = ["PDF Viewer", "Chrome PDF Viewer", "Chromium PDF Viewer"];
+        const plugins = names.map((name) => ({
+            name: name,
+            filename: "internal-pdf-viewer",
+            description: "Portable Document Format",
+            length: 0,
+        }));
+        plugins.item = (i) => plugins[i] || null;
+        plugins.namedItem = (name) => plugins.find((p) => p.name === name) || null;
+        plugins.refresh = () => {};
+        define(Navigator.prototype, "plugins", plugins);
+    }
+    if (!window.chrome) {
+        define(window, "chrome", { runtime: {} });
+    }
+})();
+"#;
+
+/// Mitigations for naive automation detection in Chromium-based browsers.
+/// Only available with the `stealth` feature.
+///
+/// This applies the well-known mitigations: the `enable-automation` switch
+/// and the `AutomationControlled` blink feature are disabled,
+/// `navigator.webdriver` is hidden, `navigator.languages` and
+/// `navigator.plugins` are given realistic values, and the user agent and
+/// its client hints no longer mention headless Chrome.
+///
+/// These mitigations only defeat simple checks. Only use them on sites you
+/// are authorized to automate.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::stealth::Stealth;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// let stealth = Stealth::new().with_languages(&["en-GB", "en"]);
+/// let mut caps = DesiredCapabilities::chrome();
+/// stealth.apply_capabilities(&mut caps)?;
+/// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// stealth.apply(driver.session())?;
+///
+/// driver.get("http://webappdemo")?;
+/// let webdriver: Option<bool> = driver.execute_script("return navigator.webdriver;")?.convert()?;
+/// assert_eq!(webdriver, None);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stealth {
+    languages: Vec<String>,
+    platform: Option<String>,
+}
+
+impl Default for Stealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stealth {
+    /// Create a new Stealth configuration using `en-US` as the language.
+    pub fn new() -> Self {
+        Self {
+            languages: vec!["en-US".to_string(), "en".to_string()],
+            platform: None,
+        }
+    }
+
+    /// Set the languages reported by `navigator.languages` and the
+    /// `Accept-Language` header, in order of preference.
+    pub fn with_languages(mut self, languages: &[&str]) -> Self {
+        self.languages = languages.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Set the platform reported in the user agent client hints, e.g.
+    /// `Windows`. By default it is derived from the user agent.
+    pub fn with_platform(mut self, platform: &str) -> Self {
+        self.platform = Some(platform.to_string());
+        self
+    }
+
+    /// Disable the automation switches. This must be done before the session
+    /// is started.
+    pub fn apply_capabilities(&self, capabilities: &mut ChromeCapabilities) -> WebDriverResult<()> {
+        capabilities.add_chrome_arg("--disable-blink-features=AutomationControlled")?;
+        capabilities.add_chrome_arg(&format!("--lang={}", self.languages.join(",")))?;
+        capabilities.add_chrome_option("excludeSwitches", ["enable-automation"])?;
+        capabilities.add_chrome_option("useAutomationExtension", false)
+    }
+
+    /// Apply the script and user agent mitigations to the session. They take
+    /// effect from the next navigation.
+    pub fn apply(&self, session: &WebDriverSession) -> WebDriverResult<()> {
+        let dev_tools = ChromeDevTools::new(session);
+        let script = STEALTH_SCRIPT.replace("__LANGUAGES__", &json!(self.languages).to_string());
+        dev_tools.execute_cdp_with_params(
+            "Page.addScriptToEvaluateOnNewDocument",
+            json!({ "source": script }),
+        )?;
+
+        let version = dev_tools.browser_version()?;
+        let user_agent = version.user_agent.replace("HeadlessChrome", "Chrome");
+        let metadata = self.user_agent_metadata(&user_agent, version.version());
+        dev_tools.override_user_agent(&user_agent, Some(&self.languages.join(",")), &metadata)
+    }
+
+    fn user_agent_metadata(&self, user_agent: &str, full_version: &str) -> UserAgentMetadata {
+        let major = full_version.split('.').next().unwrap_or_default();
+        let platform = self.platform.clone().unwrap_or_else(|| {
+            if user_agent.contains("Windows") {
+                "Windows"
+            } else if user_agent.contains("Mac OS X") {
+                "macOS"
+            } else if user_agent.contains("Android") {
+                "Android"
+            } else {
+                "Linux"
+            }
+            .to_string()
+        });
+        UserAgentMetadata {
+            brands: vec![
+                Brand::new("Not_A Brand", "8"),
+                Brand::new("Chromium", major),
+                Brand::new("Google Chrome", major),
+            ],
+            full_version_list: Some(vec![
+                Brand::new("Not_A Brand", "8.0.0.0"),
+                Brand::new("Chromium", full_version),
+                Brand::new("Google Chrome", full_version),
+            ]),
+            mobile: platform == "Android",
+            platform,
+            architecture: "x86".to_string(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::WebDriverResult;
+use crate::page_info::assertion_failed;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebElement};
+
+/// Returns every computed style property of the element, including custom
+/// properties where the browser lists them.
+const COMPUTED_STYLE_SCRIPT: &str = r#"
+    const style = getComputedStyle(arguments[0]);
+    const result = {};
+    for (let i = 0; i < style.length; i++) {
+        const name = style[i];
+        result[name] = style.getPropertyValue(name).trim();
+    }
+    return result;
+"#;
+
+/// Returns the computed value of a property, together with the expected
+/// value as the browser computes it on a hidden probe element next to the
+/// element. Both are then in the same form, e.g. colors as `rgb()` and
+/// lengths in pixels.
+const STYLE_VALUES_SCRIPT: &str = r#"
+    const [elem, name, expected] = arguments;
+    const actual = getComputedStyle(elem).getPropertyValue(name).trim();
+    const parent = elem.parentElement || document.body;
+    const probe = document.createElement("div");
+    // Lengths in em are relative to the element's own font size.
+    if (name !== "font-size") {
+        probe.style.setProperty("font-size", getComputedStyle(elem).fontSize, "important");
+    }
+    probe.style.setProperty("position", "absolute", "important");
+    probe.style.setProperty("visibility", "hidden", "important");
+    probe.style.setProperty("pointer-events", "none", "important");
+    probe.style.setProperty(name, expected, "important");
+    parent.appendChild(probe);
+    const normalized = probe.style.getPropertyValue(name)
+        ? getComputedStyle(probe).getPropertyValue(name).trim()
+        : null;
+    probe.remove();
+    return { actual, expected: normalized };
+"#;
+
+#[derive(Debug, Deserialize)]
+struct StyleValues {
+    actual: String,
+    expected: Option<String>,
+}
+
+pub(crate) fn computed_style(elem: &WebElement) -> WebDriverResult<BTreeMap<String, String>> {
+    let mut args = ScriptArgs::new();
+    args.push(elem.clone())?;
+    elem.session().execute_script_with_args(COMPUTED_STYLE_SCRIPT, &args)?.convert()
+}
+
+pub(crate) fn assert_style(elem: &WebElement, name: &str, expected: &str) -> WebDriverResult<()> {
+    let mut args = ScriptArgs::new();
+    args.push(elem.clone())?;
+    args.push(name)?;
+    args.push(expected)?;
+    let values: StyleValues =
+        elem.session().execute_script_with_args(STYLE_VALUES_SCRIPT, &args)?.convert()?;
+    let expected = expected.trim();
+    let matches = values.actual == expected || values.expected.as_deref() == Some(&values.actual);
+    if !matches {
+        let normalized = match &values.expected {
+            Some(x) if x != expected => format!(" (computed as {:?})", x),
+            Some(_) => String::new(),
+            None => String::from(" (not a valid value)"),
+        };
+        let message = format!(
+            "element style {:?} does not match\nexpected: {:?}{}\n  actual: {:?}",
+            name, expected, normalized, values.actual
+        );
+        let item = format!("style {}: {}", name, expected);
+        return Err(assertion_failed(elem.session(), item, &message));
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use serde::Deserialize;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, ScriptArgs, WebDriverSession};
+
+/// The maximum number of similar elements reported.
+const MAX_SIMILAR: usize = 5;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocatorDiagnosis {
+    elsewhere: Vec<String>,
+    similar: Vec<String>,
+}
+
+/// Works out what the selector is looking for, then reports where else an
+/// exact match exists (open shadow roots and same-origin frames), and the
+/// elements that most nearly match.
+const DIAGNOSE_SCRIPT: &str = r#"
+    const [using, value, maxSimilar] = arguments;
+    const hints = { tag: null, id: null, name: null, classes: [], text: null };
+    let m;
+    if (using === "css selector") {
+        const last = value.trim().split(/\s*[\s>+~]\s*/).pop();
+        if ((m = last.match(/^[a-zA-Z][\w-]*/))) hints.tag = m[0].toLowerCase();
+        if ((m = last.match(/#([\w-]+)/) || last.match(/\[id="([^"]*)"\]/))) hints.id = m[1];
+        if ((m = last.match(/\[name="([^"]*)"\]/))) hints.name = m[1];
+        hints.classes = Array.from(last.matchAll(/\.([\w-]+)/g), (x) => x[1]);
+    } else if (using === "xpath") {
+        const step = value.split("/").pop();
+        if ((m = step.match(/^([a-zA-Z][\w-]*)/))) hints.tag = m[1].toLowerCase();
+        if ((m = value.match(/@id\s*[=,]\s*["']([^"']*)["']/))) hints.id = m[1];
+        if ((m = value.match(/@name\s*[=,]\s*["']([^"']*)["']/))) hints.name = m[1];
+        if ((m = value.match(/@class\s*[=,]\s*["']([^"']*)["']/))) {
+            hints.classes = m[1].split(/\s+/).filter((c) => c);
+        }
+        if ((m = value.match(/(?:text\(\)|\.)\s*[=,]\s*["']([^"']*)["']/))) hints.text = m[1];
+    } else if (using === "link text" || using === "partial link text") {
+        hints.tag = "a";
+        hints.text = value;
+    } else if (using === "tag name") {
+        hints.tag = value.toLowerCase();
+    }
+
+    const distance = (a, b) => {
+        let prev = Array.from({ length: b.length + 1 }, (_, i) => i);
+        for (let i = 1; i <= a.length; i++) {
+            const cur = [i];
+            for (let j = 1; j <= b.length; j++) {
+                cur[j] = Math.min(prev[j] + 1, cur[j - 1] + 1,
+                    prev[j - 1] + (a[i - 1] === b[j - 1] ? 0 : 1));
+            }
+            prev = cur;
+        }
+        return prev[b.length];
+    };
+    const similar = (want, got) => {
+        if (!want || !got) return false;
+        want = want.toLowerCase();
+        got = got.toLowerCase();
+        return got.includes(want) || want.includes(got) ||
+            distance(want, got) <= Math.max(2, Math.floor(want.length / 4));
+    };
+    const describe = (e) => {
+        let s = "<" + e.tagName.toLowerCase();
+        for (const attr of ["id", "name", "class"]) {
+            const v = e.getAttribute(attr);
+            if (v) s += ` ${attr}="${v}"`;
+        }
+        s += ">";
+        const text = (e.innerText || e.textContent || "").trim().split("\n")[0].slice(0, 40);
+        return text ? `${s} '${text}'` : s;
+    };
+
+    // Collect the documents and open shadow roots that can be searched.
+    const roots = [];
+    const addRoot = (root, where) => {
+        roots.push({ root, where });
+        for (const e of root.querySelectorAll("*")) {
+            if (e.shadowRoot) {
+                addRoot(e.shadowRoot, `inside the shadow root of ${describe(e)}`);
+            }
+            if (e.tagName === "IFRAME" || e.tagName === "FRAME") {
+                let doc = null;
+                try { doc = e.contentDocument; } catch (_) {}
+                if (doc) addRoot(doc, `inside the frame ${describe(e)}`);
+            }
+        }
+    };
+    addRoot(document, null);
+
+    const elsewhere = [];
+    for (const { root, where } of roots.slice(1)) {
+        let found = null;
+        try {
+            if (using === "xpath") {
+                if (root.nodeType === Node.DOCUMENT_NODE) {
+                    found = root.evaluate(value, root, null,
+                        XPathResult.FIRST_ORDERED_NODE_TYPE, null).singleNodeValue;
+                }
+            } else if (using === "css selector" || using === "tag name") {
+                found = root.querySelector(value);
+            }
+        } catch (_) {}
+        if (found) elsewhere.push(`${describe(found)} ${where}`);
+    }
+
+    const scored = [];
+    let scanned = 0;
+    for (const { root, where } of roots) {
+        for (const e of root.querySelectorAll("*")) {
+            if (++scanned > 5000) break;
+            if (hints.tag && e.tagName.toLowerCase() !== hints.tag) continue;
+            let score = 0;
+            if (similar(hints.id, e.id)) score += 3;
+            if (similar(hints.name, e.getAttribute("name"))) score += 2;
+            const classes = Array.from(e.classList);
+            for (const c of hints.classes) {
+                if (classes.some((x) => similar(c, x))) score += 1;
+            }
+            if (hints.text && e.children.length === 0 &&
+                similar(hints.text, (e.innerText || e.textContent || "").trim())) {
+                score += 2;
+            }
+            if (score > 0) {
+                scored.push({ score, text: where ? `${describe(e)} ${where}` : describe(e) });
+            }
+        }
+    }
+    scored.sort((a, b) => b.score - a.score);
+    return { elsewhere, similar: scored.slice(0, maxSimilar).map((x) => x.text) };
+"#;
+
+fn diagnose(session: &WebDriverSession, by: &By) -> WebDriverResult<String> {
+    let selector = by.get_w3c_selector();
+    let mut args = ScriptArgs::new();
+    args.push(selector.name)?;
+    args.push(selector.query)?;
+    args.push(MAX_SIMILAR)?;
+    let diagnosis: LocatorDiagnosis =
+        session.execute_script_with_args(DIAGNOSE_SCRIPT, &args)?.convert()?;
+
+    let mut report = String::new();
+    for x in diagnosis.elsewhere {
+        report.push_str(&format!("\n    matching element exists {}", x));
+    }
+    if !diagnosis.similar.is_empty() {
+        report.push_str("\n    similar elements:");
+        for x in diagnosis.similar {
+            report.push_str(&format!("\n      {}", x));
+        }
+    }
+    Ok(report)
+}
+
+/// If locator suggestions are enabled and the error is NoSuchElement, add
+/// the near misses for the selector to the error message.
+pub(crate) fn with_locator_suggestions(
+    session: &WebDriverSession,
+    by: &By,
+    error: WebDriverError,
+) -> WebDriverError {
+    match error {
+        WebDriverError::NoSuchElement(mut info) if session.locator_suggestions() => {
+            match diagnose(session, by) {
+                Ok(report) => info.value.message.push_str(&report),
+                Err(e) => log::debug!("failed to diagnose selector {}: {}", by, e),
+            }
+            WebDriverError::NoSuchElement(info)
+        }
+        e => e,
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Instant;
+
+use thirtyfour::RequestData;
+use tracing::field;
+
+use crate::error::{WebDriverError, WebDriverResult};
+use crate::error_info::error_info;
+use crate::WebDriverSession;
+
+/// A short description of the result, e.g. `ok`, `no such element` or
+/// `http error`.
+fn result_status(result: &WebDriverResult<serde_json::Value>) -> String {
+    let error = match result {
+        Ok(_) => return "ok".to_string(),
+        Err(e) => e,
+    };
+    if let Some(info) = error_info(error) {
+        let status = if info.error.is_empty() {
+            info.value.error.clone().unwrap_or_default()
+        } else {
+            info.error.clone()
+        };
+        if !status.is_empty() {
+            return status;
+        }
+    }
+    let status = match error {
+        WebDriverError::Timeout(_) => "timeout",
+        WebDriverError::ReqwestError(_) | WebDriverError::IOError(_) => "http error",
+        WebDriverError::JsonError(_) | WebDriverError::DecodeError(_) => "decode error",
+        WebDriverError::UnknownResponse(_) => "unknown response",
+        _ => "error",
+    };
+    status.to_string()
+}
+
+/// The endpoint, without the session prefix, e.g. `/element/{id}/click`.
+fn endpoint(session: &WebDriverSession, url: &str) -> String {
+    let prefix = format!("/session/{}", session.session_id());
+    let endpoint = match url.strip_prefix(&prefix) {
+        Some("") => "/",
+        Some(endpoint) => endpoint,
+        None => url,
+    };
+    session.redact(endpoint)
+}
+
+/// Send the request inside a `webdriver.command` span, recording how long it
+/// took and its result.
+pub(crate) fn traced<F>(
+    session: &WebDriverSession,
+    request: RequestData,
+    send: F,
+) -> WebDriverResult<serde_json::Value>
+where
+    F: FnOnce(RequestData) -> WebDriverResult<serde_json::Value>,
+{
+    let span = tracing::debug_span!(
+        "webdriver.command",
+        method = ?request.method,
+        endpoint = %endpoint(session, &request.url),
+        session_id = %session.session_id(),
+        duration_ms = field::Empty,
+        status = field::Empty,
+        http_status = field::Empty,
+    );
+    let _entered = span.enter();
+    let start = Instant::now();
+    let result = send(request);
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+    span.record("status", result_status(&result).as_str());
+    match &result {
+        Ok(_) => tracing::debug!("command completed"),
+        Err(e) => {
+            if let Some(info) = error_info(e).filter(|info| info.status != 0) {
+                span.record("http_status", info.status);
+            }
+            tracing::debug!(error = %e, "command failed");
+        }
+    }
+    result
+}
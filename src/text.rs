@@ -0,0 +1,107 @@
+/// Normalize text for comparison: zero-width characters are removed, all runs
+/// of whitespace (including non-breaking spaces) are collapsed to a single
+/// space, and leading and trailing whitespace is removed.
+pub(crate) fn normalize_text(text: &str) -> String {
+    let text: String = text.chars().filter(|c| !matches!(c, '\u{200b}' | '\u{feff}')).collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Describe the difference between the expected and actual text, marking
+/// the first character that differs.
+pub(crate) fn text_diff(expected: &str, actual: &str) -> String {
+    let position = expected
+        .chars()
+        .zip(actual.chars())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| expected.chars().count().min(actual.chars().count()));
+    // Debug formatting escapes some characters, so measure the escaped prefix.
+    let prefix: String = actual.chars().take(position).collect();
+    let offset = format!("{:?}", prefix).chars().count() - 1;
+    format!(
+        "expected: {:?}\n  actual: {:?}\n{}^ first difference at character {}",
+        expected,
+        actual,
+        " ".repeat(10 + offset),
+        position
+    )
+}
+
+/// Match the start of the text against a pattern where `*` matches any
+/// sequence of characters. If `anchored` is true, the pattern must match the
+/// whole text.
+pub(crate) fn wildcard_matches(pattern: &str, text: &str, anchored: bool) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = match text.strip_prefix(parts[0]) {
+        Some(x) => x,
+        None => return false,
+    };
+    if parts.len() == 1 {
+        return !anchored || rest.is_empty();
+    }
+
+    for (i, part) in parts.iter().enumerate().skip(1) {
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_collapses_whitespace() {
+        assert_eq!(normalize_text("  Hello,\n\t world  "), "Hello, world");
+        assert_eq!(normalize_text("Hello,\u{a0}\u{a0}world"), "Hello, world");
+        assert_eq!(normalize_text("Hello,\u{2003}world\u{a0}"), "Hello, world");
+        assert_eq!(normalize_text(" \n\u{a0} "), "");
+    }
+
+    #[test]
+    fn normalize_removes_zero_width_characters() {
+        assert_eq!(normalize_text("Hel\u{200b}lo"), "Hello");
+        assert_eq!(normalize_text("\u{feff}Hello \u{200b} world"), "Hello world");
+    }
+
+    #[test]
+    fn diff_marks_first_difference() {
+        let diff = text_diff("Hello, world", "Hello, World");
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[0], "expected: \"Hello, world\"");
+        assert_eq!(lines[1], "  actual: \"Hello, World\"");
+        assert_eq!(lines[2].find('^'), lines[1].find('W'));
+        assert!(lines[2].ends_with("first difference at character 7"));
+    }
+
+    #[test]
+    fn diff_accounts_for_escapes() {
+        let diff = text_diff("a\tb c", "a\tb\u{a0}c");
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[1], "  actual: \"a\\tb\\u{a0}c\"");
+        assert_eq!(lines[2].find('^'), lines[1].find("\\u"));
+        assert!(lines[2].ends_with("first difference at character 3"));
+    }
+
+    #[test]
+    fn diff_of_prefix_marks_end() {
+        let diff = text_diff("Hello", "Hello, world");
+        assert!(diff.ends_with("first difference at character 5"));
+        let diff = text_diff("Hello, world", "Hello");
+        assert!(diff.ends_with("first difference at character 5"));
+    }
+
+    #[test]
+    fn wildcard_matches_prefix_or_whole_text() {
+        assert!(wildcard_matches("*/api/users*", "http://localhost/api/users?page=2", true));
+        assert!(wildcard_matches("http://*/api", "http://localhost/api", true));
+        assert!(!wildcard_matches("http://*/api", "http://localhost/api/users", true));
+        assert!(wildcard_matches("http://*/api", "http://localhost/api/users", false));
+        assert!(!wildcard_matches("*/api/*/edit", "http://localhost/api/edit", true));
+    }
+}
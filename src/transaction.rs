@@ -0,0 +1,121 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::error::WebDriverResult;
+use crate::extensions::chrome::NetworkMonitor;
+use crate::metadata::SessionMetadata;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+/// The measurements of a user flow run with
+/// [WebDriver::transaction()](trait.WebDriverCommands.html#method.transaction).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionReport {
+    /// The name of the transaction, e.g. `checkout`.
+    pub name: String,
+    /// The wall time taken by the flow.
+    pub duration: Duration,
+    /// The number of WebDriver commands sent by the flow.
+    pub commands: u64,
+    /// The number of bytes received over the network by the browser, as
+    /// encoded on the wire. This is only measured on Chromium-based browsers
+    /// with performance logging enabled, using
+    /// [NetworkMonitor::enable_logging()](extensions/chrome/struct.NetworkMonitor.html#method.enable_logging).
+    pub network_bytes: Option<u64>,
+    /// True if the flow returned Ok.
+    pub succeeded: bool,
+    /// The session metadata when the flow finished, e.g. the test name.
+    pub metadata: SessionMetadata,
+}
+
+/// Receives a [TransactionReport](struct.TransactionReport.html) whenever a
+/// transaction finishes, e.g. to send it to a metrics system.
+///
+/// A TransactionReporter can be set on a WebDriver using
+/// [WebDriver::set_transaction_reporter()](struct.GenericWebDriver.html#method.set_transaction_reporter).
+/// Reports are also logged at info level whether or not a reporter is set.
+///
+/// # Example
+/// ```rust
+/// use thirtyfour_sync::{TransactionReport, TransactionReporter};
+///
+/// #[derive(Debug)]
+/// struct StatsdReporter;
+///
+/// impl TransactionReporter for StatsdReporter {
+///     fn report(&self, report: &TransactionReport) {
+///         println!("flow.{}:{}|ms", report.name, report.duration.as_millis());
+///     }
+/// }
+/// ```
+pub trait TransactionReporter: fmt::Debug + Send + Sync {
+    /// Handle the report of a finished transaction.
+    fn report(&self, report: &TransactionReport);
+}
+
+/// Start measuring network bytes, if the browser supports it.
+fn start_network_monitor(session: &WebDriverSession) -> Option<NetworkMonitor<'_>> {
+    if !session.browser_info().is_chromium() {
+        return None;
+    }
+    let monitor = NetworkMonitor::new(session);
+    // This fails if performance logging is not enabled.
+    monitor.clear().ok().map(|_| monitor)
+}
+
+fn network_bytes(monitor: &NetworkMonitor) -> Option<u64> {
+    let events = monitor.events().ok()?;
+    let bytes = events
+        .iter()
+        .filter(|e| e.method == "Network.loadingFinished")
+        .filter_map(|e| e.params["encodedDataLength"].as_f64())
+        .sum::<f64>();
+    Some(bytes as u64)
+}
+
+pub(crate) fn run_transaction<T, F>(
+    session: &WebDriverSession,
+    name: &str,
+    f: F,
+) -> WebDriverResult<T>
+where
+    F: FnOnce() -> WebDriverResult<T>,
+{
+    let monitor = start_network_monitor(session);
+    let start_commands = session.command_count();
+    let start = Instant::now();
+    let result = f();
+    let duration = start.elapsed();
+    let commands = session.command_count() - start_commands;
+
+    let report = TransactionReport {
+        name: name.to_string(),
+        duration,
+        commands,
+        network_bytes: monitor.as_ref().and_then(network_bytes),
+        succeeded: result.is_ok(),
+        metadata: session.metadata(),
+    };
+    let outcome = if report.succeeded {
+        "succeeded"
+    } else {
+        "failed"
+    };
+    log::info!(
+        "transaction {} {} in {:?} ({} commands{}){}",
+        report.name,
+        outcome,
+        report.duration,
+        report.commands,
+        report.network_bytes.map(|b| format!(", {} bytes", b)).unwrap_or_default(),
+        if report.metadata.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", report.metadata)
+        }
+    );
+    if let Some(reporter) = session.transaction_reporter() {
+        reporter.report(&report);
+    }
+    result
+}
@@ -0,0 +1,278 @@
+use std::fs;
+use std::path::Path;
+
+use base64::encode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thirtyfour::{ExtensionCommand, RequestMethod};
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::json::convert_json;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession, WebElement};
+
+/// Makes the element passed as an argument, and any of its ancestors that
+/// are not rendered, visible and interactable. The original `style`
+/// attribute of each changed element is saved in a data attribute.
+const UNHIDE_SCRIPT: &str = r#"
+    const save = (e) => {
+        if (!e.hasAttribute("data-thirtyfour-style")) {
+            e.setAttribute("data-thirtyfour-style", JSON.stringify(e.getAttribute("style")));
+        }
+    };
+    const elem = arguments[0];
+    for (let e = elem.parentElement; e; e = e.parentElement) {
+        if (getComputedStyle(e).display === "none") {
+            save(e);
+            e.style.setProperty("display", "block", "important");
+        }
+    }
+    save(elem);
+    for (const [name, value] of [["display", "block"], ["visibility", "visible"],
+            ["opacity", "1"], ["width", "auto"], ["height", "auto"],
+            ["position", "static"], ["transform", "none"], ["clip", "auto"],
+            ["clip-path", "none"], ["pointer-events", "auto"]]) {
+        elem.style.setProperty(name, value, "important");
+    }
+"#;
+
+/// Restores the `style` attributes saved by the unhide script.
+const RESTORE_SCRIPT: &str = r#"
+    for (const e of document.querySelectorAll("[data-thirtyfour-style]")) {
+        const style = JSON.parse(e.getAttribute("data-thirtyfour-style"));
+        if (style === null) {
+            e.removeAttribute("style");
+        } else {
+            e.setAttribute("style", style);
+        }
+        e.removeAttribute("data-thirtyfour-style");
+    }
+"#;
+
+/// Make the element interactable, send the text to it, then restore the
+/// styles that were changed, even if sending the text failed.
+fn send_keys_force_visible(elem: &WebElement, text: String) -> WebDriverResult<()> {
+    let session = elem.session();
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    let result =
+        session.execute_script_with_args(UNHIDE_SCRIPT, &args).and_then(|_| elem.send_keys(text));
+    let restored = session.execute_script(RESTORE_SCRIPT);
+    result?;
+    restored?;
+    Ok(())
+}
+
+pub(crate) fn send_path_force_visible(elem: &WebElement, path: &Path) -> WebDriverResult<()> {
+    send_keys_force_visible(elem, path.to_string_lossy().into_owned())
+}
+
+/// The Selenium server command that stores an uploaded file on the machine
+/// running the browser.
+struct UploadFile(Value);
+
+impl ExtensionCommand for UploadFile {
+    fn parameters_json(&self) -> Option<Value> {
+        Some(self.0.clone())
+    }
+
+    fn method(&self) -> RequestMethod {
+        RequestMethod::Post
+    }
+
+    fn endpoint(&self) -> String {
+        String::from("/se/file")
+    }
+}
+
+/// Options for
+/// [WebElement::upload_files()](struct.WebElement.html#method.upload_files).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UploadOptions {
+    remote: bool,
+    force_visible: bool,
+}
+
+impl UploadOptions {
+    /// Create a new UploadOptions for sending local paths to a visible
+    /// input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upload each file to the Selenium server first, and send the paths
+    /// it returns. Use this when the browser runs on another machine, such
+    /// as a Selenium Grid node.
+    pub fn with_remote(mut self, remote: bool) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// Make the input visible while sending the paths, as with
+    /// [WebElement::send_keys_force_visible()](struct.WebElement.html#method.send_keys_force_visible).
+    pub fn with_force_visible(mut self, force_visible: bool) -> Self {
+        self.force_visible = force_visible;
+        self
+    }
+}
+
+/// Upload the file to the Selenium server, returning its path there.
+fn upload_remote(session: &WebDriverSession, path: &Path) -> WebDriverResult<String> {
+    let name = file_name(path)?;
+    let data = fs::read(path)?;
+    let zip = zip_stored(&name, &data);
+    let v = session.extension_command(UploadFile(json!({ "file": encode(&zip) })))?;
+    convert_json("Upload File", &v)
+}
+
+fn file_name(path: &Path) -> WebDriverResult<String> {
+    match path.file_name() {
+        Some(name) => Ok(name.to_string_lossy().into_owned()),
+        None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "{} is not a file path",
+            path.display()
+        )))),
+    }
+}
+
+const FILE_INPUT_SCRIPT: &str = r#"
+    const elem = arguments[0];
+    return {
+        isFileInput: elem.tagName === "INPUT" && elem.type === "file",
+        multiple: !!elem.multiple,
+        files: elem.files ? Array.from(elem.files, (f) => f.name) : []
+    };
+"#;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileInputState {
+    is_file_input: bool,
+    multiple: bool,
+    files: Vec<String>,
+}
+
+fn file_input_state(elem: &WebElement) -> WebDriverResult<FileInputState> {
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    elem.session().execute_script_with_args(FILE_INPUT_SCRIPT, &args)?.convert()
+}
+
+/// Send the files to the file input, then check that the input's `files`
+/// property lists them.
+pub(crate) fn upload_files(
+    elem: &WebElement,
+    paths: &[&Path],
+    options: &UploadOptions,
+) -> WebDriverResult<()> {
+    let state = file_input_state(elem)?;
+    if !state.is_file_input {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "element {} is not a file input",
+            elem
+        ))));
+    }
+    if paths.is_empty() || (paths.len() > 1 && !state.multiple) {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "cannot upload {} files to a file input {} the multiple attribute",
+            paths.len(),
+            if state.multiple {
+                "with"
+            } else {
+                "without"
+            }
+        ))));
+    }
+
+    let mut names = Vec::new();
+    let mut sent = Vec::new();
+    for path in paths {
+        names.push(file_name(path)?);
+        if options.remote {
+            sent.push(upload_remote(elem.session(), path)?);
+        } else {
+            sent.push(path.to_string_lossy().into_owned());
+        }
+    }
+    // The WebDriver spec separates multiple files with newlines.
+    let text = sent.join("\n");
+    if options.force_visible {
+        send_keys_force_visible(elem, text)?;
+    } else {
+        elem.send_keys(text)?;
+    }
+
+    let files = file_input_state(elem)?.files;
+    // Inputs with the multiple attribute add to the files already chosen.
+    if files.len() < names.len() || files[files.len() - names.len()..] != names[..] {
+        return Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(&format!(
+            "file input lists {:?} after uploading {:?}",
+            files, names
+        ))));
+    }
+    Ok(())
+}
+
+/// The CRC-32 checksum used by zip files.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Build a zip archive holding a single uncompressed file, which is the
+/// format the Selenium server expects for uploads.
+fn zip_stored(name: &str, data: &[u8]) -> Vec<u8> {
+    let crc = crc32(data);
+    let size = data.len() as u32;
+    let name = name.as_bytes();
+
+    // Version needed, flags, method (stored), time and date.
+    let mut common = Vec::new();
+    common.extend_from_slice(&20u16.to_le_bytes());
+    common.extend_from_slice(&0u16.to_le_bytes());
+    common.extend_from_slice(&0u16.to_le_bytes());
+    common.extend_from_slice(&0u16.to_le_bytes());
+    common.extend_from_slice(&0x21u16.to_le_bytes());
+    common.extend_from_slice(&crc.to_le_bytes());
+    common.extend_from_slice(&size.to_le_bytes());
+    common.extend_from_slice(&size.to_le_bytes());
+    common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    common.extend_from_slice(&0u16.to_le_bytes());
+
+    let mut zip = Vec::new();
+    zip.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+    zip.extend_from_slice(&common);
+    zip.extend_from_slice(name);
+    zip.extend_from_slice(data);
+
+    let central_offset = zip.len() as u32;
+    zip.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+    zip.extend_from_slice(&20u16.to_le_bytes());
+    zip.extend_from_slice(&common);
+    // Comment length, disk number, internal and external attributes, and
+    // the offset of the local header.
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&0u32.to_le_bytes());
+    zip.extend_from_slice(&0u32.to_le_bytes());
+    zip.extend_from_slice(name);
+    let central_size = zip.len() as u32 - central_offset;
+
+    zip.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&1u16.to_le_bytes());
+    zip.extend_from_slice(&1u16.to_le_bytes());
+    zip.extend_from_slice(&central_size.to_le_bytes());
+    zip.extend_from_slice(&central_offset.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::common::types::OptionRect;
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::WebDriverSession;
+
+const IOS_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const IPADOS_USER_AGENT: &str = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const PIXEL_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 7) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+const GALAXY_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 13; SM-G988B) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+/// Which way round a device is held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Taller than it is wide.
+    Portrait,
+    /// Wider than it is tall.
+    Landscape,
+}
+
+/// The screen size, pixel density and user agent of a device, for
+/// [WebDriver::set_viewport()](trait.WebDriverCommands.html#method.set_viewport).
+///
+/// Use a [Preset](enum.Preset.html), find one by name with `Device::find()`,
+/// or describe your own.
+///
+/// # Example:
+/// ```rust
+/// use thirtyfour_sync::{Device, Orientation, Preset};
+///
+/// let phone = Device::find("iPhone 12").unwrap().with_orientation(Orientation::Landscape);
+/// assert_eq!((phone.width, phone.height), (844, 390));
+///
+/// let kiosk = Device::new("Kiosk", 1080, 1920).with_touch(true);
+/// assert_eq!(kiosk.orientation(), Orientation::Portrait);
+/// assert_eq!(Device::from(Preset::Desktop1080p).width, 1920);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// The device name, e.g. `iPhone 12`.
+    pub name: String,
+    /// The viewport width, in CSS pixels.
+    pub width: u32,
+    /// The viewport height, in CSS pixels.
+    pub height: u32,
+    /// The number of device pixels per CSS pixel, i.e. `window.devicePixelRatio`.
+    pub device_scale_factor: f64,
+    /// Whether the page is laid out as on a mobile device, using the
+    /// `<meta name="viewport">` tag.
+    pub mobile: bool,
+    /// Whether the device has a touch screen.
+    pub touch: bool,
+    /// The user agent the device sends, or None to use the browser's own.
+    pub user_agent: Option<String>,
+}
+
+impl Device {
+    /// Describe a desktop display of the specified size, in CSS pixels.
+    pub fn new(name: &str, width: u32, height: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            width,
+            height,
+            device_scale_factor: 1.0,
+            mobile: false,
+            touch: false,
+            user_agent: None,
+        }
+    }
+
+    fn mobile(name: &str, width: u32, height: u32, scale: f64, user_agent: &str) -> Self {
+        Self::new(name, width, height)
+            .with_device_scale_factor(scale)
+            .with_mobile(true)
+            .with_touch(true)
+            .with_user_agent(user_agent)
+    }
+
+    /// Set the number of device pixels per CSS pixel.
+    pub fn with_device_scale_factor(mut self, scale: f64) -> Self {
+        self.device_scale_factor = scale;
+        self
+    }
+
+    /// Set whether the page is laid out as on a mobile device.
+    pub fn with_mobile(mut self, mobile: bool) -> Self {
+        self.mobile = mobile;
+        self
+    }
+
+    /// Set whether the device has a touch screen.
+    pub fn with_touch(mut self, touch: bool) -> Self {
+        self.touch = touch;
+        self
+    }
+
+    /// Set the user agent the device sends.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// The same device held the specified way round, swapping the width and
+    /// height if necessary.
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        if self.orientation() != orientation {
+            std::mem::swap(&mut self.width, &mut self.height);
+        }
+        self
+    }
+
+    /// Which way round the device is held. A square device is in portrait.
+    pub fn orientation(&self) -> Orientation {
+        if self.width > self.height {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        }
+    }
+
+    /// Every preset device.
+    pub fn all() -> Vec<Device> {
+        Preset::ALL.iter().map(|&p| p.into()).collect()
+    }
+
+    /// Find a preset device by name, ignoring case, spaces and punctuation,
+    /// e.g. `iPhone 12`, `iphone12` or `Desktop 1080p`.
+    pub fn find(name: &str) -> Option<Device> {
+        let key = |s: &str| -> String {
+            s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+        };
+        let name = key(name);
+        Self::all().into_iter().find(|d| key(&d.name) == name)
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}x{} @{}x)", self.name, self.width, self.height, self.device_scale_factor)
+    }
+}
+
+/// Common devices and display sizes. Convert one into a
+/// [Device](struct.Device.html) to adjust it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// iPhone SE (2nd and 3rd generation), 375x667.
+    IPhoneSE,
+    /// iPhone 12, 390x844.
+    IPhone12,
+    /// iPhone 14 Pro Max, 430x932.
+    IPhone14ProMax,
+    /// Google Pixel 7, 412x915.
+    Pixel7,
+    /// Samsung Galaxy S20 Ultra, 412x915.
+    GalaxyS20Ultra,
+    /// iPad Air, 820x1180.
+    Ipad,
+    /// iPad Pro 12.9", 1024x1366.
+    IpadPro,
+    /// A 1280x720 desktop display.
+    Desktop720p,
+    /// A 1920x1080 desktop display.
+    Desktop1080p,
+    /// A 2560x1440 desktop display.
+    Desktop1440p,
+}
+
+impl Preset {
+    /// Every preset.
+    pub const ALL: [Preset; 10] = [
+        Preset::IPhoneSE,
+        Preset::IPhone12,
+        Preset::IPhone14ProMax,
+        Preset::Pixel7,
+        Preset::GalaxyS20Ultra,
+        Preset::Ipad,
+        Preset::IpadPro,
+        Preset::Desktop720p,
+        Preset::Desktop1080p,
+        Preset::Desktop1440p,
+    ];
+}
+
+impl From<Preset> for Device {
+    fn from(preset: Preset) -> Self {
+        match preset {
+            Preset::IPhoneSE => Device::mobile("iPhone SE", 375, 667, 2.0, IOS_USER_AGENT),
+            Preset::IPhone12 => Device::mobile("iPhone 12", 390, 844, 3.0, IOS_USER_AGENT),
+            Preset::IPhone14ProMax => {
+                Device::mobile("iPhone 14 Pro Max", 430, 932, 3.0, IOS_USER_AGENT)
+            }
+            Preset::Pixel7 => Device::mobile("Pixel 7", 412, 915, 2.625, PIXEL_USER_AGENT),
+            Preset::GalaxyS20Ultra => {
+                Device::mobile("Galaxy S20 Ultra", 412, 915, 3.5, GALAXY_USER_AGENT)
+            }
+            Preset::Ipad => Device::mobile("iPad", 820, 1180, 2.0, IPADOS_USER_AGENT),
+            Preset::IpadPro => Device::mobile("iPad Pro", 1024, 1366, 2.0, IPADOS_USER_AGENT),
+            Preset::Desktop720p => Device::new("Desktop 720p", 1280, 720),
+            Preset::Desktop1080p => Device::new("Desktop 1080p", 1920, 1080),
+            Preset::Desktop1440p => Device::new("Desktop 1440p", 2560, 1440),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WindowMetrics {
+    width: u32,
+    height: u32,
+    chrome_width: i32,
+    chrome_height: i32,
+}
+
+const WINDOW_METRICS_SCRIPT: &str = r#"
+    return {
+        width: window.innerWidth,
+        height: window.innerHeight,
+        chrome_width: window.outerWidth - window.innerWidth,
+        chrome_height: window.outerHeight - window.innerHeight
+    };
+"#;
+
+fn window_metrics(session: &WebDriverSession) -> WebDriverResult<WindowMetrics> {
+    session.execute_script(WINDOW_METRICS_SCRIPT)?.convert()
+}
+
+/// Make the page see the device's viewport.
+///
+/// Chromium-based browsers emulate the device with DevTools, including its
+/// pixel density, touch screen and user agent, whatever the window size.
+/// Other browsers resize the window so that the viewport has the device's
+/// size, and the rest of the device is not emulated.
+pub(crate) fn set_viewport(session: &WebDriverSession, device: &Device) -> WebDriverResult<()> {
+    if device.width == 0 || device.height == 0 || device.device_scale_factor <= 0.0 {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "invalid viewport for device {}",
+            device
+        ))));
+    }
+
+    if session.browser_info().is_chromium() {
+        let dev_tools = ChromeDevTools::new(session);
+        // Mobile devices are turned to landscape, desktop displays already are.
+        let (angle, orientation) = match device.orientation() {
+            Orientation::Portrait => (0, "portraitPrimary"),
+            Orientation::Landscape if device.mobile => (90, "landscapePrimary"),
+            Orientation::Landscape => (0, "landscapePrimary"),
+        };
+        dev_tools.execute_cdp_with_params(
+            "Emulation.setDeviceMetricsOverride",
+            json!({
+                "width": device.width,
+                "height": device.height,
+                "deviceScaleFactor": device.device_scale_factor,
+                "mobile": device.mobile,
+                "screenOrientation": {"type": orientation, "angle": angle}
+            }),
+        )?;
+        let mut touch = json!({ "enabled": device.touch });
+        if device.touch {
+            touch["maxTouchPoints"] = json!(5);
+        }
+        dev_tools.execute_cdp_with_params("Emulation.setTouchEmulationEnabled", touch)?;
+
+        let previous = session.current_device();
+        let user_agent = match (&device.user_agent, previous.and_then(|d| d.user_agent)) {
+            (Some(user_agent), _) => Some(user_agent.clone()),
+            // Restore the browser's own user agent.
+            (None, Some(_)) => {
+                let version = dev_tools.execute_cdp("Browser.getVersion")?;
+                version["userAgent"].as_str().map(String::from)
+            }
+            (None, None) => None,
+        };
+        if let Some(user_agent) = user_agent {
+            dev_tools.execute_cdp_with_params(
+                "Network.setUserAgentOverride",
+                json!({ "userAgent": user_agent }),
+            )?;
+        }
+    } else {
+        let metrics = window_metrics(session)?;
+        session.set_window_rect(OptionRect::new().with_size(
+            device.width as i32 + metrics.chrome_width,
+            device.height as i32 + metrics.chrome_height,
+        ))?;
+    }
+    session.set_current_device(Some(device.clone()));
+    Ok(())
+}
+
+/// Turn the current device, or else the current viewport, the specified way
+/// round.
+pub(crate) fn set_orientation(
+    session: &WebDriverSession,
+    orientation: Orientation,
+) -> WebDriverResult<()> {
+    let device = match session.current_device() {
+        Some(device) => device,
+        None => {
+            let metrics = window_metrics(session)?;
+            Device::new("Current viewport", metrics.width, metrics.height)
+        }
+    };
+    set_viewport(session, &device.with_orientation(orientation))
+}
@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+
+use png::{ColorType, Decoder, Transformations};
+
+use crate::error::{no_such_element, WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::webelement::convert_element_sync;
+use crate::{WebDriverSession, WebElement};
+
+/// The number of coarse candidates that are refined at full resolution.
+const CANDIDATES: usize = 5;
+
+/// A location on the page that matched a template image.
+///
+/// Coordinates are in CSS pixels relative to the top-left of the viewport,
+/// so they can be used directly with action chains.
+#[derive(Debug, Clone)]
+pub struct ImageMatch<'a> {
+    session: &'a WebDriverSession,
+    /// The left edge of the matched area.
+    pub left: f64,
+    /// The top edge of the matched area.
+    pub top: f64,
+    /// The width of the matched area.
+    pub width: f64,
+    /// The height of the matched area.
+    pub height: f64,
+    /// The normalized cross-correlation score, from -1.0 to 1.0, where 1.0 is
+    /// a perfect match.
+    pub score: f64,
+}
+
+impl<'a> ImageMatch<'a> {
+    /// The center of the matched area.
+    pub fn center(&self) -> (f64, f64) {
+        (self.left + self.width / 2.0, self.top + self.height / 2.0)
+    }
+
+    /// The element at the center of the matched area, as returned by
+    /// `document.elementFromPoint()`.
+    pub fn element(&self) -> WebDriverResult<Option<WebElement<'a>>> {
+        let (x, y) = self.center();
+        let ret = self
+            .session
+            .execute_script(&format!("return document.elementFromPoint({}, {});", x, y))?;
+        if ret.value().is_null() {
+            return Ok(None);
+        }
+        convert_element_sync(self.session, ret.value()).map(Some)
+    }
+
+    /// Click the center of the matched area.
+    pub fn click(&self) -> WebDriverResult<()> {
+        let (x, y) = self.center();
+        self.session.action_chain().move_to(x as i32, y as i32).click().perform()
+    }
+}
+
+/// A grayscale image with pixel values from 0.0 to 255.0.
+struct GrayImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<f64>,
+}
+
+impl GrayImage {
+    fn from_png(png_data: &[u8]) -> Result<Self, String> {
+        let mut decoder = Decoder::new(png_data);
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+
+        let channels = match info.color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+            ColorType::Indexed => return Err("unexpected indexed PNG".to_string()),
+        };
+        let width = info.width as usize;
+        let height = info.height as usize;
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in buf.chunks(info.line_size).take(height) {
+            for px in row.chunks(channels).take(width) {
+                let gray = if channels >= 3 {
+                    0.299 * f64::from(px[0]) + 0.587 * f64::from(px[1]) + 0.114 * f64::from(px[2])
+                } else {
+                    f64::from(px[0])
+                };
+                pixels.push(gray);
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    fn at(&self, x: usize, y: usize) -> f64 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Shrink the image by the specified factor, averaging each block of pixels.
+    fn downscale(&self, factor: usize) -> Self {
+        if factor <= 1 {
+            return Self {
+                width: self.width,
+                height: self.height,
+                pixels: self.pixels.clone(),
+            };
+        }
+        let width = self.width / factor;
+        let height = self.height / factor;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for dy in 0..factor {
+                    for dx in 0..factor {
+                        sum += self.at(x * factor + dx, y * factor + dy);
+                    }
+                }
+                pixels.push(sum / (factor * factor) as f64);
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Summed-area tables for fast window means and variances.
+struct Integral {
+    width: usize,
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+}
+
+impl Integral {
+    fn new(image: &GrayImage) -> Self {
+        let width = image.width + 1;
+        let mut sum = vec![0.0; width * (image.height + 1)];
+        let mut sum_sq = vec![0.0; width * (image.height + 1)];
+        for y in 0..image.height {
+            for x in 0..image.width {
+                let v = image.at(x, y);
+                let i = (y + 1) * width + x + 1;
+                sum[i] = v + sum[i - 1] + sum[i - width] - sum[i - width - 1];
+                sum_sq[i] = v * v + sum_sq[i - 1] + sum_sq[i - width] - sum_sq[i - width - 1];
+            }
+        }
+        Self {
+            width,
+            sum,
+            sum_sq,
+        }
+    }
+
+    fn window(&self, table: &[f64], x: usize, y: usize, w: usize, h: usize) -> f64 {
+        let a = y * self.width + x;
+        let b = y * self.width + x + w;
+        let c = (y + h) * self.width + x;
+        let d = (y + h) * self.width + x + w;
+        table[d] - table[b] - table[c] + table[a]
+    }
+}
+
+/// A template prepared for matching: pixel values minus the mean.
+struct Template<'t> {
+    image: &'t GrayImage,
+    centered: Vec<f64>,
+    norm: f64,
+}
+
+impl<'t> Template<'t> {
+    fn new(image: &'t GrayImage) -> Option<Self> {
+        let n = image.pixels.len() as f64;
+        let mean = image.pixels.iter().sum::<f64>() / n;
+        let centered: Vec<f64> = image.pixels.iter().map(|x| x - mean).collect();
+        let norm = centered.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm < 1e-6 {
+            return None;
+        }
+        Some(Self {
+            image,
+            centered,
+            norm,
+        })
+    }
+
+    /// Normalized cross-correlation of the template at the specified position.
+    fn score(&self, haystack: &GrayImage, integral: &Integral, x: usize, y: usize) -> f64 {
+        let (w, h) = (self.image.width, self.image.height);
+        let n = (w * h) as f64;
+        let sum = integral.window(&integral.sum, x, y, w, h);
+        let sum_sq = integral.window(&integral.sum_sq, x, y, w, h);
+        let variance = sum_sq - sum * sum / n;
+        if variance < 1e-6 {
+            return 0.0;
+        }
+
+        let mut cross = 0.0;
+        for ty in 0..h {
+            let row = (y + ty) * haystack.width + x;
+            let haystack_row = &haystack.pixels[row..row + w];
+            let template_row = &self.centered[ty * w..(ty + 1) * w];
+            cross += haystack_row.iter().zip(template_row).map(|(a, b)| a * b).sum::<f64>();
+        }
+        cross / (variance.sqrt() * self.norm)
+    }
+
+    /// Score every position in the haystack, best first.
+    fn scan(&self, haystack: &GrayImage) -> Vec<(f64, usize, usize)> {
+        let integral = Integral::new(haystack);
+        let mut scores = Vec::new();
+        for y in 0..=(haystack.height - self.image.height) {
+            for x in 0..=(haystack.width - self.image.width) {
+                scores.push((self.score(haystack, &integral, x, y), x, y));
+            }
+        }
+        scores.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+        scores
+    }
+}
+
+/// Find the best match for the template in the screenshot, returning the
+/// score and the top-left position in screenshot pixels.
+///
+/// The search is done on downscaled images first, and the best candidates
+/// are then refined at full resolution.
+fn best_match(screenshot: &GrayImage, template: &GrayImage) -> Option<(f64, usize, usize)> {
+    if template.width > screenshot.width || template.height > screenshot.height {
+        return None;
+    }
+
+    let factor = (template.width.min(template.height) / 8).clamp(1, 4);
+    let coarse_screenshot = screenshot.downscale(factor);
+    let coarse_template = template.downscale(factor);
+    let coarse = Template::new(&coarse_template)?;
+
+    let mut candidates: Vec<(usize, usize)> = Vec::new();
+    for (_, x, y) in coarse.scan(&coarse_screenshot) {
+        // Skip candidates that overlap one we already have.
+        let distinct = candidates.iter().all(|(cx, cy)| {
+            (*cx as isize - x as isize).unsigned_abs() >= coarse_template.width / 2
+                || (*cy as isize - y as isize).unsigned_abs() >= coarse_template.height / 2
+        });
+        if distinct {
+            candidates.push((x, y));
+            if candidates.len() == CANDIDATES {
+                break;
+            }
+        }
+    }
+
+    let full = Template::new(template)?;
+    let integral = Integral::new(screenshot);
+    let max_x = screenshot.width - template.width;
+    let max_y = screenshot.height - template.height;
+    let mut best: Option<(f64, usize, usize)> = None;
+    for (cx, cy) in candidates {
+        let (cx, cy) = (cx * factor, cy * factor);
+        for y in cy.saturating_sub(factor)..=(cy + factor).min(max_y) {
+            for x in cx.saturating_sub(factor)..=(cx + factor).min(max_x) {
+                let score = full.score(screenshot, &integral, x, y);
+                if best.map(|b| score > b.0).unwrap_or(true) {
+                    best = Some((score, x, y));
+                }
+            }
+        }
+    }
+    best
+}
+
+pub(crate) fn find_by_image<'a>(
+    session: &'a WebDriverSession,
+    template_png: &[u8],
+    threshold: f64,
+) -> WebDriverResult<ImageMatch<'a>> {
+    let template = GrayImage::from_png(template_png).map_err(|e| {
+        WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "invalid template image: {}",
+            e
+        )))
+    })?;
+    let screenshot = GrayImage::from_png(&session.screenshot_as_png()?)
+        .map_err(|e| WebDriverError::UnknownResponse(format!("invalid screenshot: {}", e)))?;
+    let ratio: f64 = session.execute_script("return window.devicePixelRatio || 1;")?.convert()?;
+
+    match best_match(&screenshot, &template) {
+        Some((score, x, y)) if score >= threshold => Ok(ImageMatch {
+            session,
+            left: x as f64 / ratio,
+            top: y as f64 / ratio,
+            width: template.width as f64 / ratio,
+            height: template.height as f64 / ratio,
+            score,
+        }),
+        Some((score, _, _)) => Err(no_such_element(&format!(
+            "template image not found on screen (best score {:.3} is below {:.3})",
+            score, threshold
+        ))),
+        None => Err(no_such_element(
+            "template image not found on screen (template is larger than the \
+             screenshot or has no contrast)",
+        )),
+    }
+}
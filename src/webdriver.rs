@@ -1,17 +1,57 @@
 use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 
-use log::error;
+use log::{error, info};
 use serde::Serialize;
-use serde_json::Value;
 
-use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::batch::ConnectionFactory;
+#[cfg(feature = "bidi")]
+use crate::bidi::BiDiSession;
+#[cfg(feature = "bidi")]
+use crate::error::{WebDriverError, WebDriverErrorInfo};
+#[cfg(feature = "fixture-server")]
+use crate::fixture_server::FixtureServer;
+use crate::http::config::HttpClientConfig;
+use crate::http::connection_sync::{HttpRetryPolicy, WebDriverHttpClientSync};
 use crate::http::reqwest_sync::ReqwestDriverSync;
+use crate::listener::WebDriverEventListener;
+use crate::locators::Locators;
+use crate::narrator::Narrator;
+use crate::politeness::Politeness;
+use crate::query::ReadyStrategy;
+use crate::speech::{register_speech_stubs, unregister_speech_stubs};
+use crate::transaction::TransactionReporter;
 use crate::webdrivercommands::{start_session, WebDriverCommands};
-use crate::WebDriverSession;
 use crate::{common::command::Command, error::WebDriverResult, DesiredCapabilities};
+use crate::{SessionId, WebDriverSession};
 use std::time::Duration;
 
+/// Create connections of the same kind, to the same server, for batches.
+/// They share the fault rules of the session's own connection, if any.
+fn connection_factory<T>(
+    remote_server_addr: &str,
+    config: &HttpClientConfig,
+    session_conn: &T,
+) -> ConnectionFactory
+where
+    T: WebDriverHttpClientSync + 'static,
+{
+    let (remote_server_addr, config) = (remote_server_addr.to_string(), config.clone());
+    #[cfg(feature = "fault-injection")]
+    let faults = session_conn.fault_injector();
+    #[cfg(not(feature = "fault-injection"))]
+    let _ = session_conn;
+    ConnectionFactory::new(move || {
+        #[allow(unused_mut)]
+        let mut conn = T::create_with_config(&remote_server_addr, &config)?;
+        #[cfg(feature = "fault-injection")]
+        if let Some(faults) = &faults {
+            conn.set_fault_injector(faults.clone());
+        }
+        Ok(Box::new(conn) as Box<dyn WebDriverHttpClientSync>)
+    })
+}
+
 /// The WebDriver struct represents a browser session.
 ///
 /// For full documentation of all WebDriver methods,
@@ -42,7 +82,6 @@ pub type WebDriver = GenericWebDriver<ReqwestDriverSync>;
 #[derive(Debug)]
 pub struct GenericWebDriver<T: WebDriverHttpClientSync> {
     pub session: WebDriverSession,
-    capabilities: Value,
     quit_on_drop: bool,
     phantom: PhantomData<T>,
 }
@@ -71,11 +110,46 @@ where
     where
         C: Serialize,
     {
-        let conn = Arc::new(Mutex::new(T::create(remote_server_addr)?));
+        Self::new_with_config(remote_server_addr, capabilities, &HttpClientConfig::new())
+    }
+
+    /// Create a new WebDriver, sending the extra headers in the specified
+    /// config with every request, e.g. to authenticate with a proxy in front
+    /// of the WebDriver server.
+    ///
+    /// See [HttpClientConfig](http/config/struct.HttpClientConfig.html) for
+    /// details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::http::config::HttpClientConfig;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let config = HttpClientConfig::new().with_bearer_token("my-token");
+    /// let caps = DesiredCapabilities::chrome();
+    /// let driver = WebDriver::new_with_config("http://localhost:4444/wd/hub", &caps, &config)?;
+    /// driver.get("http://webappdemo")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_config<C>(
+        remote_server_addr: &str,
+        capabilities: C,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self>
+    where
+        C: Serialize,
+    {
+        let conn = T::create_with_config(remote_server_addr, config)?;
+        let factory = connection_factory(remote_server_addr, config, &conn);
+        let conn = Arc::new(Mutex::new(conn));
         let (session_id, session_capabilities) = start_session(conn.clone(), capabilities)?;
+        let mut session = WebDriverSession::new(session_id, conn);
+        session.set_capabilities(session_capabilities);
+        session.set_connection_factory(Some(factory));
         let driver = GenericWebDriver {
-            session: WebDriverSession::new(session_id, conn),
-            capabilities: session_capabilities,
+            session,
             quit_on_drop: true,
             phantom: PhantomData,
         };
@@ -83,9 +157,218 @@ where
         Ok(driver)
     }
 
+    /// Attach to an existing session on the WebDriver server, e.g. one
+    /// started by another process, instead of starting a new one.
+    ///
+    /// The session is checked by requesting the current URL, so an error is
+    /// returned if it no longer exists. The capabilities the session was
+    /// started with are not known, so anything that depends on them, such as
+    /// [browser_info()](trait.WebDriverCommands.html#method.browser_info),
+    /// will not work. Use `resume_session()` to restore them.
+    ///
+    /// Like any other WebDriver, this quits the session when dropped. Use
+    /// `into_parts()` to leave the browser running.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let session_id = std::env::var("WEBDRIVER_SESSION_ID").expect("no session id");
+    /// let driver = WebDriver::new_with_session_id("http://localhost:4444/wd/hub", session_id)?;
+    /// driver.get("http://webappdemo")?;
+    /// // Leave the browser running for the next process.
+    /// let (session_id, _) = driver.into_parts();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_session_id<S>(remote_server_addr: &str, session_id: S) -> WebDriverResult<Self>
+    where
+        S: Into<SessionId>,
+    {
+        Self::resume_session_with_config(
+            remote_server_addr,
+            session_id,
+            serde_json::Value::Null,
+            &HttpClientConfig::new(),
+        )
+    }
+
+    /// Attach to an existing session on the WebDriver server, restoring the
+    /// capabilities returned when it was started, as returned by
+    /// `into_parts()`. See `new_with_session_id()`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let (session_id, capabilities) = driver.into_parts();
+    /// let saved = serde_json::to_string(&capabilities)?;
+    ///
+    /// // Later, possibly in another process.
+    /// let capabilities = serde_json::from_str(&saved)?;
+    /// let driver =
+    ///     WebDriver::resume_session("http://localhost:4444/wd/hub", session_id, capabilities)?;
+    /// println!("resumed {}", driver.browser_info().name);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn resume_session<S>(
+        remote_server_addr: &str,
+        session_id: S,
+        capabilities: serde_json::Value,
+    ) -> WebDriverResult<Self>
+    where
+        S: Into<SessionId>,
+    {
+        Self::resume_session_with_config(
+            remote_server_addr,
+            session_id,
+            capabilities,
+            &HttpClientConfig::new(),
+        )
+    }
+
+    /// Attach to an existing session on the WebDriver server, like
+    /// `resume_session()`, sending the extra headers and using the TLS
+    /// settings in the specified config, as for `new_with_config()`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::http::config::HttpClientConfig;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let config = HttpClientConfig::new().with_bearer_token("my-token");
+    /// let session_id = std::env::var("WEBDRIVER_SESSION_ID").expect("no session id");
+    /// let driver = WebDriver::resume_session_with_config(
+    ///     "http://localhost:4444/wd/hub",
+    ///     session_id,
+    ///     serde_json::Value::Null,
+    ///     &config,
+    /// )?;
+    /// driver.get("http://webappdemo")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn resume_session_with_config<S>(
+        remote_server_addr: &str,
+        session_id: S,
+        capabilities: serde_json::Value,
+        config: &HttpClientConfig,
+    ) -> WebDriverResult<Self>
+    where
+        S: Into<SessionId>,
+    {
+        let conn = T::create_with_config(remote_server_addr, config)?;
+        let factory = connection_factory(remote_server_addr, config, &conn);
+        let mut session = WebDriverSession::new(session_id.into(), Arc::new(Mutex::new(conn)));
+        session.set_capabilities(capabilities);
+        session.set_connection_factory(Some(factory));
+        // Check that the session is still alive, without quitting it if not.
+        session.current_url()?;
+        let driver = GenericWebDriver {
+            session,
+            quit_on_drop: true,
+            phantom: PhantomData,
+        };
+
+        Ok(driver)
+    }
+
+    /// Give up this WebDriver without quitting the session, and return the
+    /// session id and the capabilities returned by the server, so that the
+    /// session can be resumed later with `resume_session()`.
+    pub fn into_parts(mut self) -> (SessionId, serde_json::Value) {
+        self.quit_on_drop = false;
+        (self.session.session_id().clone(), self.session.capabilities().clone())
+    }
+
+    /// Give up this WebDriver without quitting the session, leaving the
+    /// browser running, e.g. to inspect it after a failure or to hand it to
+    /// another process. Returns the session id, which is also logged.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.get("http://webappdemo")?;
+    /// let session_id = driver.leak();
+    /// println!("browser left running in session {}", session_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn leak(self) -> SessionId {
+        let (session_id, _) = self.into_parts();
+        info!("Leaving session {} running", session_id);
+        session_id
+    }
+
+    /// Whether the session is deleted when this WebDriver is dropped.
+    pub fn quit_on_drop(&self) -> bool {
+        self.quit_on_drop
+    }
+
+    /// Set whether the session is deleted when this WebDriver is dropped.
+    /// This is true by default. When false, the browser is left running
+    /// unless `quit()` is called explicitly.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// // Keep the browser open for debugging, unless the test gets to quit().
+    /// driver.set_quit_on_drop(std::env::var("KEEP_BROWSER").is_err());
+    /// driver.get("http://webappdemo")?;
+    /// driver.quit()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_quit_on_drop(&mut self, quit_on_drop: bool) {
+        self.quit_on_drop = quit_on_drop;
+    }
+
     /// Return a clone of the capabilities as returned by Selenium.
     pub fn capabilities(&self) -> DesiredCapabilities {
-        DesiredCapabilities::new(self.capabilities.clone())
+        DesiredCapabilities::new(self.session.capabilities().clone())
+    }
+
+    /// Connect to the WebDriver BiDi WebSocket for this session. Only available
+    /// with the `bidi` feature.
+    ///
+    /// The session must have been started with the `webSocketUrl` capability.
+    /// See [BiDiSession](bidi/struct.BiDiSession.html) for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::bidi::BiDiSession;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let mut caps = DesiredCapabilities::firefox();
+    /// BiDiSession::enable(&mut caps)?;
+    /// let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let bidi = driver.bidi()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "bidi")]
+    pub fn bidi(&self) -> WebDriverResult<BiDiSession> {
+        match self.session.capabilities()["webSocketUrl"].as_str() {
+            Some(url) => BiDiSession::connect(url),
+            None => Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(
+                "the session has no webSocketUrl; use BiDiSession::enable() before starting it",
+            ))),
+        }
     }
 
     /// End the webdriver session.
@@ -112,6 +395,381 @@ where
     pub fn set_request_timeout(&mut self, timeout: Duration) -> WebDriverResult<()> {
         self.session.set_request_timeout(timeout)
     }
+
+    /// Set the policy for sending requests again after transient HTTP
+    /// errors, such as a 502 from a load balancer or a connection reset, or
+    /// None to stop retrying. By default requests are not retried.
+    ///
+    /// See [HttpRetryPolicy](http/connection_sync/struct.HttpRetryPolicy.html)
+    /// for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::http::connection_sync::HttpRetryPolicy;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_retry_policy(Some(HttpRetryPolicy::new().with_max_attempts(5)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_retry_policy(&mut self, policy: Option<HttpRetryPolicy>) {
+        self.session.set_retry_policy(policy);
+    }
+
+    /// Set how long `click()`, `send_keys()` and `clear()` keep trying while
+    /// the element is not yet interactable, e.g. because it is hidden,
+    /// disabled or covered by another element, or None to try once. By
+    /// default they try once.
+    ///
+    /// This is separate from the HTTP request timeout, and the errors can be
+    /// told apart with
+    /// [is_interaction_timeout()](interaction/fn.is_interaction_timeout.html) and
+    /// [is_http_timeout()](interaction/fn.is_http_timeout.html). Use
+    /// [WebElement::click_within()](struct.WebElement.html#method.click_within)
+    /// and similar methods to use a different timeout for one interaction.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::interaction::{is_http_timeout, is_interaction_timeout};
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_interaction_timeout(Some(Duration::from_secs(5)));
+    /// driver.get("http://webappdemo")?;
+    /// match driver.find_element(By::Id("button1"))?.click() {
+    ///     Err(e) if is_interaction_timeout(&e) => println!("the button never became clickable"),
+    ///     Err(e) if is_http_timeout(&e) => println!("the server stopped responding"),
+    ///     result => result?,
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_interaction_timeout(&mut self, timeout: Option<Duration>) {
+        self.session.set_interaction_timeout(timeout);
+    }
+
+    /// Set the politeness policy applied to navigations made via `get()`,
+    /// or None to remove it.
+    ///
+    /// See [Politeness](politeness/struct.Politeness.html) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::politeness::Politeness;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_politeness(Some(Politeness::new("mybot")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_politeness(&mut self, politeness: Option<Politeness>) {
+        self.session.set_politeness(politeness);
+    }
+
+    /// Set the strategy used to decide when a page is ready after navigating.
+    ///
+    /// See [ReadyStrategy](query/trait.ReadyStrategy.html) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::query::NetworkIdle;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_ready_strategy(NetworkIdle::new(Duration::from_millis(500)));
+    /// driver.get("http://webappdemo")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_ready_strategy<S: ReadyStrategy + 'static>(&mut self, strategy: S) {
+        self.session.set_ready_strategy(Some(Arc::new(strategy)));
+    }
+
+    /// Remove the ReadyStrategy, so that navigation no longer waits for the
+    /// page to be ready.
+    pub fn clear_ready_strategy(&mut self) {
+        self.session.set_ready_strategy(None);
+    }
+
+    /// Tolerate responses from drivers that predate the W3C WebDriver spec,
+    /// such as old versions of chromedriver and some embedded device drivers.
+    ///
+    /// Responses are converted into the W3C shape before they are parsed:
+    /// errors reported with a JSON Wire Protocol status code are returned as
+    /// the equivalent error, and legacy element references are recognised.
+    /// Error responses in the legacy format are always recognised, even
+    /// without this setting.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_lenient_responses(true);
+    /// driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_lenient_responses(&mut self, lenient: bool) {
+        self.session.set_lenient_responses(lenient);
+    }
+
+    /// Use the legacy JSON Wire Protocol for all further commands, for old
+    /// Selenium 2 servers that do not support the W3C WebDriver protocol.
+    ///
+    /// Commands are translated into the equivalent legacy endpoints and
+    /// payloads, and responses are parsed as with
+    /// [set_lenient_responses()](#method.set_lenient_responses). Actions
+    /// are performed with the legacy mouse and keyboard endpoints, so moving
+    /// the pointer relative to the viewport is not supported. Commands with
+    /// no legacy equivalent are sent unchanged.
+    ///
+    /// Sessions can be created on legacy servers without this setting, since
+    /// the new session request includes the legacy capabilities.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::firefox();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_json_wire_protocol(true);
+    /// driver.get("http://webappdemo")?;
+    /// driver.action_chain().move_to_element_center(&driver.find_element(By::Id("button1"))?).click().perform()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_json_wire_protocol(&mut self, enabled: bool) {
+        self.session.set_json_wire_protocol(enabled);
+    }
+
+    /// When `find_element()` fails to find an element, look for near misses
+    /// and add them to the NoSuchElement error: elements with a similar id,
+    /// name, class or text, and matching elements inside frames or open
+    /// shadow roots, which the selector cannot reach from the current
+    /// context. This costs an extra script execution per failed search.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_locator_suggestions(true);
+    /// driver.get("http://webappdemo")?;
+    /// if let Err(e) = driver.find_element(By::Id("buton1")) {
+    ///     // The message suggests <button id="button1">.
+    ///     println!("{}", e);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_locator_suggestions(&mut self, enabled: bool) {
+        self.session.set_locator_suggestions(enabled);
+    }
+
+    /// Replace the page's `speechSynthesis` and `SpeechRecognition` with
+    /// stubs, in the current page and every page loaded afterwards, so that
+    /// voice features can be tested. Read what the page spoke with
+    /// [spoken_utterances()](trait.WebDriverCommands.html#method.spoken_utterances),
+    /// and feed it speech with
+    /// [recognize_speech()](trait.WebDriverCommands.html#method.recognize_speech).
+    ///
+    /// The stubs are registered once using the Chrome DevTools Protocol, so
+    /// this is only supported by Chromium-based browsers. Turning them off
+    /// applies to pages loaded afterwards.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_speech_stubs(true)?;
+    /// driver.get("http://webappdemo")?;
+    /// driver.execute_script(r#"speechSynthesis.speak(new SpeechSynthesisUtterance("Welcome back"));"#)?;
+    /// assert_eq!(driver.spoken_utterances()?[0].text, "Welcome back");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_speech_stubs(&mut self, enabled: bool) -> WebDriverResult<()> {
+        match (enabled, self.session.speech_stubs().map(String::from)) {
+            (true, None) => {
+                let identifier = register_speech_stubs(&self.session)?;
+                self.session.set_speech_stubs(Some(identifier));
+            }
+            (false, Some(identifier)) => {
+                unregister_speech_stubs(&self.session, &identifier)?;
+                self.session.set_speech_stubs(None);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Record the actions performed in this session as readable sentences,
+    /// or None to stop. Read them back with `driver.session().narrator()`.
+    ///
+    /// See [Narrator](narrator/struct.Narrator.html) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::narrator::Narrator;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_narrator(Some(Narrator::new()));
+    /// driver.get("http://webappdemo")?;
+    /// let steps = driver.session().narrator().unwrap().sentences();
+    /// assert_eq!(steps, vec!["navigated to http://webappdemo"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_narrator(&mut self, narrator: Option<Narrator>) {
+        self.session.set_narrator(narrator);
+    }
+
+    /// Send the report of every
+    /// [transaction()](trait.WebDriverCommands.html#method.transaction) to
+    /// the specified reporter.
+    ///
+    /// See [TransactionReporter](trait.TransactionReporter.html) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::{TransactionReport, TransactionReporter};
+    ///
+    /// #[derive(Debug)]
+    /// struct PrintReporter;
+    ///
+    /// impl TransactionReporter for PrintReporter {
+    ///     fn report(&self, report: &TransactionReport) {
+    ///         println!("{}: {:?}", report.name, report.duration);
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_transaction_reporter(PrintReporter);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_transaction_reporter<R: TransactionReporter + 'static>(&mut self, reporter: R) {
+        self.session.set_transaction_reporter(Some(Arc::new(reporter)));
+    }
+
+    /// Remove the TransactionReporter. Transactions are still logged.
+    pub fn clear_transaction_reporter(&mut self) {
+        self.session.set_transaction_reporter(None);
+    }
+
+    /// Call the hooks of the specified listener as this session sends
+    /// commands, after those of any listeners registered before.
+    ///
+    /// See [WebDriverEventListener](trait.WebDriverEventListener.html) for
+    /// details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::{WebDriverEventListener, WebDriverSession};
+    ///
+    /// #[derive(Debug)]
+    /// struct ClickLogger;
+    ///
+    /// impl WebDriverEventListener for ClickLogger {
+    ///     fn after_click(&self, _session: &WebDriverSession, element: &WebElement) {
+    ///         println!("clicked {}", element);
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.register_listener(Box::new(ClickLogger));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_listener(&mut self, listener: Box<dyn WebDriverEventListener>) {
+        self.session.add_listener(listener);
+    }
+
+    /// Remove all listeners registered with `register_listener()`.
+    pub fn clear_listeners(&mut self) {
+        self.session.clear_listeners();
+    }
+
+    /// Set the registry of named locators used by
+    /// [find_named()](trait.WebDriverCommands.html#method.find_named), or
+    /// None to remove it.
+    ///
+    /// See [Locators](struct.Locators.html) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::Locators;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let locators = Locators::from_json(r#"{ "buttons": { "first": { "id": "button1" } } }"#)?;
+    /// driver.set_locators(Some(locators));
+    /// driver.get("http://webappdemo")?;
+    /// driver.find_named("buttons.first")?.click()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_locators(&mut self, locators: Option<Locators>) {
+        self.session.set_locators(locators.map(Arc::new));
+    }
+
+    /// Set the fixture server used by
+    /// [get_fixture()](trait.WebDriverCommands.html#method.get_fixture), or
+    /// None to remove it. The server is stopped when the driver is dropped,
+    /// after the browser has quit.
+    ///
+    /// See [FixtureServer](struct.FixtureServer.html) for details.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::FixtureServer;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// let caps = DesiredCapabilities::chrome();
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_fixture_server(Some(FixtureServer::start()?.serve_dir("tests/fixtures")));
+    /// driver.get_fixture("/login.html")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fixture-server")]
+    pub fn set_fixture_server(&mut self, server: Option<FixtureServer>) {
+        self.session.set_fixture_server(server.map(Arc::new));
+    }
 }
 
 impl<T> WebDriverCommands for GenericWebDriver<T>
@@ -1,22 +1,69 @@
-use std::{fs::File, io::Write, path::Path, sync::Arc, time::Duration};
+use std::{
+    fs::File,
+    io::Write,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use base64::decode;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, Value};
+use serde_json::Value;
 
-use crate::error::WebDriverError;
+use crate::auth_state::{export_auth_state, seed_auth_state, AuthExportOptions, AuthState};
+use crate::batch::{Batch, BatchResults};
+use crate::browser_info::BrowserInfo;
+use crate::clock::{clock_report, ClockReport};
+use crate::error::{no_such_element, WebDriverError};
+use crate::frames::{find_element_anywhere, FoundElement, FrameGuard, FramePath};
+use crate::gamepad::{
+    connect_gamepad, disconnect_gamepad, pointer_lock_element, set_gamepad_state, GamepadState,
+};
+use crate::health::{health_check, HealthCheckOptions, HealthReport};
 use crate::http::connection_sync::WebDriverHttpClientSync;
+use crate::inline_html::load_html;
+use crate::js_event::wait_for_js_event;
+use crate::json::{convert_json, convert_json_from};
+use crate::legacy::execute_json_wire;
+use crate::listener::notify;
+use crate::locators::{named_locator, with_locator_name};
+use crate::media::{
+    assert_media_device_selected, grant_media_permissions, media_devices, selected_media_devices,
+    MediaDevice,
+};
+use crate::menu::{navigate_menu, MenuOptions};
+use crate::metadata::{set_session_metadata, SessionMetadata};
+#[cfg(feature = "ocr")]
+use crate::ocr::{find_text_on_screen, TextMatch};
+use crate::page_info::{page_info, with_page_context, PageInfo};
+use crate::page_source::page_source_deep;
+use crate::pinned::PinnedElement;
+use crate::print::{emulate_print_media, pdf_page_count, print_to_pdf, PrintOptions};
+use crate::screenshot::{
+    screenshot_with_details, screenshot_with_options, Screenshot, ScreenshotOptions,
+};
+use crate::speech::{install_speech_stubs, recognize_speech, spoken_utterances, SpokenUtterance};
+use crate::suggest::with_locator_suggestions;
+use crate::transaction::run_transaction;
+use crate::viewport::{set_orientation, set_viewport, Device, Orientation};
+#[cfg(feature = "visual-locator")]
+use crate::visual::{find_by_image, ImageMatch};
 use crate::WebDriverSession;
 use crate::{
     action_chain::ActionChain,
-    common::{
-        command::Command,
-        command::ExtensionCommand,
-        connection_common::{convert_json, convert_json_vec},
-    },
+    common::{command::Command, command::ExtensionCommand},
     error::WebDriverResult,
+    offline::set_offline,
+    paginate::Paginator,
+    query::{
+        find_elements_nowait, poll_assertion, without_implicit_wait, Condition, ElementPoller,
+        ElementQuery,
+    },
+    retry::RetryPolicy,
+    scroll::{scroll_until, ScrollOptions, ScrollReport, ScrollUntil},
     webelement::{convert_element_sync, convert_elements_sync},
+    zoom::set_zoom,
     By, Cookie, OptionRect, Rect, ScriptArgs, SessionId, SwitchTo, TimeoutConfiguration,
     WebElement, WindowHandle,
 };
@@ -62,25 +109,36 @@ where
 
     #[derive(Debug, Deserialize)]
     struct ConnectionResp {
-        #[serde(default)]
+        #[serde(default, rename(deserialize = "sessionId"))]
         session_id: String,
         value: ConnectionData,
     }
 
-    let resp: ConnectionResp = serde_json::from_value(v)?;
-    let data = resp.value;
-    let session_id = SessionId::from(if resp.session_id.is_empty() {
-        data.session_id
-    } else {
+    let resp: ConnectionResp = convert_json("New Session", &v)?;
+    let mut data = resp.value;
+    // JSON Wire Protocol servers return the session id alongside the value,
+    // and the capabilities as the value itself.
+    let legacy = data.session_id.is_empty() && !resp.session_id.is_empty();
+    let session_id = SessionId::from(if legacy {
         resp.session_id
+    } else {
+        data.session_id
     });
+    if legacy && data.capabilities.is_null() {
+        data.capabilities = v["value"].clone();
+    }
     // Set default timeouts.
     let timeout_config = TimeoutConfiguration::new(
         Some(Duration::new(60, 0)),
         Some(Duration::new(60, 0)),
         Some(Duration::new(30, 0)),
     );
-    connection.execute(Command::SetTimeouts(timeout_config).format_request(&session_id))?;
+    let request = Command::SetTimeouts(timeout_config).format_request(&session_id);
+    if legacy {
+        execute_json_wire(&*connection, request)?;
+    } else {
+        connection.execute(request)?;
+    }
 
     Ok((session_id, data.capabilities))
 }
@@ -151,6 +209,11 @@ pub trait WebDriverCommands {
 
     /// Navigate to the specified URL.
     ///
+    /// If a [Politeness](politeness/struct.Politeness.html) policy has been set,
+    /// `robots.txt` is checked and per-host rate limits are applied first.
+    /// If a [ReadyStrategy](query/trait.ReadyStrategy.html) has been set, this
+    /// waits for the page to be ready before returning.
+    ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
@@ -163,7 +226,75 @@ pub trait WebDriverCommands {
     /// # }
     /// ```
     fn get<S: Into<String>>(&self, url: S) -> WebDriverResult<()> {
-        self.cmd(Command::NavigateTo(url.into())).map(|_| ())
+        let url = url.into();
+        if let Some(politeness) = self.session().politeness() {
+            politeness.before_navigation(&url)?;
+        }
+        self.cmd(Command::NavigateTo(url))?;
+        self.wait_for_ready()
+    }
+
+    /// Load the specified HTML as the current document, without a web
+    /// server. This is useful for testing small widgets against synthetic
+    /// markup.
+    ///
+    /// The document is loaded from a `data:` URL, so scripts and styles in it
+    /// run as usual, but it has an opaque origin: cookies and web storage are
+    /// not available, and relative URLs do not resolve. Very large documents
+    /// are written into `about:blank` instead. As with `get()`, this waits
+    /// for the page to be ready before returning.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.load_html(
+    ///     r#"<button onclick="this.textContent = 'Clicked'">Click me</button>"#,
+    /// )?;
+    /// let button = driver.find_element(By::Tag("button"))?;
+    /// button.click()?;
+    /// assert_eq!(button.text()?, "Clicked");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn load_html(&self, html: &str) -> WebDriverResult<()> {
+        load_html(self.session(), html)
+    }
+
+    /// Navigate to the specified path on the fixture server set with
+    /// [WebDriver::set_fixture_server()](struct.GenericWebDriver.html#method.set_fixture_server).
+    /// Only available with the `fixture-server` feature.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::FixtureServer;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let server = FixtureServer::start()?;
+    /// server.add_html("/button.html", "<button>Click me</button>");
+    /// driver.set_fixture_server(Some(server));
+    /// driver.get_fixture("/button.html")?;
+    /// driver.find_element(By::Tag("button"))?.click()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fixture-server")]
+    fn get_fixture(&self, path: &str) -> WebDriverResult<()> {
+        let url = match self.session().fixture_server() {
+            Some(server) => server.url(path),
+            None => {
+                let msg = "no fixture server is set; use WebDriver::set_fixture_server()";
+                let info = crate::error::WebDriverErrorInfo::new(msg);
+                return Err(WebDriverError::InvalidArgument(info));
+            }
+        };
+        self.get(url)
     }
 
     /// Get the current URL as a String.
@@ -183,7 +314,7 @@ pub trait WebDriverCommands {
     /// ```
     fn current_url(&self) -> WebDriverResult<String> {
         let v = self.cmd(Command::GetCurrentUrl)?;
-        convert_json(&v["value"])
+        convert_json("Get Current URL", &v["value"])
     }
 
     /// Get the page source as a String.
@@ -203,10 +334,18 @@ pub trait WebDriverCommands {
     /// ```
     fn page_source(&self) -> WebDriverResult<String> {
         let v = self.cmd(Command::GetPageSource)?;
-        convert_json(&v["value"])
+        convert_json("Get Page Source", &v["value"])
     }
 
-    /// Get the page title as a String.
+    /// Get the page source as a String, including the contents of open
+    /// shadow roots, which `page_source()` leaves out.
+    ///
+    /// Each shadow root is serialized as a declarative shadow DOM
+    /// `<template shadowrootmode="open">` element at the start of its host,
+    /// so browsers that support declarative shadow DOM recreate it when the
+    /// source is loaded again. This makes the result suitable for archiving
+    /// and diffing. Closed shadow roots cannot be read by scripts, and are
+    /// left out.
     ///
     /// # Example:
     /// ```rust
@@ -216,17 +355,16 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// driver.get("http://webappdemo")?;
-    /// let title = driver.title()?;
-    /// #     assert_eq!(title, "Demo Web App");
+    /// let source = driver.page_source_deep()?;
+    /// assert!(source.contains("id=\"button1\""));
     /// #     Ok(())
     /// # }
     /// ```
-    fn title(&self) -> WebDriverResult<String> {
-        let v = self.cmd(Command::GetTitle)?;
-        convert_json(&v["value"])
+    fn page_source_deep(&self) -> WebDriverResult<String> {
+        page_source_deep(self.session())
     }
 
-    /// Search for an element on the current page using the specified selector.
+    /// Get the page title as a String.
     ///
     /// # Example:
     /// ```rust
@@ -235,21 +373,22 @@ pub trait WebDriverCommands {
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// let elem_text = driver.find_element(By::Name("input1"))?;
-    /// let elem_button = driver.find_element(By::Id("button-set"))?;
-    /// let elem_result = driver.find_element(By::Id("input-result"))?;
+    /// driver.get("http://webappdemo")?;
+    /// let title = driver.title()?;
+    /// #     assert_eq!(title, "Demo Web App");
     /// #     Ok(())
     /// # }
     /// ```
-    fn find_element(&self, by: By) -> WebDriverResult<WebElement> {
-        let v = self.cmd(Command::FindElement(by.get_w3c_selector()))?;
-        convert_element_sync(self.session(), &v["value"])
+    fn title(&self) -> WebDriverResult<String> {
+        let v = self.cmd(Command::GetTitle)?;
+        convert_json("Get Title", &v["value"])
     }
 
-    /// Search for all elements on the current page that match the specified
-    /// selector.
+    /// Get the current URL, title and ready state in a single request.
+    ///
+    /// This is cheaper than calling `current_url()` and `title()` separately,
+    /// which matters on remote grids. Errors from waits and assertions in
+    /// this crate include the same information.
     ///
     /// # Example:
     /// ```rust
@@ -258,20 +397,20 @@ pub trait WebDriverCommands {
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// let elems = driver.find_elements(By::ClassName("section"))?;
-    /// for elem in elems {
-    ///     assert!(elem.get_attribute("class")?.expect("Missing class on element").contains("section"));
-    /// }
+    /// driver.get("http://webappdemo")?;
+    /// let info = driver.page_info()?;
+    /// assert_eq!(info.ready_state, "complete");
+    /// println!("Now at {}", info);
     /// #     Ok(())
     /// # }
     /// ```
-    fn find_elements(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
-        let v = self.cmd(Command::FindElements(by.get_w3c_selector()))?;
-        convert_elements_sync(self.session(), &v["value"])
+    fn page_info(&self) -> WebDriverResult<PageInfo> {
+        page_info(self.session())
     }
 
-    /// Execute the specified Javascript synchronously and return the result.
+    /// Get information about the browser, such as its name and version,
+    /// from the capabilities returned when the session was created. This
+    /// works for all browsers and does not make any requests.
     ///
     /// # Example:
     /// ```rust
@@ -280,97 +419,74 @@ pub trait WebDriverCommands {
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// #     // Use find_element() to wait for the page to load.
-    /// #     driver.find_element(By::Id("button1"))?;
-    /// let ret = driver.execute_script(r#"
-    ///     let elem = document.getElementById("button1");
-    ///     elem.click();
-    ///     return elem;
-    ///     "#
-    /// )?;
-    /// let elem_out = ret.get_element()?;
-    /// assert_eq!(elem_out.text()?, "BUTTON 1");
-    /// let elem = driver.find_element(By::Id("button-result"))?;
-    /// assert_eq!(elem.text()?, "Button 1 clicked");
+    /// let info = driver.browser_info();
+    /// println!("{} {} on {}", info.name, info.version, info.platform);
     /// #     Ok(())
     /// # }
     /// ```
-    fn execute_script(&self, script: &str) -> WebDriverResult<ScriptRetSync> {
-        let v = self.cmd(Command::ExecuteScript(script.to_owned(), Vec::new()))?;
-        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    fn browser_info(&self) -> BrowserInfo {
+        BrowserInfo::from_capabilities(self.session().capabilities())
     }
 
-    /// Execute the specified Javascript synchronously and return the result.
+    /// Check that the server and this session are still working, and
+    /// measure how long each check takes.
+    ///
+    /// This queries the server's `/status` endpoint, makes a cheap request
+    /// within the session and, if enabled in the options, loads
+    /// `about:blank`. Failures are recorded in the report rather than
+    /// returned as errors, so this is suitable as a liveness probe between
+    /// jobs.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::HealthCheckOptions;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// let elem = driver.find_element(By::Id("button1"))?;
-    /// let mut args = ScriptArgs::new();
-    /// args.push(elem.clone())?;
-    /// args.push("TESTING")?;
-    /// let ret = driver.execute_script_with_args(r#"
-    ///     arguments[0].innerHTML = arguments[1];
-    ///     return arguments[0];
-    ///     "#, &args
-    /// )?;
-    /// let elem_out = ret.get_element()?;
-    /// assert_eq!(elem_out.element_id, elem.element_id);
-    /// assert_eq!(elem_out.text()?, "TESTING");
+    /// let report = driver.health_check(&HealthCheckOptions::new().with_navigation(true));
+    /// assert!(report.is_healthy(), "unhealthy: {:?}", report);
+    /// println!("session responded in {:?}", report.session.latency);
     /// #     Ok(())
     /// # }
     /// ```
-    fn execute_script_with_args(
-        &self,
-        script: &str,
-        args: &ScriptArgs,
-    ) -> WebDriverResult<ScriptRetSync> {
-        let v = self.cmd(Command::ExecuteScript(script.to_owned(), args.get_args()))?;
-        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    fn health_check(&self, options: &HealthCheckOptions) -> HealthReport {
+        health_check(self.session(), options)
     }
 
-    /// Execute the specified Javascrypt asynchronously and return the result.
+    /// Compare the clocks of the test host, the WebDriver server and the
+    /// browser, to diagnose failures of time-based assertions.
+    ///
+    /// The server time is taken from the `Date` header of the response, so
+    /// it is only available with HTTP clients that record it, which the
+    /// default client does.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// #     // Use find_element() to wait for the page to load.
-    /// #     driver.find_element(By::Id("button1"))?;
-    /// let ret = driver.execute_async_script(r#"
-    ///     // Selenium automatically provides an extra argument which is a
-    ///     // function that receives the return value(s).
-    ///     let done = arguments[0];
-    ///     window.setTimeout(() => {
-    ///         let elem = document.getElementById("button1");
-    ///         elem.click();
-    ///         done(elem);
-    ///     }, 1000);
-    ///     "#
-    /// )?;
-    /// let elem_out = ret.get_element()?;
-    /// assert_eq!(elem_out.text()?, "BUTTON 1");
-    /// let elem = driver.find_element(By::Id("button-result"))?;
-    /// assert_eq!(elem.text()?, "Button 1 clicked");
+    /// let report = driver.clock_report()?;
+    /// println!("clock skew: {}", report);
+    /// assert!(report.max_skew() < Duration::from_secs(2), "clocks differ: {}", report);
     /// #     Ok(())
     /// # }
     /// ```
-    fn execute_async_script(&self, script: &str) -> WebDriverResult<ScriptRetSync> {
-        let v = self.cmd(Command::ExecuteAsyncScript(script.to_owned(), Vec::new()))?;
-        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    fn clock_report(&self) -> WebDriverResult<ClockReport> {
+        clock_report(self.session())
     }
 
-    /// Execute the specified Javascrypt asynchronously and return the result.
+    /// Tag the session with the specified key and value, e.g. the test name
+    /// or build id, replacing any existing value for the key.
+    ///
+    /// The tags are logged, included in transaction reports and in the
+    /// page context of error messages, and forwarded to Sauce Labs,
+    /// BrowserStack or LambdaTest when the session capabilities show that the
+    /// session is running there. See [SessionMetadata](struct.SessionMetadata.html).
     ///
     /// # Example:
     /// ```rust
@@ -379,37 +495,22 @@ pub trait WebDriverCommands {
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// let elem = driver.find_element(By::Id("button1"))?;
-    /// let mut args = ScriptArgs::new();
-    /// args.push(elem.clone())?;
-    /// args.push("TESTING")?;
-    /// let ret = driver.execute_async_script_with_args(r#"
-    ///     // Selenium automatically provides an extra argument which is a
-    ///     // function that receives the return value(s).
-    ///     let done = arguments[2];
-    ///     window.setTimeout(() => {
-    ///         arguments[0].innerHTML = arguments[1];
-    ///         done(arguments[0]);
-    ///     }, 1000);
-    ///     "#, &args
-    /// )?;
-    /// let elem_out = ret.get_element()?;
-    /// assert_eq!(elem_out.element_id, elem.element_id);
-    /// assert_eq!(elem_out.text()?, "TESTING");
+    /// driver.set_session_metadata("name", "checkout with saved card")?;
+    /// driver.set_session_metadata("build", &std::env::var("CI_BUILD_ID").unwrap_or_default())?;
+    /// assert_eq!(driver.session_metadata().get("name"), Some("checkout with saved card"));
     /// #     Ok(())
     /// # }
     /// ```
-    fn execute_async_script_with_args(
-        &self,
-        script: &str,
-        args: &ScriptArgs,
-    ) -> WebDriverResult<ScriptRetSync> {
-        let v = self.cmd(Command::ExecuteAsyncScript(script.to_owned(), args.get_args()))?;
-        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    fn set_session_metadata(&self, key: &str, value: &str) -> WebDriverResult<()> {
+        set_session_metadata(self.session(), key, value)
     }
 
-    /// Get the current window handle.
+    /// The tags set with `set_session_metadata()`.
+    fn session_metadata(&self) -> SessionMetadata {
+        self.session().metadata()
+    }
+
+    /// Search for an element on the current page using the specified selector.
     ///
     /// # Example:
     /// ```rust
@@ -420,29 +521,22 @@ pub trait WebDriverCommands {
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
     /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// // Get the current window handle.
-    /// let handle = driver.current_window_handle()?;
-    /// // Open a new tab.
-    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
-    /// // Get window handles and switch to the new tab.
-    /// let handles = driver.window_handles()?;
-    /// driver.switch_to().window(&handles[1])?;
-    /// // We are now controlling the new tab.
-    /// driver.get("http://webappdemo")?;
-    /// assert_ne!(driver.current_window_handle()?, handle);
-    /// // Switch back to original tab.
-    /// driver.switch_to().window(&handle)?;
-    /// assert_eq!(driver.current_window_handle()?, handle);
+    /// let elem_text = driver.find_element(By::Name("input1"))?;
+    /// let elem_button = driver.find_element(By::Id("button-set"))?;
+    /// let elem_result = driver.find_element(By::Id("input-result"))?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn current_window_handle(&self) -> WebDriverResult<WindowHandle> {
-        let v = self.cmd(Command::GetWindowHandle)?;
-        convert_json::<String>(&v["value"]).map(WindowHandle::from)
+    fn find_element(&self, by: By) -> WebDriverResult<WebElement<'_>> {
+        notify(self.session(), |l| l.before_find(self.session(), &by));
+        let v = self
+            .cmd(Command::FindElement(by.get_w3c_selector()))
+            .map_err(|e| with_locator_suggestions(self.session(), &by, e))?;
+        convert_element_sync(self.session(), &v["value"])
     }
 
-    /// Get all window handles for the current session.
+    /// Search for all elements on the current page that match the specified
+    /// selector.
     ///
     /// # Example:
     /// ```rust
@@ -452,63 +546,85 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// assert_eq!(driver.window_handles()?.len(), 1);
-    /// // Open a new tab.
-    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
-    /// // Get window handles and switch to the new tab.
-    /// let handles = driver.window_handles()?;
-    /// assert_eq!(handles.len(), 2);
-    /// driver.switch_to().window(&handles[1])?;
+    /// let elems = driver.find_elements(By::ClassName("section"))?;
+    /// for elem in elems {
+    ///     assert!(elem.get_attribute("class")?.expect("Missing class on element").contains("section"));
+    /// }
     /// #     Ok(())
     /// # }
     /// ```
-    fn window_handles(&self) -> WebDriverResult<Vec<WindowHandle>> {
-        let v = self.cmd(Command::GetWindowHandles)?;
-        let strings: Vec<String> = convert_json_vec(&v["value"])?;
-        Ok(strings.iter().map(WindowHandle::from).collect())
+    fn find_elements(&self, by: By) -> WebDriverResult<Vec<WebElement<'_>>> {
+        notify(self.session(), |l| l.before_find(self.session(), &by));
+        let v = self.cmd(Command::FindElements(by.get_w3c_selector()))?;
+        convert_elements_sync(self.session(), &v["value"])
     }
 
-    /// Maximize the current window.
+    /// Search for an element on the current page using the locator with the
+    /// specified name in the [Locators](struct.Locators.html) registry set
+    /// with
+    /// [WebDriver::set_locators()](struct.GenericWebDriver.html#method.set_locators).
+    ///
+    /// Returns an InvalidArgument error if there is no locator with that
+    /// name. A NoSuchElement error mentions the name of the locator.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::Locators;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
-    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let mut locators = Locators::new();
+    /// locators.insert("home.button1", By::Id("button1"));
+    /// driver.set_locators(Some(locators));
     /// #     driver.get("http://webappdemo")?;
-    /// driver.maximize_window()?;
+    /// driver.find_named("home.button1")?.click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn maximize_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::MaximizeWindow).map(|_| ())
+    fn find_named(&self, name: &str) -> WebDriverResult<WebElement<'_>> {
+        let by = named_locator(self.session().locators(), name)?;
+        self.find_element(by.clone()).map_err(|e| with_locator_name(name, &by, e))
     }
 
-    /// Minimize the current window.
+    /// Search for all elements on the current page that match the locator
+    /// with the specified name. See [find_named()](#method.find_named).
+    fn find_all_named(&self, name: &str) -> WebDriverResult<Vec<WebElement<'_>>> {
+        let by = named_locator(self.session().locators(), name)?;
+        self.find_elements(by)
+    }
+
+    /// Search for an element on the current page using the specified selector,
+    /// returning None if no element matches.
+    ///
+    /// Unlike `find_element()`, this does not wait for the implicit wait timeout.
     ///
     /// # Example:
-    /// ```ignore
-    /// # // Minimize is not currently working on Chrome, but does work
-    /// # // on Firefox/geckodriver.
+    /// ```rust
     /// # use thirtyfour_sync::prelude::*;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// driver.minimize_window()?;
+    /// if let Some(button) = driver.find_element_opt(By::Id("button1"))? {
+    ///     button.click()?;
+    /// }
+    /// assert!(driver.find_element_opt(By::Id("no-such-element"))?.is_none());
+    /// #     let elem = driver.find_element(By::Id("button-result"))?;
+    /// #     assert_eq!(elem.text()?, "Button 1 clicked");
     /// #     Ok(())
     /// # }
     /// ```
-    fn minimize_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::MinimizeWindow).map(|_| ())
+    fn find_element_opt(&self, by: By) -> WebDriverResult<Option<WebElement<'_>>> {
+        Ok(find_elements_nowait(self.session(), by)?.into_iter().next())
     }
 
-    /// Make the current window fullscreen.
+    /// Return true if any element on the current page matches the specified
+    /// selector.
+    ///
+    /// This does not wait for the implicit wait timeout.
     ///
     /// # Example:
     /// ```rust
@@ -518,77 +634,1546 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// driver.fullscreen_window()?;
+    /// assert!(driver.element_exists(By::Id("pagetextinput"))?);
+    /// assert!(!driver.element_exists(By::Id("no-such-element"))?);
     /// #     Ok(())
     /// # }
     /// ```
-    fn fullscreen_window(&self) -> WebDriverResult<()> {
-        self.cmd(Command::FullscreenWindow).map(|_| ())
+    fn element_exists(&self, by: By) -> WebDriverResult<bool> {
+        Ok(!find_elements_nowait(self.session(), by)?.is_empty())
     }
 
-    /// Get the current window rectangle, in pixels.
+    /// Search for an element using each of the specified selectors in turn,
+    /// and return the index of the first selector that matched along with the
+    /// element. This is useful when a page has several possible layouts.
     ///
-    /// The returned Rect struct has members `x`, `y`, `width`, `height`,
-    /// all i32.
+    /// All selectors are tried on each poll attempt, using the default
+    /// [ElementPoller](query/enum.ElementPoller.html). If no selector matches
+    /// in time, a NoSuchElement error is returned.
     ///
     /// # Example:
     /// ```rust
-    /// use thirtyfour::OptionRect;
     /// # use thirtyfour_sync::prelude::*;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// let option_rect = OptionRect::new().with_pos(1, 1).with_size(800, 600);
-    /// driver.set_window_rect(option_rect.clone())?;
-    /// let rect = driver.get_window_rect()?;
-    /// assert_eq!(OptionRect::from(rect), option_rect);
+    /// let (index, elem) = driver.find_first_of(&[
+    ///     By::Css("#no-such-element button"),
+    ///     By::Css("div[data-section='section-buttons'] #button1"),
+    /// ])?;
+    /// assert_eq!(index, 1);
+    /// elem.click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn get_window_rect(&self) -> WebDriverResult<Rect> {
-        let v = self.cmd(Command::GetWindowRect)?;
-        convert_json(&v["value"])
+    fn find_first_of(&self, selectors: &[By]) -> WebDriverResult<(usize, WebElement<'_>)> {
+        let description = format!("no element matched any of {:?}", selectors);
+        let result = without_implicit_wait(self.session(), || {
+            ElementPoller::default().poll(&description, || {
+                for (i, by) in selectors.iter().enumerate() {
+                    if let Some(elem) = self.find_elements(by.clone())?.into_iter().next() {
+                        return Ok(Some((i, elem)));
+                    }
+                }
+                Ok(None)
+            })
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                Err(no_such_element(&with_page_context(self.session(), &description)))
+            }
+            x => x,
+        }
     }
 
-    /// Set the current window rectangle, in pixels.
-    ///
-    /// This requires an OptionRect, which is similar to Rect except all
-    /// members are wrapped in Option.
+    /// Start building a query for elements matching the specified selector,
+    /// that polls until they appear. More selectors can be added with
+    /// `or()`, and the elements can be filtered by text, attributes and
+    /// whether they are displayed. See
+    /// [ElementQuery](query/struct.ElementQuery.html).
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::OptionRect;
+    /// use std::time::Duration;
+    ///
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// let r = OptionRect::new().with_size(1280, 720);
-    /// driver.set_window_rect(r)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let button = driver
+    ///     .query(By::Tag("button"))
+    ///     .with_text("Button 1")
+    ///     .displayed()
+    ///     .with_timeout(Duration::from_secs(5))
+    ///     .first()?;
+    /// button.click()?;
+    /// assert!(!driver.query(By::Id("no-such-element")).nowait().exists()?);
     /// #     Ok(())
     /// # }
     /// ```
+    fn query<'a>(&'a self, by: By<'a>) -> ElementQuery<'a> {
+        ElementQuery::new(self.session(), None, by)
+    }
+
+    /// Find the element using the specified selector, and pin it to the
+    /// current page, so that using it after a navigation returns a clear
+    /// error or finds it again. See [PinnedElement](pinned/struct.PinnedElement.html).
     ///
-    /// You can also convert from a Rect if you want to get the window size
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let button = driver.pin_element(By::Id("button1"))?;
+    /// button.click()?;
+    /// button.get()?.wait_until().displayed()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn pin_element<'a>(&'a self, by: By<'a>) -> WebDriverResult<PinnedElement<'a>> {
+        PinnedElement::new(self.session(), by)
+    }
+
+    /// Send the independent, read-only commands added by the closure
+    /// together, over several connections at once, and return all of their
+    /// results. This saves round trips to a distant server, although the
+    /// server may still run the commands one at a time.
+    ///
+    /// The commands may run in any order, so they must not depend on each
+    /// other. See [Batch](batch/struct.Batch.html).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let ((title, url, buttons), mut results) = driver.batch(|b| {
+    ///     (b.title(), b.current_url(), b.find_elements(By::Tag("button")))
+    /// });
+    /// println!("{} at {}", results.take(title)?, results.take(url)?);
+    /// let buttons = results.take(buttons)?;
+    ///
+    /// // Read the text of every button at once.
+    /// let (keys, mut results) = driver.batch(|b| buttons.iter().map(|e| b.text(e)).collect::<Vec<_>>());
+    /// for key in keys {
+    ///     println!("button: {}", results.take(key)?);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn batch<'a, R, F>(&'a self, f: F) -> (R, BatchResults<'a>)
+    where
+        F: FnOnce(&mut Batch<'a>) -> R,
+    {
+        let mut batch = Batch::new(self.session());
+        let keys = f(&mut batch);
+        (keys, batch.run())
+    }
+
+    /// Locate a template image on the screen, for UIs that are drawn on a canvas
+    /// and have no DOM elements to find. Only available with the
+    /// `visual-locator` feature.
+    ///
+    /// The template must be a PNG captured at the same device pixel ratio as
+    /// the browser, e.g. cropped from an earlier screenshot. The `threshold` is
+    /// the minimum normalized cross-correlation score, from -1.0 to 1.0, that
+    /// counts as a match. Around 0.9 is a good starting point.
+    ///
+    /// Returns a NoSuchElement error if no part of the screen matches well enough.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/map")?;
+    /// let template = std::fs::read("tests/images/map-marker.png")?;
+    /// let marker = driver.find_by_image(&template, 0.9)?;
+    /// println!("Found marker at {:?} (score {})", marker.center(), marker.score);
+    /// marker.click()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "visual-locator")]
+    fn find_by_image(
+        &self,
+        template_png: &[u8],
+        threshold: f64,
+    ) -> WebDriverResult<ImageMatch<'_>> {
+        find_by_image(self.session(), template_png, threshold)
+    }
+
+    /// Find text on the screen using OCR, for content that is drawn on a
+    /// canvas or embedded in a PDF viewer and is not in the DOM. Only available
+    /// with the `ocr` feature, which requires the `tesseract` executable to be
+    /// installed and on the `PATH`.
+    ///
+    /// Whitespace in the text is normalized and the search is case-sensitive.
+    /// Matches can span several words but not several lines. Returns an empty
+    /// Vec if the text is not found. OCR is not exact, so check the returned
+    /// `line` rather than relying on every character being recognized.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/cart")?;
+    /// let matches = driver.find_text_on_screen("Total: ")?;
+    /// assert_eq!(matches.len(), 1);
+    /// println!("Found {:?} at {:?}", matches[0].line, matches[0].center());
+    /// matches[0].click()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "ocr")]
+    fn find_text_on_screen(&self, text: &str) -> WebDriverResult<Vec<TextMatch<'_>>> {
+        find_text_on_screen(self.session(), text)
+    }
+
+    /// Execute the specified Javascript synchronously and return the result.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     // Use find_element() to wait for the page to load.
+    /// #     driver.find_element(By::Id("button1"))?;
+    /// let ret = driver.execute_script(r#"
+    ///     let elem = document.getElementById("button1");
+    ///     elem.click();
+    ///     return elem;
+    ///     "#
+    /// )?;
+    /// let elem_out = ret.get_element()?;
+    /// assert_eq!(elem_out.text()?, "BUTTON 1");
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// assert_eq!(elem.text()?, "Button 1 clicked");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_script(&self, script: &str) -> WebDriverResult<ScriptRetSync<'_>> {
+        let v = self.cmd(Command::ExecuteScript(script.to_owned(), Vec::new()))?;
+        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    }
+
+    /// Execute the specified Javascript synchronously and return the result.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let mut args = ScriptArgs::new();
+    /// args.push(elem.clone())?;
+    /// args.push("TESTING")?;
+    /// let ret = driver.execute_script_with_args(r#"
+    ///     arguments[0].innerHTML = arguments[1];
+    ///     return arguments[0];
+    ///     "#, &args
+    /// )?;
+    /// let elem_out = ret.get_element()?;
+    /// assert_eq!(elem_out.element_id, elem.element_id);
+    /// assert_eq!(elem_out.text()?, "TESTING");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_script_with_args(
+        &self,
+        script: &str,
+        args: &ScriptArgs,
+    ) -> WebDriverResult<ScriptRetSync<'_>> {
+        let v = self.cmd(Command::ExecuteScript(script.to_owned(), args.get_args()))?;
+        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    }
+
+    /// Execute the specified Javascrypt asynchronously and return the result.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     // Use find_element() to wait for the page to load.
+    /// #     driver.find_element(By::Id("button1"))?;
+    /// let ret = driver.execute_async_script(r#"
+    ///     // Selenium automatically provides an extra argument which is a
+    ///     // function that receives the return value(s).
+    ///     let done = arguments[0];
+    ///     window.setTimeout(() => {
+    ///         let elem = document.getElementById("button1");
+    ///         elem.click();
+    ///         done(elem);
+    ///     }, 1000);
+    ///     "#
+    /// )?;
+    /// let elem_out = ret.get_element()?;
+    /// assert_eq!(elem_out.text()?, "BUTTON 1");
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// assert_eq!(elem.text()?, "Button 1 clicked");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_async_script(&self, script: &str) -> WebDriverResult<ScriptRetSync<'_>> {
+        let v = self.cmd(Command::ExecuteAsyncScript(script.to_owned(), Vec::new()))?;
+        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    }
+
+    /// Execute the specified Javascrypt asynchronously and return the result.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let mut args = ScriptArgs::new();
+    /// args.push(elem.clone())?;
+    /// args.push("TESTING")?;
+    /// let ret = driver.execute_async_script_with_args(r#"
+    ///     // Selenium automatically provides an extra argument which is a
+    ///     // function that receives the return value(s).
+    ///     let done = arguments[2];
+    ///     window.setTimeout(() => {
+    ///         arguments[0].innerHTML = arguments[1];
+    ///         done(arguments[0]);
+    ///     }, 1000);
+    ///     "#, &args
+    /// )?;
+    /// let elem_out = ret.get_element()?;
+    /// assert_eq!(elem_out.element_id, elem.element_id);
+    /// assert_eq!(elem_out.text()?, "TESTING");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_async_script_with_args(
+        &self,
+        script: &str,
+        args: &ScriptArgs,
+    ) -> WebDriverResult<ScriptRetSync<'_>> {
+        let v = self.cmd(Command::ExecuteAsyncScript(script.to_owned(), args.get_args()))?;
+        Ok(ScriptRetSync::new(self.session(), v["value"].clone()))
+    }
+
+    /// Execute the specified Javascript synchronously and deserialize the
+    /// result into the specified type. Use
+    /// [ScriptArgsExt](trait.ScriptArgsExt.html) to build the arguments.
+    ///
+    /// Returns an UnknownResponse error, with the path to the value that did
+    /// not match, if the result cannot be deserialized. To get elements from
+    /// the result, use `execute_script_with_args()` instead.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Viewport {
+    ///     width: u32,
+    ///     height: u32,
+    /// }
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let viewport: Viewport = driver.execute_script_typed(
+    ///     "return {width: window.innerWidth, height: window.innerHeight};",
+    ///     &ScriptArgs::new(),
+    /// )?;
+    /// let sum: i64 = driver.execute_script_typed(
+    ///     "return arguments[0] + arguments[1];",
+    ///     &ScriptArgs::new().with(40)?.with(2)?,
+    /// )?;
+    /// assert_eq!(sum, 42);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_script_typed<T>(&self, script: &str, args: &ScriptArgs) -> WebDriverResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let v = self.cmd(Command::ExecuteScript(script.to_owned(), args.get_args()))?;
+        convert_json_from("the script result", &v["value"])
+    }
+
+    /// Execute the specified Javascript asynchronously and deserialize the
+    /// result into the specified type. The script receives a callback as
+    /// its last argument, which it calls with the result.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let names: Vec<String> = driver.execute_async_script_typed(
+    ///     r#"
+    ///     let done = arguments[arguments.length - 1];
+    ///     window.setTimeout(() => done(arguments[0].map(n => n.toUpperCase())), 100);
+    ///     "#,
+    ///     &ScriptArgs::new().with(vec!["a", "b"])?,
+    /// )?;
+    /// assert_eq!(names, vec!["A", "B"]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn execute_async_script_typed<T>(&self, script: &str, args: &ScriptArgs) -> WebDriverResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let v = self.cmd(Command::ExecuteAsyncScript(script.to_owned(), args.get_args()))?;
+        convert_json_from("the script result", &v["value"])
+    }
+
+    /// Get the current window handle.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// // Get the current window handle.
+    /// let handle = driver.current_window_handle()?;
+    /// // Open a new tab.
+    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
+    /// // Get window handles and switch to the new tab.
+    /// let handles = driver.window_handles()?;
+    /// driver.switch_to().window(&handles[1])?;
+    /// // We are now controlling the new tab.
+    /// driver.get("http://webappdemo")?;
+    /// assert_ne!(driver.current_window_handle()?, handle);
+    /// // Switch back to original tab.
+    /// driver.switch_to().window(&handle)?;
+    /// assert_eq!(driver.current_window_handle()?, handle);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn current_window_handle(&self) -> WebDriverResult<WindowHandle> {
+        let v = self.cmd(Command::GetWindowHandle)?;
+        convert_json::<String>("Get Window Handle", &v["value"]).map(WindowHandle::from)
+    }
+
+    /// Get all window handles for the current session.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// assert_eq!(driver.window_handles()?.len(), 1);
+    /// // Open a new tab.
+    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
+    /// // Get window handles and switch to the new tab.
+    /// let handles = driver.window_handles()?;
+    /// assert_eq!(handles.len(), 2);
+    /// driver.switch_to().window(&handles[1])?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn window_handles(&self) -> WebDriverResult<Vec<WindowHandle>> {
+        let v = self.cmd(Command::GetWindowHandles)?;
+        let strings: Vec<String> = convert_json("Get Window Handles", &v["value"])?;
+        Ok(strings.iter().map(WindowHandle::from).collect())
+    }
+
+    /// Wait for a window that is not in the specified list of handles to
+    /// open, and return its handle. If more than one new window has opened,
+    /// the first one reported by the browser is returned.
+    ///
+    /// This does not switch to the new window. Returns a Timeout error if no
+    /// new window opens in time. See also
+    /// [expect_new_window()](#method.expect_new_window).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let handles = driver.window_handles()?;
+    /// driver.execute_script(r#"setTimeout(() => window.open("about:blank"), 100);"#)?;
+    /// let popup = driver.wait_for_new_window(&handles, Duration::from_secs(5))?;
+    /// driver.switch_to().window(&popup)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn wait_for_new_window(
+        &self,
+        existing: &[WindowHandle],
+        timeout: Duration,
+    ) -> WebDriverResult<WindowHandle> {
+        let description = format!("no new window opened (had {} windows)", existing.len());
+        let poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(250));
+        let result = poller.poll(&description, || {
+            Ok(self.window_handles()?.into_iter().find(|h| !existing.contains(h)))
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                Err(WebDriverError::Timeout(with_page_context(self.session(), &description)))
+            }
+            x => x,
+        }
+    }
+
+    /// Run the specified action, and wait for it to open a new window.
+    /// Returns the handle of the new window.
+    ///
+    /// The window handles are recorded before the action runs, so a window
+    /// that opens immediately is not missed. This does not switch to the
+    /// new window. Returns a Timeout error if no new window opens in time.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let popup = driver.expect_new_window(Duration::from_secs(5), |d| {
+    ///     d.execute_script(r#"window.open("about:blank");"#)?;
+    ///     Ok(())
+    /// })?;
+    /// driver.switch_to().window(&popup)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn expect_new_window<F>(&self, timeout: Duration, action: F) -> WebDriverResult<WindowHandle>
+    where
+        Self: Sized,
+        F: FnOnce(&Self) -> WebDriverResult<()>,
+    {
+        let existing = self.window_handles()?;
+        action(self)?;
+        self.wait_for_new_window(&existing, timeout)
+    }
+
+    /// Close every window except the current one, and switch back to it.
+    /// Returns the handles of the windows that were closed.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
+    /// driver.close_other_windows()?;
+    /// assert_eq!(driver.window_handles()?.len(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn close_other_windows(&self) -> WebDriverResult<Vec<WindowHandle>> {
+        let current = self.current_window_handle()?;
+        let mut closed = Vec::new();
+        for handle in self.window_handles()? {
+            if handle != current {
+                self.switch_to().window(&handle)?;
+                self.close()?;
+                closed.push(handle);
+            }
+        }
+        self.switch_to().window(&current)?;
+        Ok(closed)
+    }
+
+    /// Close every window for which the specified function returns true.
+    /// The function is called with the title and URL of each window.
+    /// Returns the handles of the windows that were closed.
+    ///
+    /// Afterwards, focus returns to the window that was current before, or
+    /// to the first remaining window if that one was closed. The last
+    /// window is never closed, since that would end the session.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
+    /// let closed = driver.close_windows_where(|_title, url| url == "about:blank")?;
+    /// assert_eq!(closed.len(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn close_windows_where<F>(&self, mut f: F) -> WebDriverResult<Vec<WindowHandle>>
+    where
+        Self: Sized,
+        F: FnMut(&str, &str) -> bool,
+    {
+        // The current window may already have been closed.
+        let current = self.current_window_handle().ok();
+        let handles = self.window_handles()?;
+        let mut closed = Vec::new();
+        for handle in &handles {
+            if closed.len() + 1 == handles.len() {
+                break;
+            }
+            self.switch_to().window(handle)?;
+            let info = self.page_info()?;
+            if f(&info.title, &info.url) {
+                self.close()?;
+                closed.push(handle.clone());
+            }
+        }
+
+        let remaining: Vec<&WindowHandle> =
+            handles.iter().filter(|h| !closed.contains(h)).collect();
+        let focus = match current {
+            Some(ref h) if remaining.contains(&h) => Some(h),
+            _ => remaining.first().copied(),
+        };
+        if let Some(handle) = focus {
+            self.switch_to().window(handle)?;
+        }
+        Ok(closed)
+    }
+
+    /// Maximize the current window.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.maximize_window()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn maximize_window(&self) -> WebDriverResult<()> {
+        self.cmd(Command::MaximizeWindow).map(|_| ())
+    }
+
+    /// Minimize the current window.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// # // Minimize is not currently working on Chrome, but does work
+    /// # // on Firefox/geckodriver.
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.minimize_window()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn minimize_window(&self) -> WebDriverResult<()> {
+        self.cmd(Command::MinimizeWindow).map(|_| ())
+    }
+
+    /// Make the current window fullscreen.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.fullscreen_window()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn fullscreen_window(&self) -> WebDriverResult<()> {
+        self.cmd(Command::FullscreenWindow).map(|_| ())
+    }
+
+    /// Get the current window rectangle, in pixels.
+    ///
+    /// The returned Rect struct has members `x`, `y`, `width`, `height`,
+    /// all i32.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use thirtyfour::OptionRect;
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let option_rect = OptionRect::new().with_pos(1, 1).with_size(800, 600);
+    /// driver.set_window_rect(option_rect.clone())?;
+    /// let rect = driver.get_window_rect()?;
+    /// assert_eq!(OptionRect::from(rect), option_rect);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn get_window_rect(&self) -> WebDriverResult<Rect> {
+        let v = self.cmd(Command::GetWindowRect)?;
+        convert_json("Get Window Rect", &v["value"])
+    }
+
+    /// Set the current window rectangle, in pixels.
+    ///
+    /// This requires an OptionRect, which is similar to Rect except all
+    /// members are wrapped in Option.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::OptionRect;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let r = OptionRect::new().with_size(1280, 720);
+    /// driver.set_window_rect(r)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// You can also convert from a Rect if you want to get the window size
     /// and modify it before setting it again.
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::OptionRect;
+    /// use thirtyfour::OptionRect;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let rect = driver.get_window_rect()?;
+    /// let option_rect = OptionRect::from(rect);
+    /// driver.set_window_rect(option_rect.with_width(1024))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_window_rect(&self, rect: OptionRect) -> WebDriverResult<()> {
+        self.cmd(Command::SetWindowRect(rect)).map(|_| ())
+    }
+
+    /// Go back. This is equivalent to clicking the browser's back button.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// driver.back()?;
+    /// #     assert_eq!(driver.title()?, "");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn back(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Back)?;
+        self.wait_for_ready()
+    }
+
+    /// Go forward. This is equivalent to clicking the browser's forward button.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// #     driver.back()?;
+    /// #     assert_eq!(driver.title()?, "");
+    /// driver.forward()?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn forward(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Forward)?;
+        self.wait_for_ready()
+    }
+
+    /// Refresh the current page.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// driver.refresh()?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn refresh(&self) -> WebDriverResult<()> {
+        self.cmd(Command::Refresh)?;
+        self.wait_for_ready()
+    }
+
+    /// Get all timeouts for the current session.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     let set_timeouts = TimeoutConfiguration::new(
+    /// #         Some(Duration::new(1, 0)),
+    /// #         Some(Duration::new(2, 0)),
+    /// #         Some(Duration::new(3, 0))
+    /// #     );
+    /// #     driver.set_timeouts(set_timeouts.clone())?;
+    /// let timeouts = driver.get_timeouts()?;
+    /// println!("Page load timeout = {:?}", timeouts.page_load());
+    /// #     assert_eq!(timeouts.script(), Some(Duration::new(1, 0)));
+    /// #     assert_eq!(timeouts.page_load(), Some(Duration::new(2, 0)));
+    /// #     assert_eq!(timeouts.implicit(), Some(Duration::new(3, 0)));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn get_timeouts(&self) -> WebDriverResult<TimeoutConfiguration> {
+        let v = self.cmd(Command::GetTimeouts)?;
+        convert_json("Get Timeouts", &v["value"])
+    }
+
+    /// Set all timeouts for the current session.
+    ///
+    /// NOTE: If you set timeouts to values greater than 120 seconds,
+    ///       remember to also increase the request timeout.
+    ///       See `WebDriver::set_request_timeout()` for more details.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// // Setting timeouts to None means those timeout values will not be updated.
+    /// let timeouts = TimeoutConfiguration::new(None, Some(Duration::new(11, 0)), None);
+    /// driver.set_timeouts(timeouts.clone())?;
+    /// #     let got_timeouts = driver.get_timeouts()?;
+    /// #     assert_eq!(got_timeouts.page_load(), Some(Duration::new(11, 0)));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
+        self.cmd(Command::SetTimeouts(timeouts)).map(|_| ())
+    }
+
+    /// Set the implicit wait timeout. This is how long the WebDriver will
+    /// wait when querying elements.
+    ///
+    /// By default this is set to 30 seconds.
+    ///
+    /// **NOTE:** Depending on the kind of testing you want to do, you may
+    /// find it more reliable to set the implicit wait time to 0 (no wait)
+    /// and implement your own polling loop outside of `thirtyfour`.
+    ///
+    /// NOTE: If you set any timeouts to values greater than 120 seconds,
+    ///       remember to also increase the request timeout.
+    ///       See `WebDriver::set_request_timeout()` for more details.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_implicit_wait_timeout(delay)?;
+    /// #     let got_timeouts = driver.get_timeouts()?;
+    /// #     assert_eq!(got_timeouts.implicit(), Some(delay));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_implicit_wait_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(None, None, Some(time_to_wait));
+        self.set_timeouts(timeouts)
+    }
+
+    /// Set the script timeout. This is how long the WebDriver will wait for a
+    /// Javascript script to execute.
+    ///
+    /// By default this is set to 60 seconds.
+    ///
+    /// NOTE: If you set any timeouts to values greater than 120 seconds,
+    ///       remember to also increase the request timeout.
+    ///       See `WebDriver::set_request_timeout()` for more details.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_script_timeout(delay)?;
+    /// #     let got_timeouts = driver.get_timeouts()?;
+    /// #     assert_eq!(got_timeouts.script(), Some(delay));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_script_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(Some(time_to_wait), None, None);
+        self.set_timeouts(timeouts)
+    }
+
+    /// Set the page load timeout. This is how long the WebDriver will wait
+    /// for the page to finish loading.
+    ///
+    /// By default this is set to 60 seconds.
+    ///
+    /// NOTE: If you set any timeouts to values greater than 120 seconds,
+    ///       remember to also increase the request timeout.
+    ///       See `WebDriver::set_request_timeout()` for more details.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::TimeoutConfiguration;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let delay = Duration::new(11, 0);
+    /// driver.set_page_load_timeout(delay)?;
+    /// #     let got_timeouts = driver.get_timeouts()?;
+    /// #     assert_eq!(got_timeouts.page_load(), Some(delay));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_page_load_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
+        let timeouts = TimeoutConfiguration::new(None, Some(time_to_wait), None);
+        self.set_timeouts(timeouts)
+    }
+
+    /// Create a new action chain for this session. Action chains can be used
+    /// to simulate more complex user input actions involving key combinations,
+    /// mouse movements, mouse click, right-click, and more.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// let elem_text = driver.find_element(By::Name("input1"))?;
+    /// let elem_button = driver.find_element(By::Id("button-set"))?;
+    ///
+    /// driver.action_chain()
+    ///     .send_keys_to_element(&elem_text, "thirtyfour")
+    ///     .move_to_element_center(&elem_button)
+    ///     .click()
+    ///     .perform()?;
+    /// #     let elem_result = driver.find_element(By::Id("input-result"))?;
+    /// #     assert_eq!(elem_result.text()?, "thirtyfour");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn action_chain(&self) -> ActionChain<'_> {
+        ActionChain::new(self.session())
+    }
+
+    /// Get all cookies.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
+    /// #     driver.add_cookie(set_cookie)?;
+    /// let cookies = driver.get_cookies()?;
+    /// for cookie in &cookies {
+    ///     println!("Got cookie: {}", cookie.value());
+    /// }
+    /// #     assert_eq!(
+    /// #         cookies.iter().filter(|x| x.value() == &serde_json::json!("value")).count(), 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn get_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
+        let v = self.cmd(Command::GetAllCookies)?;
+        convert_json::<Vec<Cookie>>("Get All Cookies", &v["value"])
+    }
+
+    /// Get the specified cookie.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
+    /// #     driver.add_cookie(set_cookie)?;
+    /// let cookie = driver.get_cookie("key")?;
+    /// println!("Got cookie: {}", cookie.value());
+    /// #     assert_eq!(cookie.value(), &serde_json::json!("value"));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn get_cookie(&self, name: &str) -> WebDriverResult<Cookie> {
+        let v = self.cmd(Command::GetNamedCookie(name.to_string()))?;
+        convert_json::<Cookie>("Get Named Cookie", &v["value"])
+    }
+
+    /// Delete the specified cookie.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
+    /// #     driver.add_cookie(set_cookie)?;
+    /// #     assert!(driver.get_cookie("key").is_ok());
+    /// driver.delete_cookie("key")?;
+    /// #     assert!(driver.get_cookie("key").is_err());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn delete_cookie(&self, name: &str) -> WebDriverResult<()> {
+        self.cmd(Command::DeleteCookie(name.to_string())).map(|_| ())
+    }
+
+    /// Delete all cookies.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
+    /// #     driver.add_cookie(set_cookie)?;
+    /// #     assert!(driver.get_cookie("key").is_ok());
+    /// driver.delete_all_cookies()?;
+    /// #     assert!(driver.get_cookie("key").is_err());
+    /// #     assert!(driver.get_cookies()?.is_empty());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn delete_all_cookies(&self) -> WebDriverResult<()> {
+        self.cmd(Command::DeleteAllCookies).map(|_| ())
+    }
+
+    /// Add the specified cookie.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let cookie = Cookie::new("key", serde_json::json!("value"));
+    /// driver.add_cookie(cookie)?;
+    /// #     let got_cookie = driver.get_cookie("key")?;
+    /// #     assert_eq!(got_cookie.value(), &serde_json::json!("value"));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
+        self.cmd(Command::AddCookie(cookie)).map(|_| ())
+    }
+
+    /// Apply a previously exported [AuthState](struct.AuthState.html) to this
+    /// session, so that it starts out signed in.
+    ///
+    /// Call this before the first navigation. Cookies and storage can only be
+    /// set on a page of their origin, so this visits `/robots.txt` on each
+    /// origin in turn, which avoids running the site's own scripts, and then
+    /// leaves the browser on `about:blank`. Cookies whose domain has no
+    /// storage are set from `https://` on that domain, and cookies without a
+    /// domain are set on the first origin.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::AuthState;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let state = AuthState::from_file("alice.json")?;
+    /// driver.seed_auth_state(&state)?;
+    /// driver.get("http://localhost:8000/account")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn seed_auth_state(&self, state: &AuthState) -> WebDriverResult<()> {
+        seed_auth_state(self.session(), state)
+    }
+
+    /// Export the cookies and web storage of the specified origins, e.g.
+    /// after signing in, so that later sessions can start signed in with
+    /// [WebDriver::seed_auth_state()](trait.WebDriverCommands.html#method.seed_auth_state).
+    ///
+    /// Each origin is read from the current page if it is on that origin, or
+    /// else from `/robots.txt` on the origin, in which case the browser goes
+    /// back to the current URL afterwards. `sessionStorage` is that of the
+    /// current window.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::AuthExportOptions;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000")?;
+    /// // ... sign in ...
+    /// let options = AuthExportOptions::new().redact_secrets();
+    /// let state = driver.export_auth_state(&["http://localhost:8000"], &options)?;
+    /// state.to_file("alice.json")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn export_auth_state(
+        &self,
+        origins: &[&str],
+        options: &AuthExportOptions,
+    ) -> WebDriverResult<AuthState> {
+        export_auth_state(self.session(), origins, options)
+    }
+
+    /// Take a screenshot of the current window and return it as a
+    /// base64-encoded String.
+    fn screenshot_as_base64(&self) -> WebDriverResult<String> {
+        let v = self.cmd(Command::TakeScreenshot)?;
+        convert_json("Take Screenshot", &v["value"])
+    }
+
+    /// Take a screenshot of the current window and return it as PNG bytes.
+    fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        let s = self.screenshot_as_base64()?;
+        let bytes: Vec<u8> = decode(&s)?;
+        Ok(bytes)
+    }
+
+    /// Take a screenshot of the current window and write it to the specified
+    /// filename.
+    fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.screenshot_as_png()?;
+        let mut file = File::create(path)?;
+        file.write_all(&png)?;
+        Ok(())
+    }
+
+    /// Take a screenshot of the current window with the specified options,
+    /// and return it as PNG bytes.
+    ///
+    /// Elements to be redacted are covered with black boxes while the
+    /// screenshot is taken, so nothing behind them is captured. Only the
+    /// elements that exist when the screenshot is taken are redacted.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::ScreenshotOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let options = ScreenshotOptions::new().redact(&[By::Name("input1"), By::Name("input2")]);
+    /// let png = driver.screenshot_with_options(&options)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn screenshot_with_options(&self, options: &ScreenshotOptions) -> WebDriverResult<Vec<u8>> {
+        screenshot_with_options(self.session(), options)
+    }
+
+    /// Take a screenshot of the current window with the specified options,
+    /// along with its size in image pixels and CSS pixels and the device
+    /// pixel ratio of the display.
+    ///
+    /// Screenshots taken on a HiDPI display have more image pixels than CSS
+    /// pixels, e.g. twice as many in each direction at a ratio of 2. To get
+    /// the same size of image on every machine, resize the screenshot with
+    /// [ScreenshotOptions::with_device_pixel_ratio()](struct.ScreenshotOptions.html#method.with_device_pixel_ratio),
+    /// which requires the `screenshot-scaling` feature.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::ScreenshotOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let screenshot = driver.screenshot_with_details(&ScreenshotOptions::new())?;
+    /// println!(
+    ///     "{}x{} pixels, {}x{} CSS pixels at {}x",
+    ///     screenshot.width,
+    ///     screenshot.height,
+    ///     screenshot.logical_width(),
+    ///     screenshot.logical_height(),
+    ///     screenshot.device_pixel_ratio
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn screenshot_with_details(&self, options: &ScreenshotOptions) -> WebDriverResult<Screenshot> {
+        screenshot_with_details(self.session(), options)
+    }
+
+    /// Take a screenshot of the current window with the specified options,
+    /// and write it to the specified filename.
+    fn save_screenshot_with_options(
+        &self,
+        path: &Path,
+        options: &ScreenshotOptions,
+    ) -> WebDriverResult<()> {
+        let png = self.screenshot_with_options(options)?;
+        let mut file = File::create(path)?;
+        file.write_all(&png)?;
+        Ok(())
+    }
+
+    /// Return a SwitchTo struct for switching to another window or frame.
+    fn switch_to(&self) -> SwitchTo<'_> {
+        SwitchTo::new(self.session())
+    }
+
+    /// Search for an element in the current page and, if it is not found
+    /// there, in every frame on the page, including nested frames.
+    ///
+    /// The search starts from the top-level page and does not wait for
+    /// elements to appear. The result holds the element, the
+    /// [FramePath](struct.FramePath.html) of the frame containing it, and a
+    /// guard that keeps the session switched into that frame so the element
+    /// can be used. Dropping the result switches back to the top-level page.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pageiframe"))?.click()?;
+    /// let found = driver.find_element_anywhere(By::Id("button1"))?;
+    /// println!("found in {}", found.path());
+    /// found.element.click()?;
+    /// drop(found);
+    /// // Back in the top-level page.
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn find_element_anywhere(&self, by: By) -> WebDriverResult<FoundElement<'_>> {
+        find_element_anywhere(self.session(), by)
+    }
+
+    /// Switch from the top-level page through each frame in the path. The
+    /// returned guard switches back to the top-level page when dropped.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::FramePath;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pageiframe"))?.click()?;
+    /// let path = driver.find_element_anywhere(By::Id("button1"))?.path().clone();
+    /// // Later...
+    /// let guard = driver.enter_frame(&path)?;
+    /// driver.find_element(By::Id("button1"))?.click()?;
+    /// guard.leave()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn enter_frame(&self, path: &FramePath) -> WebDriverResult<FrameGuard<'_>> {
+        FrameGuard::enter(self.session(), path.clone())
+    }
+
+    /// Set the current window name.
+    /// Useful for switching between windows/tabs using `driver.switch_to().window_name(name)`.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// // Get the current window handle.
+    /// let handle = driver.current_window_handle()?;
+    /// driver.set_window_name("main")?;
+    /// // Open a new tab.
+    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
+    /// // Get window handles and switch to the new tab.
+    /// let handles = driver.window_handles()?;
+    /// driver.switch_to().window(&handles[1])?;
+    /// // We are now controlling the new tab.
+    /// driver.get("http://webappdemo")?;
+    /// assert_ne!(driver.current_window_handle()?, handle);
+    /// // Switch back to original tab using window name.
+    /// driver.switch_to().window_name("main")?;
+    /// assert_eq!(driver.current_window_handle()?, handle);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_window_name(&self, window_name: &str) -> WebDriverResult<()> {
+        self.execute_script(&format!(r#"window.name = "{}""#, window_name))?;
+        Ok(())
+    }
+
+    /// Running an extension command.
+    /// Extension commands are browser specific commands and using browser specific endpoints and
+    /// parameters.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use serde::Serialize;
+    /// use thirtyfour_sync::prelude::*;
+    /// use thirtyfour::{ExtensionCommand, RequestMethod};
+    ///
+    /// #[derive(Serialize)]
+    /// pub struct AddonInstallCommand {
+    ///    pub path: String,
+    ///    pub temporary: Option<bool>,
+    /// }
+    ///
+    /// impl ExtensionCommand for AddonInstallCommand {
+    ///    fn parameters_json(&self) -> Option<serde_json::Value> {
+    ///        Some(serde_json::to_value(self).unwrap())
+    ///    }
+    ///    fn method(&self) -> RequestMethod {
+    ///        RequestMethod::Post
+    ///    }
+    ///
+    ///    fn endpoint(&self) -> String {
+    ///        String::from("/moz/addon/install")
+    ///    }
+    /// }
+    ///
+    /// fn main()-> WebDriverResult<()> {
+    ///     let caps = DesiredCapabilities::firefox();
+    ///     let driver = WebDriver::new("http://localhost:4444", &caps)?;
+    ///
+    ///     let install_command = AddonInstallCommand {
+    ///         path: String::from("/path/to/addon.xpi"),
+    ///         temporary: Some(true),
+    ///     };
+    ///
+    ///     let response = driver.extension_command(install_command)?;
+    ///
+    ///     assert_eq!(response.is_string(), true);
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// ```
+    fn extension_command<T: ExtensionCommand + Send + Sync + 'static>(
+        &self,
+        ext_cmd: T,
+    ) -> WebDriverResult<serde_json::Value> {
+        let response = self.cmd(Command::ExtensionCommand(Box::new(ext_cmd)))?;
+
+        Ok(response["value"].clone())
+    }
+
+    /// Repeatedly scroll to the bottom of the page until the specified condition
+    /// is met, waiting for the page to settle (no DOM mutations or new network
+    /// resources) after each step.
+    ///
+    /// This is useful for harvesting content from pages that use infinite
+    /// scrolling or lazy loading. The returned report includes the number of
+    /// new elements that appeared after each step.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::{ScrollOptions, ScrollUntil};
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// let rect = driver.get_window_rect()?;
-    /// let option_rect = OptionRect::from(rect);
-    /// driver.set_window_rect(option_rect.with_width(1024))?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let report = driver.scroll_until(
+    ///     ScrollUntil::NoNewElements(By::Css("div.section")),
+    ///     ScrollOptions::new().with_max_steps(10),
+    /// )?;
+    /// for step in &report.steps {
+    ///     println!("{} new elements", step.new_elements);
+    /// }
+    /// #     assert!(report.total_elements() > 0);
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_window_rect(&self, rect: OptionRect) -> WebDriverResult<()> {
-        self.cmd(Command::SetWindowRect(rect)).map(|_| ())
+    fn scroll_until(
+        &self,
+        until: ScrollUntil,
+        options: ScrollOptions,
+    ) -> WebDriverResult<ScrollReport> {
+        scroll_until(self.session(), until, options)
     }
 
-    /// Go back. This is equivalent to clicking the browser's back button.
+    /// Navigate a hover menu: hover over the entry with each label in turn,
+    /// waiting for each submenu to appear, then click the last entry.
+    ///
+    /// Entries are found by their visible text, preferring links, buttons
+    /// and elements with a `menuitem` role. The pointer stays on each level
+    /// for a short time, so that menus which wait for hover intent open. Use
+    /// [navigate_menu_with_options()](#method.navigate_menu_with_options)
+    /// to change the timings.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000")?;
+    /// driver.navigate_menu(&["Products", "Databases", "Postgres"])?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn navigate_menu(&self, labels: &[&str]) -> WebDriverResult<()> {
+        navigate_menu(self.session(), labels, &MenuOptions::default())
+    }
+
+    /// Navigate a hover menu as with [navigate_menu()](#method.navigate_menu),
+    /// using the specified dwell time and poller.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::MenuOptions;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000")?;
+    /// let options = MenuOptions::new().with_dwell(Duration::from_millis(800));
+    /// driver.navigate_menu_with_options(&["Products", "Databases", "Postgres"], &options)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn navigate_menu_with_options(
+        &self,
+        labels: &[&str],
+        options: &MenuOptions,
+    ) -> WebDriverResult<()> {
+        navigate_menu(self.session(), labels, options)
+    }
+
+    /// Create a Paginator for stepping through the pages of a paginated listing,
+    /// using the specified selector to locate the "next" button.
+    ///
+    /// See [Paginator](struct.Paginator.html) for more details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.get("http://localhost:8000/search?q=rust")?;
+    /// let titles = driver
+    ///     .paginate(By::Css("a.next-page"))
+    ///     .with_max_pages(5)
+    ///     .for_each_page(|d, _page| d.title())?;
+    /// println!("visited {} pages", titles.len());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn paginate<'a>(&'a self, next_button: By<'a>) -> Paginator<'a> {
+        Paginator::new(self.session(), next_button)
+    }
+
+    /// Emulate losing the network connection, and wait for the page to receive
+    /// the `offline` event. This is only supported by Chromium-based browsers.
+    ///
+    /// The browser stays offline across navigations until `go_online()` is called.
     ///
     /// # Example:
     /// ```rust
@@ -598,17 +2183,20 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// driver.back()?;
-    /// #     assert_eq!(driver.title()?, "");
+    /// driver.go_offline()?;
+    /// let online: bool = driver.execute_script("return navigator.onLine;")?.convert()?;
+    /// assert!(!online);
+    /// driver.go_online()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn back(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Back).map(|_| ())
+    fn go_offline(&self) -> WebDriverResult<()> {
+        set_offline(self.session(), true)
     }
 
-    /// Go forward. This is equivalent to clicking the browser's forward button.
+    /// Restore the network connection after `go_offline()`, and wait for the
+    /// page to receive the `online` event. Other network conditions that were
+    /// set, such as throttling, are kept.
     ///
     /// # Example:
     /// ```rust
@@ -618,19 +2206,80 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// #     driver.back()?;
-    /// #     assert_eq!(driver.title()?, "");
-    /// driver.forward()?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// driver.go_offline()?;
+    /// driver.go_online()?;
+    /// let online: bool = driver.execute_script("return navigator.onLine;")?.convert()?;
+    /// assert!(online);
     /// #     Ok(())
     /// # }
     /// ```
-    fn forward(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Forward).map(|_| ())
+    fn go_online(&self) -> WebDriverResult<()> {
+        set_offline(self.session(), false)
     }
 
-    /// Refresh the current page.
+    /// Make the page see the viewport of the specified device, e.g. a
+    /// [Preset](enum.Preset.html) such as `Preset::IPhone12`.
+    ///
+    /// Chromium-based browsers emulate the device with DevTools: its
+    /// viewport size, pixel density, mobile layout, touch screen and user
+    /// agent, whatever the size of the window. This persists across
+    /// navigations. Other browsers resize the window so that the viewport
+    /// has the device's size, and the rest of the device is not emulated.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::Preset;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_viewport(Preset::IPhone12)?;
+    /// driver.get("http://webappdemo")?;
+    /// let width: u32 = driver.execute_script("return window.innerWidth;")?.convert()?;
+    /// assert_eq!(width, 390);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_viewport<D>(&self, device: D) -> WebDriverResult<()>
+    where
+        D: Into<Device>,
+    {
+        set_viewport(self.session(), &device.into())
+    }
+
+    /// Turn the device set with `set_viewport()` the specified way round. If
+    /// no device was set, the current viewport is turned instead.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::{Orientation, Preset};
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_viewport(Preset::Ipad)?;
+    /// driver.set_orientation(Orientation::Landscape)?;
+    /// driver.get("http://webappdemo")?;
+    /// let landscape: bool =
+    ///     driver.execute_script("return matchMedia('(orientation: landscape)').matches;")?.convert()?;
+    /// assert!(landscape);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn set_orientation(&self, orientation: Orientation) -> WebDriverResult<()> {
+        set_orientation(self.session(), orientation)
+    }
+
+    /// Zoom the page by the specified factor, e.g. `2.0` for 200%, the way
+    /// the browser's own zoom control does. The page reflows as if the
+    /// window were narrower, which is what WCAG resize-text checks need.
+    ///
+    /// On Chromium-based browsers this uses DevTools device metrics, and the
+    /// zoom persists across navigations. Elsewhere the CSS `zoom` property
+    /// is set on the root element, which must be done again after each
+    /// navigation.
     ///
     /// # Example:
     /// ```rust
@@ -640,198 +2289,353 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// driver.refresh()?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
+    /// driver.set_zoom(2.0)?;
+    /// let scrolls: bool = driver
+    ///     .execute_script("return document.documentElement.scrollWidth > window.innerWidth;")?
+    ///     .convert()?;
+    /// assert!(!scrolls, "page should reflow without horizontal scrolling");
+    /// driver.reset_zoom()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn refresh(&self) -> WebDriverResult<()> {
-        self.cmd(Command::Refresh).map(|_| ())
+    fn set_zoom(&self, factor: f64) -> WebDriverResult<()> {
+        set_zoom(self.session(), factor)
     }
 
-    /// Get all timeouts for the current session.
+    /// Restore the page to 100% zoom after `set_zoom()`.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.set_zoom(1.5)?;
+    /// driver.reset_zoom()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn reset_zoom(&self) -> WebDriverResult<()> {
+        set_zoom(self.session(), 1.0)
+    }
+
+    /// Apply the page's print stylesheets, as in print preview, so that
+    /// elements can be checked the way they will print. This is only
+    /// supported by Chromium-based browsers.
     ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     let set_timeouts = TimeoutConfiguration::new(
-    /// #         Some(Duration::new(1, 0)),
-    /// #         Some(Duration::new(2, 0)),
-    /// #         Some(Duration::new(3, 0))
-    /// #     );
-    /// #     driver.set_timeouts(set_timeouts.clone())?;
-    /// let timeouts = driver.get_timeouts()?;
-    /// println!("Page load timeout = {:?}", timeouts.page_load());
-    /// #     assert_eq!(timeouts.script(), Some(Duration::new(1, 0)));
-    /// #     assert_eq!(timeouts.page_load(), Some(Duration::new(2, 0)));
-    /// #     assert_eq!(timeouts.implicit(), Some(Duration::new(3, 0)));
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.emulate_print_media(true)?;
+    /// let printing: bool = driver.execute_script("return matchMedia('print').matches;")?.convert()?;
+    /// assert!(printing);
+    /// driver.emulate_print_media(false)?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn get_timeouts(&self) -> WebDriverResult<TimeoutConfiguration> {
-        let v = self.cmd(Command::GetTimeouts)?;
-        convert_json(&v["value"])
+    fn emulate_print_media(&self, enabled: bool) -> WebDriverResult<()> {
+        emulate_print_media(self.session(), enabled)
     }
 
-    /// Set all timeouts for the current session.
+    /// Print the current page and return the PDF bytes.
     ///
-    /// NOTE: If you set timeouts to values greater than 120 seconds,
-    ///       remember to also increase the request timeout.
-    ///       See `WebDriver::set_request_timeout()` for more details.
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::PrintOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let pdf = driver.print_to_pdf(&PrintOptions::new())?;
+    /// assert!(pdf.starts_with(b"%PDF"));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn print_to_pdf(&self, options: &PrintOptions) -> WebDriverResult<Vec<u8>> {
+        print_to_pdf(self.session(), options)
+    }
+
+    /// Print the current page and return the number of pages it takes.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
+    /// use thirtyfour_sync::PrintOptions;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let pages = driver.print_page_count(&PrintOptions::new())?;
+    /// assert!(pages <= 2, "report should fit on two pages");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn print_page_count(&self, options: &PrintOptions) -> WebDriverResult<usize> {
+        Ok(pdf_page_count(&print_to_pdf(self.session(), options)?))
+    }
+
+    /// Grant the page access to the camera and microphone, so that
+    /// `getUserMedia()` does not show a permission prompt.
     ///
+    /// On Chromium-based browsers the permissions are granted for every
+    /// origin. Other browsers grant them for the current origin, if they
+    /// support the W3C Permissions extension command; Firefox does not, so
+    /// use [FirefoxCapabilitiesExt::allow_media()](extensions/firefox/trait.FirefoxCapabilitiesExt.html#tymethod.allow_media)
+    /// before starting the session instead.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// // Setting timeouts to None means those timeout values will not be updated.
-    /// let timeouts = TimeoutConfiguration::new(None, Some(Duration::new(11, 0)), None);
-    /// driver.set_timeouts(timeouts.clone())?;
-    /// #     let got_timeouts = driver.get_timeouts()?;
-    /// #     assert_eq!(got_timeouts.page_load(), Some(Duration::new(11, 0)));
+    /// driver.get("http://localhost:8000/call")?;
+    /// driver.grant_media_permissions()?;
+    /// driver.find_element(By::Id("join"))?.click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_timeouts(&self, timeouts: TimeoutConfiguration) -> WebDriverResult<()> {
-        self.cmd(Command::SetTimeouts(timeouts)).map(|_| ())
+    fn grant_media_permissions(&self) -> WebDriverResult<()> {
+        grant_media_permissions(self.session())
     }
 
-    /// Set the implicit wait timeout. This is how long the WebDriver will
-    /// wait when querying elements.
+    /// List the cameras, microphones and speakers available to the page.
     ///
-    /// By default this is set to 30 seconds.
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let cameras = driver
+    ///     .media_devices()?
+    ///     .into_iter()
+    ///     .filter(|d| d.kind == "videoinput")
+    ///     .count();
+    /// println!("{} cameras", cameras);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn media_devices(&self) -> WebDriverResult<Vec<MediaDevice>> {
+        media_devices(self.session())
+    }
+
+    /// List the devices whose live camera or microphone tracks are shown or
+    /// played by `<video>` and `<audio>` elements in the page, such as a
+    /// video call's self view.
+    fn selected_media_devices(&self) -> WebDriverResult<Vec<MediaDevice>> {
+        selected_media_devices(self.session())
+    }
+
+    /// Assert that the page is using a camera or microphone whose label
+    /// contains the specified text. See
+    /// [selected_media_devices()](#method.selected_media_devices) for how
+    /// devices in use are found.
     ///
-    /// **NOTE:** Depending on the kind of testing you want to do, you may
-    /// find it more reliable to set the implicit wait time to 0 (no wait)
-    /// and implement your own polling loop outside of `thirtyfour`.
+    /// An error listing the devices in use is returned if none of them has a
+    /// matching label.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/call")?;
+    /// driver.grant_media_permissions()?;
+    /// driver.find_element(By::Id("join"))?.click()?;
+    /// driver.assert_media_device_selected("fake_device_0")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    fn assert_media_device_selected(&self, label: &str) -> WebDriverResult<()> {
+        assert_media_device_selected(self.session(), label)
+    }
+
+    /// Replace `speechSynthesis` and `SpeechRecognition` in the current page
+    /// with stubs. Use
+    /// [WebDriver::set_speech_stubs()](struct.GenericWebDriver.html#method.set_speech_stubs)
+    /// to install them in every page loaded afterwards instead.
+    ///
+    /// The synthesis stub records each utterance and fires its `start` and
+    /// `end` events straight away. The recognition stub never hears
+    /// anything by itself; see [recognize_speech()](#method.recognize_speech).
+    fn install_speech_stubs(&self) -> WebDriverResult<()> {
+        install_speech_stubs(self.session())
+    }
+
+    /// The utterances the current page has spoken via the speech stubs,
+    /// oldest first. Returns an error if the stubs are not installed.
+    fn spoken_utterances(&self) -> WebDriverResult<Vec<SpokenUtterance>> {
+        spoken_utterances(self.session())
+    }
+
+    /// Deliver a final recognition result, as if the user said the
+    /// transcript, to each speech recognition the page has started. Unless
+    /// a recognition is continuous, it then ends.
     ///
-    /// NOTE: If you set any timeouts to values greater than 120 seconds,
-    ///       remember to also increase the request timeout.
-    ///       See `WebDriver::set_request_timeout()` for more details.
+    /// Returns an error if the speech stubs are not installed, or if no
+    /// recognition is listening.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_implicit_wait_timeout(delay)?;
-    /// #     let got_timeouts = driver.get_timeouts()?;
-    /// #     assert_eq!(got_timeouts.implicit(), Some(delay));
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.install_speech_stubs()?;
+    /// driver.execute_script(
+    ///     r#"const recognition = new SpeechRecognition();
+    ///     recognition.onresult = (e) => { document.title = e.results[0][0].transcript; };
+    ///     recognition.start();"#,
+    /// )?;
+    /// driver.recognize_speech("weather in london")?;
+    /// assert_eq!(driver.title()?, "weather in london");
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_implicit_wait_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(None, None, Some(time_to_wait));
-        self.set_timeouts(timeouts)
+    fn recognize_speech(&self, transcript: &str) -> WebDriverResult<()> {
+        recognize_speech(self.session(), transcript, 0.95, true)
     }
 
-    /// Set the script timeout. This is how long the WebDriver will wait for a
-    /// Javascript script to execute.
-    ///
-    /// By default this is set to 60 seconds.
+    /// Deliver a recognition result with the specified confidence, from 0.0
+    /// to 1.0. Interim results, which are not final, are only delivered to
+    /// recognitions that asked for them with `interimResults`.
+    fn recognize_speech_with_confidence(
+        &self,
+        transcript: &str,
+        confidence: f64,
+        is_final: bool,
+    ) -> WebDriverResult<()> {
+        recognize_speech(self.session(), transcript, confidence, is_final)
+    }
+
+    /// Connect a fake gamepad at the specified index, from 0 to 3, firing
+    /// `gamepadconnected`. `navigator.getGamepads()` is replaced in the
+    /// current page, so that it reports the fake gamepads only.
     ///
-    /// NOTE: If you set any timeouts to values greater than 120 seconds,
-    ///       remember to also increase the request timeout.
-    ///       See `WebDriver::set_request_timeout()` for more details.
+    /// The page sees the gamepad's state the next time it polls
+    /// `navigator.getGamepads()`, usually in its animation loop.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
+    /// use thirtyfour_sync::GamepadState;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_script_timeout(delay)?;
-    /// #     let got_timeouts = driver.get_timeouts()?;
-    /// #     assert_eq!(got_timeouts.script(), Some(delay));
+    /// #     driver.get("http://webappdemo")?;
+    /// driver.connect_gamepad(0, "Xbox Wireless Controller", &GamepadState::standard())?;
+    /// // Jump.
+    /// driver.set_gamepad_state(0, &GamepadState::standard().with_button(0, 1.0))?;
+    /// std::thread::sleep(std::time::Duration::from_millis(100));
+    /// driver.set_gamepad_state(0, &GamepadState::standard())?;
+    /// driver.disconnect_gamepad(0)?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_script_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(Some(time_to_wait), None, None);
-        self.set_timeouts(timeouts)
+    fn connect_gamepad(&self, index: u32, id: &str, state: &GamepadState) -> WebDriverResult<()> {
+        connect_gamepad(self.session(), index, id, state)
     }
 
-    /// Set the page load timeout. This is how long the WebDriver will wait
-    /// for the page to finish loading.
-    ///
-    /// By default this is set to 60 seconds.
-    ///
-    /// NOTE: If you set any timeouts to values greater than 120 seconds,
-    ///       remember to also increase the request timeout.
-    ///       See `WebDriver::set_request_timeout()` for more details.
+    /// Update the buttons and axes of a fake gamepad connected with
+    /// `connect_gamepad()`.
+    fn set_gamepad_state(&self, index: u32, state: &GamepadState) -> WebDriverResult<()> {
+        set_gamepad_state(self.session(), index, state)
+    }
+
+    /// Disconnect a fake gamepad connected with `connect_gamepad()`, firing
+    /// `gamepaddisconnected`.
+    fn disconnect_gamepad(&self, index: u32) -> WebDriverResult<()> {
+        disconnect_gamepad(self.session(), index)
+    }
+
+    /// The element that has locked the pointer, if any. Pages such as games
+    /// lock the pointer with `requestPointerLock()` to read raw mouse
+    /// movement.
     ///
     /// # Example:
-    /// ```rust
+    /// ```no_run
     /// # use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::TimeoutConfiguration;
-    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// let delay = Duration::new(11, 0);
-    /// driver.set_page_load_timeout(delay)?;
-    /// #     let got_timeouts = driver.get_timeouts()?;
-    /// #     assert_eq!(got_timeouts.page_load(), Some(delay));
+    /// #     driver.get("http://localhost:8000/game")?;
+    /// driver.find_element(By::Tag("canvas"))?.click()?;
+    /// if let Some(elem) = driver.pointer_lock_element()? {
+    ///     println!("pointer locked by {}", elem.tag_name()?);
+    ///     driver.exit_pointer_lock()?;
+    /// }
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_page_load_timeout(&self, time_to_wait: Duration) -> WebDriverResult<()> {
-        let timeouts = TimeoutConfiguration::new(None, Some(time_to_wait), None);
-        self.set_timeouts(timeouts)
+    fn pointer_lock_element(&self) -> WebDriverResult<Option<WebElement<'_>>> {
+        pointer_lock_element(self.session())
     }
 
-    /// Create a new action chain for this session. Action chains can be used
-    /// to simulate more complex user input actions involving key combinations,
-    /// mouse movements, mouse click, right-click, and more.
+    /// Release the pointer if it is locked, as pressing Escape would.
+    fn exit_pointer_lock(&self) -> WebDriverResult<()> {
+        self.execute_script("if (document.pointerLockElement) document.exitPointerLock();")?;
+        Ok(())
+    }
+
+    /// Run the specified function, and run it again if it fails with an
+    /// error that the [RetryPolicy](struct.RetryPolicy.html) considers
+    /// retryable, such as a stale element or an intercepted click.
+    ///
+    /// The function should redo the whole operation, including finding any
+    /// elements it uses, so that a retry starts from a clean state.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::RetryPolicy;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// let elem_text = driver.find_element(By::Name("input1"))?;
-    /// let elem_button = driver.find_element(By::Id("button-set"))?;
-    ///
-    /// driver.action_chain()
-    ///     .send_keys_to_element(&elem_text, "thirtyfour")
-    ///     .move_to_element_center(&elem_button)
-    ///     .click()
-    ///     .perform()?;
-    /// #     let elem_result = driver.find_element(By::Id("input-result"))?;
-    /// #     assert_eq!(elem_result.text()?, "thirtyfour");
+    /// let text = driver.retrying(&RetryPolicy::new().with_max_attempts(5), |d| {
+    ///     d.find_element(By::Id("button1"))?.click()?;
+    ///     d.find_element(By::Id("button-result"))?.text()
+    /// })?;
+    /// #     assert_eq!(text, "Button 1 clicked");
     /// #     Ok(())
     /// # }
     /// ```
-    fn action_chain(&self) -> ActionChain {
-        ActionChain::new(self.session())
+    fn retrying<T, F>(&self, policy: &RetryPolicy, mut f: F) -> WebDriverResult<T>
+    where
+        Self: Sized,
+        F: FnMut(&Self) -> WebDriverResult<T>,
+    {
+        policy.run(|| f(self))
     }
 
-    /// Get all cookies.
+    /// Run a named user flow, such as a checkout, and measure its wall time,
+    /// the number of WebDriver commands it sends, and, where possible, the
+    /// number of bytes the browser receives over the network.
+    ///
+    /// The measurements are logged, and passed to the
+    /// [TransactionReporter](trait.TransactionReporter.html) if one is set,
+    /// whether or not the flow succeeds. The result of the flow is returned.
     ///
     /// # Example:
     /// ```rust
@@ -840,224 +2644,232 @@ pub trait WebDriverCommands {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
-    /// #     driver.add_cookie(set_cookie)?;
-    /// let cookies = driver.get_cookies()?;
-    /// for cookie in &cookies {
-    ///     println!("Got cookie: {}", cookie.value());
-    /// }
-    /// #     assert_eq!(
-    /// #         cookies.iter().filter(|x| x.value() == &serde_json::json!("value")).count(), 1);
+    /// let text = driver.transaction("click-button", |d| {
+    ///     d.find_element(By::Id("button1"))?.click()?;
+    ///     d.find_element(By::Id("button-result"))?.text()
+    /// })?;
+    /// #     assert_eq!(text, "Button 1 clicked");
     /// #     Ok(())
     /// # }
     /// ```
-    fn get_cookies(&self) -> WebDriverResult<Vec<Cookie>> {
-        let v = self.cmd(Command::GetAllCookies)?;
-        convert_json_vec::<Cookie>(&v["value"])
+    fn transaction<T, F>(&self, name: &str, f: F) -> WebDriverResult<T>
+    where
+        Self: Sized,
+        F: FnOnce(&Self) -> WebDriverResult<T>,
+    {
+        run_transaction(self.session(), name, || f(self))
     }
 
-    /// Get the specified cookie.
+    /// Wait for the current page to be ready, according to the
+    /// [ReadyStrategy](query/trait.ReadyStrategy.html) set on this driver.
+    /// Does nothing if no ReadyStrategy has been set.
+    ///
+    /// This is called automatically after `get()`, `back()`, `forward()` and
+    /// `refresh()`, but can be useful after clicking a link.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::query::JsCondition;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
-    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// let mut driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// driver.set_ready_strategy(JsCondition::new("window.appReady"));
     /// #     driver.get("http://webappdemo")?;
-    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
-    /// #     driver.add_cookie(set_cookie)?;
-    /// let cookie = driver.get_cookie("key")?;
-    /// println!("Got cookie: {}", cookie.value());
-    /// #     assert_eq!(cookie.value(), &serde_json::json!("value"));
+    /// driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// driver.wait_for_ready()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn get_cookie(&self, name: &str) -> WebDriverResult<Cookie> {
-        let v = self.cmd(Command::GetNamedCookie(name.to_string()))?;
-        convert_json::<Cookie>(&v["value"])
+    fn wait_for_ready(&self) -> WebDriverResult<()> {
+        match self.session().ready_strategy() {
+            Some(strategy) => strategy.wait_until_ready(self.session()),
+            None => Ok(()),
+        }
     }
 
-    /// Delete the specified cookie.
+    /// Wait for the page to dispatch an event with the specified name, such
+    /// as a custom event emitted by the app, and return its `detail` as JSON,
+    /// or null if it has none.
+    ///
+    /// The target is a CSS selector for the element to listen on, or
+    /// `window` or `document`. Events that bubble can be caught on an
+    /// ancestor. The listener is only installed when this is called, so it
+    /// does not see events dispatched earlier. The script timeout is
+    /// extended for the wait if needed.
+    ///
+    /// Returns a NoSuchElement error if the selector matches nothing, or a
+    /// Timeout error if the event is not dispatched in time.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
-    /// #     driver.add_cookie(set_cookie)?;
-    /// #     assert!(driver.get_cookie("key").is_ok());
-    /// driver.delete_cookie("key")?;
-    /// #     assert!(driver.get_cookie("key").is_err());
+    /// let detail = driver.wait_for_js_event("window", "app:synced", Duration::from_secs(10))?;
+    /// println!("synced {} items", detail["count"]);
     /// #     Ok(())
     /// # }
     /// ```
-    fn delete_cookie(&self, name: &str) -> WebDriverResult<()> {
-        self.cmd(Command::DeleteCookie(name.to_string())).map(|_| ())
+    fn wait_for_js_event(
+        &self,
+        target: &str,
+        event_name: &str,
+        timeout: Duration,
+    ) -> WebDriverResult<Value> {
+        wait_for_js_event(self.session(), target, event_name, timeout)
     }
 
-    /// Delete all cookies.
+    /// Wait until any one of the specified conditions is met, and return the
+    /// index of that condition. Conditions are checked in order on each poll
+    /// attempt, so if more than one is met, the first one wins.
+    ///
+    /// Returns a Timeout error if none of the conditions are met in time.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::query::Condition;
+    /// use std::time::Duration;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let set_cookie = Cookie::new("key", serde_json::json!("value"));
-    /// #     driver.add_cookie(set_cookie)?;
-    /// #     assert!(driver.get_cookie("key").is_ok());
-    /// driver.delete_all_cookies()?;
-    /// #     assert!(driver.get_cookie("key").is_err());
-    /// #     assert!(driver.get_cookies()?.is_empty());
+    /// driver.find_element(By::Id("button1"))?.click()?;
+    /// let conditions = [
+    ///     Condition::element_displayed(By::Id("no-such-element")),
+    ///     Condition::new("button 1 was clicked", |session| {
+    ///         let text = session.find_element(By::Id("button-result"))?.text()?;
+    ///         Ok(text == "Button 1 clicked")
+    ///     }),
+    /// ];
+    /// let index = driver.wait_for_any(&conditions, Duration::from_secs(10))?;
+    /// assert_eq!(index, 1);
     /// #     Ok(())
     /// # }
     /// ```
-    fn delete_all_cookies(&self) -> WebDriverResult<()> {
-        self.cmd(Command::DeleteAllCookies).map(|_| ())
+    fn wait_for_any(&self, conditions: &[Condition], timeout: Duration) -> WebDriverResult<usize> {
+        let descriptions: Vec<&str> = conditions.iter().map(|x| x.description()).collect();
+        let description = format!("none of the conditions were met: {:?}", descriptions);
+        let poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(250));
+        let result = poller.poll(&description, || {
+            for (i, condition) in conditions.iter().enumerate() {
+                if condition.check(self.session())? {
+                    return Ok(Some(i));
+                }
+            }
+            Ok(None)
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                Err(WebDriverError::Timeout(with_page_context(self.session(), &description)))
+            }
+            x => x,
+        }
     }
 
-    /// Add the specified cookie.
+    /// Wait for the specified element to go stale, and then find the element
+    /// that replaced it using the specified selector.
+    ///
+    /// This is useful when an action re-renders part of the page, since the
+    /// old element may still match the selector until it is removed. The
+    /// timeout covers both waiting for the old element to go stale and
+    /// finding the new one. Returns a Timeout error if either takes too long.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// let cookie = Cookie::new("key", serde_json::json!("value"));
-    /// driver.add_cookie(cookie)?;
-    /// #     let got_cookie = driver.get_cookie("key")?;
-    /// #     assert_eq!(got_cookie.value(), &serde_json::json!("value"));
+    /// let button = driver.find_element(By::Id("button1"))?;
+    /// driver.refresh()?;
+    /// let button =
+    ///     driver.wait_for_replacement(&button, By::Id("button1"), Duration::from_secs(10))?;
+    /// button.click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn add_cookie(&self, cookie: Cookie) -> WebDriverResult<()> {
-        self.cmd(Command::AddCookie(cookie)).map(|_| ())
-    }
-
-    /// Take a screenshot of the current window and return it as a
-    /// base64-encoded String.
-    fn screenshot_as_base64(&self) -> WebDriverResult<String> {
-        let v = self.cmd(Command::TakeScreenshot)?;
-        convert_json(&v["value"])
-    }
-
-    /// Take a screenshot of the current window and return it as PNG bytes.
-    fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
-        let s = self.screenshot_as_base64()?;
-        let bytes: Vec<u8> = decode(&s)?;
-        Ok(bytes)
-    }
-
-    /// Take a screenshot of the current window and write it to the specified
-    /// filename.
-    fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
-        let png = self.screenshot_as_png()?;
-        let mut file = File::create(path)?;
-        file.write_all(&png)?;
-        Ok(())
-    }
+    fn wait_for_replacement(
+        &self,
+        old: &WebElement,
+        by: By,
+        timeout: Duration,
+    ) -> WebDriverResult<WebElement<'_>> {
+        let started = Instant::now();
+        old.wait_until_stale(timeout)?;
 
-    /// Return a SwitchTo struct for switching to another window or frame.
-    fn switch_to(&self) -> SwitchTo {
-        SwitchTo::new(self.session())
+        let description = format!("no replacement element found for {:?}", by);
+        let remaining = timeout.checked_sub(started.elapsed()).unwrap_or_default();
+        let poller = ElementPoller::TimeoutWithInterval(remaining, Duration::from_millis(250));
+        let result = poller.poll(&description, || {
+            Ok(find_elements_nowait(self.session(), by.clone())?.into_iter().next())
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                Err(WebDriverError::Timeout(with_page_context(self.session(), &description)))
+            }
+            x => x,
+        }
     }
 
-    /// Set the current window name.
-    /// Useful for switching between windows/tabs using `driver.switch_to().window_name(name)`.
+    /// Assert that the specified condition becomes true within the timeout,
+    /// checking it every 250ms. Use this instead of a plain `assert!` when
+    /// the page updates asynchronously.
+    ///
+    /// If the condition returns an error, it is returned immediately rather
+    /// than treated as false, so the condition should return Ok(false) for
+    /// states it expects to pass through, such as an element not being
+    /// found yet.
+    ///
+    /// If the condition is still false when the timeout is reached, a
+    /// timeout error is returned.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     assert_eq!(driver.title()?, "Demo Web App");
-    /// // Get the current window handle.
-    /// let handle = driver.current_window_handle()?;
-    /// driver.set_window_name("main")?;
-    /// // Open a new tab.
-    /// driver.execute_script(r#"window.open("about:blank", target="_blank");"#)?;
-    /// // Get window handles and switch to the new tab.
-    /// let handles = driver.window_handles()?;
-    /// driver.switch_to().window(&handles[1])?;
-    /// // We are now controlling the new tab.
-    /// driver.get("http://webappdemo")?;
-    /// assert_ne!(driver.current_window_handle()?, handle);
-    /// // Switch back to original tab using window name.
-    /// driver.switch_to().window_name("main")?;
-    /// assert_eq!(driver.current_window_handle()?, handle);
+    /// driver.find_element(By::Id("button1"))?.click()?;
+    /// driver.assert_eventually(
+    ///     |d| match d.find_element_opt(By::Id("button-result"))? {
+    ///         Some(elem) => Ok(elem.text()? == "Button 1 clicked"),
+    ///         None => Ok(false),
+    ///     },
+    ///     Duration::from_secs(5),
+    /// )?;
     /// #     Ok(())
     /// # }
     /// ```
-    fn set_window_name(&self, window_name: &str) -> WebDriverResult<()> {
-        self.execute_script(&format!(r#"window.name = "{}""#, window_name))?;
+    fn assert_eventually<F>(&self, mut condition: F, timeout: Duration) -> WebDriverResult<()>
+    where
+        Self: Sized,
+        F: FnMut(&Self) -> WebDriverResult<bool>,
+    {
+        let failure = poll_assertion(timeout, || match condition(self)? {
+            true => Ok(Ok(())),
+            false => Ok(Err(String::new())),
+        })?;
+        if let Some((attempts, _)) = failure {
+            let message =
+                format!("condition was not met within {:?} ({} attempts)", timeout, attempts);
+            return Err(WebDriverError::Timeout(with_page_context(self.session(), &message)));
+        }
         Ok(())
     }
-
-    /// Running an extension command.
-    /// Extension commands are browser specific commands and using browser specific endpoints and
-    /// parameters.
-    ///
-    /// # Example
-    /// ```no_run
-    /// use serde::Serialize;
-    /// use thirtyfour_sync::prelude::*;
-    /// use thirtyfour::{ExtensionCommand, RequestMethod};
-    ///
-    /// #[derive(Serialize)]
-    /// pub struct AddonInstallCommand {
-    ///    pub path: String,
-    ///    pub temporary: Option<bool>,
-    /// }
-    ///
-    /// impl ExtensionCommand for AddonInstallCommand {
-    ///    fn parameters_json(&self) -> Option<serde_json::Value> {
-    ///        Some(serde_json::to_value(self).unwrap())
-    ///    }
-    ///    fn method(&self) -> RequestMethod {
-    ///        RequestMethod::Post
-    ///    }
-    ///
-    ///    fn endpoint(&self) -> String {
-    ///        String::from("/moz/addon/install")
-    ///    }
-    /// }
-    ///
-    /// fn main()-> WebDriverResult<()> {
-    ///     let caps = DesiredCapabilities::firefox();
-    ///     let driver = WebDriver::new("http://localhost:4444", &caps)?;
-    ///
-    ///     let install_command = AddonInstallCommand {
-    ///         path: String::from("/path/to/addon.xpi"),
-    ///         temporary: Some(true),
-    ///     };
-    ///
-    ///     let response = driver.extension_command(install_command)?;
-    ///
-    ///     assert_eq!(response.is_string(), true);
-    ///
-    ///     Ok(())
-    /// }
-    ///
-    /// ```
-    fn extension_command<T: ExtensionCommand + Send + Sync + 'static>(
-        &self,
-        ext_cmd: T,
-    ) -> WebDriverResult<serde_json::Value> {
-        let response = self.cmd(Command::ExtensionCommand(Box::new(ext_cmd)))?;
-
-        Ok(response["value"].clone())
-    }
 }
 
 /// Helper struct for getting return values from scripts.
@@ -1088,19 +2900,18 @@ impl<'a> ScriptRetSync<'a> {
     where
         T: DeserializeOwned,
     {
-        let v: T = from_value(self.value.clone())?;
-        Ok(v)
+        convert_json_from("the script result", &self.value)
     }
 
     /// Get a single WebElement return value.
     /// Your script must return only a single element for this to work.
-    pub fn get_element(&self) -> WebDriverResult<WebElement> {
+    pub fn get_element(&self) -> WebDriverResult<WebElement<'_>> {
         convert_element_sync(self.driver, &self.value)
     }
 
     /// Get a vec of WebElements from the return value.
     /// Your script must return an array of elements for this to work.
-    pub fn get_elements(&self) -> WebDriverResult<Vec<WebElement>> {
+    pub fn get_elements(&self) -> WebDriverResult<Vec<WebElement<'_>>> {
         convert_elements_sync(self.driver, &self.value)
     }
 }
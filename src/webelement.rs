@@ -1,16 +1,36 @@
-use std::{fmt, fs::File, io::Write, path::Path, write};
+use std::{
+    collections::BTreeMap, fmt, fs::File, io::Write, path::Path, str::FromStr, time::Duration,
+    write,
+};
 
 use base64::decode;
 use serde::ser::{Serialize, SerializeMap, Serializer};
 
+use crate::canvas::capture_canvas_frame;
 use crate::common::command::MAGIC_ELEMENTID;
-use crate::error::WebDriverError;
+use crate::error::{no_such_element, WebDriverError, WebDriverErrorInfo};
+use crate::geometry::{ElementCenter, ViewportGeometry, VIEWPORT_GEOMETRY_SCRIPT};
+use crate::interaction::interact;
+use crate::json::{convert_json, convert_json_from};
+use crate::listener::notify;
+use crate::locators::{named_locator, with_locator_name};
+use crate::menu::visible_elements;
+use crate::page_info::{assertion_failed, with_page_context};
+use crate::print::{print_page_span, PrintOptions};
+use crate::query::{
+    poll_assertion, without_implicit_wait, ElementPoller, ElementQuery, ElementWaiter,
+};
+use crate::secret::SecretString;
+use crate::selectors::xpath_literal;
+use crate::style::{assert_style, computed_style};
+use crate::suggest::with_locator_suggestions;
+use crate::text::{normalize_text, text_diff};
+use crate::upload::{send_path_force_visible, upload_files, UploadOptions};
 use crate::webdrivercommands::WebDriverCommands;
 use crate::WebDriverSession;
 use crate::{
     common::{
         command::Command,
-        connection_common::convert_json,
         keys::TypingData,
         types::{ElementId, ElementRect, ElementRef},
     },
@@ -18,12 +38,54 @@ use crate::{
     By, ScriptArgs,
 };
 
+/// Returns whether the element matches the selector, or the error message
+/// if the selector is invalid.
+const MATCHES_SELECTOR_SCRIPT: &str = r#"
+    try {
+        return arguments[0].matches(arguments[1]);
+    } catch (e) {
+        return String(e.message || e);
+    }
+"#;
+
+/// Selects the characters from `arguments[1]` to `arguments[2]` of the text
+/// nodes within `arguments[0]`. Returns the selected text, or null if the
+/// range is out of bounds.
+const SELECT_TEXT_RANGE_SCRIPT: &str = r#"
+    const [elem, startChar, endChar] = arguments;
+    // Ranges count UTF-16 code units, so convert the character offsets.
+    const chars = Array.from(elem.textContent);
+    if (startChar > endChar || endChar > chars.length) return null;
+    const start = chars.slice(0, startChar).join("").length;
+    const end = chars.slice(0, endChar).join("").length;
+    const walker = document.createTreeWalker(elem, NodeFilter.SHOW_TEXT);
+    const range = document.createRange();
+    let offset = 0;
+    let started = false;
+    for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+        const length = node.textContent.length;
+        if (!started && start <= offset + length) {
+            range.setStart(node, start - offset);
+            started = true;
+        }
+        if (started && end <= offset + length) {
+            range.setEnd(node, end - offset);
+            const selection = window.getSelection();
+            selection.removeAllRanges();
+            selection.addRange(range);
+            return selection.toString();
+        }
+        offset += length;
+    }
+    return null;
+"#;
+
 /// Unwrap the raw JSON into a WebElement struct.
 pub fn convert_element_sync<'a>(
     driver: &'a WebDriverSession,
     value: &serde_json::Value,
 ) -> WebDriverResult<WebElement<'a>> {
-    let elem_id: ElementRef = serde_json::from_value(value.clone())?;
+    let elem_id: ElementRef = convert_json_from("the element reference", value)?;
     Ok(WebElement::new(driver, ElementId::from(elem_id.id)))
 }
 
@@ -32,7 +94,7 @@ pub fn convert_elements_sync<'a>(
     driver: &'a WebDriverSession,
     value: &serde_json::Value,
 ) -> WebDriverResult<Vec<WebElement<'a>>> {
-    let values: Vec<ElementRef> = serde_json::from_value(value.clone())?;
+    let values: Vec<ElementRef> = convert_json_from("the element references", value)?;
     Ok(values.into_iter().map(|x| WebElement::new(driver, ElementId::from(x.id))).collect())
 }
 
@@ -95,6 +157,11 @@ impl<'a> WebElement<'a> {
         }
     }
 
+    /// The session this element belongs to.
+    pub(crate) fn session(&self) -> &'a WebDriverSession {
+        self.session
+    }
+
     ///Convenience wrapper for executing a WebDriver command.
     fn cmd(&self, command: Command) -> WebDriverResult<serde_json::Value> {
         self.session.cmd(command)
@@ -103,7 +170,7 @@ impl<'a> WebElement<'a> {
     /// Get the bounding rectangle for this WebElement.
     pub fn rect(&self) -> WebDriverResult<ElementRect> {
         let v = self.cmd(Command::GetElementRect(self.element_id.clone()))?;
-        let r: ElementRect = serde_json::from_value((&v["value"]).clone())?;
+        let r: ElementRect = convert_json("Get Element Rect", &v["value"])?;
         Ok(r)
     }
 
@@ -124,7 +191,7 @@ impl<'a> WebElement<'a> {
     /// ```
     pub fn tag_name(&self) -> WebDriverResult<String> {
         let v = self.cmd(Command::GetElementTagName(self.element_id.clone()))?;
-        convert_json(&v["value"])
+        convert_json("Get Element Tag Name", &v["value"])
     }
 
     /// Get the class name for this WebElement.
@@ -186,15 +253,38 @@ impl<'a> WebElement<'a> {
     /// ```
     pub fn text(&self) -> WebDriverResult<String> {
         let v = self.cmd(Command::GetElementText(self.element_id.clone()))?;
-        convert_json(&v["value"])
+        convert_json("Get Element Text", &v["value"])
     }
 
-    /// Convenience method for getting the (optional) value attribute of this element.
-    pub fn value(&self) -> WebDriverResult<Option<String>> {
-        self.get_attribute("value")
+    /// Get the text contents for this WebElement, normalized for comparison.
+    ///
+    /// Leading and trailing whitespace is removed, runs of whitespace
+    /// (including non-breaking spaces) are collapsed to a single space, and
+    /// zero-width spaces are removed.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("button1"))?.click()?;
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// assert_eq!(elem.normalized_text()?, "Button 1 clicked");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn normalized_text(&self) -> WebDriverResult<String> {
+        Ok(normalize_text(&self.text()?))
     }
 
-    /// Click the WebElement.
+    /// Assert that the normalized text of this WebElement equals the expected
+    /// text, which is normalized the same way.
+    ///
+    /// If the text does not match, the error message shows both values and
+    /// marks the first character that differs.
     ///
     /// # Example:
     /// ```rust
@@ -204,19 +294,27 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// let elem = driver.find_element(By::Id("button1"))?;
-    /// elem.click()?;
-    /// #     let elem = driver.find_element(By::Id("button-result"))?;
-    /// #     assert_eq!(elem.text()?, "Button 1 clicked");
+    /// #     driver.find_element(By::Id("button1"))?.click()?;
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// elem.assert_text_eq("Button 1 clicked")?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn click(&self) -> WebDriverResult<()> {
-        self.cmd(Command::ElementClick(self.element_id.clone()))?;
+    pub fn assert_text_eq(&self, expected: &str) -> WebDriverResult<()> {
+        let expected = normalize_text(expected);
+        let actual = self.normalized_text()?;
+        if actual != expected {
+            let message = format!("element text does not match\n{}", text_diff(&expected, &actual));
+            let item = format!("element text {:?}", expected);
+            return Err(assertion_failed(self.session, item, &message));
+        }
         Ok(())
     }
 
-    /// Clear the WebElement contents.
+    /// Assert that the normalized text of this WebElement contains the
+    /// expected text, which is normalized the same way.
+    ///
+    /// If the text is not found, the error message shows both values.
     ///
     /// # Example:
     /// ```rust
@@ -226,113 +324,146 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input2"))?;
-    /// #     elem.clear()?;
-    /// # let cleared_text = elem.text()?;
-    /// #     assert_eq!(cleared_text, "");
+    /// #     driver.find_element(By::Id("button1"))?.click()?;
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// elem.assert_text_contains("clicked")?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn clear(&self) -> WebDriverResult<()> {
-        self.cmd(Command::ElementClear(self.element_id.clone()))?;
+    pub fn assert_text_contains(&self, expected: &str) -> WebDriverResult<()> {
+        let expected = normalize_text(expected);
+        let actual = self.normalized_text()?;
+        if !actual.contains(&expected) {
+            let message = format!(
+                "element text does not contain the expected text\nexpected: {:?}\n  actual: {:?}",
+                expected, actual
+            );
+            let item = format!("element text containing {:?}", expected);
+            return Err(assertion_failed(self.session, item, &message));
+        }
         Ok(())
     }
 
-    /// Get the specified property.
+    /// Assert that the normalized text of this WebElement becomes equal to
+    /// the expected text, which is normalized the same way, within the
+    /// timeout. Use this instead of
+    /// [assert_text_eq()](#method.assert_text_eq) when the page updates
+    /// asynchronously.
+    ///
+    /// If the element goes stale while the page updates, this is treated as
+    /// a mismatch and the text is read again. Other errors are returned
+    /// immediately.
+    ///
+    /// If the text still does not match when the timeout is reached, a
+    /// timeout error is returned whose message compares the expected text
+    /// with the last text seen.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input2"))?;
-    /// let bool_value_option = elem.get_property("checked")?;  // Option<String>
-    /// assert_eq!(bool_value_option, Some("true".to_string()));
-    /// let string_value_option = elem.get_property("name")?;  // Option<String>
-    /// assert_eq!(string_value_option, Some("input2".to_string()));
-    /// #     assert_eq!(elem.get_property("invalid-property")?, None);
+    /// driver.find_element(By::Id("button1"))?.click()?;
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// elem.assert_text_eventually("Button 1 clicked", Duration::from_secs(5))?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn get_property(&self, name: &str) -> WebDriverResult<Option<String>> {
-        let v = self.cmd(Command::GetElementProperty(self.element_id.clone(), name.to_owned()))?;
-        if v["value"].is_null() {
-            Ok(None)
-        } else if !v["value"].is_string() {
-            Ok(Some(v["value"].to_string()))
-        } else {
-            convert_json(&v["value"]).map(Some)
+    pub fn assert_text_eventually(&self, expected: &str, timeout: Duration) -> WebDriverResult<()> {
+        let expected = normalize_text(expected);
+        let failure = poll_assertion(timeout, || match self.normalized_text() {
+            Ok(actual) if actual == expected => Ok(Ok(())),
+            Ok(actual) => Ok(Err(text_diff(&expected, &actual))),
+            Err(e @ WebDriverError::StaleElementReference(_)) => Ok(Err(format!("error: {}", e))),
+            Err(e) => Err(e),
+        })?;
+        if let Some((attempts, last)) = failure {
+            let message = format!(
+                "element text did not match within {:?} ({} attempts)\n{}",
+                timeout, attempts, last
+            );
+            return Err(WebDriverError::Timeout(with_page_context(self.session, &message)));
         }
+        Ok(())
     }
 
-    /// Get the specified attribute.
+    /// Estimate the first and last page, counting from 1, that this element
+    /// appears on when the page is printed with the specified options.
+    ///
+    /// The estimate divides the current layout into pages of the printable
+    /// height, so call
+    /// [WebDriver::emulate_print_media()](trait.WebDriverCommands.html#method.emulate_print_media)
+    /// first, and make the window roughly as wide as the printable area.
+    /// Elements with `break-inside: avoid` that fit on one page are moved to
+    /// the next page as the browser would, but the effect of that on later
+    /// content and of forced page breaks is not taken into account.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::PrintOptions;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input2"))?;
-    /// let attribute_option = elem.get_attribute("name")?;  // Option<String>
-    /// assert_eq!(attribute_option, Some("input2".to_string()));
-    /// #     assert_eq!(elem.get_attribute("invalid-attribute")?, None);
+    /// driver.emulate_print_media(true)?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let (first, last) = elem.print_page_span(&PrintOptions::new())?;
+    /// println!("printed on pages {} to {}", first, last);
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn get_attribute(&self, name: &str) -> WebDriverResult<Option<String>> {
-        let v = self.cmd(Command::GetElementAttribute(self.element_id.clone(), name.to_owned()))?;
-        if !v["value"].is_string() {
-            Ok(None)
-        } else {
-            convert_json(&v["value"])
-        }
+    pub fn print_page_span(&self, options: &PrintOptions) -> WebDriverResult<(u32, u32)> {
+        print_page_span(self, options)
     }
 
-    /// Get the specified CSS property.
+    /// Assert that this element is not split across pages when the page is
+    /// printed with the specified options. See
+    /// [print_page_span()](#method.print_page_span) for how pages are
+    /// estimated.
+    ///
+    /// An error is returned if the element starts and ends on different
+    /// pages.
     ///
     /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::PrintOptions;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input2"))?;
-    /// let css_color = elem.get_css_property("color")?;
-    /// assert_eq!(css_color, r"rgba(0, 0, 0, 1)");
-    /// #     assert_eq!(elem.get_css_property("invalid-css-property")?, "");
+    /// driver.emulate_print_media(true)?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.assert_not_split_across_pages(&PrintOptions::new())?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn get_css_property(&self, name: &str) -> WebDriverResult<String> {
-        let v = self.cmd(Command::GetElementCSSValue(self.element_id.clone(), name.to_owned()))?;
-        if !v["value"].is_string() {
-            Ok(String::new())
-        } else {
-            convert_json(&v["value"])
+    pub fn assert_not_split_across_pages(&self, options: &PrintOptions) -> WebDriverResult<()> {
+        let (first, last) = self.print_page_span(options)?;
+        if first != last {
+            let message =
+                format!("element is split across pages {} to {} when printed", first, last);
+            let item = String::from("element on a single printed page");
+            return Err(assertion_failed(self.session, item, &message));
         }
+        Ok(())
     }
 
-    /// Return true if the WebElement is currently selected, otherwise false.
-    pub fn is_selected(&self) -> WebDriverResult<bool> {
-        let v = self.cmd(Command::IsElementSelected(self.element_id.clone()))?;
-        convert_json(&v["value"])
+    /// Convenience method for getting the (optional) value attribute of this element.
+    pub fn value(&self) -> WebDriverResult<Option<String>> {
+        self.get_attribute("value")
     }
 
-    /// Return true if the WebElement is currently displayed, otherwise false.
+    /// Click the WebElement.
     ///
-    /// # Example
+    /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
     /// #
@@ -340,42 +471,55 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let elem = driver.find_element(By::Id("button1"))?;
-    /// let displayed = elem.is_displayed()?;
-    /// #     assert_eq!(displayed, true);
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.click()?;
+    /// #     let elem = driver.find_element(By::Id("button-result"))?;
+    /// #     assert_eq!(elem.text()?, "Button 1 clicked");
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn is_displayed(&self) -> WebDriverResult<bool> {
-        let v = self.cmd(Command::IsElementDisplayed(self.element_id.clone()))?;
-        convert_json(&v["value"])
+    pub fn click(&self) -> WebDriverResult<()> {
+        self.click_with_timeout(self.session.interaction_timeout())
     }
 
-    /// Return true if the WebElement is currently enabled, otherwise false.
+    /// Click the WebElement, trying again until it is interactable or the
+    /// timeout is reached, instead of using the session's interaction
+    /// timeout.
     ///
-    /// # Example
+    /// See [WebDriver::set_interaction_timeout()](struct.GenericWebDriver.html#method.set_interaction_timeout).
+    ///
+    /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
+    /// # use std::time::Duration;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let elem = driver.find_element(By::Id("button1"))?;
-    /// let enabled = elem.is_enabled()?;
-    /// #     assert_eq!(enabled, true);
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.click_within(Duration::from_secs(10))?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn is_enabled(&self) -> WebDriverResult<bool> {
-        let v = self.cmd(Command::IsElementEnabled(self.element_id.clone()))?;
-        convert_json(&v["value"])
+    pub fn click_within(&self, timeout: Duration) -> WebDriverResult<()> {
+        self.click_with_timeout(Some(timeout))
     }
 
-    /// Return true if the WebElement is currently clickable (visible and enabled),
-    /// otherwise false.
+    fn click_with_timeout(&self, timeout: Option<Duration>) -> WebDriverResult<()> {
+        notify(self.session, |l| l.before_click(self.session, self));
+        interact(self.session, timeout, "click", || {
+            self.cmd(Command::ElementClick(self.element_id.clone()))?;
+            Ok(())
+        })?;
+        notify(self.session, |l| l.after_click(self.session, self));
+        Ok(())
+    }
+
+    /// Right-click the WebElement, using an action chain so that the page
+    /// receives the `contextmenu` event.
     ///
-    /// # Example
+    /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
     /// #
@@ -383,30 +527,19 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let elem = driver.find_element(By::Id("button1"))?;
-    /// let clickable = elem.is_clickable()?;
-    /// #     assert_eq!(clickable, true);
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.context_click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn is_clickable(&self) -> WebDriverResult<bool> {
-        Ok(self.is_displayed()? && self.is_enabled()?)
+    pub fn context_click(&self) -> WebDriverResult<()> {
+        self.session.action_chain().context_click_element(self).perform()
     }
 
-    /// Return true if the WebElement is currently (still) present
-    /// and not stale.
-    ///
-    /// NOTE: This method simply queries the tag name in order to
-    ///       determine whether the element is still present.
+    /// Double-click the WebElement, using an action chain. In text, this
+    /// selects a word.
     ///
-    /// IMPORTANT:
-    /// If an element is re-rendered it may be considered stale even
-    /// though to the user it looks like it is still there.
-    ///
-    /// The recommended way to check for the presence of an element is
-    /// to simply search for the element again.
-    ///
-    /// # Example
+    /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
     /// #
@@ -414,27 +547,17 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// #     let elem = driver.find_element(By::Id("button1"))?;
-    /// let present = elem.is_present()?;
-    /// #     assert_eq!(present, true);
-    /// #     // Check negative case as well.
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     assert_eq!(elem.is_present()?, false);
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.double_click()?;
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn is_present(&self) -> WebDriverResult<bool> {
-        let present = match self.tag_name() {
-            Ok(_) => true,
-            Err(WebDriverError::NoSuchElement(_))
-            | Err(WebDriverError::StaleElementReference(_)) => false,
-            Err(e) => return Err(e),
-        };
-        Ok(present)
+    pub fn double_click(&self) -> WebDriverResult<()> {
+        self.session.action_chain().double_click_element(self).perform()
     }
 
-    /// Search for a child element of this WebElement using the specified
-    /// selector.
+    /// Triple-click the center of the WebElement, which selects the
+    /// paragraph under the pointer, and return the selected text.
     ///
     /// # Example:
     /// ```rust
@@ -444,22 +567,32 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
-    /// let child_elem = elem.find_element(By::Tag("button"))?;
-    /// #     child_elem.click()?;
-    /// #     let result_elem = elem.find_element(By::Id("button-result"))?;
-    /// #     assert_eq!(result_elem.text()?, "Button 1 clicked");
+    /// #     driver.find_element(By::Id("button1"))?.click()?;
+    /// let elem = driver.find_element(By::Id("button-result"))?;
+    /// let selected = elem.triple_click_select()?;
+    /// assert!(selected.contains("Button 1 clicked"));
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn find_element(&self, by: By) -> WebDriverResult<WebElement> {
-        let v = self
-            .cmd(Command::FindElementFromElement(self.element_id.clone(), by.get_w3c_selector()))?;
-        convert_element_sync(self.session, &v["value"])
+    pub fn triple_click_select(&self) -> WebDriverResult<String> {
+        self.session
+            .action_chain()
+            .move_to_element_center(self)
+            .click()
+            .click()
+            .click()
+            .perform()?;
+        self.session.execute_script("return window.getSelection().toString();")?.convert()
     }
 
-    /// Search for all child elements of this WebElement that match the
-    /// specified selector.
+    /// Select the text of this WebElement from character `start` up to, but
+    /// not including, character `end`, and return the selected text.
+    ///
+    /// Offsets count the characters of the element's text nodes, as in its
+    /// `textContent`, so text hidden with CSS is counted too. Characters are
+    /// Unicode scalar values, as returned by `str::chars()`, not the UTF-16
+    /// code units JavaScript counts. Returns an InvalidArgument error if the
+    /// range is out of bounds.
     ///
     /// # Example:
     /// ```rust
@@ -469,44 +602,81 @@ impl<'a> WebElement<'a> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
-    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
-    /// let child_elems = elem.find_elements(By::Tag("button"))?;
-    /// #     assert_eq!(child_elems.len(), 2);
-    /// for child_elem in child_elems {
-    ///     assert_eq!(child_elem.tag_name()?, "button");
-    /// }
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let selected = elem.select_text_range(0, 6)?;
+    /// let text = elem.get_property("textContent")?.unwrap_or_default();
+    /// assert_eq!(selected, &text[..6]);
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn find_elements(&self, by: By) -> WebDriverResult<Vec<WebElement>> {
-        let v = self.cmd(Command::FindElementsFromElement(
-            self.element_id.clone(),
-            by.get_w3c_selector(),
-        ))?;
-        convert_elements_sync(self.session, &v["value"])
+    pub fn select_text_range(&self, start: usize, end: usize) -> WebDriverResult<String> {
+        let mut args = ScriptArgs::new();
+        args.push(self)?;
+        args.push(start)?;
+        args.push(end)?;
+        let selected: Option<String> =
+            self.session.execute_script_with_args(SELECT_TEXT_RANGE_SCRIPT, &args)?.convert()?;
+        selected.ok_or_else(|| {
+            WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "text range {}..{} is out of bounds for element {}",
+                start, end, self
+            )))
+        })
     }
 
-    /// Send the specified input.
+    /// Right-click the WebElement, wait for the page's own context menu to
+    /// show an entry with the specified text, and click that entry.
+    ///
+    /// Entries with a `menuitem` role are preferred. Otherwise the last
+    /// visible `<li>`, `<a>`, `<button>` or `option` role element with the
+    /// text is used, since menus are usually added at the end of the page.
+    /// The text is compared after normalizing whitespace. Returns a Timeout
+    /// error if no such entry appears within the default poll timeout.
+    ///
+    /// Native browser context menus cannot be automated.
     ///
     /// # Example:
-    /// You can specify anything that implements `Into<TypingData>`. This
-    /// includes &str and String.
-    /// ```rust
+    /// ```no_run
     /// # use thirtyfour_sync::prelude::*;
     /// #
     /// # fn main() -> WebDriverResult<()> {
     /// #     let caps = DesiredCapabilities::chrome();
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
-    /// #     driver.get("http://webappdemo")?;
-    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input1"))?;
-    /// elem.send_keys("selenium")?;
-    /// #     assert_eq!(elem.value()?, Some("selenium".to_string()));
+    /// #     driver.get("http://localhost:8000/files")?;
+    /// let row = driver.find_element(By::Css("table.files tr:first-child"))?;
+    /// row.context_click_and_select("Rename")?;
     /// #     Ok(())
     /// # }
     /// ```
+    pub fn context_click_and_select(&self, menu_item_text: &str) -> WebDriverResult<()> {
+        self.context_click()?;
+
+        let text = xpath_literal(menu_item_text);
+        let menu_items =
+            format!("//*[starts-with(@role, 'menuitem')][normalize-space(.)={}]", text);
+        let other_items = format!(
+            "//*[self::li or self::a or self::button or @role='option'][normalize-space(.)={}]",
+            text
+        );
+        let description = format!("no context menu entry {:?} appeared", menu_item_text);
+        let result = ElementPoller::default().poll(&description, || {
+            if let Some(item) = visible_elements(self.session, &menu_items)?.into_iter().next() {
+                return Ok(Some(item));
+            }
+            Ok(visible_elements(self.session, &other_items)?.pop())
+        });
+        let item = match result {
+            Err(WebDriverError::Timeout(_)) => {
+                return Err(WebDriverError::Timeout(with_page_context(self.session, &description)))
+            }
+            x => x?,
+        };
+        item.click()
+    }
+
+    /// Clear the WebElement contents.
     ///
-    /// You can also send special key combinations like this:
+    /// # Example:
     /// ```rust
     /// # use thirtyfour_sync::prelude::*;
     /// #
@@ -515,46 +685,32 @@ impl<'a> WebElement<'a> {
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
     /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// #     let elem = driver.find_element(By::Name("input1"))?;
-    /// elem.send_keys("selenium")?;
-    /// elem.send_keys(Keys::Control + "a")?;
-    /// elem.send_keys(TypingData::from("thirtyfour") + Keys::Enter)?;
-    /// #     assert_eq!(elem.value()?, Some("thirtyfour".to_string()));
+    /// #     let elem = driver.find_element(By::Name("input2"))?;
+    /// #     elem.clear()?;
+    /// # let cleared_text = elem.text()?;
+    /// #     assert_eq!(cleared_text, "");
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn send_keys<S>(&self, keys: S) -> WebDriverResult<()>
-    where
-        S: Into<TypingData>,
-    {
-        self.cmd(Command::ElementSendKeys(self.element_id.clone(), keys.into()))?;
-        Ok(())
-    }
-
-    /// Take a screenshot of this WebElement and return it as a base64-encoded
-    /// String.
-    pub fn screenshot_as_base64(&self) -> WebDriverResult<String> {
-        let v = self.cmd(Command::TakeElementScreenshot(self.element_id.clone()))?;
-        convert_json(&v["value"])
+    pub fn clear(&self) -> WebDriverResult<()> {
+        self.clear_with_timeout(self.session.interaction_timeout())
     }
 
-    /// Take a screenshot of this WebElement and return it as PNG bytes.
-    pub fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
-        let s = self.screenshot_as_base64()?;
-        let bytes: Vec<u8> = decode(&s)?;
-        Ok(bytes)
+    /// Clear the WebElement contents, trying again until it is interactable
+    /// or the timeout is reached, instead of using the session's interaction
+    /// timeout.
+    pub fn clear_within(&self, timeout: Duration) -> WebDriverResult<()> {
+        self.clear_with_timeout(Some(timeout))
     }
 
-    /// Take a screenshot of this WebElement and write it to the specified
-    /// filename.
-    pub fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
-        let png = self.screenshot_as_png()?;
-        let mut file = File::create(path)?;
-        file.write_all(&png)?;
-        Ok(())
+    fn clear_with_timeout(&self, timeout: Option<Duration>) -> WebDriverResult<()> {
+        interact(self.session, timeout, "clear", || {
+            self.cmd(Command::ElementClear(self.element_id.clone()))?;
+            Ok(())
+        })
     }
 
-    /// Focus this WebElement using JavaScript.
+    /// Get the specified property.
     ///
     /// # Example:
     /// ```rust
@@ -565,16 +721,745 @@ impl<'a> WebElement<'a> {
     /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
     /// #     driver.get("http://webappdemo")?;
     /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
-    /// let elem = driver.find_element(By::Name("input1"))?;
-    /// elem.focus()?;
-    /// #     driver.action_chain().send_keys("selenium").perform()?;
-    /// #     assert_eq!(elem.value()?, Some("selenium".to_string()));
-    /// #     Ok(())
-    /// # }
-    /// ```
-    pub fn focus(&self) -> WebDriverResult<()> {
+    /// #     let elem = driver.find_element(By::Name("input2"))?;
+    /// let bool_value_option = elem.get_property("checked")?;  // Option<String>
+    /// assert_eq!(bool_value_option, Some("true".to_string()));
+    /// let string_value_option = elem.get_property("name")?;  // Option<String>
+    /// assert_eq!(string_value_option, Some("input2".to_string()));
+    /// #     assert_eq!(elem.get_property("invalid-property")?, None);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn get_property(&self, name: &str) -> WebDriverResult<Option<String>> {
+        let v = self.cmd(Command::GetElementProperty(self.element_id.clone(), name.to_owned()))?;
+        if v["value"].is_null() {
+            Ok(None)
+        } else if !v["value"].is_string() {
+            Ok(Some(v["value"].to_string()))
+        } else {
+            convert_json("Get Element Property", &v["value"]).map(Some)
+        }
+    }
+
+    /// Get the specified attribute.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     let elem = driver.find_element(By::Name("input2"))?;
+    /// let attribute_option = elem.get_attribute("name")?;  // Option<String>
+    /// assert_eq!(attribute_option, Some("input2".to_string()));
+    /// #     assert_eq!(elem.get_attribute("invalid-attribute")?, None);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn get_attribute(&self, name: &str) -> WebDriverResult<Option<String>> {
+        let v = self.cmd(Command::GetElementAttribute(self.element_id.clone(), name.to_owned()))?;
+        if !v["value"].is_string() {
+            Ok(None)
+        } else {
+            convert_json("Get Element Attribute", &v["value"])
+        }
+    }
+
+    /// Get the specified CSS property.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     let elem = driver.find_element(By::Name("input2"))?;
+    /// let css_color = elem.get_css_property("color")?;
+    /// assert_eq!(css_color, r"rgba(0, 0, 0, 1)");
+    /// #     assert_eq!(elem.get_css_property("invalid-css-property")?, "");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn get_css_property(&self, name: &str) -> WebDriverResult<String> {
+        let v = self.cmd(Command::GetElementCSSValue(self.element_id.clone(), name.to_owned()))?;
+        if !v["value"].is_string() {
+            Ok(String::new())
+        } else {
+            convert_json("Get Element CSS Value", &v["value"])
+        }
+    }
+
+    /// Get the specified CSS property, parsed as the specified type. This is
+    /// most useful with [Color](struct.Color.html), which compares equal
+    /// whatever format the browser returns the color in.
+    ///
+    /// Returns an UnknownResponse error if the value cannot be parsed.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::Color;
+    ///
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     let elem = driver.find_element(By::Name("input2"))?;
+    /// let color = elem.get_css_property_as::<Color>("color")?;
+    /// assert_eq!(color, "#000".parse()?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn get_css_property_as<T>(&self, name: &str) -> WebDriverResult<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.get_css_property(name)?;
+        value.parse().map_err(|e| {
+            WebDriverError::UnknownResponse(format!(
+                "failed to parse CSS property {} value {:?}: {}",
+                name, value, e
+            ))
+        })
+    }
+
+    /// Get every computed style property of this WebElement in one call, as
+    /// a map from property name to value.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let style = elem.computed_style()?;
+    /// println!("{} on {}", style["color"], style["background-color"]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn computed_style(&self) -> WebDriverResult<BTreeMap<String, String>> {
+        computed_style(self)
+    }
+
+    /// Assert that the computed value of a CSS property of this WebElement
+    /// matches the expected value.
+    ///
+    /// The expected value is computed by the browser in the same context
+    /// before comparing, so that equivalent values match, e.g. `red`,
+    /// `#ff0000` and `rgb(255, 0, 0)`, or `1.5em` and `24px` where the
+    /// font size is 16px.
+    ///
+    /// If the values do not match, the error message shows the expected
+    /// value, how the browser computed it, and the actual value.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.assert_style("display", "inline-block")?;
+    /// elem.assert_style("color", "#fff")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn assert_style(&self, name: &str, expected: &str) -> WebDriverResult<()> {
+        assert_style(self, name, expected)
+    }
+
+    /// Return true if the WebElement is currently selected, otherwise false.
+    pub fn is_selected(&self) -> WebDriverResult<bool> {
+        let v = self.cmd(Command::IsElementSelected(self.element_id.clone()))?;
+        convert_json("Is Element Selected", &v["value"])
+    }
+
+    /// Return true if the WebElement is currently displayed, otherwise false.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let elem = driver.find_element(By::Id("button1"))?;
+    /// let displayed = elem.is_displayed()?;
+    /// #     assert_eq!(displayed, true);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_displayed(&self) -> WebDriverResult<bool> {
+        let v = self.cmd(Command::IsElementDisplayed(self.element_id.clone()))?;
+        convert_json("Is Element Displayed", &v["value"])
+    }
+
+    /// Return true if the WebElement is currently enabled, otherwise false.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let elem = driver.find_element(By::Id("button1"))?;
+    /// let enabled = elem.is_enabled()?;
+    /// #     assert_eq!(enabled, true);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_enabled(&self) -> WebDriverResult<bool> {
+        let v = self.cmd(Command::IsElementEnabled(self.element_id.clone()))?;
+        convert_json("Is Element Enabled", &v["value"])
+    }
+
+    /// Return true if the WebElement is currently clickable (visible and enabled),
+    /// otherwise false.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let elem = driver.find_element(By::Id("button1"))?;
+    /// let clickable = elem.is_clickable()?;
+    /// #     assert_eq!(clickable, true);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_clickable(&self) -> WebDriverResult<bool> {
+        Ok(self.is_displayed()? && self.is_enabled()?)
+    }
+
+    /// Return true if the WebElement matches the specified CSS selector,
+    /// using `Element.matches()`.
+    ///
+    /// This is useful for checking state that is only exposed through
+    /// pseudo-classes, such as `:focus-visible`, `:hover`, `:checked`,
+    /// `:invalid` or `:placeholder-shown`. Returns an InvalidSelector error
+    /// if the browser does not understand the selector.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.focus()?;
+    /// assert!(elem.matches_selector(":focus-visible")?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn matches_selector(&self, selector: &str) -> WebDriverResult<bool> {
+        let mut args = ScriptArgs::new();
+        args.push(self)?;
+        args.push(selector)?;
+        let ret = self.session.execute_script_with_args(MATCHES_SELECTOR_SCRIPT, &args)?;
+        match ret.value() {
+            serde_json::Value::Bool(matches) => Ok(*matches),
+            e => Err(WebDriverError::InvalidSelector(WebDriverErrorInfo::new(&format!(
+                "invalid selector {:?}: {}",
+                selector,
+                e.as_str().unwrap_or_default()
+            )))),
+        }
+    }
+
+    /// Return true if the WebElement is currently (still) present
+    /// and not stale.
+    ///
+    /// NOTE: This method simply queries the tag name in order to
+    ///       determine whether the element is still present.
+    ///
+    /// IMPORTANT:
+    /// If an element is re-rendered it may be considered stale even
+    /// though to the user it looks like it is still there.
+    ///
+    /// The recommended way to check for the presence of an element is
+    /// to simply search for the element again.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     let elem = driver.find_element(By::Id("button1"))?;
+    /// let present = elem.is_present()?;
+    /// #     assert_eq!(present, true);
+    /// #     // Check negative case as well.
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     assert_eq!(elem.is_present()?, false);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_present(&self) -> WebDriverResult<bool> {
+        let present = match self.tag_name() {
+            Ok(_) => true,
+            Err(WebDriverError::NoSuchElement(_))
+            | Err(WebDriverError::StaleElementReference(_)) => false,
+            Err(e) => return Err(e),
+        };
+        Ok(present)
+    }
+
+    /// Wait until the WebElement goes stale, for example because the page
+    /// navigated away or the element was removed or re-rendered.
+    ///
+    /// This is useful after an action that replaces part of the page, to
+    /// avoid interacting with the old content before it is gone.
+    /// Returns a Timeout error if the element is still present after the
+    /// timeout.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// elem.wait_until_stale(Duration::from_secs(10))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn wait_until_stale(&self, timeout: Duration) -> WebDriverResult<()> {
+        let description = format!("element {} did not go stale", self);
+        let poller = ElementPoller::TimeoutWithInterval(timeout, Duration::from_millis(250));
+        let result = poller.poll(&description, || {
+            Ok(if self.is_present()? {
+                None
+            } else {
+                Some(())
+            })
+        });
+        match result {
+            Err(WebDriverError::Timeout(_)) => {
+                Err(WebDriverError::Timeout(with_page_context(self.session, &description)))
+            }
+            x => x,
+        }
+    }
+
+    /// Wait for this WebElement to reach a particular state, such as
+    /// displayed or clickable. See
+    /// [ElementWaiter](query/struct.ElementWaiter.html) for the available
+    /// conditions.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::time::Duration;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.wait_until().displayed()?;
+    /// driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// elem.wait_until().with_timeout(Duration::from_secs(10)).stale()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn wait_until(&self) -> ElementWaiter<'_, 'a> {
+        ElementWaiter::new(self)
+    }
+
+    /// Search for a child element of this WebElement using the specified
+    /// selector.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
+    /// let child_elem = elem.find_element(By::Tag("button"))?;
+    /// #     child_elem.click()?;
+    /// #     let result_elem = elem.find_element(By::Id("button-result"))?;
+    /// #     assert_eq!(result_elem.text()?, "Button 1 clicked");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn find_element(&self, by: By) -> WebDriverResult<WebElement<'_>> {
+        notify(self.session, |l| l.before_find(self.session, &by));
+        let v = self
+            .cmd(Command::FindElementFromElement(self.element_id.clone(), by.get_w3c_selector()))
+            .map_err(|e| with_locator_suggestions(self.session, &by, e))?;
+        convert_element_sync(self.session, &v["value"])
+    }
+
+    /// Search for all child elements of this WebElement that match the
+    /// specified selector.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
+    /// let child_elems = elem.find_elements(By::Tag("button"))?;
+    /// #     assert_eq!(child_elems.len(), 2);
+    /// for child_elem in child_elems {
+    ///     assert_eq!(child_elem.tag_name()?, "button");
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn find_elements(&self, by: By) -> WebDriverResult<Vec<WebElement<'_>>> {
+        notify(self.session, |l| l.before_find(self.session, &by));
+        let v = self.cmd(Command::FindElementsFromElement(
+            self.element_id.clone(),
+            by.get_w3c_selector(),
+        ))?;
+        convert_elements_sync(self.session, &v["value"])
+    }
+
+    /// Search for a child element of this WebElement using the locator with
+    /// the specified name. See
+    /// [WebDriver::find_named()](trait.WebDriverCommands.html#method.find_named).
+    pub fn find_named(&self, name: &str) -> WebDriverResult<WebElement<'_>> {
+        let by = named_locator(self.session.locators(), name)?;
+        self.find_element(by.clone()).map_err(|e| with_locator_name(name, &by, e))
+    }
+
+    /// Search for all child elements of this WebElement that match the
+    /// locator with the specified name. See
+    /// [WebDriver::find_named()](trait.WebDriverCommands.html#method.find_named).
+    pub fn find_all_named(&self, name: &str) -> WebDriverResult<Vec<WebElement<'_>>> {
+        let by = named_locator(self.session.locators(), name)?;
+        self.find_elements(by)
+    }
+
+    /// Start building a query for child elements of this WebElement matching
+    /// the specified selector, that polls until they appear. See
+    /// [WebDriver::query()](trait.WebDriverCommands.html#method.query).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let section = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
+    /// let buttons = section.query(By::Tag("button")).displayed().all()?;
+    /// #     assert_eq!(buttons.len(), 2);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn query(&self, by: By<'a>) -> ElementQuery<'a> {
+        ElementQuery::new(self.session, Some(self.clone()), by)
+    }
+
+    /// Search for a child element of this WebElement using the specified
+    /// selector, returning None if no element matches.
+    ///
+    /// Unlike `find_element()`, this does not wait for the implicit wait timeout.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
+    /// let child_elem = elem.find_element_opt(By::Tag("button"))?;
+    /// assert!(child_elem.is_some());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn find_element_opt(&self, by: By) -> WebDriverResult<Option<WebElement<'_>>> {
+        let elems = without_implicit_wait(self.session, || self.find_elements(by))?;
+        Ok(elems.into_iter().next())
+    }
+
+    /// Return true if any child element of this WebElement matches the
+    /// specified selector.
+    ///
+    /// This does not wait for the implicit wait timeout.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Css("div[data-section='section-buttons']"))?;
+    /// assert!(elem.exists_in(By::Tag("button"))?);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn exists_in(&self, by: By) -> WebDriverResult<bool> {
+        Ok(self.find_element_opt(by)?.is_some())
+    }
+
+    /// Send the specified input.
+    ///
+    /// # Example:
+    /// You can specify anything that implements `Into<TypingData>`. This
+    /// includes &str and String.
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     let elem = driver.find_element(By::Name("input1"))?;
+    /// elem.send_keys("selenium")?;
+    /// #     assert_eq!(elem.value()?, Some("selenium".to_string()));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// You can also send special key combinations like this:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// #     let elem = driver.find_element(By::Name("input1"))?;
+    /// elem.send_keys("selenium")?;
+    /// elem.send_keys(Keys::Control + "a")?;
+    /// elem.send_keys(TypingData::from("thirtyfour") + Keys::Enter)?;
+    /// #     assert_eq!(elem.value()?, Some("thirtyfour".to_string()));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn send_keys<S>(&self, keys: S) -> WebDriverResult<()>
+    where
+        S: Into<TypingData>,
+    {
+        self.send_keys_with_timeout(keys.into(), self.session.interaction_timeout())
+    }
+
+    /// Type the keys into the WebElement, trying again until it is
+    /// interactable or the timeout is reached, instead of using the session's
+    /// interaction timeout.
+    pub fn send_keys_within<S>(&self, keys: S, timeout: Duration) -> WebDriverResult<()>
+    where
+        S: Into<TypingData>,
+    {
+        self.send_keys_with_timeout(keys.into(), Some(timeout))
+    }
+
+    fn send_keys_with_timeout(
+        &self,
+        keys: TypingData,
+        timeout: Option<Duration>,
+    ) -> WebDriverResult<()> {
+        let keys: String = keys.as_vec().into_iter().collect();
+        interact(self.session, timeout, "type into", || {
+            self.cmd(Command::ElementSendKeys(self.element_id.clone(), keys.as_str().into()))?;
+            Ok(())
+        })
+    }
+
+    /// Type a secret, such as a password, into this WebElement.
+    ///
+    /// This works like [send_keys()](#method.send_keys), except that the
+    /// secret is redacted from the Debug output of the SecretString, and from
+    /// any errors returned by this session from now on. It is also redacted
+    /// by [WebDriverSession::redact()](struct.WebDriverSession.html#method.redact).
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::SecretString;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// let password = SecretString::from(std::env::var("TEST_PASSWORD").unwrap_or_default());
+    /// let elem = driver.find_element(By::Name("input1"))?;
+    /// elem.send_secret(&password)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn send_secret(&self, secret: &SecretString) -> WebDriverResult<()> {
+        self.session.add_secret(secret);
+        self.send_keys(secret.expose_secret())
+    }
+
+    /// Send a file path to a file input that is hidden, for example by a UI
+    /// kit that shows a styled button instead.
+    ///
+    /// The input, and any ancestors that are not displayed, are made visible
+    /// with inline styles while the path is sent, then their original
+    /// styles are restored. The page's `change` event fires as usual.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use std::path::Path;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/profile")?;
+    /// let input = driver.find_element(By::Css("input[type='file']"))?;
+    /// input.send_keys_force_visible(Path::new("/tmp/avatar.png"))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn send_keys_force_visible(&self, path: &Path) -> WebDriverResult<()> {
+        send_path_force_visible(self, path)
+    }
+
+    /// Choose one or more files in this file input, then check that the
+    /// input's `files` property lists them.
+    ///
+    /// Uploading more than one file requires the input to have the
+    /// `multiple` attribute. With [UploadOptions](struct.UploadOptions.html)
+    /// the files can first be uploaded to a remote Selenium server, and
+    /// hidden inputs can be made visible while the paths are sent.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// use thirtyfour_sync::UploadOptions;
+    /// use std::path::Path;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/upload")?;
+    /// let input = driver.find_element(By::Css("input[type='file'][multiple]"))?;
+    /// input.upload_files(
+    ///     &[Path::new("report.pdf"), Path::new("photo.jpg")],
+    ///     &UploadOptions::new().with_remote(true),
+    /// )?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn upload_files(&self, paths: &[&Path], options: &UploadOptions) -> WebDriverResult<()> {
+        upload_files(self, paths, options)
+    }
+
+    /// Take a screenshot of this WebElement and return it as a base64-encoded
+    /// String.
+    pub fn screenshot_as_base64(&self) -> WebDriverResult<String> {
+        let v = self.cmd(Command::TakeElementScreenshot(self.element_id.clone()))?;
+        convert_json("Take Element Screenshot", &v["value"])
+    }
+
+    /// Take a screenshot of this WebElement and return it as PNG bytes.
+    pub fn screenshot_as_png(&self) -> WebDriverResult<Vec<u8>> {
+        let s = self.screenshot_as_base64()?;
+        let bytes: Vec<u8> = decode(&s)?;
+        Ok(bytes)
+    }
+
+    /// Take a screenshot of this WebElement and write it to the specified
+    /// filename.
+    pub fn screenshot(&self, path: &Path) -> WebDriverResult<()> {
+        let png = self.screenshot_as_png()?;
+        let mut file = File::create(path)?;
+        file.write_all(&png)?;
+        Ok(())
+    }
+
+    /// Capture the current frame of this `<canvas>` as PNG bytes, for
+    /// comparing chart or WebGL rendering.
+    ///
+    /// The canvas is read back inside an animation frame callback, which
+    /// works for 2D canvases and for WebGL canvases created with
+    /// `preserveDrawingBuffer`. Without it, WebGL canvases can usually still
+    /// be read back this way, but if the page draws outside its animation
+    /// frames, or the canvas is tainted by cross-origin images, the frame
+    /// reads back blank or not at all. Then a screenshot of the canvas is
+    /// taken instead, which is in device pixels and includes anything drawn
+    /// on top of the canvas.
+    ///
+    /// On Chromium-based browsers,
+    /// [ChromeDevTools::preserve_drawing_buffers()](extensions/chrome/struct.ChromeDevTools.html#method.preserve_drawing_buffers)
+    /// makes readback reliable, at some cost in rendering performance.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://localhost:8000/dashboard")?;
+    /// let chart = driver.find_element(By::Css("canvas#sales-chart"))?;
+    /// let png = chart.capture_webgl_frame()?;
+    /// println!("captured {} bytes", png.len());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn capture_webgl_frame(&self) -> WebDriverResult<Vec<u8>> {
+        capture_canvas_frame(self)
+    }
+
+    /// Focus this WebElement using JavaScript.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// let elem = driver.find_element(By::Name("input1"))?;
+    /// elem.focus()?;
+    /// #     driver.action_chain().send_keys("selenium").perform()?;
+    /// #     assert_eq!(elem.value()?, Some("selenium".to_string()));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn focus(&self) -> WebDriverResult<()> {
         let mut args = ScriptArgs::new();
-        args.push(&self)?;
+        args.push(self)?;
         self.session.execute_script_with_args(r#"arguments[0].focus();"#, &args)?;
         Ok(())
     }
@@ -596,11 +1481,156 @@ impl<'a> WebElement<'a> {
     /// ```
     pub fn scroll_into_view(&self) -> WebDriverResult<()> {
         let mut args = ScriptArgs::new();
-        args.push(&self)?;
+        args.push(self)?;
         self.session.execute_script_with_args(r#"arguments[0].scrollIntoView();"#, &args)?;
         Ok(())
     }
 
+    fn viewport_geometry(&self) -> WebDriverResult<ViewportGeometry> {
+        let mut args = ScriptArgs::new();
+        args.push(self)?;
+        self.session.execute_script_with_args(VIEWPORT_GEOMETRY_SCRIPT, &args)?.convert()
+    }
+
+    /// Get the center of this WebElement, in both viewport coordinates (as
+    /// used by action chains) and page coordinates.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// let center = elem.center()?;
+    /// println!("Button is at ({}, {}) on the page", center.page_x, center.page_y);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn center(&self) -> WebDriverResult<ElementCenter> {
+        Ok(self.viewport_geometry()?.center())
+    }
+
+    /// Return true if any part of this WebElement is inside the viewport.
+    ///
+    /// This only considers the element's position, not whether it is hidden
+    /// or covered by another element.
+    pub fn is_in_viewport(&self) -> WebDriverResult<bool> {
+        Ok(self.viewport_geometry()?.is_in_viewport())
+    }
+
+    /// Get the fraction of this WebElement's area that is inside the
+    /// viewport, from 0.0 to 1.0, in the same way as `IntersectionObserver`.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// let elem = driver.find_element(By::Id("button1"))?;
+    /// elem.scroll_into_view()?;
+    /// assert!(elem.intersection_ratio()? > 0.5);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn intersection_ratio(&self) -> WebDriverResult<f64> {
+        Ok(self.viewport_geometry()?.intersection_ratio())
+    }
+
+    /// Get the `<form>` element that this element belongs to, or None if it is
+    /// not part of a form.
+    ///
+    /// For form controls this respects the `form` attribute, otherwise the
+    /// nearest ancestor `<form>` is returned.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// let elem = driver.find_element(By::Name("input1"))?;
+    /// if let Some(form) = elem.enclosing_form()? {
+    ///     println!("Form action: {:?}", form.get_attribute("action")?);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn enclosing_form(&self) -> WebDriverResult<Option<WebElement<'a>>> {
+        let mut args = ScriptArgs::new();
+        args.push(self)?;
+        let ret = self.session.execute_script_with_args(
+            r#"return arguments[0].form || arguments[0].closest("form");"#,
+            &args,
+        )?;
+        if ret.value().is_null() {
+            return Ok(None);
+        }
+        convert_element_sync(self.session, ret.value()).map(Some)
+    }
+
+    /// Submit the form that this element belongs to.
+    ///
+    /// If the form has a submit button, it is clicked so that any handlers
+    /// attached to the button run. Otherwise the form is submitted using
+    /// `requestSubmit()`, which runs validation and fires the `submit` event.
+    ///
+    /// Returns a NoSuchElement error if this element is not part of a form.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_sync::prelude::*;
+    /// #
+    /// # fn main() -> WebDriverResult<()> {
+    /// #     let caps = DesiredCapabilities::chrome();
+    /// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+    /// #     driver.get("http://webappdemo")?;
+    /// #     driver.find_element(By::Id("pagetextinput"))?.click()?;
+    /// let elem = driver.find_element(By::Name("input1"))?;
+    /// elem.send_keys("selenium")?;
+    /// elem.submit_enclosing_form()?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn submit_enclosing_form(&self) -> WebDriverResult<()> {
+        let form = match self.enclosing_form()? {
+            Some(x) => x,
+            None => return Err(no_such_element("element is not part of a form")),
+        };
+
+        let mut args = ScriptArgs::new();
+        args.push(&form)?;
+        let ret = self.session.execute_script_with_args(
+            r#"
+            const form = arguments[0];
+            for (const el of form.elements) {
+                if (el.type === "submit" && !el.disabled) {
+                    return el;
+                }
+            }
+            if (form.requestSubmit) {
+                form.requestSubmit();
+            } else {
+                form.submit();
+            }
+            return null;
+            "#,
+            &args,
+        )?;
+        if !ret.value().is_null() {
+            convert_element_sync(self.session, ret.value())?.click()?;
+        }
+        Ok(())
+    }
+
     /// Get the innerHtml property of this element.
     ///
     /// # Example:
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::Value;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::error::{WebDriverError, WebDriverResult};
+
+/// How long the reader thread blocks on the socket before checking for
+/// outgoing messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// Decides which incoming events a listener receives.
+pub(crate) type EventFilter = Box<dyn Fn(&Value) -> bool + Send>;
+
+struct Listener {
+    filter: EventFilter,
+    sender: Sender<Value>,
+}
+
+#[derive(Default)]
+struct Shared {
+    pending: HashMap<u64, Sender<Value>>,
+    listeners: Vec<Listener>,
+    error: Option<String>,
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A JSON-over-WebSocket client for protocols where each command carries an
+/// `id` that is echoed in its response, and events have no `id`. This covers
+/// both WebDriver BiDi and CDP.
+///
+/// Messages are read on a background thread, so events are collected even
+/// while the caller is busy doing something else.
+pub(crate) struct WsClient {
+    shared: Arc<Mutex<Shared>>,
+    outgoing: Mutex<Sender<String>>,
+    next_id: AtomicU64,
+    stop: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl WsClient {
+    pub(crate) fn connect(url: &str) -> WebDriverResult<Self> {
+        let (socket, _) = tungstenite::connect(url).map_err(|e| {
+            WebDriverError::FatalError(format!("failed to connect to {}: {}", url, e))
+        })?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        }
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = channel();
+        let reader = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || run_reader(socket, receiver, &shared, &stop))
+        };
+
+        Ok(Self {
+            shared,
+            outgoing: Mutex::new(sender),
+            next_id: AtomicU64::new(1),
+            stop,
+            reader: Some(reader),
+        })
+    }
+
+    /// Send the message with a new `id` and wait for the response with the
+    /// same `id`. The response is returned as-is; interpreting errors is up
+    /// to the protocol.
+    pub(crate) fn request(&self, mut message: Value, timeout: Duration) -> WebDriverResult<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        message["id"] = Value::from(id);
+        let (sender, receiver) = channel();
+        {
+            let mut shared = lock(&self.shared);
+            if let Some(e) = &shared.error {
+                return Err(WebDriverError::FatalError(e.clone()));
+            }
+            shared.pending.insert(id, sender);
+        }
+
+        let sent = self
+            .outgoing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .send(message.to_string())
+            .is_ok();
+        let response = if sent {
+            receiver.recv_timeout(timeout).ok()
+        } else {
+            None
+        };
+        match response {
+            Some(r) => Ok(r),
+            None => {
+                let mut shared = lock(&self.shared);
+                shared.pending.remove(&id);
+                Err(match &shared.error {
+                    Some(e) => WebDriverError::FatalError(e.clone()),
+                    None => WebDriverError::Timeout(format!(
+                        "no response to {}",
+                        message["method"].as_str().unwrap_or("command")
+                    )),
+                })
+            }
+        }
+    }
+
+    /// Receive all future events that match the filter. The listener is
+    /// removed when the receiver is dropped.
+    pub(crate) fn listen(&self, filter: EventFilter) -> Receiver<Value> {
+        let (sender, receiver) = channel();
+        lock(&self.shared).listeners.push(Listener {
+            filter,
+            sender,
+        });
+        receiver
+    }
+}
+
+impl Drop for WsClient {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// Dispatch an incoming message to the waiting request or event listeners.
+fn dispatch(shared: &Mutex<Shared>, text: &str) {
+    let message: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("ignoring invalid websocket message: {}", e);
+            return;
+        }
+    };
+
+    let mut shared = lock(shared);
+    if let Some(id) = message["id"].as_u64() {
+        if let Some(sender) = shared.pending.remove(&id) {
+            let _ = sender.send(message);
+        }
+    } else if message["method"].is_string() {
+        // Drop listeners whose receivers have gone away.
+        shared
+            .listeners
+            .retain(|l| !(l.filter)(&message) || l.sender.send(message.clone()).is_ok());
+    }
+}
+
+fn run_reader(
+    mut socket: Socket,
+    outgoing: Receiver<String>,
+    shared: &Mutex<Shared>,
+    stop: &AtomicBool,
+) {
+    let error = loop {
+        if stop.load(Ordering::SeqCst) {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            break "websocket closed".to_string();
+        }
+
+        match outgoing.try_recv() {
+            Ok(text) => {
+                if let Err(e) = socket.send(Message::Text(text)) {
+                    break format!("websocket send failed: {}", e);
+                }
+                continue;
+            }
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => {}
+        }
+
+        match socket.read() {
+            Ok(Message::Text(text)) => dispatch(shared, &text),
+            Ok(Message::Close(_)) => break "websocket closed by the browser".to_string(),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => break format!("websocket connection failed: {}", e),
+        }
+    };
+
+    // Wake up anything still waiting, so it fails rather than timing out.
+    let mut shared = lock(shared);
+    shared.error = Some(error);
+    shared.pending.clear();
+    shared.listeners.clear();
+}
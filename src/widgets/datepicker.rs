@@ -0,0 +1,310 @@
+use std::time::Duration;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::query::{find_elements_nowait, ElementPoller};
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{By, Keys, TypingData, WebDriverSession, WebElement};
+
+const MONTH_NAMES: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Sets dates in third-party date picker widgets by clicking through them
+/// like a user would.
+///
+/// The picker is described with CSS selectors. The selector for the day to
+/// click is a template, in which these placeholders are replaced:
+///
+/// - `{year}`: the four digit year.
+/// - `{month}`: the month from 1 to 12, and `{mm}` the same with two digits.
+/// - `{month0}`: the month from 0 to 11, as used by many JavaScript pickers.
+/// - `{day}`: the day of the month, and `{dd}` the same with two digits.
+/// - `{iso}`: the date as `YYYY-MM-DD`.
+///
+/// To set a date, the input is clicked to open the picker. The month label
+/// is read, and the next or previous month (or year, if configured) buttons
+/// are clicked until it shows the target month. Then the day is clicked.
+///
+/// If the picker does not open, its label cannot be understood, or the
+/// target month cannot be reached, the date is typed into the input using
+/// the input format instead. Use `with_direct_input_only()` to skip the
+/// picker altogether.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::widgets::datepicker::DatePicker;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://localhost:8000/booking")?;
+/// // A custom picker.
+/// DatePicker::new(driver.session(), "#start-date", "td[data-date='{iso}']")
+///     .with_month_label(".calendar-header .title")
+///     .with_month_navigation(".calendar-header .prev", ".calendar-header .next")
+///     .with_input_format("{dd}/{mm}/{year}")
+///     .set_date(2024, 3, 5)?;
+///
+/// // jQuery UI.
+/// DatePicker::jquery_ui(driver.session(), "#end-date").set_date(2024, 12, 31)?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DatePicker<'a> {
+    session: &'a WebDriverSession,
+    input: String,
+    day: String,
+    month_label: Option<String>,
+    prev_month: Option<String>,
+    next_month: Option<String>,
+    prev_year: Option<String>,
+    next_year: Option<String>,
+    input_format: String,
+    max_clicks: usize,
+    poller: ElementPoller,
+}
+
+impl<'a> DatePicker<'a> {
+    /// Create a new DatePicker for the input matching the CSS selector,
+    /// which clicks the day matching the selector template.
+    ///
+    /// Without a month label and navigation buttons, only dates in the month
+    /// that the picker opens on can be clicked.
+    pub fn new(session: &'a WebDriverSession, input: &str, day: &str) -> Self {
+        Self {
+            session,
+            input: input.to_string(),
+            day: day.to_string(),
+            month_label: None,
+            prev_month: None,
+            next_month: None,
+            prev_year: None,
+            next_year: None,
+            input_format: "{iso}".to_string(),
+            max_clicks: 240,
+            poller: ElementPoller::TimeoutWithInterval(
+                Duration::from_secs(5),
+                Duration::from_millis(100),
+            ),
+        }
+    }
+
+    /// Create a new DatePicker for a jQuery UI datepicker attached to the
+    /// input matching the CSS selector.
+    pub fn jquery_ui(session: &'a WebDriverSession, input: &str) -> Self {
+        Self::new(
+            session,
+            input,
+            "#ui-datepicker-div td[data-handler='selectDay'][data-month='{month0}']\
+             [data-year='{year}'] a[data-date='{day}']",
+        )
+        .with_month_label("#ui-datepicker-div .ui-datepicker-title")
+        .with_month_navigation(
+            "#ui-datepicker-div .ui-datepicker-prev",
+            "#ui-datepicker-div .ui-datepicker-next",
+        )
+        .with_input_format("{mm}/{dd}/{year}")
+    }
+
+    /// Set the CSS selector for the element showing the current month and
+    /// year, e.g. `March 2024`. Month names are recognised in English, in
+    /// full or abbreviated to three letters.
+    pub fn with_month_label(mut self, selector: &str) -> Self {
+        self.month_label = Some(selector.to_string());
+        self
+    }
+
+    /// Set the CSS selectors for the previous and next month buttons.
+    pub fn with_month_navigation(mut self, prev: &str, next: &str) -> Self {
+        self.prev_month = Some(prev.to_string());
+        self.next_month = Some(next.to_string());
+        self
+    }
+
+    /// Set the CSS selectors for the previous and next year buttons, which
+    /// are used to reach a different year with fewer clicks.
+    pub fn with_year_navigation(mut self, prev: &str, next: &str) -> Self {
+        self.prev_year = Some(prev.to_string());
+        self.next_year = Some(next.to_string());
+        self
+    }
+
+    /// Set the template used to type the date into the input, when the
+    /// picker cannot be used. The default is `{iso}`.
+    pub fn with_input_format(mut self, format: &str) -> Self {
+        self.input_format = format.to_string();
+        self
+    }
+
+    /// Set the maximum number of navigation clicks before giving up on the
+    /// picker. The default is 240, which is 20 years of months.
+    pub fn with_max_clicks(mut self, max_clicks: usize) -> Self {
+        self.max_clicks = max_clicks;
+        self
+    }
+
+    /// Never use the picker, and always type the date into the input.
+    pub fn with_direct_input_only(mut self) -> Self {
+        self.month_label = None;
+        self.day = String::new();
+        self
+    }
+
+    /// Set the poller used while waiting for the picker to open and update.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+
+    /// Set the date, using the picker if possible and typing the date into
+    /// the input otherwise. The month is from 1 to 12.
+    pub fn set_date(&self, year: i32, month: u32, day: u32) -> WebDriverResult<()> {
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "invalid date {:04}-{:02}-{:02}",
+                year, month, day
+            ))));
+        }
+
+        let input = self.session.find_element(By::Css(&self.input))?;
+        if !self.day.is_empty() {
+            match self.pick(&input, year, month, day) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => log::debug!("date picker failed, typing the date instead: {}", e),
+            }
+        }
+        self.type_date(&input, year, month, day)
+    }
+
+    fn fill(&self, template: &str, year: i32, month: u32, day: u32) -> String {
+        template
+            .replace("{iso}", &format!("{:04}-{:02}-{:02}", year, month, day))
+            .replace("{year}", &year.to_string())
+            .replace("{month0}", &(month - 1).to_string())
+            .replace("{month}", &month.to_string())
+            .replace("{mm}", &format!("{:02}", month))
+            .replace("{day}", &day.to_string())
+            .replace("{dd}", &format!("{:02}", day))
+    }
+
+    fn find_visible(&self, selector: &str) -> WebDriverResult<Option<WebElement<'a>>> {
+        Ok(find_elements_nowait(self.session, By::Css(selector))?
+            .into_iter()
+            .find(|e| matches!(e.is_displayed(), Ok(true))))
+    }
+
+    fn wait_visible(&self, selector: &str) -> WebDriverResult<WebElement<'a>> {
+        let description = format!("date picker element {:?} did not appear", selector);
+        self.poller.poll(&description, || self.find_visible(selector))
+    }
+
+    /// Read the month and year shown by the picker.
+    fn shown_month(&self, label: &str) -> WebDriverResult<Option<(i32, u32)>> {
+        let text = self.wait_visible(label)?.text()?.to_lowercase();
+        let year = text
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|s| s.len() == 4)
+            .and_then(|s| s.parse().ok());
+        let month = MONTH_NAMES
+            .iter()
+            .position(|name| text.contains(name))
+            .or_else(|| MONTH_NAMES.iter().position(|name| text.contains(&name[..3])))
+            .map(|i| i as u32 + 1);
+        Ok(year.zip(month))
+    }
+
+    /// Click the navigation button, and wait for the month label to change.
+    fn navigate(&self, label: &str, button: &str) -> WebDriverResult<()> {
+        let before = self.wait_visible(label)?.text()?;
+        self.wait_visible(button)?.click()?;
+        let description = format!("date picker did not move on from {:?}", before);
+        self.poller.poll(&description, || {
+            Ok(match self.find_visible(label)? {
+                Some(elem) if elem.text()? != before => Some(()),
+                _ => None,
+            })
+        })
+    }
+
+    /// Click through the picker. Returns false if the picker cannot be used
+    /// to reach the date.
+    fn pick(&self, input: &WebElement, year: i32, month: u32, day: u32) -> WebDriverResult<bool> {
+        input.click()?;
+
+        if let Some(label) = &self.month_label {
+            let target = year * 12 + month as i32;
+            let mut clicks = 0;
+            loop {
+                let (shown_year, shown_month) = match self.shown_month(label)? {
+                    Some(x) => x,
+                    None => return Ok(false),
+                };
+                let shown = shown_year * 12 + shown_month as i32;
+                if shown == target {
+                    break;
+                }
+                if clicks >= self.max_clicks {
+                    return Ok(false);
+                }
+                let year_button = if (target - shown).abs() >= 12 {
+                    self.year_button(target > shown)
+                } else {
+                    None
+                };
+                let button = match year_button.or_else(|| self.month_button(target > shown)) {
+                    Some(x) => x,
+                    None => return Ok(false),
+                };
+                self.navigate(label, button)?;
+                clicks += 1;
+            }
+        }
+
+        let day_selector = self.fill(&self.day, year, month, day);
+        self.wait_visible(&day_selector)?.click()?;
+        Ok(true)
+    }
+
+    fn month_button(&self, forward: bool) -> Option<&str> {
+        if forward {
+            self.next_month.as_deref()
+        } else {
+            self.prev_month.as_deref()
+        }
+    }
+
+    fn year_button(&self, forward: bool) -> Option<&str> {
+        if forward {
+            self.next_year.as_deref()
+        } else {
+            self.prev_year.as_deref()
+        }
+    }
+
+    fn type_date(
+        &self,
+        input: &WebElement,
+        year: i32,
+        month: u32,
+        day: u32,
+    ) -> WebDriverResult<()> {
+        let text = self.fill(&self.input_format, year, month, day);
+        input.clear()?;
+        input.send_keys(TypingData::from(text) + Keys::Tab)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::error::{no_such_element, WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::query::without_implicit_wait;
+use crate::selectors::css_string;
+use crate::{By, WebElement};
+
+/// Selects options in a `<select>` element, like a user would, by clicking
+/// them.
+///
+/// Options can be chosen by their value, their position or their visible
+/// text. For a multi-select, every matching option is selected, and options
+/// can also be deselected.
+///
+/// # Example:
+/// ```no_run
+/// # use thirtyfour_sync::prelude::*;
+/// use thirtyfour_sync::widgets::select::SelectElement;
+///
+/// # fn main() -> WebDriverResult<()> {
+/// #     let caps = DesiredCapabilities::chrome();
+/// #     let driver = WebDriver::new("http://localhost:4444/wd/hub", &caps)?;
+/// #     driver.get("http://localhost:8000/settings")?;
+/// let elem = driver.find_element(By::Id("select1"))?;
+/// let select = SelectElement::new(&elem)?;
+/// select.select_by_visible_text("Option 2")?;
+/// assert_eq!(select.first_selected_option()?.get_property("value")?.as_deref(), Some("option2"));
+///
+/// select.select_by_index(0)?;
+/// select.select_by_value("option3")?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SelectElement<'a> {
+    element: WebElement<'a>,
+    multiple: bool,
+}
+
+/// Collapse runs of whitespace, the way visible text is compared.
+fn normalize_space(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl<'a> SelectElement<'a> {
+    /// Create a new SelectElement for the element, which must be a
+    /// `<select>` element.
+    pub fn new(element: &WebElement<'a>) -> WebDriverResult<Self> {
+        let tag_name = element.tag_name()?;
+        if !tag_name.eq_ignore_ascii_case("select") {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "SelectElement requires a <select> element, not <{}>",
+                tag_name
+            ))));
+        }
+        let multiple = element.get_property("multiple")?;
+        Ok(Self {
+            element: element.clone(),
+            multiple: multiple.as_deref() == Some("true"),
+        })
+    }
+
+    /// The `<select>` element.
+    pub fn element(&self) -> &WebElement<'a> {
+        &self.element
+    }
+
+    /// Returns true if more than one option can be selected at a time.
+    pub fn is_multiple(&self) -> bool {
+        self.multiple
+    }
+
+    fn find_options(&self, css: &str) -> WebDriverResult<Vec<WebElement<'_>>> {
+        without_implicit_wait(self.element.session(), || self.element.find_elements(By::Css(css)))
+    }
+
+    /// Every option, including those in option groups.
+    pub fn options(&self) -> WebDriverResult<Vec<WebElement<'_>>> {
+        self.find_options("option")
+    }
+
+    /// The options that are currently selected.
+    pub fn selected_options(&self) -> WebDriverResult<Vec<WebElement<'_>>> {
+        let mut selected = Vec::new();
+        for option in self.options()? {
+            if option.is_selected()? {
+                selected.push(option);
+            }
+        }
+        Ok(selected)
+    }
+
+    /// The first selected option, which for a single select is the selected
+    /// option. Returns a NoSuchElement error if no option is selected.
+    pub fn first_selected_option(&self) -> WebDriverResult<WebElement<'_>> {
+        for option in self.options()? {
+            if option.is_selected()? {
+                return Ok(option);
+            }
+        }
+        Err(no_such_element("no option is selected"))
+    }
+
+    /// Select or deselect the option, by clicking it if necessary.
+    fn set_selected(&self, option: &WebElement, selected: bool) -> WebDriverResult<()> {
+        if option.is_selected()? == selected {
+            return Ok(());
+        }
+        if selected && !option.is_enabled()? {
+            return Err(WebDriverError::InvalidElementState(WebDriverErrorInfo::new(
+                "a disabled option cannot be selected",
+            )));
+        }
+        option.click()
+    }
+
+    /// Select the matching options, or only the first for a single select.
+    /// Returns a NoSuchElement error if none match.
+    fn select(&self, options: Vec<WebElement>, description: &str) -> WebDriverResult<()> {
+        if options.is_empty() {
+            return Err(no_such_element(&format!("no option {}", description)));
+        }
+        let count = if self.multiple {
+            options.len()
+        } else {
+            1
+        };
+        for option in options.iter().take(count) {
+            self.set_selected(option, true)?;
+        }
+        Ok(())
+    }
+
+    /// Deselect the matching options. Returns a NoSuchElement error if none
+    /// match, and an UnsupportedOperation error if this is not a
+    /// multi-select.
+    fn deselect(&self, options: Vec<WebElement>, description: &str) -> WebDriverResult<()> {
+        self.require_multiple()?;
+        if options.is_empty() {
+            return Err(no_such_element(&format!("no option {}", description)));
+        }
+        for option in &options {
+            self.set_selected(option, false)?;
+        }
+        Ok(())
+    }
+
+    fn require_multiple(&self) -> WebDriverResult<()> {
+        if self.multiple {
+            Ok(())
+        } else {
+            Err(WebDriverError::UnsupportedOperation(WebDriverErrorInfo::new(
+                "options can only be deselected in a multi-select",
+            )))
+        }
+    }
+
+    fn by_value(&self, value: &str) -> WebDriverResult<Vec<WebElement<'_>>> {
+        self.find_options(&format!("option[value={}]", css_string(value)))
+    }
+
+    fn by_index(&self, index: usize) -> WebDriverResult<Vec<WebElement<'_>>> {
+        Ok(self.options()?.into_iter().nth(index).into_iter().collect())
+    }
+
+    fn by_visible_text(&self, text: &str) -> WebDriverResult<Vec<WebElement<'_>>> {
+        let text = normalize_space(text);
+        let mut matching = Vec::new();
+        for option in self.options()? {
+            if normalize_space(&option.text()?) == text {
+                matching.push(option);
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Select the options with the specified `value` attribute.
+    pub fn select_by_value(&self, value: &str) -> WebDriverResult<()> {
+        self.select(self.by_value(value)?, &format!("with value {:?}", value))
+    }
+
+    /// Select the option at the specified position, counting from 0.
+    pub fn select_by_index(&self, index: usize) -> WebDriverResult<()> {
+        self.select(self.by_index(index)?, &format!("at index {}", index))
+    }
+
+    /// Select the options whose visible text matches, ignoring differences
+    /// in whitespace.
+    pub fn select_by_visible_text(&self, text: &str) -> WebDriverResult<()> {
+        self.select(self.by_visible_text(text)?, &format!("with text {:?}", text))
+    }
+
+    /// Deselect the options with the specified `value` attribute, in a
+    /// multi-select.
+    pub fn deselect_by_value(&self, value: &str) -> WebDriverResult<()> {
+        self.deselect(self.by_value(value)?, &format!("with value {:?}", value))
+    }
+
+    /// Deselect the option at the specified position, in a multi-select.
+    pub fn deselect_by_index(&self, index: usize) -> WebDriverResult<()> {
+        self.deselect(self.by_index(index)?, &format!("at index {}", index))
+    }
+
+    /// Deselect the options whose visible text matches, in a multi-select.
+    pub fn deselect_by_visible_text(&self, text: &str) -> WebDriverResult<()> {
+        self.deselect(self.by_visible_text(text)?, &format!("with text {:?}", text))
+    }
+
+    /// Deselect every option, in a multi-select.
+    pub fn deselect_all(&self) -> WebDriverResult<()> {
+        self.require_multiple()?;
+        for option in self.selected_options()? {
+            self.set_selected(&option, false)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use crate::extensions::chrome::ChromeDevTools;
+use crate::webdrivercommands::WebDriverCommands;
+use crate::{ScriptArgs, WebDriverSession};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ViewportMetrics {
+    width: f64,
+    height: f64,
+    device_pixel_ratio: f64,
+}
+
+const VIEWPORT_METRICS_SCRIPT: &str = r#"
+    return {
+        width: window.innerWidth,
+        height: window.innerHeight,
+        devicePixelRatio: window.devicePixelRatio
+    };
+"#;
+
+const CSS_ZOOM_SCRIPT: &str = r#"
+    const zoom = arguments[0];
+    document.documentElement.style.zoom = zoom === 1 ? "" : String(zoom);
+"#;
+
+/// Zoom the page by the specified factor, the way the browser's own zoom
+/// does: the layout viewport shrinks in CSS pixels and the page reflows.
+///
+/// Chromium-based browsers are zoomed with DevTools device metrics, which
+/// persist across navigations. Other browsers fall back to the CSS `zoom`
+/// property on the root element, which only applies to the current page.
+/// Keyboard shortcuts can't be used, since browsers ignore synthesized
+/// input for their own zoom controls.
+pub(crate) fn set_zoom(session: &WebDriverSession, factor: f64) -> WebDriverResult<()> {
+    if !factor.is_finite() || factor <= 0.0 {
+        return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+            "zoom factor must be a positive number, got {}",
+            factor
+        ))));
+    }
+
+    if session.browser_info().is_chromium() {
+        let dev_tools = ChromeDevTools::new(session);
+        // Measure the viewport without any previous zoom applied.
+        dev_tools.execute_cdp("Emulation.clearDeviceMetricsOverride")?;
+        if (factor - 1.0).abs() < f64::EPSILON {
+            return Ok(());
+        }
+        let metrics: ViewportMetrics =
+            session.execute_script(VIEWPORT_METRICS_SCRIPT)?.convert()?;
+        dev_tools.execute_cdp_with_params(
+            "Emulation.setDeviceMetricsOverride",
+            json!({
+                "width": (metrics.width / factor).round() as u64,
+                "height": (metrics.height / factor).round() as u64,
+                "deviceScaleFactor": metrics.device_pixel_ratio * factor,
+                "mobile": false
+            }),
+        )?;
+        Ok(())
+    } else {
+        let mut args = ScriptArgs::new();
+        args.push(factor)?;
+        session.execute_script_with_args(CSS_ZOOM_SCRIPT, &args)?;
+        Ok(())
+    }
+}